@@ -7,6 +7,7 @@ use std::process::ExitCode;
 use apollo_compiler::ExecutableDocument;
 use apollo_federation::error::{FederationError, SingleFederationError};
 use apollo_federation::query_graph;
+use apollo_federation::query_plan::parity::PlanFixture;
 use apollo_federation::query_plan::query_planner::QueryPlanner;
 use apollo_federation::query_plan::query_planner::QueryPlannerConfig;
 use apollo_federation::subgraph;
@@ -37,10 +38,19 @@ enum Command {
     },
     /// Outputs the formatted query plan for the given query and schema
     Plan {
+        /// Output the query plan in the JSON format of the JS query planner
+        #[arg(long)]
+        json: bool,
         query: PathBuf,
         /// Path(s) to one supergraph schema file, `-` for stdin or multiple subgraph schemas.
         schemas: Vec<PathBuf>,
     },
+    /// Compares the query plans of fixture files with the JS query planner plans they contain
+    PlanParity {
+        /// Path(s) to fixture files, each with `--- supergraph`, `--- operation` and `--- plan`
+        /// sections.
+        fixtures: Vec<PathBuf>,
+    },
     /// Validate one supergraph schema file or multiple subgraph schemas
     Validate {
         /// Path(s) to one supergraph schema file, `-` for stdin or multiple subgraph schemas.
@@ -51,6 +61,20 @@ enum Command {
         /// Path(s) to subgraph schemas.
         schemas: Vec<PathBuf>,
     },
+    /// Outputs the contract supergraph schema selected by `@tag`s, or its API schema
+    Contract {
+        /// Tag of the elements to include (can be repeated)
+        #[arg(long = "include")]
+        include_tags: Vec<String>,
+        /// Tag of the elements to exclude (can be repeated)
+        #[arg(long = "exclude")]
+        exclude_tags: Vec<String>,
+        /// Output the API schema of the contract instead of its supergraph schema
+        #[arg(long)]
+        api: bool,
+        /// Path(s) to one supergraph schema file, `-` for stdin or multiple subgraph schemas.
+        schemas: Vec<PathBuf>,
+    },
     /// Extract subgraph schemas from a supergraph schema to stdout (or in a directory if specified)
     Extract {
         /// The path to the supergraph schema file, or `-` for stdin
@@ -66,9 +90,23 @@ fn main() -> ExitCode {
         Command::Api { schemas } => to_api_schema(&schemas),
         Command::QueryGraph { schemas } => dot_query_graph(&schemas),
         Command::FederatedGraph { schemas } => dot_federated_graph(&schemas),
-        Command::Plan { query, schemas } => plan(&query, &schemas),
+        Command::Plan {
+            json,
+            query,
+            schemas,
+        } => plan(json, &query, &schemas),
+        Command::PlanParity { fixtures } => match cmd_plan_parity(&fixtures) {
+            Ok(false) => return ExitCode::FAILURE,
+            result => result.map(|_| ()),
+        },
         Command::Validate { schemas } => cmd_validate(&schemas),
         Command::Compose { schemas } => cmd_compose(&schemas),
+        Command::Contract {
+            include_tags,
+            exclude_tags,
+            api,
+            schemas,
+        } => cmd_contract(include_tags, exclude_tags, api, &schemas),
         Command::Extract {
             supergraph_schema,
             destination_dir,
@@ -113,7 +151,7 @@ fn compose_files(file_paths: &[PathBuf]) -> Result<apollo_federation::Supergraph
             subgraph::Subgraph::parse_and_expand(basename, &url, &doc_str).unwrap()
         })
         .collect();
-    let supergraph = apollo_federation::Supergraph::compose(schemas.iter().collect()).unwrap();
+    let supergraph = apollo_federation::Supergraph::compose(schemas.iter().collect())?;
     Ok(supergraph)
 }
 
@@ -160,7 +198,7 @@ fn dot_federated_graph(file_paths: &[PathBuf]) -> Result<(), FederationError> {
     Ok(())
 }
 
-fn plan(query_path: &Path, schema_paths: &[PathBuf]) -> Result<(), FederationError> {
+fn plan(json: bool, query_path: &Path, schema_paths: &[PathBuf]) -> Result<(), FederationError> {
     let query = read_input(query_path);
     let supergraph = load_supergraph(schema_paths)?;
     let query_doc =
@@ -168,10 +206,44 @@ fn plan(query_path: &Path, schema_paths: &[PathBuf]) -> Result<(), FederationErr
     // TODO: add CLI parameters for config as needed
     let config = QueryPlannerConfig::default();
     let planner = QueryPlanner::new(&supergraph, config)?;
-    print!("{}", planner.build_query_plan(&query_doc, None)?);
+    let query_plan = planner.build_query_plan(&query_doc, None, Default::default())?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&query_plan).expect("query plans serialize to JSON")
+        );
+    } else {
+        print!("{query_plan}");
+    }
     Ok(())
 }
 
+/// Returns whether the plans of all fixtures match.
+fn cmd_plan_parity(fixture_paths: &[PathBuf]) -> Result<bool, FederationError> {
+    let mut failures = 0;
+    for fixture_path in fixture_paths {
+        let name = fixture_path.file_stem().unwrap().to_string_lossy();
+        let fixture = PlanFixture::parse(name, &read_input(fixture_path))?;
+        let differences = fixture.run(QueryPlannerConfig::default())?;
+        if differences.is_empty() {
+            println!("[PASS] {}", fixture.name);
+        } else {
+            failures += 1;
+            println!("[FAIL] {}", fixture.name);
+            for difference in differences {
+                println!("{difference}");
+            }
+        }
+    }
+    if failures > 0 {
+        println!(
+            "{failures} of {} fixture(s) have a different plan",
+            fixture_paths.len()
+        );
+    }
+    Ok(failures == 0)
+}
+
 fn cmd_validate(file_paths: &[PathBuf]) -> Result<(), FederationError> {
     load_supergraph(file_paths)?;
     println!("[SUCCESS]");
@@ -184,6 +256,31 @@ fn cmd_compose(file_paths: &[PathBuf]) -> Result<(), FederationError> {
     Ok(())
 }
 
+fn cmd_contract(
+    include_tags: Vec<String>,
+    exclude_tags: Vec<String>,
+    api: bool,
+    file_paths: &[PathBuf],
+) -> Result<(), FederationError> {
+    let supergraph = load_supergraph(file_paths)?;
+    let contract = supergraph.to_contract(
+        &apollo_federation::ContractOptions {
+            include_tags: include_tags.into_iter().collect(),
+            exclude_tags: exclude_tags.into_iter().collect(),
+        },
+        apollo_federation::ApiSchemaOptions {
+            include_defer: true,
+            include_stream: false,
+        },
+    )?;
+    if api {
+        println!("{}", contract.api_schema.schema());
+    } else {
+        println!("{}", contract.supergraph.schema.schema());
+    }
+    Ok(())
+}
+
 fn cmd_extract(file_path: &Path, dest: Option<&PathBuf>) -> Result<(), FederationError> {
     let supergraph = load_supergraph_file(file_path)?;
     let subgraphs = supergraph.extract_subgraphs()?;
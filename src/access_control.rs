@@ -0,0 +1,259 @@
+//! Support for the access-control directives `@authenticated`, `@requiresScopes` and `@policy`.
+//!
+//! Subgraphs get those directives from the federation spec (v2.5 and later), while supergraphs
+//! link each of them through its own spec (e.g. `https://specs.apollo.dev/requiresScopes/v0.1`).
+use apollo_compiler::ast::{Directive, Value};
+use apollo_compiler::executable::{Selection, SelectionSet};
+use apollo_compiler::schema::{ExtendedType, Name};
+use apollo_compiler::validation::Valid;
+use apollo_compiler::{name, ExecutableDocument};
+use indexmap::IndexSet;
+
+use crate::error::{FederationError, SingleFederationError};
+use crate::link::spec::{Identity, Version, APOLLO_SPEC_DOMAIN};
+use crate::link::LinksMetadata;
+use crate::schema::position::CompositeTypeDefinitionPosition;
+use crate::schema::ValidFederationSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum AccessControlDirective {
+    Authenticated,
+    RequiresScopes,
+    Policy,
+}
+
+impl AccessControlDirective {
+    pub(crate) const ALL: [Self; 3] = [Self::Authenticated, Self::RequiresScopes, Self::Policy];
+
+    /// The name of the directive, which is both its name in the federation spec and the name of
+    /// the spec linked in supergraphs.
+    pub(crate) fn name(self) -> Name {
+        match self {
+            Self::Authenticated => name!("authenticated"),
+            Self::RequiresScopes => name!("requiresScopes"),
+            Self::Policy => name!("policy"),
+        }
+    }
+
+    pub(crate) fn identity(self) -> Identity {
+        Identity {
+            domain: APOLLO_SPEC_DOMAIN.to_string(),
+            name: self.name(),
+        }
+    }
+
+    /// The version of the spec linked in supergraphs.
+    pub(crate) fn version(self) -> Version {
+        Version { major: 0, minor: 1 }
+    }
+
+    /// The argument of the directive, if any, along with the name of the scalar used in its
+    /// `[[scalar!]!]!` type.
+    pub(crate) fn argument(self) -> Option<(Name, Name)> {
+        match self {
+            Self::Authenticated => None,
+            Self::RequiresScopes => Some((name!("scopes"), name!("Scope"))),
+            Self::Policy => Some((name!("policies"), name!("Policy"))),
+        }
+    }
+
+    /// Returns the name of each access-control directive in a schema linking the given spec,
+    /// skipping the directives that are not defined in `schema`.
+    pub(crate) fn names_in_schema(
+        metadata: &LinksMetadata,
+        identity_of: impl Fn(Self) -> Identity,
+        is_defined: impl Fn(&Name) -> bool,
+    ) -> Vec<(Self, Name)> {
+        Self::ALL
+            .into_iter()
+            .filter_map(|directive| {
+                let link = metadata.for_identity(&identity_of(directive))?;
+                let name_in_schema = link.directive_name_in_schema(&directive.name());
+                is_defined(&name_in_schema).then_some((directive, name_in_schema))
+            })
+            .collect()
+    }
+}
+
+/// The access-control requirements of the elements selected by an operation, as declared by the
+/// `@authenticated`, `@requiresScopes` and `@policy` directives of the supergraph.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccessControlRequirements {
+    /// Whether the operation selects an element marked `@authenticated`.
+    pub requires_authentication: bool,
+    /// The scopes mentioned by the `@requiresScopes` of the selected elements.
+    pub scopes: IndexSet<String>,
+    /// The policies mentioned by the `@policy` of the selected elements.
+    pub policies: IndexSet<String>,
+}
+
+impl AccessControlRequirements {
+    fn add(&mut self, directive: AccessControlDirective, application: &Directive) {
+        let values = match directive {
+            AccessControlDirective::Authenticated => {
+                self.requires_authentication = true;
+                return;
+            }
+            AccessControlDirective::RequiresScopes => &mut self.scopes,
+            AccessControlDirective::Policy => &mut self.policies,
+        };
+        let Some((argument_name, _)) = directive.argument() else {
+            return;
+        };
+        let Some(Value::List(alternatives)) = application
+            .argument_by_name(&argument_name)
+            .map(|value| value.as_ref())
+        else {
+            return;
+        };
+        for alternative in alternatives {
+            let Value::List(items) = alternative.as_ref() else {
+                continue;
+            };
+            for item in items {
+                if let Value::String(item) = item.as_ref() {
+                    values.insert(item.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Collects the access-control requirements of the given operation of `document`.
+pub(crate) fn access_control_requirements(
+    supergraph_schema: &ValidFederationSchema,
+    document: &Valid<ExecutableDocument>,
+    operation_name: Option<&str>,
+) -> Result<AccessControlRequirements, FederationError> {
+    let operation = document.get_operation(operation_name).map_err(|_| {
+        // The messages of graphql-js, which the router reports for such requests.
+        let message = match operation_name {
+            Some(operation_name) => format!("Unknown operation named \"{operation_name}\"."),
+            None => "Must provide operation name if query contains multiple operations.".to_owned(),
+        };
+        SingleFederationError::InvalidGraphQL { message }
+    })?;
+    let mut collector = RequirementsCollector {
+        schema: supergraph_schema,
+        document,
+        directive_names: supergraph_schema
+            .metadata()
+            .map(|metadata| {
+                AccessControlDirective::names_in_schema(
+                    metadata,
+                    AccessControlDirective::identity,
+                    |name| {
+                        supergraph_schema
+                            .schema()
+                            .directive_definitions
+                            .contains_key(name)
+                    },
+                )
+            })
+            .unwrap_or_default(),
+        visited_fragments: IndexSet::new(),
+        requirements: Default::default(),
+    };
+    if !collector.directive_names.is_empty() {
+        collector.collect_selection_set(&operation.selection_set);
+    }
+    Ok(collector.requirements)
+}
+
+struct RequirementsCollector<'a> {
+    schema: &'a ValidFederationSchema,
+    document: &'a ExecutableDocument,
+    directive_names: Vec<(AccessControlDirective, Name)>,
+    visited_fragments: IndexSet<&'a Name>,
+    requirements: AccessControlRequirements,
+}
+
+impl<'a> RequirementsCollector<'a> {
+    fn collect_directives(&mut self, directives: impl IntoIterator<Item = &'a Directive>) {
+        for application in directives {
+            if let Some((directive, _)) = self
+                .directive_names
+                .iter()
+                .find(|(_, name)| *name == application.name)
+            {
+                self.requirements.add(*directive, application);
+            }
+        }
+    }
+
+    fn collect_type(&mut self, type_name: &Name) {
+        let schema: &'a ValidFederationSchema = self.schema;
+        let directives = match schema.schema().types.get(type_name) {
+            Some(ExtendedType::Object(type_)) => &type_.directives,
+            Some(ExtendedType::Interface(type_)) => &type_.directives,
+            Some(ExtendedType::Scalar(type_)) => &type_.directives,
+            Some(ExtendedType::Enum(type_)) => &type_.directives,
+            _ => return,
+        };
+        self.collect_directives(directives.iter().map(|directive| directive.as_ref()));
+    }
+
+    /// The object types an abstract type may resolve to, or nothing for other types.
+    fn runtime_types(&self, type_name: &Name) -> Vec<Name> {
+        let Some(Ok(pos)) = self
+            .schema
+            .try_get_type(type_name.clone())
+            .map(CompositeTypeDefinitionPosition::try_from)
+        else {
+            return Vec::new();
+        };
+        if matches!(pos, CompositeTypeDefinitionPosition::Object(_)) {
+            return Vec::new();
+        }
+        self.schema
+            .possible_runtime_types(pos)
+            .map(|types| types.into_iter().map(|pos| pos.type_name).collect())
+            .unwrap_or_default()
+    }
+
+    fn collect_selection_set(&mut self, selection_set: &'a SelectionSet) {
+        self.collect_type(&selection_set.ty);
+        // Selections on an abstract type may resolve to any of its implementations or members, so
+        // their requirements apply too.
+        let runtime_types = self.runtime_types(&selection_set.ty);
+        for runtime_type in &runtime_types {
+            self.collect_type(runtime_type);
+        }
+        for selection in &selection_set.selections {
+            match selection {
+                Selection::Field(field) => {
+                    self.collect_directives(field.definition.directives.iter().map(|d| d.as_ref()));
+                    for runtime_type in &runtime_types {
+                        let schema: &'a ValidFederationSchema = self.schema;
+                        if let Some(definition) = schema
+                            .schema()
+                            .get_object(runtime_type)
+                            .and_then(|type_| type_.fields.get(&field.name))
+                        {
+                            self.collect_directives(
+                                definition.directives.iter().map(|d| d.as_ref()),
+                            );
+                        }
+                    }
+                    self.collect_type(field.definition.ty.inner_named_type());
+                    self.collect_selection_set(&field.selection_set);
+                }
+                Selection::InlineFragment(inline_fragment) => {
+                    self.collect_selection_set(&inline_fragment.selection_set);
+                }
+                Selection::FragmentSpread(fragment_spread) => {
+                    if !self
+                        .visited_fragments
+                        .insert(&fragment_spread.fragment_name)
+                    {
+                        continue;
+                    }
+                    let document: &'a ExecutableDocument = self.document;
+                    if let Some(fragment) = document.fragments.get(&fragment_spread.fragment_name) {
+                        self.collect_selection_set(&fragment.selection_set);
+                    }
+                }
+            }
+        }
+    }
+}
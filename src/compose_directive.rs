@@ -0,0 +1,699 @@
+//! Support for `@composeDirective`, through which subgraphs ask for the custom directives of the
+//! specs they link to be kept in the supergraph.
+//!
+//! For instance, with the following in a subgraph, the definition of `@hello` and its
+//! applications are added to the supergraph, along with a `@link` to the `hello` spec:
+//!
+//! ```graphql
+//! extend schema
+//!   @link(url: "https://specs.apollo.dev/federation/v2.1", import: ["@composeDirective"])
+//!   @link(url: "https://myspecs.dev/hello/v1.0", import: ["@hello"])
+//!   @composeDirective(name: "@hello")
+//! ```
+// PORT_NOTE: Corresponds to the JS `ComposeDirectiveManager`.
+use std::fmt::Display;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use apollo_compiler::ast::{Argument, Directive, DirectiveDefinition, Value};
+use apollo_compiler::schema::{Component, ExtendedType, Name};
+use apollo_compiler::{name, Node, NodeStr, Schema};
+use indexmap::{IndexMap, IndexSet};
+
+use crate::error::SingleFederationError;
+use crate::link::database::links_metadata;
+use crate::link::spec::Identity;
+use crate::link::{Link, LinksMetadata};
+use crate::merge::{print_subgraph_names, CompositionHint, HintCode, MergeError, SubgraphLocation};
+use crate::subgraph::spec::{
+    COMPOSE_DIRECTIVE_NAME, INACCESSIBLE_DIRECTIVE_NAME, TAG_DIRECTIVE_NAME,
+};
+use crate::subgraph::ValidSubgraph;
+
+/// The directives composed by the subgraphs, as validated by
+/// [`ComposeDirectiveManager::validate()`].
+pub(crate) struct ComposeDirectiveManager {
+    /// The composed directives, keyed by their name in subgraphs and in the supergraph.
+    directives: IndexMap<Name, ComposedDirective>,
+    pub(crate) errors: Vec<MergeError>,
+    pub(crate) hints: Vec<CompositionHint>,
+}
+
+struct ComposedDirective {
+    /// The link of the spec the directive belongs to, at the latest version linked by the
+    /// subgraphs composing the directive.
+    link: Arc<Link>,
+    /// The name of the directive in its spec.
+    element: Name,
+    /// The definition of the directive in the subgraph linking the latest version of the spec.
+    definition: Node<DirectiveDefinition>,
+    /// The name of the subgraph `definition` comes from.
+    definition_subgraph: String,
+}
+
+/// A `@composeDirective` application of a subgraph.
+struct ComposeDirectiveApplication<'a> {
+    subgraph: &'a ValidSubgraph,
+    location: SubgraphLocation,
+    link: Arc<Link>,
+    element: Name,
+    definition: &'a Node<DirectiveDefinition>,
+}
+
+impl ComposeDirectiveManager {
+    /// Collects the `@composeDirective` applications of the subgraphs, and checks that the
+    /// composed directives are consistent across subgraphs:
+    /// - the composed directives must be defined by a linked spec other than the federation one,
+    /// - the subgraphs must link the same major version of a spec,
+    /// - a directive must have the same name in every subgraph composing it, and that name must
+    ///   not be used by another composed directive.
+    pub(crate) fn validate(subgraphs: &[&ValidSubgraph]) -> Self {
+        let mut manager = Self {
+            directives: IndexMap::new(),
+            errors: Vec::new(),
+            hints: Vec::new(),
+        };
+        let mut applications = Vec::new();
+        for subgraph in subgraphs {
+            let Ok(Some(metadata)) = links_metadata(&subgraph.schema) else {
+                continue;
+            };
+            manager.collect_applications(subgraph, &metadata, &mut applications);
+        }
+
+        let mut by_identity: IndexMap<&Identity, Vec<&ComposeDirectiveApplication>> =
+            IndexMap::new();
+        for application in &applications {
+            by_identity
+                .entry(&application.link.url.identity)
+                .or_default()
+                .push(application);
+        }
+        for (identity, applications) in by_identity {
+            let major_versions = applications
+                .iter()
+                .map(|application| application.link.url.version.major)
+                .collect::<IndexSet<_>>();
+            if major_versions.len() > 1 {
+                manager.errors.push(MergeError {
+                    error: SingleFederationError::DirectiveCompositionError {
+                        message: format!(
+                            "Core feature \"{identity}\" requested to be merged has major version mismatch across subgraphs"
+                        ),
+                    },
+                    locations: applications
+                        .iter()
+                        .map(|application| application.location.clone())
+                        .collect(),
+                });
+                continue;
+            }
+            let latest = applications
+                .iter()
+                .copied()
+                .reduce(|latest, application| {
+                    if application.link.url.version > latest.link.url.version {
+                        application
+                    } else {
+                        latest
+                    }
+                })
+                .expect("applications are grouped by identity");
+
+            let mut by_element: IndexMap<&Name, Vec<&ComposeDirectiveApplication>> =
+                IndexMap::new();
+            for application in &applications {
+                by_element
+                    .entry(&application.element)
+                    .or_default()
+                    .push(application);
+            }
+            for (element, applications) in by_element {
+                manager.add_directive(latest, element, &applications);
+            }
+        }
+        manager
+    }
+
+    fn collect_applications<'a>(
+        &mut self,
+        subgraph: &'a ValidSubgraph,
+        metadata: &LinksMetadata,
+        applications: &mut Vec<ComposeDirectiveApplication<'a>>,
+    ) {
+        let Some(federation_link) = metadata.for_identity(&Identity::federation_identity()) else {
+            return;
+        };
+        let compose_directive_name =
+            federation_link.directive_name_in_schema(&COMPOSE_DIRECTIVE_NAME);
+        for application in subgraph
+            .schema
+            .schema_definition
+            .directives
+            .get_all(&compose_directive_name)
+        {
+            let location = SubgraphLocation::new(subgraph, application.location());
+            let Some(Value::String(argument)) = application
+                .argument_by_name("name")
+                .map(|value| value.as_ref())
+            else {
+                continue;
+            };
+            let Some(directive_name) = argument.strip_prefix('@') else {
+                self.errors.push(MergeError {
+                    error: SingleFederationError::DirectiveCompositionError {
+                        message: format!(
+                            "Argument to @composeDirective \"{argument}\" in subgraph \"{}\" must have a leading \"@\"",
+                            subgraph.name,
+                        ),
+                    },
+                    locations: vec![location],
+                });
+                continue;
+            };
+            let Some(definition) = Name::new(directive_name)
+                .ok()
+                .and_then(|name| subgraph.schema.directive_definitions.get(&name))
+            else {
+                self.errors.push(MergeError {
+                    error: SingleFederationError::DirectiveCompositionError {
+                        message: format!(
+                            "Could not find matching directive definition for argument to @composeDirective \"{argument}\" in subgraph \"{}\".",
+                            subgraph.name,
+                        ),
+                    },
+                    locations: vec![location],
+                });
+                continue;
+            };
+            let Some(source) = metadata.source_link_of_directive(&definition.name) else {
+                self.errors.push(MergeError {
+                    error: SingleFederationError::DirectiveCompositionError {
+                        message: format!(
+                            "Directive \"{argument}\" in subgraph \"{}\" cannot be composed because it is not a member of a core feature",
+                            subgraph.name,
+                        ),
+                    },
+                    locations: vec![location],
+                });
+                continue;
+            };
+            let element = match &source.import {
+                Some(import) => import.element.clone(),
+                None => element_name(&source.link, &definition.name),
+            };
+            let identity = &source.link.url.identity;
+            if *identity == Identity::federation_identity()
+                || *identity == Identity::link_identity()
+            {
+                if element == TAG_DIRECTIVE_NAME || element == INACCESSIBLE_DIRECTIVE_NAME {
+                    self.hints.push(CompositionHint {
+                        code: HintCode::DirectiveCompositionInfo,
+                        message: format!(
+                            "Directive \"{argument}\" should not be explicitly manually composed since it is a federation directive composed by default"
+                        ),
+                        locations: vec![location],
+                    });
+                } else {
+                    self.errors.push(MergeError {
+                        error: SingleFederationError::DirectiveCompositionError {
+                            message: format!(
+                                "Composing federation directive \"{argument}\" in subgraph \"{}\" is not supported",
+                                subgraph.name,
+                            ),
+                        },
+                        locations: vec![location],
+                    });
+                }
+                continue;
+            }
+            applications.push(ComposeDirectiveApplication {
+                subgraph,
+                location,
+                link: source.link,
+                element,
+                definition,
+            });
+        }
+    }
+
+    /// Records a directive of the spec linked by `latest`, if it's named consistently.
+    fn add_directive(
+        &mut self,
+        latest: &ComposeDirectiveApplication,
+        element: &Name,
+        applications: &[&ComposeDirectiveApplication],
+    ) {
+        let mut by_name: IndexMap<&Name, Vec<&ComposeDirectiveApplication>> = IndexMap::new();
+        for application in applications {
+            by_name
+                .entry(&application.definition.name)
+                .or_default()
+                .push(application);
+        }
+        if by_name.len() > 1 {
+            let names = by_name
+                .iter()
+                .map(|(name, applications)| {
+                    format!(
+                        "\"@{name}\" in {}",
+                        print_subgraph_names(
+                            applications
+                                .iter()
+                                .map(|application| application.subgraph.name.as_str())
+                                .collect::<IndexSet<_>>()
+                                .into_iter()
+                        )
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" and ");
+            self.errors.push(MergeError {
+                error: SingleFederationError::DirectiveCompositionError {
+                    message: format!(
+                        "Composed directive \"@{element}\" of core feature \"{}\" is not named consistently in all subgraphs: it is named {names}",
+                        latest.link.url.identity,
+                    ),
+                },
+                locations: applications
+                    .iter()
+                    .map(|application| application.location.clone())
+                    .collect(),
+            });
+            return;
+        }
+        let Some((name, _)) = by_name.first() else {
+            return;
+        };
+        let name = (*name).clone();
+        if let Some(existing) = self.directives.get(&name) {
+            if existing.link.url.identity != latest.link.url.identity
+                || existing.element != *element
+            {
+                self.errors.push(MergeError {
+                    error: SingleFederationError::DirectiveCompositionError {
+                        message: format!(
+                            "Composed directive \"@{name}\" references different directives in different subgraphs: \"@{}\" of core feature \"{}\" and \"@{element}\" of core feature \"{}\"",
+                            existing.element,
+                            existing.link.url.identity,
+                            latest.link.url.identity,
+                        ),
+                    },
+                    locations: applications
+                        .iter()
+                        .map(|application| application.location.clone())
+                        .collect(),
+                });
+            }
+            return;
+        }
+        // The definition comes from a subgraph linking the latest version of the spec, if one of
+        // them composes the directive.
+        let source = applications
+            .iter()
+            .find(|application| application.link.url.version == latest.link.url.version)
+            .unwrap_or(&applications[0]);
+        self.directives.insert(
+            name,
+            ComposedDirective {
+                link: latest.link.clone(),
+                element: element.clone(),
+                definition: source.definition.clone(),
+                definition_subgraph: source.subgraph.name.clone(),
+            },
+        );
+    }
+
+    /// Links the specs of the composed directives in the supergraph, adds their definitions, and
+    /// copies their applications from the subgraphs.
+    pub(crate) fn merge(&mut self, supergraph: &mut Schema, subgraphs: &[&ValidSubgraph]) {
+        if self.directives.is_empty() {
+            return;
+        }
+        self.add_links(supergraph);
+        for composed in self.directives.values() {
+            supergraph.directive_definitions.insert(
+                composed.definition.name.clone(),
+                composed.definition.clone(),
+            );
+            // Scalars of linked specs aren't merged with the other types, so the types used by the
+            // arguments of the directive that the supergraph lacks are copied from the subgraph the
+            // definition comes from, along with the types used by the fields of copied input objects.
+            let Some(subgraph) = subgraphs
+                .iter()
+                .find(|subgraph| subgraph.name == composed.definition_subgraph)
+            else {
+                continue;
+            };
+            let mut type_names: Vec<Name> = composed
+                .definition
+                .arguments
+                .iter()
+                .map(|argument| argument.ty.inner_named_type().clone())
+                .collect();
+            let mut visited = IndexSet::new();
+            while let Some(type_name) = type_names.pop() {
+                if !visited.insert(type_name.clone()) {
+                    continue;
+                }
+                if supergraph.types.contains_key(&type_name) {
+                    continue;
+                }
+                let Some(ty) = subgraph.schema.types.get(&type_name) else {
+                    continue;
+                };
+                let copied = match ty {
+                    ExtendedType::Scalar(scalar) => {
+                        let mut scalar = scalar.as_ref().clone();
+                        scalar.directives = Default::default();
+                        ExtendedType::Scalar(Node::new(scalar))
+                    }
+                    ExtendedType::Enum(enum_type) => {
+                        let mut enum_type = enum_type.as_ref().clone();
+                        enum_type.directives = Default::default();
+                        for value in enum_type.values.values_mut() {
+                            value.make_mut().directives = Default::default();
+                        }
+                        ExtendedType::Enum(Node::new(enum_type))
+                    }
+                    ExtendedType::InputObject(input_object) => {
+                        type_names.extend(
+                            input_object
+                                .fields
+                                .values()
+                                .map(|field| field.ty.inner_named_type().clone()),
+                        );
+                        let mut input_object = input_object.as_ref().clone();
+                        input_object.directives = Default::default();
+                        for field in input_object.fields.values_mut() {
+                            field.make_mut().directives = Default::default();
+                        }
+                        ExtendedType::InputObject(Node::new(input_object))
+                    }
+                    _ => continue,
+                };
+                supergraph.types.insert(type_name, copied);
+            }
+        }
+        for subgraph in subgraphs {
+            self.merge_applications(supergraph, subgraph);
+        }
+    }
+
+    /// Adds a `@link` to the latest version of each spec with composed directives, importing the
+    /// directives that aren't named after the spec.
+    fn add_links(&self, supergraph: &mut Schema) {
+        let mut by_identity: IndexMap<&Identity, Vec<(&Name, &ComposedDirective)>> =
+            IndexMap::new();
+        for (name, composed) in &self.directives {
+            by_identity
+                .entry(&composed.link.url.identity)
+                .or_default()
+                .push((name, composed));
+        }
+        for directives in by_identity.values() {
+            let link = &directives[0].1.link;
+            let mut arguments = vec![Node::new(Argument {
+                name: name!("url"),
+                value: Node::new(Value::String(NodeStr::new(&link.url.to_string()))),
+            })];
+            if let Some(alias) = &link.spec_alias {
+                arguments.push(Node::new(Argument {
+                    name: name!("as"),
+                    value: Node::new(Value::String(NodeStr::new(alias))),
+                }));
+            }
+            let imports = directives
+                .iter()
+                .filter(|(name, composed)| {
+                    **name != default_directive_name(&composed.link, &composed.element)
+                })
+                .map(|(name, composed)| {
+                    let element = Value::String(NodeStr::new(&format!("@{}", composed.element)));
+                    if **name == composed.element {
+                        Node::new(element)
+                    } else {
+                        Node::new(Value::Object(vec![
+                            (name!("name"), Node::new(element)),
+                            (
+                                name!("as"),
+                                Node::new(Value::String(NodeStr::new(&format!("@{name}")))),
+                            ),
+                        ]))
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !imports.is_empty() {
+                arguments.push(Node::new(Argument {
+                    name: name!("import"),
+                    value: Node::new(Value::List(imports)),
+                }));
+            }
+            supergraph
+                .schema_definition
+                .make_mut()
+                .directives
+                .push(Component::new(Directive {
+                    name: name!("link"),
+                    arguments,
+                }));
+        }
+    }
+
+    /// Copies the applications of the composed directives in `subgraph` to the matching elements
+    /// of the supergraph. The directives of subgraphs that don't compose them are copied as well,
+    /// provided they come from the same spec.
+    fn merge_applications(&mut self, supergraph: &mut Schema, subgraph: &ValidSubgraph) {
+        let Ok(Some(metadata)) = links_metadata(&subgraph.schema) else {
+            return;
+        };
+        let names = self
+            .directives
+            .iter()
+            .filter(|(name, composed)| {
+                subgraph.schema.directive_definitions.contains_key(*name)
+                    && metadata
+                        .source_link_of_directive(name)
+                        .is_some_and(|source| {
+                            source.link.url.identity == composed.link.url.identity
+                        })
+            })
+            .map(|(name, _)| name.clone())
+            .collect::<IndexSet<_>>();
+        if names.is_empty() {
+            return;
+        }
+        let mut merger = ApplicationMerger {
+            subgraph,
+            names: &names,
+            directives: &self.directives,
+            hints: &mut self.hints,
+        };
+
+        merger.merge(
+            &mut supergraph.schema_definition.make_mut().directives,
+            subgraph
+                .schema
+                .schema_definition
+                .directives
+                .iter()
+                .map(Deref::deref),
+            "schema",
+        );
+        for (type_name, subgraph_type) in &subgraph.schema.types {
+            let Some(supergraph_type) = supergraph.types.get_mut(type_name) else {
+                continue;
+            };
+            match supergraph_type {
+                ExtendedType::Scalar(scalar) => merger.merge(
+                    &mut scalar.make_mut().directives,
+                    subgraph_type.directives().iter().map(Deref::deref),
+                    type_name,
+                ),
+                ExtendedType::Union(union_) => merger.merge(
+                    &mut union_.make_mut().directives,
+                    subgraph_type.directives().iter().map(Deref::deref),
+                    type_name,
+                ),
+                ExtendedType::Enum(enum_) => {
+                    let enum_ = enum_.make_mut();
+                    merger.merge(
+                        &mut enum_.directives,
+                        subgraph_type.directives().iter().map(Deref::deref),
+                        type_name,
+                    );
+                    let ExtendedType::Enum(subgraph_enum) = subgraph_type else {
+                        continue;
+                    };
+                    for (value_name, value) in &subgraph_enum.values {
+                        if let Some(supergraph_value) = enum_.values.get_mut(value_name) {
+                            merger.merge(
+                                &mut supergraph_value.make_mut().directives,
+                                value.directives.iter(),
+                                format_args!("{type_name}.{value_name}"),
+                            );
+                        }
+                    }
+                }
+                ExtendedType::InputObject(input_object) => {
+                    let input_object = input_object.make_mut();
+                    merger.merge(
+                        &mut input_object.directives,
+                        subgraph_type.directives().iter().map(Deref::deref),
+                        type_name,
+                    );
+                    let ExtendedType::InputObject(subgraph_input_object) = subgraph_type else {
+                        continue;
+                    };
+                    for (field_name, field) in &subgraph_input_object.fields {
+                        if let Some(supergraph_field) = input_object.fields.get_mut(field_name) {
+                            merger.merge(
+                                &mut supergraph_field.make_mut().directives,
+                                field.directives.iter(),
+                                format_args!("{type_name}.{field_name}"),
+                            );
+                        }
+                    }
+                }
+                ExtendedType::Object(object) => {
+                    let object = object.make_mut();
+                    merger.merge(
+                        &mut object.directives,
+                        subgraph_type.directives().iter().map(Deref::deref),
+                        type_name,
+                    );
+                    merger.merge_fields(&mut object.fields, subgraph_type, type_name);
+                }
+                ExtendedType::Interface(intf) => {
+                    let intf = intf.make_mut();
+                    merger.merge(
+                        &mut intf.directives,
+                        subgraph_type.directives().iter().map(Deref::deref),
+                        type_name,
+                    );
+                    merger.merge_fields(&mut intf.fields, subgraph_type, type_name);
+                }
+            }
+        }
+    }
+}
+
+/// Copies the applications of composed directives from one subgraph to the supergraph.
+struct ApplicationMerger<'a> {
+    subgraph: &'a ValidSubgraph,
+    /// The names of the composed directives whose applications are copied from the subgraph.
+    names: &'a IndexSet<Name>,
+    directives: &'a IndexMap<Name, ComposedDirective>,
+    hints: &'a mut Vec<CompositionHint>,
+}
+
+impl<'a> ApplicationMerger<'a> {
+    fn merge_fields(
+        &mut self,
+        supergraph_fields: &mut IndexMap<Name, Component<apollo_compiler::ast::FieldDefinition>>,
+        subgraph_type: &ExtendedType,
+        type_name: &Name,
+    ) {
+        let subgraph_fields = match subgraph_type {
+            ExtendedType::Object(object) => &object.fields,
+            ExtendedType::Interface(intf) => &intf.fields,
+            _ => return,
+        };
+        for (field_name, field) in subgraph_fields {
+            let Some(supergraph_field) = supergraph_fields.get_mut(field_name) else {
+                continue;
+            };
+            let supergraph_field = supergraph_field.make_mut();
+            self.merge(
+                &mut supergraph_field.directives,
+                field.directives.iter(),
+                format_args!("{type_name}.{field_name}"),
+            );
+            for argument in &field.arguments {
+                let Some(supergraph_argument) = supergraph_field
+                    .arguments
+                    .iter_mut()
+                    .find(|supergraph_argument| supergraph_argument.name == argument.name)
+                else {
+                    continue;
+                };
+                self.merge(
+                    &mut supergraph_argument.make_mut().directives,
+                    argument.directives.iter(),
+                    format_args!("{type_name}.{field_name}({}:)", argument.name),
+                );
+            }
+        }
+    }
+
+    /// Adds the applications of composed directives in `subgraph_directives` to
+    /// `supergraph_directives`. Repeatable directives keep every distinct application, while
+    /// non-repeatable ones keep the first application found.
+    fn merge<'b, D>(
+        &mut self,
+        supergraph_directives: &mut Vec<D>,
+        subgraph_directives: impl IntoIterator<Item = &'b Node<Directive>>,
+        element: impl Display,
+    ) where
+        D: AsRef<Directive> + From<Directive>,
+    {
+        for application in subgraph_directives {
+            if !self.names.contains(&application.name) {
+                continue;
+            }
+            let repeatable = self
+                .directives
+                .get(&application.name)
+                .is_some_and(|composed| composed.definition.repeatable);
+            let existing = supergraph_directives
+                .iter()
+                .map(|existing| existing.as_ref())
+                .find(|existing| {
+                    existing.name == application.name
+                        && (!repeatable || *existing == application.as_ref())
+                });
+            match existing {
+                None => supergraph_directives.push(D::from(application.as_ref().clone())),
+                Some(existing) if existing != application.as_ref() => {
+                    self.hints.push(CompositionHint {
+                        code: HintCode::InconsistentNonRepeatableDirectiveArguments,
+                        message: format!(
+                            "Non-repeatable directive \"@{}\" is applied to \"{element}\" with inconsistent arguments across subgraphs. The supergraph will use \"{}\", but subgraph \"{}\" applies \"{}\".",
+                            application.name,
+                            existing,
+                            self.subgraph.name,
+                            application.as_ref(),
+                        ),
+                        locations: vec![SubgraphLocation::new(
+                            self.subgraph,
+                            application.location(),
+                        )],
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// The name of a directive of a spec in a schema that links the spec without importing the
+/// directive.
+fn default_directive_name(link: &Link, element: &Name) -> Name {
+    if *element == link.url.identity.name {
+        link.spec_name_in_schema().clone()
+    } else {
+        Name::new_unchecked(format!("{}__{element}", link.spec_name_in_schema()).into())
+    }
+}
+
+/// The name in its spec of a directive that isn't imported, i.e. the reverse of
+/// [`default_directive_name()`].
+fn element_name(link: &Link, name_in_schema: &Name) -> Name {
+    if name_in_schema == link.spec_name_in_schema() {
+        return link.url.identity.name.clone();
+    }
+    name_in_schema
+        .split_once("__")
+        .and_then(|(_, element)| Name::new(element).ok())
+        .unwrap_or_else(|| name_in_schema.clone())
+}
@@ -0,0 +1,605 @@
+//! Post-merge validation of a composed supergraph.
+//!
+//! Merging only checks that the subgraph definitions are compatible with one another. This module
+//! checks that every query accepted by the supergraph API can actually be resolved by querying the
+//! subgraphs. To do so, it walks the query graph of the API schema and, for every path in it,
+//! ensures that the federated query graph (built from the extracted subgraphs) has at least one
+//! way to follow that path.
+// PORT_NOTE: This corresponds to `validate.ts` in the JS composition package. The JS code walks
+// the federated query graph with transition-based paths; here, we instead convert the edges of the
+// API schema query graph into operation elements and reuse the operation-based paths of query
+// planning, which resolve the same transitions (including indirect key and root type edges).
+
+use std::sync::Arc;
+
+use apollo_compiler::ast::Type;
+use apollo_compiler::schema::ExtendedType;
+use apollo_compiler::{NodeStr, Schema};
+use indexmap::{IndexMap, IndexSet};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+use crate::api_schema::ApiSchemaOptions;
+use crate::error::{FederationError, SingleFederationError};
+use crate::merge::{MergeError, SubgraphLocation};
+use crate::query_graph::build_federated_query_graph;
+use crate::query_graph::build_query_graph::build_query_graph;
+use crate::query_graph::condition_resolver::{
+    ConditionResolution, ConditionResolutionCacheResult, ConditionResolver, ConditionResolverCache,
+};
+use crate::query_graph::graph_path::{
+    create_initial_options, ExcludedConditions, ExcludedDestinations, OpGraphPath,
+    OpGraphPathContext, OpPathElement, SimultaneousPaths, SimultaneousPathsWithLazyIndirectPaths,
+};
+use crate::query_graph::{QueryGraph, QueryGraphEdgeTransition, QueryGraphNodeType};
+use crate::query_plan::operation::{
+    NormalizedField, NormalizedFieldData, NormalizedInlineFragment, NormalizedInlineFragmentData,
+    SelectionId,
+};
+use crate::schema::position::SchemaRootDefinitionKind;
+use crate::schema::ValidFederationSchema;
+use crate::Supergraph;
+
+/// Checks that all the queries of the API of the given (merged) supergraph can be satisfied by
+/// its subgraphs, and returns an error for each one that cannot.
+// PORT_NOTE: In the JS codebase, this was named `validateGraphComposition`.
+pub(crate) fn validate_graph_composition(
+    supergraph: &Supergraph,
+) -> Result<Vec<MergeError>, FederationError> {
+    let api_schema = supergraph.to_api_schema(ApiSchemaOptions::default())?;
+    let api_graph = build_query_graph(NodeStr::new("supergraph"), api_schema.clone())?;
+    let federated_graph = build_federated_query_graph(
+        supergraph.schema.clone(),
+        api_schema,
+        Some(false),
+        Some(false),
+    )?;
+    ValidationTraversal::new(supergraph.schema.clone(), api_graph, federated_graph).validate()
+}
+
+/// A step of the traversal: a node of the API schema query graph, along with the ways the
+/// subgraphs can reach it.
+struct ValidationState {
+    root_kind: SchemaRootDefinitionKind,
+    /// The node reached in the API schema query graph.
+    supergraph_node: NodeIndex,
+    /// The operation elements leading from the root to `supergraph_node`. Those are used to build
+    /// an example operation when an error is found.
+    elements: Vec<OpPathElement>,
+    /// The options for resolving `elements` in the federated query graph.
+    subgraph_options: Vec<SimultaneousPathsWithLazyIndirectPaths>,
+}
+
+struct ValidationTraversal {
+    supergraph_schema: ValidFederationSchema,
+    api_graph: QueryGraph,
+    federated_graph: Arc<QueryGraph>,
+    condition_resolver: ValidationConditionResolver,
+    /// For each node of the API schema query graph, the sets of federated query graph nodes we were
+    /// in when visiting it.
+    previous_visits: IndexMap<NodeIndex, Vec<IndexSet<NodeIndex>>>,
+    errors: Vec<MergeError>,
+}
+
+impl ValidationTraversal {
+    fn new(
+        supergraph_schema: ValidFederationSchema,
+        api_graph: QueryGraph,
+        federated_graph: QueryGraph,
+    ) -> Self {
+        let federated_graph = Arc::new(federated_graph);
+        Self {
+            condition_resolver: ValidationConditionResolver::new(
+                supergraph_schema.clone(),
+                federated_graph.clone(),
+            ),
+            supergraph_schema,
+            api_graph,
+            federated_graph,
+            previous_visits: IndexMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn validate(mut self) -> Result<Vec<MergeError>, FederationError> {
+        let mut stack = Vec::new();
+        for (root_kind, api_root) in self.api_graph.root_kinds_to_nodes()? {
+            let Some(federated_root) = self
+                .federated_graph
+                .root_kinds_to_nodes()?
+                .get(root_kind)
+                .copied()
+            else {
+                continue;
+            };
+            let initial_path = OpGraphPath::new(
+                self.federated_graph.clone(),
+                federated_root,
+                Default::default(),
+            )?;
+            let subgraph_options = create_initial_options(
+                initial_path,
+                &self.federated_graph.node_weight(federated_root)?.type_,
+                Default::default(),
+                &mut self.condition_resolver,
+                Default::default(),
+                Default::default(),
+            )?;
+            stack.push(ValidationState {
+                root_kind: *root_kind,
+                supergraph_node: *api_root,
+                elements: Vec::new(),
+                subgraph_options,
+            });
+        }
+        while let Some(state) = stack.pop() {
+            self.handle_state(state, &mut stack)?;
+        }
+        Ok(self.errors)
+    }
+
+    fn handle_state(
+        &mut self,
+        state: ValidationState,
+        stack: &mut Vec<ValidationState>,
+    ) -> Result<(), FederationError> {
+        if !self.record_visit(&state)? {
+            return Ok(());
+        }
+        let edges = self
+            .api_graph
+            .out_edges(state.supergraph_node)
+            .map(|edge_ref| (edge_ref.id(), edge_ref.target()))
+            .collect::<Vec<_>>();
+        for (edge, target) in edges {
+            let Some(element) = self.operation_element_for_edge(edge)? else {
+                continue;
+            };
+            let mut advanced = false;
+            let mut subgraph_options = Vec::new();
+            for option in &state.subgraph_options {
+                let mut option = option.clone();
+                if let Some(options) = option.advance_with_operation_element(
+                    self.supergraph_schema.clone(),
+                    &element,
+                    &mut self.condition_resolver,
+                )? {
+                    advanced = true;
+                    subgraph_options.extend(options);
+                }
+            }
+            if !advanced {
+                let error = self.unsatisfiable_error(&state, &element)?;
+                self.errors.push(error);
+                continue;
+            }
+            // An empty list of options means the element can never have results (e.g. a type
+            // condition that no runtime type satisfies), so there is nothing more to check.
+            if subgraph_options.is_empty() || self.api_graph.out_edges(target).next().is_none() {
+                continue;
+            }
+            let mut elements = state.elements.clone();
+            elements.push(element);
+            stack.push(ValidationState {
+                root_kind: state.root_kind,
+                supergraph_node: target,
+                elements,
+                subgraph_options,
+            });
+        }
+        Ok(())
+    }
+
+    /// Records that we visited the state's supergraph node with the state's subgraph nodes, and
+    /// returns `false` if the node was already visited with a subset of them. In that case,
+    /// everything reachable from the state has already been validated (with fewer options), so
+    /// there is no point in exploring it again.
+    fn record_visit(&mut self, state: &ValidationState) -> Result<bool, FederationError> {
+        let subgraph_nodes = state
+            .subgraph_options
+            .iter()
+            .flat_map(|option| option.paths.0.iter())
+            .map(|path| path.tail)
+            .collect::<IndexSet<_>>();
+        let previous_visits = self
+            .previous_visits
+            .entry(state.supergraph_node)
+            .or_default();
+        if previous_visits
+            .iter()
+            .any(|previous| previous.is_subset(&subgraph_nodes))
+        {
+            return Ok(false);
+        }
+        previous_visits.push(subgraph_nodes);
+        Ok(true)
+    }
+
+    fn operation_element_for_edge(
+        &self,
+        edge: EdgeIndex,
+    ) -> Result<Option<OpPathElement>, FederationError> {
+        let schema = self.api_graph.schema()?;
+        Ok(match &self.api_graph.edge_weight(edge)?.transition {
+            QueryGraphEdgeTransition::FieldCollection {
+                field_definition_position,
+                ..
+            } => Some(
+                NormalizedField::new(NormalizedFieldData {
+                    schema: schema.clone(),
+                    field_position: field_definition_position.clone(),
+                    alias: None,
+                    arguments: Default::default(),
+                    directives: Default::default(),
+                    sibling_typename: None,
+                })
+                .into(),
+            ),
+            QueryGraphEdgeTransition::Downcast {
+                from_type_position,
+                to_type_position,
+                ..
+            } => Some(
+                NormalizedInlineFragment::new(NormalizedInlineFragmentData {
+                    schema: schema.clone(),
+                    parent_type_position: from_type_position.clone(),
+                    type_condition_position: Some(to_type_position.clone()),
+                    directives: Default::default(),
+                    selection_id: SelectionId::new(),
+                })
+                .into(),
+            ),
+            _ => None,
+        })
+    }
+
+    fn unsatisfiable_error(
+        &self,
+        state: &ValidationState,
+        element: &OpPathElement,
+    ) -> Result<MergeError, FederationError> {
+        let mut elements = state.elements.clone();
+        elements.push(element.clone());
+        let example = example_operation(
+            self.api_graph.schema()?.schema(),
+            state.root_kind,
+            &elements,
+        );
+
+        let reasons = self.unsatisfiable_reasons(state, element)?;
+        let mut message = format!(
+            "The following supergraph API query:\n{example}\ncannot be satisfied by the subgraphs because:"
+        );
+        for (subgraph, reason) in &reasons {
+            message.push_str(&format!("\n- from subgraph \"{subgraph}\": {reason}"));
+        }
+        Ok(MergeError {
+            error: SingleFederationError::SatisfiabilityError { message },
+            locations: reasons
+                .iter()
+                .map(|(subgraph, _)| subgraph)
+                .collect::<IndexSet<_>>()
+                .into_iter()
+                .map(|subgraph| SubgraphLocation {
+                    subgraph: subgraph.clone(),
+                    location: None,
+                })
+                .collect(),
+        })
+    }
+
+    /// Explains, for each subgraph the given state can be in, why the element cannot be resolved.
+    fn unsatisfiable_reasons(
+        &self,
+        state: &ValidationState,
+        element: &OpPathElement,
+    ) -> Result<Vec<(String, String)>, FederationError> {
+        let graph = &self.federated_graph;
+        let mut tails_by_subgraph: IndexMap<String, Vec<NodeIndex>> = IndexMap::new();
+        for path in state
+            .subgraph_options
+            .iter()
+            .flat_map(|option| option.paths.0.iter())
+        {
+            let source = graph.node_weight(path.tail)?.source.to_string();
+            tails_by_subgraph.entry(source).or_default().push(path.tail);
+        }
+
+        let field = match element {
+            OpPathElement::Field(field) => field,
+            OpPathElement::InlineFragment(fragment) => {
+                let type_condition = fragment
+                    .data()
+                    .type_condition_position
+                    .as_ref()
+                    .map(|position| position.type_name().to_string())
+                    .unwrap_or_default();
+                return Ok(tails_by_subgraph
+                    .into_keys()
+                    .map(|subgraph| {
+                        let reason =
+                            format!("cannot find a way to resolve \"... on {type_condition}\".");
+                        (subgraph, reason)
+                    })
+                    .collect());
+            }
+        };
+        let type_name = field.data().field_position.type_name();
+        let field_name = field.data().field_position.field_name();
+        let coordinate = format!("{type_name}.{field_name}");
+
+        let mut reasons = Vec::new();
+        for (subgraph, tails) in &tails_by_subgraph {
+            for tail in tails {
+                let QueryGraphNodeType::SchemaType(tail_type) = &graph.node_weight(*tail)?.type_
+                else {
+                    continue;
+                };
+                let schema = graph.schema_by_source(subgraph)?.schema();
+                let reason = if !has_field(schema, tail_type.type_name(), field_name) {
+                    format!("cannot find field \"{coordinate}\".")
+                } else if graph.out_edges(*tail).any(|edge_ref| {
+                    matches!(
+                        &edge_ref.weight().transition,
+                        QueryGraphEdgeTransition::FieldCollection { field_definition_position, .. }
+                            if field_definition_position.field_name() == field_name
+                    )
+                }) {
+                    format!("cannot satisfy @requires conditions on field \"{coordinate}\".")
+                } else {
+                    format!("field \"{coordinate}\" is not resolvable because marked @external.")
+                };
+                reasons.push((subgraph.clone(), reason));
+            }
+
+            for (other_subgraph, other_schema) in graph.sources() {
+                if tails_by_subgraph.contains_key(other_subgraph.as_str())
+                    || !has_field(other_schema.schema(), type_name, field_name)
+                {
+                    continue;
+                }
+                let key_edge =
+                    tails
+                        .iter()
+                        .flat_map(|tail| graph.out_edges(*tail))
+                        .find(|edge_ref| {
+                            matches!(
+                                edge_ref.weight().transition,
+                                QueryGraphEdgeTransition::KeyResolution
+                            ) && graph
+                                .node_weight(edge_ref.target())
+                                .is_ok_and(|node| node.source == *other_subgraph)
+                        });
+                let reason = match key_edge
+                    .and_then(|edge_ref| edge_ref.weight().conditions.as_ref())
+                {
+                    Some(conditions) => {
+                        let fields = conditions.to_string();
+                        let fields = fields
+                            .strip_prefix('{')
+                            .and_then(|fields| fields.strip_suffix('}'))
+                            .unwrap_or(&fields)
+                            .trim();
+                        format!(
+                            "cannot move to subgraph \"{other_subgraph}\" using @key(fields: \"{fields}\") of \"{type_name}\", the key field(s) cannot be resolved from subgraph \"{subgraph}\"."
+                        )
+                    }
+                    None => format!(
+                        "cannot move to subgraph \"{other_subgraph}\", which has field \"{coordinate}\", because type \"{type_name}\" has no @key defined in subgraph \"{other_subgraph}\"."
+                    ),
+                };
+                reasons.push((subgraph.clone(), reason));
+            }
+        }
+        reasons.dedup();
+        Ok(reasons)
+    }
+}
+
+fn has_field(schema: &Schema, type_name: &str, field_name: &str) -> bool {
+    match schema.types.get(type_name) {
+        Some(ExtendedType::Object(ty)) => ty.fields.contains_key(field_name),
+        Some(ExtendedType::Interface(ty)) => ty.fields.contains_key(field_name),
+        _ => false,
+    }
+}
+
+/// Builds the text of an operation following the given elements from the root, to be displayed in
+/// error messages.
+// PORT_NOTE: In the JS codebase, this was named `buildWitnessOperation`.
+fn example_operation(
+    schema: &Schema,
+    root_kind: SchemaRootDefinitionKind,
+    elements: &[OpPathElement],
+) -> String {
+    let mut operation = match root_kind {
+        SchemaRootDefinitionKind::Query => "{".to_owned(),
+        SchemaRootDefinitionKind::Mutation => "mutation {".to_owned(),
+        SchemaRootDefinitionKind::Subscription => "subscription {".to_owned(),
+    };
+    let last_is_leaf = match elements.last() {
+        Some(OpPathElement::Field(field)) => field.data().is_leaf().unwrap_or(true),
+        _ => false,
+    };
+    let mut depth = 0;
+    for (i, element) in elements.iter().enumerate() {
+        depth += 1;
+        operation.push('\n');
+        operation.push_str(&"  ".repeat(depth));
+        match element {
+            OpPathElement::Field(field) => {
+                let position = &field.data().field_position;
+                operation.push_str(position.field_name());
+                if let Ok(definition) = position.get(schema) {
+                    let arguments = definition
+                        .arguments
+                        .iter()
+                        .filter(|argument| {
+                            argument.ty.is_non_null() && argument.default_value.is_none()
+                        })
+                        .map(|argument| {
+                            format!("{}: {}", argument.name, example_value(schema, &argument.ty))
+                        })
+                        .collect::<Vec<_>>();
+                    if !arguments.is_empty() {
+                        operation.push_str(&format!("({})", arguments.join(", ")));
+                    }
+                }
+            }
+            OpPathElement::InlineFragment(fragment) => {
+                operation.push_str("...");
+                if let Some(type_condition) = &fragment.data().type_condition_position {
+                    operation.push_str(&format!(" on {}", type_condition.type_name()));
+                }
+            }
+        }
+        if i + 1 == elements.len() && last_is_leaf {
+            depth -= 1;
+        } else {
+            operation.push_str(" {");
+        }
+    }
+    if !last_is_leaf {
+        operation.push('\n');
+        operation.push_str(&"  ".repeat(depth + 1));
+        operation.push_str("...");
+    }
+    while depth > 0 {
+        operation.push('\n');
+        operation.push_str(&"  ".repeat(depth));
+        operation.push('}');
+        depth -= 1;
+    }
+    operation.push_str("\n}");
+    operation
+}
+
+/// Builds a value of the given type for use in example operations.
+fn example_value(schema: &Schema, ty: &Type) -> String {
+    if ty.is_list() {
+        return "[]".to_owned();
+    }
+    match schema.types.get(ty.inner_named_type()) {
+        Some(ExtendedType::Scalar(scalar)) => match scalar.name.as_str() {
+            "Int" => "0".to_owned(),
+            "Float" => "3.14".to_owned(),
+            "Boolean" => "true".to_owned(),
+            "ID" => "\"0\"".to_owned(),
+            _ => "\"A string value\"".to_owned(),
+        },
+        Some(ExtendedType::Enum(enum_type)) => enum_type
+            .values
+            .keys()
+            .next()
+            .map(|value| value.to_string())
+            .unwrap_or_default(),
+        Some(ExtendedType::InputObject(input_object)) => {
+            let fields = input_object
+                .fields
+                .values()
+                .filter(|field| field.ty.is_non_null() && field.default_value.is_none())
+                .map(|field| format!("{}: {}", field.name, example_value(schema, &field.ty)))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", fields.join(", "))
+        }
+        _ => "\"A string value\"".to_owned(),
+    }
+}
+
+/// Resolves edge conditions during validation, by checking that there is some way to collect
+/// every selection of the conditions. Satisfied conditions are all considered to have cost 1.
+// PORT_NOTE: In the JS codebase, this was named `simpleValidationConditionResolver`.
+struct ValidationConditionResolver {
+    supergraph_schema: ValidFederationSchema,
+    federated_graph: Arc<QueryGraph>,
+    resolver_cache: ConditionResolverCache,
+}
+
+impl ValidationConditionResolver {
+    fn new(supergraph_schema: ValidFederationSchema, federated_graph: Arc<QueryGraph>) -> Self {
+        Self {
+            supergraph_schema,
+            federated_graph,
+            resolver_cache: ConditionResolverCache::new(),
+        }
+    }
+
+    fn resolve_conditions(
+        &mut self,
+        edge: EdgeIndex,
+        context: &OpGraphPathContext,
+        excluded_destinations: &ExcludedDestinations,
+        excluded_conditions: &ExcludedConditions,
+    ) -> Result<ConditionResolution, FederationError> {
+        let graph = self.federated_graph.clone();
+        let Some(conditions) = &graph.edge_weight(edge)?.conditions else {
+            return Ok(ConditionResolution::no_conditions());
+        };
+        let head = graph.edge_endpoints(edge)?.0;
+        let initial_path = OpGraphPath::new(graph.clone(), head, Default::default())?;
+        let initial_options = vec![SimultaneousPathsWithLazyIndirectPaths::new(
+            SimultaneousPaths::from(initial_path),
+            context.clone(),
+            excluded_destinations.clone(),
+            excluded_conditions.add_item(conditions),
+        )];
+
+        let mut stack = conditions
+            .selections
+            .values()
+            .map(|selection| (selection, initial_options.clone()))
+            .collect::<Vec<_>>();
+        while let Some((selection, options)) = stack.pop() {
+            let element = selection.element()?;
+            let mut new_options = Vec::new();
+            for mut option in options {
+                if let Some(options) = option.advance_with_operation_element(
+                    self.supergraph_schema.clone(),
+                    &element,
+                    self,
+                )? {
+                    new_options.extend(options);
+                }
+            }
+            // If we got no options, that particular selection of the conditions cannot be
+            // satisfied, so the overall conditions cannot be either.
+            if new_options.is_empty() {
+                return Ok(ConditionResolution::unsatisfied_conditions());
+            }
+            if let Some(selection_set) = selection.selection_set()? {
+                for selection in selection_set.selections.values() {
+                    stack.push((selection, new_options.clone()));
+                }
+            }
+        }
+        // We found some path for every selection of the conditions.
+        Ok(ConditionResolution::Satisfied {
+            cost: 1,
+            path_tree: None,
+        })
+    }
+}
+
+impl ConditionResolver for ValidationConditionResolver {
+    fn resolve(
+        &mut self,
+        edge: EdgeIndex,
+        context: &OpGraphPathContext,
+        excluded_destinations: &ExcludedDestinations,
+        excluded_conditions: &ExcludedConditions,
+    ) -> Result<ConditionResolution, FederationError> {
+        let cache_result =
+            self.resolver_cache
+                .contains(edge, context, excluded_destinations, excluded_conditions);
+        if let ConditionResolutionCacheResult::Hit(cached_resolution) = cache_result {
+            return Ok(cached_resolution);
+        }
+        let resolution =
+            self.resolve_conditions(edge, context, excluded_destinations, excluded_conditions)?;
+        if cache_result.is_miss() {
+            self.resolver_cache
+                .insert(edge, resolution.clone(), excluded_destinations.clone());
+        }
+        Ok(resolution)
+    }
+}
@@ -0,0 +1,529 @@
+//! Implements contract generation: a contract is a variant of a supergraph whose API only exposes
+//! the elements selected by `@tag` include and exclude sets.
+//!
+//! The contract supergraph is the original supergraph with the filtered out elements marked
+//! `@inaccessible`, so its API schema is computed the same way as any other supergraph's.
+use apollo_compiler::ast::{Argument, Directive, Value};
+use apollo_compiler::schema::{
+    Component, DirectiveDefinition, DirectiveLocation, ExtendedType, FieldDefinition, Name,
+};
+use apollo_compiler::validation::Valid;
+use apollo_compiler::{name, Node, NodeStr, Schema};
+use indexmap::IndexSet;
+
+use crate::error::FederationError;
+use crate::link::inaccessible_spec_definition::INACCESSIBLE_DIRECTIVE_NAME_IN_SPEC;
+use crate::link::spec::Identity;
+use crate::link::LinksMetadata;
+use crate::schema::ValidFederationSchema;
+
+/// The `@tag`s selecting the elements of a contract.
+#[derive(Debug, Default, Clone)]
+pub struct ContractOptions {
+    /// When not empty, only the elements tagged with one of those tags are kept. Object and
+    /// interface types are also kept if one of their fields is tagged, and the fields of a tagged
+    /// type are kept even if they aren't tagged themselves.
+    pub include_tags: IndexSet<String>,
+    /// The elements tagged with one of those tags are removed, even if they are also tagged with
+    /// an included tag.
+    pub exclude_tags: IndexSet<String>,
+}
+
+/// An element of a supergraph that can be marked `@inaccessible`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Element {
+    Type(Name),
+    Field(Name, Name),
+    Argument(Name, Name, Name),
+    InputField(Name, Name),
+    EnumValue(Name, Name),
+}
+
+/// Returns the contract variant of the given supergraph schema.
+///
+/// Elements are marked `@inaccessible` if they are filtered out by the tags of `options`, if they
+/// can't be part of the API without a filtered out element (e.g. a field whose type is filtered
+/// out), or if they are no longer reachable from the root types.
+pub(crate) fn to_contract_schema(
+    supergraph_schema: &ValidFederationSchema,
+    options: &ContractOptions,
+) -> Result<Valid<Schema>, FederationError> {
+    let Some(metadata) = supergraph_schema.metadata() else {
+        return Err(FederationError::internal(
+            "Cannot compute a contract for a schema without @link",
+        ));
+    };
+    let schema = supergraph_schema.schema();
+    let mut filter = ContractFilter {
+        schema,
+        metadata,
+        tag_name: metadata
+            .for_identity(&Identity::tag_identity())
+            .map(|link| link.directive_name_in_schema(&name!("tag"))),
+        options,
+        hidden: IndexSet::new(),
+    };
+    filter.hide_tagged_elements();
+    filter.hide_dependent_elements();
+    filter.hide_unreachable_types();
+
+    let mut contract = schema.clone().into_inner();
+    let inaccessible_name = match metadata.for_identity(&Identity::inaccessible_identity()) {
+        Some(link) => link.directive_name_in_schema(&INACCESSIBLE_DIRECTIVE_NAME_IN_SPEC),
+        None => add_inaccessible_spec(&mut contract),
+    };
+    for element in &filter.hidden {
+        mark_inaccessible(&mut contract, element, &inaccessible_name);
+    }
+    Ok(contract.validate()?)
+}
+
+struct ContractFilter<'a> {
+    schema: &'a Schema,
+    metadata: &'a LinksMetadata,
+    tag_name: Option<Name>,
+    options: &'a ContractOptions,
+    hidden: IndexSet<Element>,
+}
+
+impl<'a> ContractFilter<'a> {
+    fn tags(&self, directives: impl IntoIterator<Item = &'a Directive>) -> Vec<&'a str> {
+        let Some(tag_name) = &self.tag_name else {
+            return Vec::new();
+        };
+        directives
+            .into_iter()
+            .filter(|directive| directive.name == *tag_name)
+            .filter_map(|directive| directive.argument_by_name("name")?.as_str())
+            .collect()
+    }
+
+    fn is_excluded(&self, tags: &[&str]) -> bool {
+        tags.iter()
+            .any(|tag| self.options.exclude_tags.contains(*tag))
+    }
+
+    fn is_included(&self, tags: &[&str]) -> bool {
+        tags.iter()
+            .any(|tag| self.options.include_tags.contains(*tag))
+    }
+
+    fn is_root_type(&self, type_name: &Name) -> bool {
+        let definition = &self.schema.schema_definition;
+        [
+            &definition.query,
+            &definition.mutation,
+            &definition.subscription,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|root| root.name == *type_name)
+    }
+
+    /// Whether the type is part of the supergraph API, as opposed to built-in types and types of
+    /// linked specs (e.g. `join__Graph`).
+    fn is_api_type(&self, type_name: &Name, type_: &ExtendedType) -> bool {
+        !type_.is_built_in() && self.metadata.source_link_of_type(type_name).is_none()
+    }
+
+    fn api_types(&self) -> impl Iterator<Item = (&'a Name, &'a ExtendedType)> + '_ {
+        self.schema
+            .types
+            .iter()
+            .filter(|(type_name, type_)| self.is_api_type(type_name, type_))
+    }
+
+    fn is_hidden_type(&self, type_name: &Name) -> bool {
+        self.hidden.contains(&Element::Type(type_name.clone()))
+    }
+
+    fn hide(&mut self, element: Element) -> bool {
+        self.hidden.insert(element)
+    }
+
+    fn hide_tagged_elements(&mut self) {
+        let filter_by_inclusion = !self.options.include_tags.is_empty();
+        for (type_name, type_) in self.api_types().collect::<Vec<_>>() {
+            let type_tags = self.tags(type_.directives().iter().map(|d| d.as_ref()));
+            let type_included = self.is_included(&type_tags);
+            let mut type_hidden = self.is_excluded(&type_tags);
+            match type_ {
+                ExtendedType::Object(_) | ExtendedType::Interface(_) => {
+                    let fields = match type_ {
+                        ExtendedType::Object(object) => &object.fields,
+                        ExtendedType::Interface(intf) => &intf.fields,
+                        _ => unreachable!(),
+                    };
+                    let mut has_included_field = false;
+                    for (field_name, field) in fields {
+                        let field_tags = self.tags(field.directives.iter().map(|d| d.as_ref()));
+                        let field_included = self.is_included(&field_tags);
+                        has_included_field |= field_included;
+                        if self.is_excluded(&field_tags)
+                            || (filter_by_inclusion && !field_included && !type_included)
+                        {
+                            self.hide(Element::Field(type_name.clone(), field_name.clone()));
+                        }
+                        for argument in &field.arguments {
+                            let argument_tags =
+                                self.tags(argument.directives.iter().map(|d| d.as_ref()));
+                            if self.is_excluded(&argument_tags) {
+                                self.hide(Element::Argument(
+                                    type_name.clone(),
+                                    field_name.clone(),
+                                    argument.name.clone(),
+                                ));
+                            }
+                        }
+                    }
+                    type_hidden |= filter_by_inclusion
+                        && !type_included
+                        && !has_included_field
+                        && !self.is_root_type(type_name);
+                }
+                ExtendedType::Union(_) => {
+                    type_hidden |= filter_by_inclusion && !type_included;
+                }
+                ExtendedType::Enum(enum_) => {
+                    for (value_name, value) in &enum_.values {
+                        let value_tags = self.tags(value.directives.iter().map(|d| d.as_ref()));
+                        if self.is_excluded(&value_tags) {
+                            self.hide(Element::EnumValue(type_name.clone(), value_name.clone()));
+                        }
+                    }
+                }
+                ExtendedType::InputObject(input_object) => {
+                    for (field_name, field) in &input_object.fields {
+                        let field_tags = self.tags(field.directives.iter().map(|d| d.as_ref()));
+                        if self.is_excluded(&field_tags) {
+                            self.hide(Element::InputField(type_name.clone(), field_name.clone()));
+                        }
+                    }
+                }
+                ExtendedType::Scalar(_) => {}
+            }
+            if type_hidden {
+                self.hide(Element::Type(type_name.clone()));
+            }
+        }
+    }
+
+    /// Hides the elements that can't be in the API once the tagged elements are hidden, until
+    /// there are no more of them.
+    fn hide_dependent_elements(&mut self) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (type_name, type_) in self.api_types().collect::<Vec<_>>() {
+                if self.is_hidden_type(type_name) {
+                    continue;
+                }
+                match type_ {
+                    ExtendedType::Object(_) | ExtendedType::Interface(_) => {
+                        let fields = match type_ {
+                            ExtendedType::Object(object) => &object.fields,
+                            ExtendedType::Interface(intf) => &intf.fields,
+                            _ => unreachable!(),
+                        };
+                        for (field_name, field) in fields {
+                            changed |= self.hide_dependent_field(type_name, field_name, field);
+                        }
+                        if !self.is_root_type(type_name)
+                            && fields.keys().all(|field_name| {
+                                self.hidden.contains(&Element::Field(
+                                    type_name.clone(),
+                                    field_name.clone(),
+                                ))
+                            })
+                        {
+                            changed |= self.hide(Element::Type(type_name.clone()));
+                        }
+                    }
+                    ExtendedType::Union(union_) => {
+                        if union_
+                            .members
+                            .iter()
+                            .all(|member| self.is_hidden_type(&member.name))
+                        {
+                            changed |= self.hide(Element::Type(type_name.clone()));
+                        }
+                    }
+                    ExtendedType::Enum(enum_) => {
+                        if enum_.values.keys().all(|value_name| {
+                            self.hidden.contains(&Element::EnumValue(
+                                type_name.clone(),
+                                value_name.clone(),
+                            ))
+                        }) {
+                            changed |= self.hide(Element::Type(type_name.clone()));
+                        }
+                    }
+                    ExtendedType::InputObject(input_object) => {
+                        let mut type_hidden = true;
+                        for (field_name, field) in &input_object.fields {
+                            let element =
+                                Element::InputField(type_name.clone(), field_name.clone());
+                            if self.hidden.contains(&element) {
+                                continue;
+                            }
+                            if !self.is_hidden_type(field.ty.inner_named_type()) {
+                                type_hidden = false;
+                            } else if field.is_required() {
+                                // A required input field can't be removed, so its type can't be
+                                // either.
+                                break;
+                            } else {
+                                changed |= self.hide(element);
+                            }
+                        }
+                        if type_hidden {
+                            changed |= self.hide(Element::Type(type_name.clone()));
+                        }
+                    }
+                    ExtendedType::Scalar(_) => {}
+                }
+            }
+        }
+    }
+
+    /// Hides the field if its type is hidden or one of its required arguments can't be provided
+    /// in the API, and hides the optional arguments whose type is hidden. Returns whether anything
+    /// was hidden.
+    fn hide_dependent_field(
+        &mut self,
+        type_name: &Name,
+        field_name: &Name,
+        field: &FieldDefinition,
+    ) -> bool {
+        let field_element = Element::Field(type_name.clone(), field_name.clone());
+        if self.hidden.contains(&field_element) {
+            return false;
+        }
+        if self.is_hidden_type(field.ty.inner_named_type()) {
+            return self.hide(field_element);
+        }
+        let mut changed = false;
+        for argument in &field.arguments {
+            let argument_element =
+                Element::Argument(type_name.clone(), field_name.clone(), argument.name.clone());
+            if argument.is_required()
+                && (self.hidden.contains(&argument_element)
+                    || self.is_hidden_type(argument.ty.inner_named_type()))
+            {
+                return self.hide(field_element);
+            }
+            if self.is_hidden_type(argument.ty.inner_named_type()) {
+                changed |= self.hide(argument_element);
+            }
+        }
+        changed
+    }
+
+    /// Hides the types that are not reachable from the root types through elements that are
+    /// still visible.
+    fn hide_unreachable_types(&mut self) {
+        let definition = &self.schema.schema_definition;
+        let mut reachable: IndexSet<&Name> = [
+            &definition.query,
+            &definition.mutation,
+            &definition.subscription,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|root| &root.name)
+        .filter(|root| !self.is_hidden_type(root))
+        .collect();
+        let mut index = 0;
+        while let Some(type_name) = reachable.get_index(index).copied() {
+            index += 1;
+            let Some(type_) = self.schema.types.get(type_name) else {
+                continue;
+            };
+            let mut referenced: Vec<&Name> = Vec::new();
+            match type_ {
+                ExtendedType::Object(_) | ExtendedType::Interface(_) => {
+                    let fields = match type_ {
+                        ExtendedType::Object(object) => &object.fields,
+                        ExtendedType::Interface(intf) => &intf.fields,
+                        _ => unreachable!(),
+                    };
+                    for (field_name, field) in fields {
+                        if self
+                            .hidden
+                            .contains(&Element::Field(type_name.clone(), field_name.clone()))
+                        {
+                            continue;
+                        }
+                        referenced.push(field.ty.inner_named_type());
+                        for argument in &field.arguments {
+                            if !self.hidden.contains(&Element::Argument(
+                                type_name.clone(),
+                                field_name.clone(),
+                                argument.name.clone(),
+                            )) {
+                                referenced.push(argument.ty.inner_named_type());
+                            }
+                        }
+                    }
+                    if let ExtendedType::Interface(_) = type_ {
+                        for (implementer_name, implementer) in &self.schema.types {
+                            if let ExtendedType::Object(object) = implementer {
+                                if object.implements_interfaces.contains(type_name) {
+                                    referenced.push(implementer_name);
+                                }
+                            }
+                        }
+                    }
+                }
+                ExtendedType::Union(union_) => {
+                    referenced.extend(union_.members.iter().map(|member| &member.name));
+                }
+                ExtendedType::InputObject(input_object) => {
+                    for (field_name, field) in &input_object.fields {
+                        if !self
+                            .hidden
+                            .contains(&Element::InputField(type_name.clone(), field_name.clone()))
+                        {
+                            referenced.push(field.ty.inner_named_type());
+                        }
+                    }
+                }
+                ExtendedType::Scalar(_) | ExtendedType::Enum(_) => {}
+            }
+            reachable.extend(
+                referenced
+                    .into_iter()
+                    .filter(|type_name| !self.is_hidden_type(type_name)),
+            );
+        }
+        for (type_name, _) in self.api_types().collect::<Vec<_>>() {
+            if !reachable.contains(type_name) {
+                self.hide(Element::Type(type_name.clone()));
+            }
+        }
+    }
+}
+
+/// Links the inaccessible spec and defines `@inaccessible` in a supergraph that doesn't use it
+/// yet, returning the name of the directive.
+fn add_inaccessible_spec(schema: &mut Schema) -> Name {
+    schema
+        .schema_definition
+        .make_mut()
+        .directives
+        .push(Component::new(Directive {
+            name: name!("link"),
+            arguments: vec![
+                Node::new(Argument {
+                    name: name!("url"),
+                    value: Node::new(Value::String(NodeStr::new(
+                        "https://specs.apollo.dev/inaccessible/v0.2",
+                    ))),
+                }),
+                Node::new(Argument {
+                    name: name!("for"),
+                    value: Node::new(Value::Enum(name!("SECURITY"))),
+                }),
+            ],
+        }));
+    schema.directive_definitions.insert(
+        INACCESSIBLE_DIRECTIVE_NAME_IN_SPEC,
+        Node::new(DirectiveDefinition {
+            description: None,
+            name: INACCESSIBLE_DIRECTIVE_NAME_IN_SPEC,
+            arguments: Vec::new(),
+            repeatable: false,
+            locations: vec![
+                DirectiveLocation::FieldDefinition,
+                DirectiveLocation::Object,
+                DirectiveLocation::Interface,
+                DirectiveLocation::Union,
+                DirectiveLocation::ArgumentDefinition,
+                DirectiveLocation::Scalar,
+                DirectiveLocation::Enum,
+                DirectiveLocation::EnumValue,
+                DirectiveLocation::InputObject,
+                DirectiveLocation::InputFieldDefinition,
+            ],
+        }),
+    );
+    INACCESSIBLE_DIRECTIVE_NAME_IN_SPEC
+}
+
+fn mark_inaccessible(schema: &mut Schema, element: &Element, inaccessible_name: &Name) {
+    let directive = Directive {
+        name: inaccessible_name.clone(),
+        arguments: Vec::new(),
+    };
+    let type_name = match element {
+        Element::Type(type_name)
+        | Element::Field(type_name, _)
+        | Element::Argument(type_name, _, _)
+        | Element::InputField(type_name, _)
+        | Element::EnumValue(type_name, _) => type_name,
+    };
+    let Some(type_) = schema.types.get_mut(type_name) else {
+        return;
+    };
+    if let Element::Type(_) = element {
+        let directives = match type_ {
+            ExtendedType::Scalar(type_) => &mut type_.make_mut().directives,
+            ExtendedType::Object(type_) => &mut type_.make_mut().directives,
+            ExtendedType::Interface(type_) => &mut type_.make_mut().directives,
+            ExtendedType::Union(type_) => &mut type_.make_mut().directives,
+            ExtendedType::Enum(type_) => &mut type_.make_mut().directives,
+            ExtendedType::InputObject(type_) => &mut type_.make_mut().directives,
+        };
+        if !directives.has(inaccessible_name) {
+            directives.push(Component::new(directive));
+        }
+        return;
+    }
+    let directives = match element {
+        Element::Field(_, field_name) | Element::Argument(_, field_name, _) => {
+            let Some(field) = (match type_ {
+                ExtendedType::Object(type_) => type_.make_mut().fields.get_mut(field_name),
+                ExtendedType::Interface(type_) => type_.make_mut().fields.get_mut(field_name),
+                _ => None,
+            }) else {
+                return;
+            };
+            let field = field.make_mut();
+            match element {
+                Element::Argument(_, _, argument_name) => {
+                    let Some(argument) = field
+                        .arguments
+                        .iter_mut()
+                        .find(|argument| argument.name == *argument_name)
+                    else {
+                        return;
+                    };
+                    &mut argument.make_mut().directives
+                }
+                _ => &mut field.directives,
+            }
+        }
+        Element::InputField(_, field_name) => {
+            let ExtendedType::InputObject(type_) = type_ else {
+                return;
+            };
+            let Some(field) = type_.make_mut().fields.get_mut(field_name) else {
+                return;
+            };
+            &mut field.make_mut().directives
+        }
+        Element::EnumValue(_, value_name) => {
+            let ExtendedType::Enum(type_) = type_ else {
+                return;
+            };
+            let Some(value) = type_.make_mut().values.get_mut(value_name) else {
+                return;
+            };
+            &mut value.make_mut().directives
+        }
+        Element::Type(_) => return,
+    };
+    if !directives.has(inaccessible_name) {
+        directives.push(Node::new(directive));
+    }
+}
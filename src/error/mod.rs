@@ -1,4 +1,5 @@
 use crate::subgraph::spec::FederationSpecError;
+use apollo_compiler::execution::{GraphQLError, GraphQLLocation};
 use apollo_compiler::validation::DiagnosticList;
 use apollo_compiler::{ast::InvalidNameError, validation::WithErrors};
 use lazy_static::lazy_static;
@@ -183,6 +184,26 @@ pub enum SingleFederationError {
     InterfaceKeyMissingImplementationType { message: String },
     #[error("@defer is not supported on subscriptions")]
     DeferredSubscriptionUnsupported,
+    #[error("[{subgraph}] {error}")]
+    InvalidSubgraph {
+        subgraph: String,
+        error: Box<SingleFederationError>,
+        /// The locations of the offending elements in the subgraph's sources, when known.
+        locations: Vec<GraphQLLocation>,
+    },
+    #[error(
+        "The query planner generated an invalid operation for subgraph \"{subgraph}\": {}\nOperation:\n{operation}",
+        .errors.iter().map(|error| error.message.as_str()).collect::<Vec<_>>().join("\n")
+    )]
+    InvalidSubgraphOperation {
+        subgraph: String,
+        /// The operation, as sent to the subgraph.
+        operation: String,
+        /// The validation errors of the operation, whose locations point into `operation`.
+        errors: Vec<GraphQLError>,
+    },
+    #[error("Too many options generated for {selection}, reached the limit of {limit}.")]
+    PathsLimitExceeded { selection: String, limit: u32 },
 }
 
 impl SingleFederationError {
@@ -354,6 +375,11 @@ impl SingleFederationError {
                 ErrorCode::InterfaceKeyMissingImplementationType
             }
             SingleFederationError::DeferredSubscriptionUnsupported => ErrorCode::Internal,
+            SingleFederationError::InvalidSubgraph { error, .. } => error.code(),
+            SingleFederationError::InvalidSubgraphOperation { .. } => ErrorCode::Internal,
+            SingleFederationError::PathsLimitExceeded { .. } => {
+                ErrorCode::QueryPlanComplexityExceeded
+            }
         }
     }
 }
@@ -1128,6 +1154,18 @@ lazy_static! {
         }),
     );
 
+    // PORT_NOTE: The JS codebase throws a plain `Error` when reaching `debug.pathsLimit`, which
+    // surfaces as an internal error even though it's due to the configuration of the query
+    // planner, so this code has no JS equivalent.
+    static ref QUERY_PLAN_COMPLEXITY_EXCEEDED: ErrorCodeDefinition = ErrorCodeDefinition::new(
+        "QUERY_PLAN_COMPLEXITY_EXCEEDED".to_owned(),
+        "Indicates that planning an operation exceeded a limit of the query planner configuration, like `debug.paths_limit`.".to_owned(),
+        Some(ErrorCodeMetadata {
+            added_in: "2.9.0",
+            replaces: &[],
+        }),
+    );
+
     static ref INTERNAL: ErrorCodeDefinition = ErrorCodeDefinition::new(
         "INTERNAL".to_owned(),
         "An internal federation error occured.".to_owned(),
@@ -1214,6 +1252,7 @@ pub enum ErrorCode {
     InterfaceObjectUsageError,
     InterfaceKeyNotOnImplementation,
     InterfaceKeyMissingImplementationType,
+    QueryPlanComplexityExceeded,
 }
 
 impl ErrorCode {
@@ -1311,6 +1350,7 @@ impl ErrorCode {
             ErrorCode::InterfaceKeyMissingImplementationType => {
                 &INTERFACE_KEY_MISSING_IMPLEMENTATION_TYPE
             }
+            ErrorCode::QueryPlanComplexityExceeded => &QUERY_PLAN_COMPLEXITY_EXCEEDED,
         }
     }
 }
@@ -1,7 +1,11 @@
 #![allow(dead_code)] // TODO: This is fine while we're iterating, but should be removed later.
 
+mod access_control;
 mod api_schema;
 mod compat;
+mod compose_directive;
+mod composition_validation;
+mod contract;
 pub mod error;
 mod indented_display;
 pub mod link;
@@ -11,7 +15,9 @@ pub mod query_plan;
 pub mod schema;
 pub mod subgraph;
 
+pub use crate::access_control::AccessControlRequirements;
 pub use crate::api_schema::ApiSchemaOptions;
+pub use crate::contract::ContractOptions;
 use crate::error::FederationError;
 use crate::error::SingleFederationError;
 use crate::link::join_spec_definition::JoinSpecDefinition;
@@ -19,12 +25,15 @@ use crate::link::link_spec_definition::LinkSpecDefinition;
 use crate::link::spec::Identity;
 use crate::link::spec_definition::SpecDefinitions;
 use crate::merge::merge_subgraphs;
+use crate::merge::MergeError;
 use crate::merge::MergeFailure;
+use crate::merge::MergeSuccess;
 pub use crate::query_graph::extract_subgraphs_from_supergraph::ValidFederationSubgraph;
 pub use crate::query_graph::extract_subgraphs_from_supergraph::ValidFederationSubgraphs;
 use crate::schema::ValidFederationSchema;
 use crate::subgraph::ValidSubgraph;
 use apollo_compiler::validation::Valid;
+use apollo_compiler::ExecutableDocument;
 use apollo_compiler::NodeStr;
 use apollo_compiler::Schema;
 use link::join_spec_definition::JOIN_VERSIONS;
@@ -38,15 +47,6 @@ pub(crate) fn validate_supergraph_for_query_planning(
     validate_supergraph(supergraph_schema, &JOIN_VERSIONS)
 }
 
-pub(crate) fn validate_supergraph_for_non_query_planning(
-    supergraph_schema: &FederationSchema,
-) -> Result<SupergraphSpecs, FederationError> {
-    validate_supergraph(
-        supergraph_schema,
-        &link::join_spec_definition::NON_QUERY_PLANNING_JOIN_VERSIONS,
-    )
-}
-
 /// Checks that required supergraph directives are in the schema, and returns which ones were used.
 pub(crate) fn validate_supergraph(
     supergraph_schema: &FederationSchema,
@@ -81,6 +81,13 @@ pub struct Supergraph {
     pub schema: ValidFederationSchema,
 }
 
+/// A variant of a supergraph whose API only exposes the elements selected by `@tag`s.
+pub struct Contract {
+    /// The supergraph with the filtered out elements marked `@inaccessible`.
+    pub supergraph: Supergraph,
+    pub api_schema: ValidFederationSchema,
+}
+
 impl Supergraph {
     pub fn new(schema_str: &str) -> Result<Self, FederationError> {
         let schema = Schema::parse_and_validate(schema_str, "schema.graphql")?;
@@ -91,7 +98,7 @@ impl Supergraph {
         let schema = schema.into_inner();
         let schema = FederationSchema::new(schema)?;
 
-        let _ = validate_supergraph_for_non_query_planning(&schema)?;
+        let _ = validate_supergraph_for_query_planning(&schema)?;
 
         Ok(Self {
             // We know it's valid because the input was.
@@ -99,12 +106,33 @@ impl Supergraph {
         })
     }
 
+    /// Merges the given subgraphs into a supergraph, and validates that every query of the
+    /// resulting supergraph API can be satisfied by the subgraphs.
     pub fn compose(subgraphs: Vec<&ValidSubgraph>) -> Result<Self, MergeFailure> {
-        let schema = merge_subgraphs(subgraphs)?.schema;
-        Ok(Self {
-            schema: ValidFederationSchema::new(schema)
-                .map_err(|err| todo!("missing error handling: {err}"))?,
-        })
+        let MergeSuccess {
+            schema,
+            composition_hints,
+        } = merge_subgraphs(subgraphs)?;
+        let failure = |schema: Option<Schema>, errors: Vec<MergeError>| MergeFailure {
+            schema,
+            errors,
+            composition_hints: composition_hints.clone(),
+        };
+        let supergraph = Self {
+            schema: ValidFederationSchema::new(schema.clone()).map_err(|err| {
+                failure(
+                    Some(schema.into_inner()),
+                    MergeError::from_federation_error(err),
+                )
+            })?,
+        };
+        let errors = composition_validation::validate_graph_composition(&supergraph)
+            .map_err(|err| failure(None, MergeError::from_federation_error(err)))?;
+        if !errors.is_empty() {
+            let schema = supergraph.schema.schema().clone().into_inner();
+            return Err(failure(Some(schema), errors));
+        }
+        Ok(supergraph)
     }
 
     /// Generates an API Schema from this supergraph schema. The API Schema represents the combined
@@ -116,6 +144,33 @@ impl Supergraph {
         api_schema::to_api_schema(self.schema.clone(), options)
     }
 
+    /// Generates the contract of this supergraph for the given `@tag` include and exclude sets,
+    /// along with its API schema.
+    pub fn to_contract(
+        &self,
+        contract_options: &ContractOptions,
+        api_schema_options: ApiSchemaOptions,
+    ) -> Result<Contract, FederationError> {
+        let schema = contract::to_contract_schema(&self.schema, contract_options)?;
+        let supergraph = Self::from_schema(schema)?;
+        let api_schema = supergraph.to_api_schema(api_schema_options)?;
+        Ok(Contract {
+            supergraph,
+            api_schema,
+        })
+    }
+
+    /// Returns whether the given operation of `document` selects elements requiring
+    /// authentication, and which scopes and policies those elements mention, so that requests can
+    /// be rejected or filtered before planning.
+    pub fn access_control_requirements(
+        &self,
+        document: &Valid<ExecutableDocument>,
+        operation_name: Option<&str>,
+    ) -> Result<AccessControlRequirements, FederationError> {
+        access_control::access_control_requirements(&self.schema, document, operation_name)
+    }
+
     pub fn extract_subgraphs(&self) -> Result<ValidFederationSubgraphs, FederationError> {
         crate::query_graph::extract_subgraphs_from_supergraph::extract_subgraphs_from_supergraph(
             &self.schema,
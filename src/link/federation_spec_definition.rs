@@ -1,6 +1,7 @@
 use crate::error::{FederationError, SingleFederationError};
 use crate::link::argument::{
-    directive_optional_boolean_argument, directive_required_fieldset_argument,
+    directive_optional_boolean_argument, directive_optional_string_argument,
+    directive_required_fieldset_argument, directive_required_string_argument,
 };
 use crate::link::spec::{Identity, Url, Version};
 use crate::link::spec_definition::{SpecDefinition, SpecDefinitions};
@@ -26,6 +27,7 @@ pub(crate) const FEDERATION_FIELDS_ARGUMENT_NAME: Name = name!("fields");
 pub(crate) const FEDERATION_RESOLVABLE_ARGUMENT_NAME: Name = name!("resolvable");
 pub(crate) const FEDERATION_REASON_ARGUMENT_NAME: Name = name!("reason");
 pub(crate) const FEDERATION_FROM_ARGUMENT_NAME: Name = name!("from");
+pub(crate) const FEDERATION_OVERRIDE_LABEL_ARGUMENT_NAME: Name = name!("label");
 
 pub(crate) struct KeyDirectiveArguments {
     pub(crate) fields: NodeStr,
@@ -40,6 +42,11 @@ pub(crate) struct ProvidesDirectiveArguments {
     pub(crate) fields: NodeStr,
 }
 
+pub(crate) struct OverrideDirectiveArguments {
+    pub(crate) from: NodeStr,
+    pub(crate) label: Option<NodeStr>,
+}
+
 #[derive(Debug)]
 pub(crate) struct FederationSpecDefinition {
     url: Url,
@@ -337,22 +344,65 @@ impl FederationSpecDefinition {
         })
     }
 
+    pub(crate) fn override_directive_definition<'schema>(
+        &self,
+        schema: &'schema FederationSchema,
+    ) -> Result<&'schema Node<DirectiveDefinition>, FederationError> {
+        self.directive_definition(schema, &FEDERATION_OVERRIDE_DIRECTIVE_NAME_IN_SPEC)?
+            .ok_or_else(|| {
+                SingleFederationError::Internal {
+                    message: format!(
+                        "Unexpectedly could not find federation spec's \"@{}\" directive definition",
+                        FEDERATION_OVERRIDE_DIRECTIVE_NAME_IN_SPEC
+                    ),
+                }.into()
+            })
+    }
+
+    pub(crate) fn override_directive_arguments(
+        &self,
+        application: &Node<Directive>,
+    ) -> Result<OverrideDirectiveArguments, FederationError> {
+        Ok(OverrideDirectiveArguments {
+            from: directive_required_string_argument(application, &FEDERATION_FROM_ARGUMENT_NAME)?,
+            label: directive_optional_string_argument(
+                application,
+                &FEDERATION_OVERRIDE_LABEL_ARGUMENT_NAME,
+            )?,
+        })
+    }
+
     pub(crate) fn override_directive(
         &self,
         schema: &FederationSchema,
         from: NodeStr,
+        label: Option<NodeStr>,
     ) -> Result<Directive, FederationError> {
         let name_in_schema = self
             .directive_name_in_schema(schema, &FEDERATION_OVERRIDE_DIRECTIVE_NAME_IN_SPEC)?
             .ok_or_else(|| SingleFederationError::Internal {
                 message: "Unexpectedly could not find federation spec in schema".to_owned(),
             })?;
+        let mut arguments = vec![Node::new(Argument {
+            name: FEDERATION_FROM_ARGUMENT_NAME,
+            value: Node::new(Value::String(from)),
+        })];
+        if let Some(label) = label {
+            if *self.version() < (Version { major: 2, minor: 7 }) {
+                return Err(SingleFederationError::Internal {
+                    message: "Must be using federation >= v2.7 to use progressive override"
+                        .to_owned(),
+                }
+                .into());
+            }
+            arguments.push(Node::new(Argument {
+                name: FEDERATION_OVERRIDE_LABEL_ARGUMENT_NAME,
+                value: Node::new(Value::String(label)),
+            }));
+        }
         Ok(Directive {
             name: name_in_schema,
-            arguments: vec![Node::new(Argument {
-                name: FEDERATION_FROM_ARGUMENT_NAME,
-                value: Node::new(Value::String(from)),
-            })],
+            arguments,
         })
     }
 }
@@ -394,6 +444,22 @@ lazy_static! {
             major: 2,
             minor: 5,
         }));
+        definitions.add(FederationSpecDefinition::new(Version {
+            major: 2,
+            minor: 6,
+        }));
+        definitions.add(FederationSpecDefinition::new(Version {
+            major: 2,
+            minor: 7,
+        }));
+        definitions.add(FederationSpecDefinition::new(Version {
+            major: 2,
+            minor: 8,
+        }));
+        definitions.add(FederationSpecDefinition::new(Version {
+            major: 2,
+            minor: 9,
+        }));
         definitions
     };
 }
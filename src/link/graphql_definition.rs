@@ -18,6 +18,10 @@ impl DeferDirectiveArguments {
     pub(crate) fn label(&self) -> Option<&NodeStr> {
         self.label.as_ref()
     }
+
+    pub(crate) fn if_(&self) -> Option<&BooleanOrVariable> {
+        self.if_.as_ref()
+    }
 }
 
 pub(crate) fn defer_directive_arguments(
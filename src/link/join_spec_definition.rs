@@ -18,6 +18,7 @@ pub(crate) const JOIN_FIELD_DIRECTIVE_NAME_IN_SPEC: Name = name!("field");
 pub(crate) const JOIN_IMPLEMENTS_DIRECTIVE_NAME_IN_SPEC: Name = name!("implements");
 pub(crate) const JOIN_UNIONMEMBER_DIRECTIVE_NAME_IN_SPEC: Name = name!("unionMember");
 pub(crate) const JOIN_ENUMVALUE_DIRECTIVE_NAME_IN_SPEC: Name = name!("enumValue");
+pub(crate) const JOIN_OWNER_DIRECTIVE_NAME_IN_SPEC: Name = name!("owner");
 
 pub(crate) const JOIN_NAME_ARGUMENT_NAME: Name = name!("name");
 pub(crate) const JOIN_URL_ARGUMENT_NAME: Name = name!("url");
@@ -32,6 +33,7 @@ pub(crate) const JOIN_TYPE_ARGUMENT_NAME: Name = name!("type");
 pub(crate) const JOIN_EXTERNAL_ARGUMENT_NAME: Name = name!("external");
 pub(crate) const JOIN_OVERRIDE_ARGUMENT_NAME: Name = name!("override");
 pub(crate) const JOIN_USEROVERRIDDEN_ARGUMENT_NAME: Name = name!("usedOverridden");
+pub(crate) const JOIN_OVERRIDE_LABEL_ARGUMENT_NAME: Name = name!("overrideLabel");
 pub(crate) const JOIN_INTERFACE_ARGUMENT_NAME: Name = name!("interface");
 pub(crate) const JOIN_MEMBER_ARGUMENT_NAME: Name = name!("member");
 
@@ -56,6 +58,11 @@ pub(crate) struct FieldDirectiveArguments {
     pub(crate) external: Option<bool>,
     pub(crate) override_: Option<NodeStr>,
     pub(crate) user_overridden: Option<bool>,
+    pub(crate) override_label: Option<NodeStr>,
+}
+
+pub(crate) struct OwnerDirectiveArguments {
+    pub(crate) graph: Name,
 }
 
 pub(crate) struct ImplementsDirectiveArguments {
@@ -212,6 +219,10 @@ impl JoinSpecDefinition {
                 application,
                 &JOIN_USEROVERRIDDEN_ARGUMENT_NAME,
             )?,
+            override_label: directive_optional_string_argument(
+                application,
+                &JOIN_OVERRIDE_LABEL_ARGUMENT_NAME,
+            )?,
         })
     }
 
@@ -297,6 +308,33 @@ impl JoinSpecDefinition {
             graph: directive_required_enum_argument(application, &JOIN_GRAPH_ARGUMENT_NAME)?,
         })
     }
+
+    /// Note that @join__owner only exists in join v0.1 (i.e. in Fed 1 supergraphs).
+    pub(crate) fn owner_directive_definition<'schema>(
+        &self,
+        schema: &'schema FederationSchema,
+    ) -> Result<Option<&'schema Node<DirectiveDefinition>>, FederationError> {
+        if *self.version() >= (Version { major: 0, minor: 2 }) {
+            return Ok(None);
+        }
+        self.directive_definition(schema, &JOIN_OWNER_DIRECTIVE_NAME_IN_SPEC)?
+            .ok_or_else(|| {
+                SingleFederationError::Internal {
+                    message: "Unexpectedly could not find join spec in schema".to_owned(),
+                }
+                .into()
+            })
+            .map(Some)
+    }
+
+    pub(crate) fn owner_directive_arguments(
+        &self,
+        application: &Node<Directive>,
+    ) -> Result<OwnerDirectiveArguments, FederationError> {
+        Ok(OwnerDirectiveArguments {
+            graph: directive_required_enum_argument(application, &JOIN_GRAPH_ARGUMENT_NAME)?,
+        })
+    }
 }
 
 impl SpecDefinition for JoinSpecDefinition {
@@ -324,13 +362,6 @@ lazy_static! {
             Version { major: 0, minor: 3 },
             Some(Version { major: 2, minor: 0 }),
         ));
-        definitions
-    };
-
-    /// Versions supported for purpose other than query planning.
-    /// TODO: remove once v0.4 is properly implemented in query planning
-    pub(crate) static ref NON_QUERY_PLANNING_JOIN_VERSIONS: SpecDefinitions<JoinSpecDefinition> = {
-        let mut definitions = JOIN_VERSIONS.clone();
         definitions.add(JoinSpecDefinition::new(
             Version { major: 0, minor: 4 },
             Some(Version { major: 2, minor: 7 }),
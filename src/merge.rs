@@ -1,43 +1,312 @@
 use std::collections::HashSet;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::iter;
 use std::sync::Arc;
 
 use apollo_compiler::ast::DirectiveList;
 use apollo_compiler::ast::{
     Argument, Directive, DirectiveDefinition, DirectiveLocation, EnumValueDefinition,
-    FieldDefinition, NamedType, Value,
+    FieldDefinition, NamedType, Type, Value,
 };
+use apollo_compiler::execution::GraphQLLocation;
 use apollo_compiler::schema::{
     Component, EnumType, ExtendedType, InputObjectType, InputValueDefinition, InterfaceType, Name,
     ObjectType, ScalarType, UnionType,
 };
 use apollo_compiler::ty;
 use apollo_compiler::validation::Valid;
-use apollo_compiler::{name, Node, NodeStr, Schema};
+use apollo_compiler::{name, Node, NodeLocation, NodeStr, Schema};
 use indexmap::map::Entry::{Occupied, Vacant};
 use indexmap::map::Iter;
 use indexmap::{IndexMap, IndexSet};
 
+use crate::access_control::AccessControlDirective;
+use crate::compose_directive::ComposeDirectiveManager;
+use crate::error::{ErrorCode, FederationError, MultipleFederationErrors, SingleFederationError};
+use crate::link::database::links_metadata;
+use crate::link::federation_spec_definition::{
+    FEDERATION_EXTERNAL_DIRECTIVE_NAME_IN_SPEC, FEDERATION_INTERFACEOBJECT_DIRECTIVE_NAME_IN_SPEC,
+    FEDERATION_KEY_DIRECTIVE_NAME_IN_SPEC, FEDERATION_OVERRIDE_DIRECTIVE_NAME_IN_SPEC,
+    FEDERATION_PROVIDES_DIRECTIVE_NAME_IN_SPEC, FEDERATION_REQUIRES_DIRECTIVE_NAME_IN_SPEC,
+    FEDERATION_SHAREABLE_DIRECTIVE_NAME_IN_SPEC,
+};
+use crate::link::spec::{Identity, Version};
+use crate::schema::argument_composition_strategies::ArgumentCompositionStrategy;
+use crate::schema::definitions::is_strict_subtype;
+use crate::schema::position::{
+    InputObjectFieldDefinitionPosition, InterfaceFieldDefinitionPosition,
+    ObjectFieldDefinitionPosition, ObjectOrInterfaceFieldDefinitionPosition,
+};
+use crate::subgraph::spec::{
+    CONTEXT_DIRECTIVE_NAME, COST_DIRECTIVE_NAME, FROM_CONTEXT_DIRECTIVE_NAME,
+    LIST_SIZE_DIRECTIVE_NAME,
+};
 use crate::subgraph::ValidSubgraph;
 
-type MergeWarning = &'static str;
-type MergeError = &'static str;
+/// The location of a schema element involved in a composition error or hint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubgraphLocation {
+    /// The name of the subgraph the element is defined in.
+    pub subgraph: String,
+    /// The position of the element in the subgraph's sources, when the element was parsed (as
+    /// opposed to being added programmatically, e.g. by federation spec expansion).
+    pub location: Option<GraphQLLocation>,
+}
+
+impl SubgraphLocation {
+    pub(crate) fn new(subgraph: &ValidSubgraph, location: Option<NodeLocation>) -> Self {
+        Self {
+            subgraph: subgraph.name.clone(),
+            location: GraphQLLocation::from_node(&subgraph.schema.sources, location),
+        }
+    }
+}
+
+/// An error preventing the subgraphs from being composed.
+#[derive(Debug, Clone)]
+pub struct MergeError {
+    pub error: SingleFederationError,
+    pub locations: Vec<SubgraphLocation>,
+}
+
+impl MergeError {
+    pub fn code(&self) -> ErrorCode {
+        self.error.code()
+    }
+
+    /// The names of the subgraphs involved in this error, without duplicates.
+    pub fn subgraphs(&self) -> impl Iterator<Item = &str> {
+        self.locations
+            .iter()
+            .map(|location| location.subgraph.as_str())
+            .collect::<IndexSet<_>>()
+            .into_iter()
+    }
+
+    /// Converts errors raised outside of merging (e.g. while validating the merged schema), which
+    /// aren't tied to any particular subgraph.
+    pub(crate) fn from_federation_error(error: FederationError) -> Vec<Self> {
+        let errors = match error {
+            FederationError::SingleFederationError(error) => vec![error],
+            FederationError::MultipleFederationErrors(errors) => errors.errors,
+            FederationError::AggregateFederationError(error) => error.causes,
+        };
+        errors
+            .into_iter()
+            .map(|error| Self {
+                error,
+                locations: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+impl Display for MergeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+// PORT_NOTE: These are the codes of the JS `HINTS` definitions. The JS codebase also associates
+// a level (warn/info/debug) with each code, which we don't port until there's a consumer for it.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, strum_macros::Display, strum_macros::EnumIter,
+)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum HintCode {
+    InconsistentButCompatibleFieldType,
+    InconsistentButCompatibleArgumentType,
+    InconsistentDefaultValuePresence,
+    InconsistentEntity,
+    InconsistentObjectValueTypeField,
+    InconsistentInterfaceValueTypeField,
+    InconsistentInputObjectField,
+    InconsistentUnionMember,
+    InconsistentEnumValueForInputEnum,
+    InconsistentEnumValueForOutputEnum,
+    InconsistentTypeSystemDirectiveRepeatable,
+    InconsistentTypeSystemDirectiveLocations,
+    InconsistentExecutableDirectivePresence,
+    NoExecutableDirectiveLocationsIntersection,
+    InconsistentExecutableDirectiveRepeatable,
+    InconsistentExecutableDirectiveLocations,
+    InconsistentDescription,
+    InconsistentArgumentPresence,
+    FromSubgraphDoesNotExist,
+    OverriddenFieldCanBeRemoved,
+    OverrideDirectiveCanBeRemoved,
+    OverrideMigrationInProgress,
+    UnusedEnumType,
+    InconsistentNonRepeatableDirectiveArguments,
+    MergedNonRepeatableDirectiveArguments,
+    DirectiveCompositionInfo,
+    DirectiveCompositionWarn,
+    InconsistentRuntimeTypesForShareableReturn,
+    ImplicitlyUpgradedFederationVersion,
+}
+
+/// A non-fatal observation made while composing, e.g. an inconsistency between subgraphs that
+/// composition was able to resolve.
+#[derive(Debug, Clone)]
+pub struct CompositionHint {
+    pub code: HintCode,
+    pub message: String,
+    pub locations: Vec<SubgraphLocation>,
+}
+
+impl CompositionHint {
+    /// The names of the subgraphs involved in this hint, without duplicates.
+    pub fn subgraphs(&self) -> impl Iterator<Item = &str> {
+        self.locations
+            .iter()
+            .map(|location| location.subgraph.as_str())
+            .collect::<IndexSet<_>>()
+            .into_iter()
+    }
+}
+
+impl Display for CompositionHint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}]: {}", self.code, self.message)
+    }
+}
 
 struct Merger {
     errors: Vec<MergeError>,
-    composition_hints: Vec<MergeWarning>,
+    composition_hints: Vec<CompositionHint>,
+    /// The type each subgraph gives to a field, recorded while merging types. The supergraph type
+    /// of a field depends on all of those, so they're merged once all subgraphs are processed.
+    field_sources: IndexMap<ObjectOrInterfaceFieldDefinitionPosition, Vec<FieldSource>>,
+    argument_sources: IndexMap<(ObjectOrInterfaceFieldDefinitionPosition, Name), Vec<FieldSource>>,
+    input_field_sources: IndexMap<InputObjectFieldDefinitionPosition, Vec<FieldSource>>,
+    /// The values each subgraph defines for an enum type. How those are merged depends on whether
+    /// the enum is used in input or output positions, which is only known once all subgraphs are
+    /// processed.
+    enum_sources: IndexMap<NamedType, Vec<EnumSource>>,
+    /// The name of the access-control directives defined in each subgraph, keyed by subgraph
+    /// name.
+    access_control_directive_names: IndexMap<String, Vec<(AccessControlDirective, Name)>>,
+    /// The access-control directives applied to each type (and field, when the second element of
+    /// the key is set) in the subgraphs, with the argument value of each application.
+    access_control_sources:
+        IndexMap<(NamedType, Option<Name>), IndexMap<AccessControlDirective, Vec<Value>>>,
+    /// The definitions of each object field in the subgraphs, along with what's needed to validate
+    /// their `@override` and `@shareable` applications. Those can only be validated once all
+    /// subgraphs are processed, as they relate fields of different subgraphs.
+    object_field_sources: IndexMap<ObjectFieldDefinitionPosition, Vec<ObjectFieldSource>>,
+    /// The subgraphs declaring each type with `@interfaceObject`, along with the fields (other than
+    /// `@external` ones) they resolve for it.
+    interface_object_sources: IndexMap<NamedType, Vec<(SubgraphLocation, IndexSet<Name>)>>,
+    /// The name of the federation directives in each subgraph, keyed by subgraph name.
+    federation_directive_names: IndexMap<String, FederationDirectiveNames>,
+}
+
+/// The names the federation directives used by composition have in a subgraph. Those may differ
+/// from their name in the federation spec when renamed on import (e.g. `@key` imported as
+/// `@primaryKey`), or when not imported at all (e.g. `@federation__key`).
+#[derive(Clone)]
+struct FederationDirectiveNames {
+    key_directive_name: Name,
+    requires_directive_name: Name,
+    provides_directive_name: Name,
+    external_directive_name: Name,
+    shareable_directive_name: Name,
+    override_directive_name: Name,
+    interface_object_directive_name: Name,
+    /// The federation directives that can't be composed yet (see
+    /// `Merger::validate_unsupported_federation_directives()`), if the linked federation version
+    /// defines them.
+    unsupported_directive_names: Vec<Name>,
+}
+
+impl FederationDirectiveNames {
+    fn new(subgraph: &ValidSubgraph) -> Self {
+        // Federation 1 subgraphs are expanded with a link importing the federation 1 directives,
+        // so every subgraph is expected to link to the federation spec.
+        let link = links_metadata(&subgraph.schema)
+            .ok()
+            .flatten()
+            .and_then(|metadata| metadata.for_identity(&Identity::federation_identity()));
+        let name_in_schema = |name_in_spec: Name| match &link {
+            Some(link) => link.directive_name_in_schema(&name_in_spec),
+            None => name_in_spec,
+        };
+        Self {
+            key_directive_name: name_in_schema(FEDERATION_KEY_DIRECTIVE_NAME_IN_SPEC),
+            requires_directive_name: name_in_schema(FEDERATION_REQUIRES_DIRECTIVE_NAME_IN_SPEC),
+            provides_directive_name: name_in_schema(FEDERATION_PROVIDES_DIRECTIVE_NAME_IN_SPEC),
+            external_directive_name: name_in_schema(FEDERATION_EXTERNAL_DIRECTIVE_NAME_IN_SPEC),
+            shareable_directive_name: name_in_schema(FEDERATION_SHAREABLE_DIRECTIVE_NAME_IN_SPEC),
+            override_directive_name: name_in_schema(FEDERATION_OVERRIDE_DIRECTIVE_NAME_IN_SPEC),
+            interface_object_directive_name: name_in_schema(
+                FEDERATION_INTERFACEOBJECT_DIRECTIVE_NAME_IN_SPEC,
+            ),
+            unsupported_directive_names: [
+                (CONTEXT_DIRECTIVE_NAME, 8),
+                (FROM_CONTEXT_DIRECTIVE_NAME, 8),
+                (COST_DIRECTIVE_NAME, 9),
+                (LIST_SIZE_DIRECTIVE_NAME, 9),
+            ]
+            .into_iter()
+            .filter(|(_, minor)| {
+                link.as_ref().is_some_and(|link| {
+                    link.url.version
+                        >= Version {
+                            major: 2,
+                            minor: *minor,
+                        }
+                })
+            })
+            .map(|(name_in_spec, _)| name_in_schema(name_in_spec))
+            .collect(),
+        }
+    }
+}
+
+/// An enum type definition in one of the subgraphs being merged.
+struct EnumSource {
+    location: SubgraphLocation,
+    values: IndexSet<Name>,
+}
+
+/// An object field definition in one of the subgraphs being merged.
+struct ObjectFieldSource {
+    graph: Name,
+    location: SubgraphLocation,
+    /// The `from` and `label` arguments of the `@override` application of the field, if any.
+    override_from: Option<(NodeStr, Option<NodeStr>)>,
+    is_external: bool,
+    /// The name of the `@requires` or `@provides` directive applied to the field, if any.
+    requires_or_provides: Option<&'static str>,
+    /// Whether the field is used by a federation directive or to implement an interface, in which
+    /// case it's kept in the supergraph for its subgraph even when overridden.
+    is_used: bool,
+    /// Whether the field is `@external` but provided by a `@provides` of the subgraph, in which
+    /// case the subgraph still resolves it (for some paths).
+    is_provided: bool,
+    is_shareable: bool,
+    /// The object types the field can resolve to in its subgraph, when its type is abstract.
+    runtime_types: Option<IndexSet<Name>>,
+}
+
+/// A field, argument or input field definition in one of the subgraphs being merged.
+struct FieldSource {
+    graph: Name,
+    location: SubgraphLocation,
+    ty: Type,
+    default_value: Option<Node<Value>>,
+    is_external: bool,
 }
 
 pub struct MergeSuccess {
     pub schema: Valid<Schema>,
-    pub composition_hints: Vec<MergeWarning>,
+    pub composition_hints: Vec<CompositionHint>,
 }
 
 pub struct MergeFailure {
     pub schema: Option<Schema>,
     pub errors: Vec<MergeError>,
-    pub composition_hints: Vec<MergeWarning>,
+    pub composition_hints: Vec<CompositionHint>,
 }
 
 impl Debug for MergeFailure {
@@ -49,6 +318,15 @@ impl Debug for MergeFailure {
     }
 }
 
+impl From<MergeFailure> for FederationError {
+    fn from(value: MergeFailure) -> Self {
+        MultipleFederationErrors {
+            errors: value.errors.into_iter().map(|error| error.error).collect(),
+        }
+        .into()
+    }
+}
+
 pub fn merge_subgraphs(subgraphs: Vec<&ValidSubgraph>) -> Result<MergeSuccess, MergeFailure> {
     let mut merger = Merger::new();
     merger.merge(subgraphs)
@@ -59,6 +337,15 @@ impl Merger {
         Merger {
             composition_hints: Vec::new(),
             errors: Vec::new(),
+            field_sources: IndexMap::new(),
+            argument_sources: IndexMap::new(),
+            input_field_sources: IndexMap::new(),
+            enum_sources: IndexMap::new(),
+            access_control_directive_names: IndexMap::new(),
+            access_control_sources: IndexMap::new(),
+            object_field_sources: IndexMap::new(),
+            interface_object_sources: IndexMap::new(),
+            federation_directive_names: IndexMap::new(),
         }
     }
     fn merge(&mut self, mut subgraphs: Vec<&ValidSubgraph>) -> Result<MergeSuccess, MergeFailure> {
@@ -69,9 +356,20 @@ impl Merger {
             // name and avoids collisions).
             if let Ok(subgraph_name) = Name::new(&subgraph.name.to_uppercase()) {
                 subgraphs_and_enum_values.push((*subgraph, subgraph_name));
+                self.federation_directive_names.insert(
+                    subgraph.name.clone(),
+                    FederationDirectiveNames::new(subgraph),
+                );
             } else {
-                self.errors
-                    .push("Subgraph name couldn't be transformed into valid GraphQL name");
+                self.errors.push(MergeError {
+                    error: SingleFederationError::InvalidSubgraphName {
+                        message: format!(
+                            "Subgraph name \"{}\" couldn't be transformed into valid GraphQL name",
+                            subgraph.name,
+                        ),
+                    },
+                    locations: vec![SubgraphLocation::new(subgraph, None)],
+                });
             }
         }
         if !self.errors.is_empty() {
@@ -82,13 +380,37 @@ impl Merger {
             });
         }
 
+        self.validate_unsupported_federation_directives(&subgraphs);
+
         let mut supergraph = Schema::new();
-        // TODO handle @compose
+        let mut compose_directive_manager = ComposeDirectiveManager::validate(&subgraphs);
+        self.errors
+            .extend(std::mem::take(&mut compose_directive_manager.errors));
+        self.composition_hints
+            .extend(std::mem::take(&mut compose_directive_manager.hints));
 
         // add core features
         // TODO verify federation versions across subgraphs
         add_core_feature_link(&mut supergraph);
-        add_core_feature_join(&mut supergraph, &subgraphs_and_enum_values);
+        // Progressive override labels (introduced in federation 2.7) require version 0.4 of the
+        // join spec, which is only linked when they are used.
+        let uses_override_labels = subgraphs.iter().any(|subgraph| {
+            let names = &self.federation_directive_names[&subgraph.name];
+            subgraph.schema.types.values().any(|type_| match type_ {
+                ExtendedType::Object(type_) => type_.fields.values().any(|field| {
+                    let override_directive = field.directives.get(&names.override_directive_name);
+                    override_directive.is_some_and(|directive| {
+                        directive_string_arg_value(directive, &name!("label")).is_some()
+                    })
+                }),
+                _ => false,
+            })
+        });
+        add_core_feature_join(
+            &mut supergraph,
+            &subgraphs_and_enum_values,
+            uses_override_labels,
+        );
 
         // create stubs
         for (subgraph, subgraph_name) in &subgraphs_and_enum_values {
@@ -98,7 +420,17 @@ impl Merger {
             }
 
             self.merge_schema(&mut supergraph, subgraph);
-            // TODO merge directives
+
+            let metadata = links_metadata(&subgraph.schema).ok().flatten();
+            if let Some(metadata) = &metadata {
+                let names = AccessControlDirective::names_in_schema(
+                    metadata,
+                    |_| Identity::federation_identity(),
+                    |name| subgraph.schema.directive_definitions.contains_key(name),
+                );
+                self.access_control_directive_names
+                    .insert(subgraph.name.clone(), names);
+            }
 
             for (key, value) in &subgraph.schema.types {
                 if value.is_built_in() || !is_mergeable_type(key) {
@@ -109,24 +441,28 @@ impl Merger {
                 match value {
                     ExtendedType::Enum(value) => self.merge_enum_type(
                         &mut supergraph.types,
+                        subgraph,
                         subgraph_name.clone(),
                         key.clone(),
                         value,
                     ),
                     ExtendedType::InputObject(value) => self.merge_input_object_type(
                         &mut supergraph.types,
+                        subgraph,
                         subgraph_name.clone(),
                         key.clone(),
                         value,
                     ),
                     ExtendedType::Interface(value) => self.merge_interface_type(
                         &mut supergraph.types,
+                        subgraph,
                         subgraph_name.clone(),
                         key.clone(),
                         value,
                     ),
                     ExtendedType::Object(value) => self.merge_object_type(
                         &mut supergraph.types,
+                        subgraph,
                         subgraph_name.clone(),
                         key.clone(),
                         value,
@@ -137,20 +473,36 @@ impl Merger {
                         key.clone(),
                         value,
                     ),
-                    ExtendedType::Scalar(_value) => {
-                        // DO NOTHING
+                    ExtendedType::Scalar(value) => {
+                        // skip scalars of linked specs, e.g. an imported `link__Import`
+                        if metadata
+                            .as_ref()
+                            .is_some_and(|metadata| metadata.source_link_of_type(key).is_some())
+                        {
+                            continue;
+                        }
+                        self.merge_scalar_type(
+                            &mut supergraph.types,
+                            subgraph,
+                            subgraph_name.clone(),
+                            key.clone(),
+                            value,
+                        )
                     }
                 }
             }
-
-            // merge executable directives
-            for (_, directive) in subgraph.schema.directive_definitions.iter() {
-                if is_executable_directive(directive) {
-                    merge_directive(&mut supergraph.directive_definitions, directive);
-                }
-            }
         }
 
+        self.merge_executable_directives(&mut supergraph, &subgraphs_and_enum_values);
+        self.merge_field_types(&mut supergraph);
+        self.merge_object_fields(&mut supergraph, &subgraphs);
+        self.merge_enum_values(&mut supergraph);
+        self.merge_access_control_directives(&mut supergraph);
+        self.merge_interface_objects(&mut supergraph, &subgraphs);
+        compose_directive_manager.merge(&mut supergraph, &subgraphs);
+        self.composition_hints
+            .extend(compose_directive_manager.hints);
+
         if self.errors.is_empty() {
             // TODO: validate here and extend `MergeFailure` to propagate validation errors
             let supergraph = Valid::assume_valid(supergraph);
@@ -167,23 +519,116 @@ impl Merger {
         }
     }
 
-    fn merge_descriptions<T: Eq + Clone>(&mut self, merged: &mut Option<T>, new: &Option<T>) {
+    fn merge_descriptions(
+        &mut self,
+        merged: &mut Option<NodeStr>,
+        new: &Option<NodeStr>,
+        subgraph: &ValidSubgraph,
+        element: impl Display,
+    ) {
         match (&mut *merged, new) {
             (_, None) => {}
             (None, Some(_)) => merged.clone_from(new),
             (Some(a), Some(b)) => {
-                if a != b {
-                    // TODO add info about type and from/to subgraph
-                    self.composition_hints.push("conflicting descriptions");
+                if a.as_str() != b.as_str() {
+                    self.composition_hints.push(CompositionHint {
+                        code: HintCode::InconsistentDescription,
+                        message: format!(
+                            "Element \"{element}\" has inconsistent descriptions across subgraphs. The supergraph will use description \"{a}\", but subgraph \"{}\" has description \"{b}\".",
+                            subgraph.name,
+                        ),
+                        locations: vec![SubgraphLocation::new(subgraph, b.location())],
+                    });
+                }
+            }
+        }
+    }
+
+    /// Rejects the applications of the federation 2.8 and 2.9 directives whose composition isn't
+    /// supported yet (`@context` and `@fromContext`, which need the `contextArguments` of the join
+    /// spec, and `@cost` and `@listSize`, which need the cost spec), rather than silently dropping
+    /// them from the supergraph.
+    fn validate_unsupported_federation_directives(&mut self, subgraphs: &[&ValidSubgraph]) {
+        for subgraph in subgraphs {
+            let names = &self.federation_directive_names[&subgraph.name];
+            if names.unsupported_directive_names.is_empty() {
+                continue;
+            }
+            let is_unsupported = |directive: &Node<Directive>| {
+                names.unsupported_directive_names.contains(&directive.name)
+            };
+            // The unsupported applications, along with the coordinate of the element they're
+            // applied to.
+            let mut applications: Vec<(Node<Directive>, String)> = Vec::new();
+            for (type_name, type_) in &subgraph.schema.types {
+                if type_.is_built_in() {
+                    continue;
+                }
+                for directive in type_.directives().iter() {
+                    if is_unsupported(directive) {
+                        applications.push((directive.node.clone(), type_name.to_string()));
+                    }
+                }
+                let fields = match type_ {
+                    ExtendedType::Object(type_) => &type_.fields,
+                    ExtendedType::Interface(type_) => &type_.fields,
+                    ExtendedType::InputObject(type_) => {
+                        for (field_name, field) in &type_.fields {
+                            for directive in field.directives.iter() {
+                                if is_unsupported(directive) {
+                                    applications.push((
+                                        directive.clone(),
+                                        format!("{type_name}.{field_name}"),
+                                    ));
+                                }
+                            }
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                for (field_name, field) in fields {
+                    for directive in field.directives.iter() {
+                        if is_unsupported(directive) {
+                            applications
+                                .push((directive.clone(), format!("{type_name}.{field_name}")));
+                        }
+                    }
+                    for argument in &field.arguments {
+                        for directive in argument.directives.iter() {
+                            if is_unsupported(directive) {
+                                applications.push((
+                                    directive.clone(),
+                                    format!("{type_name}.{field_name}({}:)", argument.name),
+                                ));
+                            }
+                        }
+                    }
                 }
             }
+            for (directive, coordinate) in applications {
+                self.errors.push(MergeError {
+                    error: SingleFederationError::UnsupportedFeature {
+                        message: format!(
+                            "Directive \"@{}\" is applied to \"{coordinate}\", but composing it into the supergraph is not supported yet",
+                            directive.name,
+                        ),
+                    },
+                    locations: vec![SubgraphLocation::new(subgraph, directive.location())],
+                });
+            }
         }
     }
 
     fn merge_schema(&mut self, supergraph_schema: &mut Schema, subgraph: &ValidSubgraph) {
         let supergraph_def = &mut supergraph_schema.schema_definition.make_mut();
         let subgraph_def = &subgraph.schema.schema_definition;
-        self.merge_descriptions(&mut supergraph_def.description, &subgraph_def.description);
+        self.merge_descriptions(
+            &mut supergraph_def.description,
+            &subgraph_def.description,
+            subgraph,
+            "schema",
+        );
 
         if subgraph_def.query.is_some() {
             supergraph_def.query.clone_from(&subgraph_def.query);
@@ -204,22 +649,42 @@ impl Merger {
     fn merge_enum_type(
         &mut self,
         types: &mut IndexMap<NamedType, ExtendedType>,
+        subgraph: &ValidSubgraph,
         subgraph_name: Name,
         enum_name: NamedType,
         enum_type: &Node<EnumType>,
     ) {
         let existing_type = types
             .entry(enum_name.clone())
-            .or_insert(copy_enum_type(enum_name, enum_type));
+            .or_insert(copy_enum_type(enum_name.clone(), enum_type));
         if let ExtendedType::Enum(e) = existing_type {
             let join_type_directives =
                 join_type_applied_directive(subgraph_name.clone(), iter::empty(), false);
             e.make_mut().directives.extend(join_type_directives);
 
-            self.merge_descriptions(&mut e.make_mut().description, &enum_type.description);
-
-            // TODO we need to merge those fields LAST so we know whether enum is used as input/output/both as different merge rules will apply
-            // below logic only works for output enums
+            self.merge_descriptions(
+                &mut e.make_mut().description,
+                &enum_type.description,
+                subgraph,
+                &enum_name,
+            );
+            self.add_access_control_sources(
+                subgraph,
+                &enum_name,
+                None,
+                enum_type.directives.iter().map(|d| d.as_ref()),
+            );
+
+            self.enum_sources
+                .entry(enum_name.clone())
+                .or_default()
+                .push(EnumSource {
+                    location: SubgraphLocation::new(subgraph, enum_type.location()),
+                    values: enum_type.values.keys().cloned().collect(),
+                });
+
+            // We add every value for now, and remove the values not defined in all subgraphs in
+            // `merge_enum_values()` if the enum turns out to be used as input.
             for (enum_value_name, enum_value) in enum_type.values.iter() {
                 let ev = e
                     .make_mut()
@@ -230,7 +695,12 @@ impl Merger {
                         description: None,
                         directives: Default::default(),
                     }));
-                self.merge_descriptions(&mut ev.make_mut().description, &enum_value.description);
+                self.merge_descriptions(
+                    &mut ev.make_mut().description,
+                    &enum_value.description,
+                    subgraph,
+                    format_args!("{enum_name}.{enum_value_name}"),
+                );
                 ev.make_mut().directives.push(Node::new(Directive {
                     name: name!("join__enumValue"),
                     arguments: vec![
@@ -246,190 +716,1686 @@ impl Merger {
         }
     }
 
-    fn merge_input_object_type(
+    fn merge_input_object_type(
+        &mut self,
+        types: &mut IndexMap<NamedType, ExtendedType>,
+        subgraph: &ValidSubgraph,
+        subgraph_name: Name,
+        input_object_name: NamedType,
+        input_object: &Node<InputObjectType>,
+    ) {
+        let existing_type =
+            types
+                .entry(input_object_name.clone())
+                .or_insert(copy_input_object_type(
+                    input_object_name.clone(),
+                    input_object,
+                ));
+        if let ExtendedType::InputObject(obj) = existing_type {
+            let join_type_directives =
+                join_type_applied_directive(subgraph_name.clone(), iter::empty(), false);
+            let mutable_object = obj.make_mut();
+            mutable_object.directives.extend(join_type_directives);
+
+            for (field_name, field) in input_object.fields.iter() {
+                let existing_field = mutable_object.fields.entry(field_name.clone());
+                match existing_field {
+                    Vacant(_i) => {
+                        // TODO warning - mismatch on input fields
+                    }
+                    Occupied(_i) => {
+                        // merge_options(&i.get_mut().description, &field.description);
+                        // TODO check description
+                        // TODO process directives
+                        self.input_field_sources
+                            .entry(InputObjectFieldDefinitionPosition {
+                                type_name: input_object_name.clone(),
+                                field_name: field_name.clone(),
+                            })
+                            .or_default()
+                            .push(FieldSource {
+                                graph: subgraph_name.clone(),
+                                location: SubgraphLocation::new(subgraph, field.location()),
+                                ty: field.ty.as_ref().clone(),
+                                default_value: field.default_value.clone(),
+                                is_external: false,
+                            });
+                    }
+                }
+            }
+        } else {
+            // TODO conflict on type
+        }
+    }
+
+    fn merge_interface_type(
+        &mut self,
+        types: &mut IndexMap<NamedType, ExtendedType>,
+        subgraph: &ValidSubgraph,
+        subgraph_name: Name,
+        interface_name: NamedType,
+        interface: &Node<InterfaceType>,
+    ) {
+        let existing_type = types
+            .entry(interface_name.clone())
+            .or_insert(copy_interface_type(interface_name.clone(), interface));
+        if let ExtendedType::Interface(intf) = existing_type {
+            let names = self.federation_directive_names[&subgraph.name].clone();
+            let key_directives = interface.directives.get_all(&names.key_directive_name);
+            let join_type_directives =
+                join_type_applied_directive(subgraph_name.clone(), key_directives, false);
+            let mutable_intf = intf.make_mut();
+            mutable_intf.directives.extend(join_type_directives);
+            self.add_access_control_sources(
+                subgraph,
+                &interface_name,
+                None,
+                interface.directives.iter().map(|d| d.as_ref()),
+            );
+
+            for (field_name, field) in interface.fields.iter() {
+                self.add_access_control_sources(
+                    subgraph,
+                    &interface_name,
+                    Some(field_name),
+                    field.directives.iter().map(|d| d.as_ref()),
+                );
+                self.add_field_sources(
+                    subgraph,
+                    &subgraph_name,
+                    InterfaceFieldDefinitionPosition {
+                        type_name: interface_name.clone(),
+                        field_name: field_name.clone(),
+                    }
+                    .into(),
+                    field,
+                    &names,
+                );
+                if let Some(directive) = field.directives.get(&names.override_directive_name) {
+                    self.errors.push(MergeError {
+                        error: SingleFederationError::OverrideOnInterface {
+                            message: format!(
+                                "@override cannot be used on field \"{interface_name}.{field_name}\" on subgraph \"{}\": @override is not supported on interface type fields.",
+                                subgraph.name,
+                            ),
+                        },
+                        locations: vec![SubgraphLocation::new(subgraph, directive.location())],
+                    });
+                }
+                let existing_field = mutable_intf.fields.entry(field_name.clone());
+                let supergraph_field = match existing_field {
+                    // TODO warning mismatch missing fields
+                    Vacant(i) => i.insert(Component::new(FieldDefinition {
+                        name: field.name.clone(),
+                        description: field.description.clone(),
+                        arguments: vec![],
+                        ty: field.ty.clone(),
+                        directives: Default::default(),
+                    })),
+                    // TODO check description
+                    // TODO process directives
+                    Occupied(i) => i.into_mut(),
+                };
+                self.add_field_arguments(supergraph_field, field, subgraph, &interface_name);
+            }
+        } else {
+            // TODO conflict on type
+        }
+    }
+
+    fn merge_object_type(
+        &mut self,
+        types: &mut IndexMap<NamedType, ExtendedType>,
+        subgraph: &ValidSubgraph,
+        subgraph_name: Name,
+        object_name: NamedType,
+        object: &Node<ObjectType>,
+    ) {
+        let names = self.federation_directive_names[&subgraph.name].clone();
+        let is_interface_object = object
+            .directives
+            .has(&names.interface_object_directive_name);
+        let existing_type = types
+            .entry(object_name.clone())
+            .or_insert(copy_object_type_stub(
+                object_name.clone(),
+                object,
+                is_interface_object,
+            ));
+        if let ExtendedType::Object(obj) = existing_type {
+            let key_fields: HashSet<&str> =
+                parse_keys(object.directives.get_all(&names.key_directive_name));
+            let is_join_field = !key_fields.is_empty() || object_name == "Query";
+            let key_directives = object.directives.get_all(&names.key_directive_name);
+            let join_type_directives =
+                join_type_applied_directive(subgraph_name.clone(), key_directives, false);
+            let mutable_object = obj.make_mut();
+            mutable_object.directives.extend(join_type_directives);
+            self.merge_descriptions(
+                &mut mutable_object.description,
+                &object.description,
+                subgraph,
+                &object_name,
+            );
+            self.add_access_control_sources(
+                subgraph,
+                &object_name,
+                None,
+                object.directives.iter().map(|d| d.as_ref()),
+            );
+            object.implements_interfaces.iter().for_each(|intf_name| {
+                // IndexSet::insert deduplicates
+                mutable_object
+                    .implements_interfaces
+                    .insert(intf_name.clone());
+                let join_implements_directive =
+                    join_type_implements(subgraph_name.clone(), intf_name);
+                mutable_object.directives.push(join_implements_directive);
+            });
+
+            for (field_name, field) in object.fields.iter() {
+                // skip federation built-in queries
+                if field_name == "_service" || field_name == "_entities" {
+                    continue;
+                }
+
+                self.add_access_control_sources(
+                    subgraph,
+                    &object_name,
+                    Some(field_name),
+                    field.directives.iter().map(|d| d.as_ref()),
+                );
+                self.add_field_sources(
+                    subgraph,
+                    &subgraph_name,
+                    ObjectFieldDefinitionPosition {
+                        type_name: object_name.clone(),
+                        field_name: field_name.clone(),
+                    }
+                    .into(),
+                    field,
+                    &names,
+                );
+                self.add_object_field_source(
+                    subgraph,
+                    &subgraph_name,
+                    &object_name,
+                    object,
+                    field,
+                    &names,
+                );
+                let existing_field = mutable_object.fields.entry(field_name.clone());
+                let supergraph_field = match existing_field {
+                    Occupied(f) => f.into_mut(),
+                    Vacant(f) => f.insert(Component::new(FieldDefinition {
+                        name: field.name.clone(),
+                        description: field.description.clone(),
+                        arguments: vec![],
+                        directives: Default::default(),
+                        ty: field.ty.clone(),
+                    })),
+                };
+                self.merge_descriptions(
+                    &mut supergraph_field.make_mut().description,
+                    &field.description,
+                    subgraph,
+                    format_args!("{object_name}.{field_name}"),
+                );
+                self.add_field_arguments(supergraph_field, field, subgraph, &object_name);
+
+                if is_join_field {
+                    let is_key_field = key_fields.contains(field_name.as_str());
+                    if !is_key_field {
+                        supergraph_field.make_mut().directives.push(Node::new(
+                            subgraph_join_field_directive(subgraph_name.clone(), field, &names),
+                        ));
+                    }
+                }
+            }
+        } else if let ExtendedType::Interface(intf) = existing_type {
+            let key_directives = object.directives.get_all(&names.key_directive_name);
+            let join_type_directives = join_type_applied_directive(
+                subgraph_name.clone(),
+                key_directives,
+                is_interface_object,
+            );
+            let mutable_intf = intf.make_mut();
+            mutable_intf.directives.extend(join_type_directives);
+            if !is_interface_object {
+                return;
+            }
+            self.merge_descriptions(
+                &mut mutable_intf.description,
+                &object.description,
+                subgraph,
+                &object_name,
+            );
+            self.add_access_control_sources(
+                subgraph,
+                &object_name,
+                None,
+                object.directives.iter().map(|d| d.as_ref()),
+            );
+            // An interface object is always an entity, so its fields are resolved like the ones
+            // of other entities.
+            let key_fields: HashSet<&str> =
+                parse_keys(object.directives.get_all(&names.key_directive_name));
+            let mut resolved_fields = IndexSet::new();
+            for (field_name, field) in object.fields.iter() {
+                self.add_access_control_sources(
+                    subgraph,
+                    &object_name,
+                    Some(field_name),
+                    field.directives.iter().map(|d| d.as_ref()),
+                );
+                self.add_field_sources(
+                    subgraph,
+                    &subgraph_name,
+                    InterfaceFieldDefinitionPosition {
+                        type_name: object_name.clone(),
+                        field_name: field_name.clone(),
+                    }
+                    .into(),
+                    field,
+                    &names,
+                );
+                if !field.directives.has(&names.external_directive_name) {
+                    resolved_fields.insert(field_name.clone());
+                }
+                let supergraph_field = mutable_intf
+                    .fields
+                    .entry(field_name.clone())
+                    .or_insert_with(|| {
+                        Component::new(FieldDefinition {
+                            name: field.name.clone(),
+                            description: field.description.clone(),
+                            arguments: vec![],
+                            directives: Default::default(),
+                            ty: field.ty.clone(),
+                        })
+                    });
+                self.merge_descriptions(
+                    &mut supergraph_field.make_mut().description,
+                    &field.description,
+                    subgraph,
+                    format_args!("{object_name}.{field_name}"),
+                );
+                self.add_field_arguments(supergraph_field, field, subgraph, &object_name);
+                if !key_fields.contains(field_name.as_str()) {
+                    supergraph_field.make_mut().directives.push(Node::new(
+                        subgraph_join_field_directive(subgraph_name.clone(), field, &names),
+                    ));
+                }
+            }
+            self.interface_object_sources
+                .entry(object_name)
+                .or_default()
+                .push((
+                    SubgraphLocation::new(subgraph, object.location()),
+                    resolved_fields,
+                ));
+        };
+    }
+
+    /// Adds the arguments of a subgraph field to the corresponding supergraph field. Whether they
+    /// are kept, and with which type and default value, is decided once all subgraphs are merged.
+    // PORT_NOTE: Part of `addArgumentsShallow()` in the JS codebase.
+    fn add_field_arguments(
+        &mut self,
+        supergraph_field: &mut Component<FieldDefinition>,
+        field: &FieldDefinition,
+        subgraph: &ValidSubgraph,
+        parent_name: &Name,
+    ) {
+        let supergraph_field = supergraph_field.make_mut();
+        for argument in &field.arguments {
+            let Some(existing) = supergraph_field
+                .arguments
+                .iter_mut()
+                .find(|existing| existing.name == argument.name)
+            else {
+                supergraph_field
+                    .arguments
+                    .push(Node::new(InputValueDefinition {
+                        description: argument.description.clone(),
+                        name: argument.name.clone(),
+                        ty: argument.ty.clone(),
+                        default_value: argument.default_value.clone(),
+                        directives: Default::default(),
+                    }));
+                continue;
+            };
+            self.merge_descriptions(
+                &mut existing.make_mut().description,
+                &argument.description,
+                subgraph,
+                format_args!("{parent_name}.{}({}:)", field.name, argument.name),
+            );
+        }
+    }
+
+    fn add_field_sources(
+        &mut self,
+        subgraph: &ValidSubgraph,
+        subgraph_name: &Name,
+        field_pos: ObjectOrInterfaceFieldDefinitionPosition,
+        field: &Component<FieldDefinition>,
+        names: &FederationDirectiveNames,
+    ) {
+        let is_external = field.directives.has(&names.external_directive_name);
+        for argument in field.arguments.iter() {
+            self.argument_sources
+                .entry((field_pos.clone(), argument.name.clone()))
+                .or_default()
+                .push(FieldSource {
+                    graph: subgraph_name.clone(),
+                    location: SubgraphLocation::new(subgraph, argument.location()),
+                    ty: argument.ty.as_ref().clone(),
+                    default_value: argument.default_value.clone(),
+                    is_external,
+                });
+        }
+        self.field_sources
+            .entry(field_pos)
+            .or_default()
+            .push(FieldSource {
+                graph: subgraph_name.clone(),
+                location: SubgraphLocation::new(subgraph, field.location()),
+                ty: field.ty.clone(),
+                default_value: None,
+                is_external,
+            });
+    }
+
+    fn add_object_field_source(
+        &mut self,
+        subgraph: &ValidSubgraph,
+        subgraph_name: &Name,
+        object_name: &Name,
+        object: &ObjectType,
+        field: &Component<FieldDefinition>,
+        names: &FederationDirectiveNames,
+    ) {
+        let override_from = field
+            .directives
+            .get(&names.override_directive_name)
+            .and_then(|directive| {
+                let from = directive_string_arg_value(directive, &name!("from"))?;
+                let label = directive_string_arg_value(directive, &name!("label"));
+                Some((from.clone(), label.cloned()))
+            });
+        let requires_or_provides = [
+            ("requires", &names.requires_directive_name),
+            ("provides", &names.provides_directive_name),
+        ]
+        .into_iter()
+        .find(|(_, directive_name)| field.directives.has(directive_name))
+        .map(|(name_in_spec, _)| name_in_spec);
+        let is_external = field.directives.has(&names.external_directive_name);
+        let is_provided =
+            is_external && is_field_provided(subgraph, object_name, &field.name, names);
+        // Federation 1 subgraphs have no `@shareable`, and any field may be resolved by several of
+        // them.
+        let is_shareable = !subgraph.is_fed_2
+            || is_provided
+            || field.directives.has(&names.shareable_directive_name)
+            || object.directives.has(&names.shareable_directive_name)
+            || object
+                .directives
+                .get_all(&names.key_directive_name)
+                .any(|key| field_set_selects(key, &field.name));
+        self.object_field_sources
+            .entry(ObjectFieldDefinitionPosition {
+                type_name: object_name.clone(),
+                field_name: field.name.clone(),
+            })
+            .or_default()
+            .push(ObjectFieldSource {
+                graph: subgraph_name.clone(),
+                location: SubgraphLocation::new(subgraph, field.location()),
+                override_from,
+                is_external,
+                requires_or_provides,
+                is_used: is_field_used(subgraph, object_name, object, &field.name, names),
+                is_provided,
+                is_shareable,
+                runtime_types: possible_runtime_types(subgraph, field.ty.inner_named_type()),
+            });
+    }
+
+    /// Validates that a field resolved by several subgraphs is shareable in all of them, and that
+    /// those subgraphs have common runtime types for it when its type is abstract. Overridden
+    /// fields and `@external` fields that aren't provided are not resolved by their subgraph.
+    // PORT_NOTE: Named `validateFieldSharing()` in the JS codebase. The runtime types check is part
+    // of the satisfiability validation in the JS codebase, whose messages start with a query
+    // reaching the field.
+    fn validate_field_sharing(
+        &mut self,
+        field_pos: &ObjectFieldDefinitionPosition,
+        sources: &[ObjectFieldSource],
+        overridden_graphs: &IndexSet<Name>,
+    ) {
+        let resolving = sources
+            .iter()
+            .filter(|source| {
+                !overridden_graphs.contains(&source.graph)
+                    && (!source.is_external || source.is_provided)
+            })
+            .collect::<Vec<_>>();
+        if resolving.len() < 2 {
+            return;
+        }
+        let non_shareable = resolving
+            .iter()
+            .filter(|source| !source.is_shareable)
+            .map(|source| source.location.subgraph.as_str())
+            .collect::<Vec<_>>();
+        if !non_shareable.is_empty() {
+            let non_shareables = if non_shareable.len() == resolving.len() {
+                "all of them".to_owned()
+            } else {
+                print_subgraph_names(non_shareable.into_iter())
+            };
+            self.errors.push(MergeError {
+                error: SingleFederationError::InvalidFieldSharing {
+                    message: format!(
+                        "Non-shareable field \"{field_pos}\" is resolved from multiple subgraphs: it is resolved from {} and defined as non-shareable in {non_shareables}",
+                        print_subgraph_names(resolving.iter().map(|source| source.location.subgraph.as_str())),
+                    ),
+                },
+                locations: resolving
+                    .iter()
+                    .map(|source| source.location.clone())
+                    .collect(),
+            });
+            return;
+        }
+
+        let runtime_types = resolving
+            .iter()
+            .filter_map(|source| Some((*source, source.runtime_types.as_ref()?)))
+            .collect::<Vec<_>>();
+        let Some(((_, first), rest)) = runtime_types.split_first() else {
+            return;
+        };
+        if rest.iter().all(|(_, types)| types == first) {
+            return;
+        }
+        let common_types = first
+            .iter()
+            .filter(|type_name| rest.iter().all(|(_, types)| types.contains(*type_name)))
+            .map(|type_name| format!("\"{type_name}\""))
+            .collect::<Vec<_>>();
+        let runtime_types_description = runtime_types
+            .iter()
+            .map(|(source, types)| {
+                let types = types
+                    .iter()
+                    .map(|type_name| format!("\"{type_name}\""))
+                    .collect::<Vec<_>>();
+                format!(
+                    " - in subgraph \"{}\", type{} {}",
+                    source.location.subgraph,
+                    if types.len() == 1 { "" } else { "s" },
+                    types.join(", "),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(";\n");
+        let locations = runtime_types
+            .iter()
+            .map(|(source, _)| source.location.clone())
+            .collect();
+        if common_types.is_empty() {
+            self.errors.push(MergeError {
+                error: SingleFederationError::ShareableHasMismatchedRuntimeTypes {
+                    message: format!(
+                        "Shared field \"{field_pos}\" has a non-intersecting set of possible runtime types across subgraphs. Runtime types in subgraphs are:\n{runtime_types_description}.\nThis is not allowed as shared fields must resolve the same way in all subgraphs, and that imply at least some common runtime types between the subgraphs."
+                    ),
+                },
+                locations,
+            });
+        } else {
+            self.composition_hints.push(CompositionHint {
+                code: HintCode::InconsistentRuntimeTypesForShareableReturn,
+                message: format!(
+                    "Shared field \"{field_pos}\" has different sets of possible runtime types across subgraphs.\nSince a shared field must be resolved the same way in all subgraphs, make sure that {} only resolve \"{field_pos}\" to objects of type{} {}. In particular:\n{runtime_types_description}.\nOtherwise the @shareable contract will be broken.",
+                    print_subgraph_names(runtime_types.iter().map(|(source, _)| source.location.subgraph.as_str())),
+                    if common_types.len() == 1 { "" } else { "s" },
+                    common_types.join(", "),
+                ),
+                locations,
+            });
+        }
+    }
+
+    /// Validates the usages of `@interfaceObject` and of keys on interfaces, and adds the fields
+    /// that interface objects resolve to the implementations of their interface.
+    fn merge_interface_objects(&mut self, supergraph: &mut Schema, subgraphs: &[&ValidSubgraph]) {
+        let interface_object_sources = std::mem::take(&mut self.interface_object_sources);
+        for (interface_name, sources) in &interface_object_sources {
+            let is_interface_somewhere = subgraphs.iter().any(|subgraph| {
+                matches!(
+                    subgraph.schema.types.get(interface_name),
+                    Some(ExtendedType::Interface(_))
+                )
+            });
+            if !is_interface_somewhere {
+                self.errors.push(MergeError {
+                    error: SingleFederationError::InterfaceObjectUsageError {
+                        message: format!(
+                            "Type \"{interface_name}\" is declared with @interfaceObject in all the subgraphs in which it is defined (it is defined in {} but should be defined as an interface in at least one subgraph)",
+                            print_subgraph_names(sources.iter().map(|(location, _)| location.subgraph.as_str())),
+                        ),
+                    },
+                    locations: sources.iter().map(|(location, _)| location.clone()).collect(),
+                });
+            }
+        }
+
+        // An interface object resolves its fields for all the implementations of the interface,
+        // so implementations get the fields they don't define. Those have a `@join__field`
+        // without graph, as they don't come from any subgraph's definition of the type.
+        // PORT_NOTE: Named `addMissingInterfaceObjectFieldsToImplementations()` in the JS codebase.
+        let interface_fields = interface_object_sources
+            .iter()
+            .filter_map(|(interface_name, sources)| {
+                let Some(ExtendedType::Interface(interface)) = supergraph.types.get(interface_name)
+                else {
+                    return None;
+                };
+                let fields = sources
+                    .iter()
+                    .flat_map(|(_, fields)| fields)
+                    .filter_map(|field_name| interface.fields.get(field_name))
+                    .map(|field| {
+                        let mut field = field.as_ref().clone();
+                        field
+                            .directives
+                            .retain(|directive| !directive.name.starts_with("join__"));
+                        field.directives.push(Node::new(Directive {
+                            name: name!("join__field"),
+                            arguments: vec![],
+                        }));
+                        field
+                    })
+                    .collect::<Vec<_>>();
+                Some((interface_name, fields))
+            })
+            .collect::<Vec<_>>();
+        for type_ in supergraph.types.values_mut() {
+            let ExtendedType::Object(object) = type_ else {
+                continue;
+            };
+            for (interface_name, fields) in &interface_fields {
+                if !object.implements_interfaces.contains(*interface_name) {
+                    continue;
+                }
+                for field in fields {
+                    if !object.fields.contains_key(&field.name) {
+                        object
+                            .make_mut()
+                            .fields
+                            .insert(field.name.clone(), Component::new(field.clone()));
+                    }
+                }
+            }
+        }
+
+        // A subgraph with a resolvable key on an interface must be able to resolve the interface
+        // as any of its implementations.
+        for subgraph in subgraphs {
+            for (interface_name, type_) in &subgraph.schema.types {
+                let ExtendedType::Interface(interface) = type_ else {
+                    continue;
+                };
+                let names = &self.federation_directive_names[&subgraph.name];
+                let Some(key) = interface
+                    .directives
+                    .get_all(&names.key_directive_name)
+                    .find(|key| {
+                        directive_bool_arg_value(key, &name!("resolvable")) != Some(&false)
+                    })
+                else {
+                    continue;
+                };
+                let missing_types = supergraph
+                    .types
+                    .iter()
+                    .filter(|(type_name, type_)| {
+                        matches!(type_, ExtendedType::Object(object)
+                            if object.implements_interfaces.contains(interface_name))
+                            && !matches!(
+                                subgraph.schema.types.get(*type_name),
+                                Some(ExtendedType::Object(object))
+                                    if object.implements_interfaces.contains(interface_name)
+                            )
+                    })
+                    .map(|(type_name, _)| format!("\"{type_name}\""))
+                    .collect::<Vec<_>>();
+                let (types_description, pronoun) = match missing_types.as_slice() {
+                    [] => continue,
+                    [type_name] => (format!("type {type_name}"), "it"),
+                    [init @ .., last] => (format!("types {} and {last}", init.join(", ")), "them"),
+                };
+                self.errors.push(MergeError {
+                    error: SingleFederationError::InterfaceKeyMissingImplementationType {
+                        message: format!(
+                            "Interface type \"{interface_name}\" has a resolvable key ({key}) in subgraph \"{}\" but that subgraph is missing some of the supergraph implementation types of \"{interface_name}\". Subgraph \"{}\" should define {types_description} (and have {pronoun} implement \"{interface_name}\").",
+                            subgraph.name,
+                            subgraph.name,
+                            key = key.as_ref(),
+                        ),
+                    },
+                    locations: vec![SubgraphLocation::new(subgraph, key.location())],
+                });
+            }
+        }
+    }
+
+    /// Validates and applies the `@override` applications of the object fields, then validates
+    /// their shareability.
+    fn merge_object_fields(&mut self, supergraph: &mut Schema, subgraphs: &[&ValidSubgraph]) {
+        let object_field_sources = std::mem::take(&mut self.object_field_sources);
+        for (field_pos, sources) in &object_field_sources {
+            let overridden_graphs = if sources.iter().any(|source| source.override_from.is_some()) {
+                self.merge_field_overrides(supergraph, subgraphs, field_pos, sources)
+            } else {
+                IndexSet::new()
+            };
+            self.validate_field_sharing(field_pos, sources, &overridden_graphs);
+        }
+    }
+
+    /// Validates the `@override` applications of the subgraphs and records them in the
+    /// `@join__field` applications of the supergraph. A field overridden by another subgraph is
+    /// no longer resolved by its subgraph, unless it's still used there by a federation directive
+    /// or the override is progressive (i.e. has a label). Returns the graphs whose field is
+    /// overridden.
+    // PORT_NOTE: Named `validateOverride()` in the JS codebase, which also returns the mapping
+    // used when adding `@join__field`. Both are done here once the fields have been merged.
+    fn merge_field_overrides(
+        &mut self,
+        supergraph: &mut Schema,
+        subgraphs: &[&ValidSubgraph],
+        field_pos: &ObjectFieldDefinitionPosition,
+        sources: &[ObjectFieldSource],
+    ) -> IndexSet<Name> {
+        let mut overridden_graphs = IndexSet::new();
+        let subgraphs_with_override = sources
+            .iter()
+            .filter(|source| source.override_from.is_some())
+            .map(|source| source.location.subgraph.as_str())
+            .collect::<Vec<_>>();
+        // The `@join__field` arguments to add for each graph, or `None` for graphs that no
+        // longer resolve the field.
+        let mut join_field_arguments: IndexMap<&Name, Option<Vec<Node<Argument>>>> =
+            IndexMap::new();
+        for source in sources {
+            let Some((from, label)) = &source.override_from else {
+                continue;
+            };
+            let subgraph_name = &source.location.subgraph;
+            let mut label_argument = label.as_ref().map(|label| {
+                Node::new(Argument {
+                    name: name!("overrideLabel"),
+                    value: Node::new(Value::String(label.clone())),
+                })
+            });
+            let mut arguments = vec![Node::new(Argument {
+                name: name!("override"),
+                value: Node::new(Value::String(from.clone())),
+            })];
+            arguments.extend(label_argument.clone());
+            join_field_arguments.insert(&source.graph, Some(arguments));
+
+            if !subgraphs
+                .iter()
+                .any(|subgraph| subgraph.name == from.as_str())
+            {
+                self.composition_hints.push(CompositionHint {
+                        code: HintCode::FromSubgraphDoesNotExist,
+                        message: format!(
+                            "Source subgraph \"{from}\" for field \"{field_pos}\" on subgraph \"{subgraph_name}\" does not exist."
+                        ),
+                        locations: vec![source.location.clone()],
+                    });
+            } else if from.as_str() == subgraph_name {
+                self.errors.push(MergeError {
+                        error: SingleFederationError::OverrideFromSelfError {
+                            message: format!(
+                                "Source and destination subgraphs \"{from}\" are the same for overridden field \"{field_pos}\""
+                            ),
+                        },
+                        locations: vec![source.location.clone()],
+                    });
+            } else if subgraphs_with_override.contains(&from.as_str()) {
+                self.errors.push(MergeError {
+                        error: SingleFederationError::OverrideSourceHasOverride {
+                            message: format!(
+                                "Field \"{field_pos}\" on subgraph \"{subgraph_name}\" is also marked with directive @override in subgraph \"{from}\". Only one @override directive is allowed per field."
+                            ),
+                        },
+                        locations: vec![source.location.clone()],
+                    });
+            } else if let Some(from_source) = sources
+                .iter()
+                .find(|from_source| from_source.location.subgraph == from.as_str())
+            {
+                let conflict = from_source
+                    .requires_or_provides
+                    .map(|directive_name| (directive_name, from.as_str()))
+                    .or(source
+                        .is_external
+                        .then_some(("external", subgraph_name.as_str())));
+                if let Some((directive_name, conflict_subgraph_name)) = conflict {
+                    self.errors.push(MergeError {
+                            error: SingleFederationError::OverrideCollisionWithAnotherDirective {
+                                message: format!(
+                                    "@override cannot be used on field \"{field_pos}\" on subgraph \"{subgraph_name}\" since \"{field_pos}\" on \"{conflict_subgraph_name}\" is marked with directive \"@{directive_name}\""
+                                ),
+                            },
+                            locations: vec![source.location.clone(), from_source.location.clone()],
+                        });
+                    continue;
+                }
+                let (code, message) = if from_source.is_external {
+                    (
+                            HintCode::OverrideDirectiveCanBeRemoved,
+                            format!(
+                                "Field \"{field_pos}\" on subgraph \"{subgraph_name}\" is not resolved anymore by the from subgraph (it is marked \"@external\" in \"{from}\"). The @override directive can be removed."
+                            ),
+                        )
+                } else if label.is_some() {
+                    (
+                            HintCode::OverrideMigrationInProgress,
+                            format!(
+                                "Field \"{field_pos}\" on subgraph \"{subgraph_name}\" is currently being migrated with progressive @override. Once the migration is complete, remove the field from subgraph \"{from}\"."
+                            ),
+                        )
+                } else if from_source.is_used {
+                    (
+                            HintCode::OverriddenFieldCanBeRemoved,
+                            format!(
+                                "Field \"{field_pos}\" on subgraph \"{from}\" is overridden. It is still used in some federation directive(s) (@key, @requires, and/or @provides) and/or to satisfy interface constraint(s), but consider marking it @external explicitly or removing it along with its references."
+                            ),
+                        )
+                } else {
+                    (
+                            HintCode::OverriddenFieldCanBeRemoved,
+                            format!(
+                                "Field \"{field_pos}\" on subgraph \"{from}\" is overridden. Consider removing it."
+                            ),
+                        )
+                };
+                self.composition_hints.push(CompositionHint {
+                    code,
+                    message,
+                    locations: vec![from_source.location.clone()],
+                });
+                if from_source.is_external {
+                    continue;
+                }
+                let from_arguments = if from_source.is_used {
+                    let mut arguments = vec![Node::new(Argument {
+                        name: name!("usedOverridden"),
+                        value: Node::new(Value::Boolean(true)),
+                    })];
+                    arguments.extend(label_argument.take());
+                    Some(arguments)
+                } else {
+                    label_argument.take().map(|argument| vec![argument])
+                };
+                join_field_arguments.insert(&from_source.graph, from_arguments);
+                overridden_graphs.insert(from_source.graph.clone());
+            } else {
+                self.composition_hints.push(CompositionHint {
+                        code: HintCode::OverrideDirectiveCanBeRemoved,
+                        message: format!(
+                            "Field \"{field_pos}\" on subgraph \"{subgraph_name}\" no longer exists in the from subgraph. The @override directive can be removed."
+                        ),
+                        locations: vec![source.location.clone()],
+                    });
+            }
+        }
+
+        let Some(ExtendedType::Object(type_)) = supergraph.types.get_mut(&field_pos.type_name)
+        else {
+            return overridden_graphs;
+        };
+        let Some(field) = type_.make_mut().fields.get_mut(&field_pos.field_name) else {
+            return overridden_graphs;
+        };
+        let directives = &mut field.make_mut().directives;
+        // Whether the field is resolved by a subgraph now depends on the overrides, so each
+        // subgraph defining it must be listed explicitly.
+        let graphs = sources.iter().map(|source| source.graph.clone());
+        add_join_field_graphs(directives, graphs);
+        directives.retain(|directive| {
+            directive.name != "join__field"
+                || !join_field_arguments.iter().any(|(graph, arguments)| {
+                    arguments.is_none()
+                        && directive_arg_value(directive, &name!("graph"))
+                            == Some(&Value::Enum((*graph).clone()))
+                })
+        });
+        for directive in directives.iter_mut() {
+            let Some(Value::Enum(graph)) = directive_arg_value(directive, &name!("graph")) else {
+                continue;
+            };
+            if let Some(Some(arguments)) = join_field_arguments.get(graph) {
+                let arguments = arguments.clone();
+                directive.make_mut().arguments.extend(arguments);
+            }
+        }
+        overridden_graphs
+    }
+
+    /// Merges the definitions of the executable directives of the subgraphs. As the supergraph
+    /// can only forward to a subgraph the directives it supports, only directives defined in every
+    /// subgraph are kept, with the locations and arguments common to all subgraphs.
+    // PORT_NOTE: Named `mergeExecutableDirectiveDefinition()` in the JS codebase.
+    fn merge_executable_directives(
+        &mut self,
+        supergraph: &mut Schema,
+        subgraphs_and_enum_values: &[(&ValidSubgraph, Name)],
+    ) {
+        let mut definitions: IndexMap<&Name, Vec<_>> = IndexMap::new();
+        for (subgraph, graph) in subgraphs_and_enum_values {
+            for (directive_name, definition) in &subgraph.schema.directive_definitions {
+                // skip built-ins
+                if supergraph
+                    .directive_definitions
+                    .contains_key(directive_name)
+                {
+                    continue;
+                }
+                if is_executable_directive(definition) {
+                    definitions
+                        .entry(directive_name)
+                        .or_default()
+                        .push((*subgraph, graph, definition));
+                }
+            }
+        }
+
+        for (directive_name, sources) in definitions {
+            let locations = sources
+                .iter()
+                .map(|(subgraph, _, definition)| {
+                    SubgraphLocation::new(subgraph, definition.location())
+                })
+                .collect::<Vec<_>>();
+            if sources.len() < subgraphs_and_enum_values.len() {
+                self.composition_hints.push(CompositionHint {
+                    code: HintCode::InconsistentExecutableDirectivePresence,
+                    message: format!(
+                        "Executable directive \"@{directive_name}\" will not be part of the supergraph as it does not appear in all subgraphs: it is defined in {} but not in {}",
+                        print_subgraph_names(sources.iter().map(|(subgraph, _, _)| subgraph.name.as_str())),
+                        print_subgraph_names(
+                            subgraphs_and_enum_values
+                                .iter()
+                                .filter(|(subgraph, _)| !sources.iter().any(|(source, _, _)| source.name == subgraph.name))
+                                .map(|(subgraph, _)| subgraph.name.as_str())
+                        ),
+                    ),
+                    locations,
+                });
+                continue;
+            }
+
+            let executable_locations = |definition: &DirectiveDefinition| {
+                definition
+                    .locations
+                    .iter()
+                    .filter(|location| EXECUTABLE_DIRECTIVE_LOCATIONS.contains(location))
+                    .copied()
+                    .collect::<IndexSet<_>>()
+            };
+            let mut directive_locations = executable_locations(sources[0].2);
+            for (_, _, definition) in &sources[1..] {
+                directive_locations.retain(|location| definition.locations.contains(location));
+            }
+            if directive_locations.is_empty() {
+                self.composition_hints.push(CompositionHint {
+                    code: HintCode::NoExecutableDirectiveLocationsIntersection,
+                    message: format!(
+                        "Executable directive \"@{directive_name}\" has no location that is common to all subgraphs: it will not be part of the supergraph"
+                    ),
+                    locations,
+                });
+                continue;
+            }
+            let describe_locations = |locations: &IndexSet<DirectiveLocation>| {
+                let (plural, locations) = match locations.len() {
+                    1 => ("", locations[0].to_string()),
+                    _ => (
+                        "s",
+                        locations
+                            .iter()
+                            .map(|location| location.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                };
+                format!("location{plural} \"{locations}\"")
+            };
+            if sources
+                .iter()
+                .any(|(_, _, definition)| executable_locations(definition) != directive_locations)
+            {
+                self.composition_hints.push(CompositionHint {
+                    code: HintCode::InconsistentExecutableDirectiveLocations,
+                    message: format!(
+                        "Executable directive \"@{directive_name}\" has inconsistent locations across subgraphs and will use {} (intersection of all subgraphs) in the supergraph, but has: {}",
+                        describe_locations(&directive_locations),
+                        describe_subgraph_mismatch(sources.iter().map(|(subgraph, _, definition)| {
+                            (subgraph.name.as_str(), Some(describe_locations(&executable_locations(definition))))
+                        })),
+                    ),
+                    locations: locations.clone(),
+                });
+            }
+
+            let repeatable = sources
+                .iter()
+                .all(|(_, _, definition)| definition.repeatable);
+            if !repeatable
+                && sources
+                    .iter()
+                    .any(|(_, _, definition)| definition.repeatable)
+            {
+                self.composition_hints.push(CompositionHint {
+                    code: HintCode::InconsistentExecutableDirectiveRepeatable,
+                    message: format!(
+                        "Executable directive \"@{directive_name}\" will not be marked repeatable in the supergraph as it is inconsistently marked repeatable in subgraphs: it is {}",
+                        describe_subgraph_mismatch(sources.iter().map(|(subgraph, _, definition)| {
+                            let repeatable = if definition.repeatable { "repeatable" } else { "not repeatable" };
+                            (subgraph.name.as_str(), Some(repeatable.to_owned()))
+                        })),
+                    ),
+                    locations: locations.clone(),
+                });
+            }
+
+            let mut description = None;
+            let mut argument_sources: IndexMap<&Name, Vec<FieldSource>> = IndexMap::new();
+            for (subgraph, graph, definition) in &sources {
+                self.merge_descriptions(
+                    &mut description,
+                    &definition.description,
+                    subgraph,
+                    format_args!("@{directive_name}"),
+                );
+                for argument in &definition.arguments {
+                    argument_sources
+                        .entry(&argument.name)
+                        .or_default()
+                        .push(FieldSource {
+                            graph: (*graph).clone(),
+                            location: SubgraphLocation::new(subgraph, argument.location()),
+                            ty: argument.ty.as_ref().clone(),
+                            default_value: argument.default_value.clone(),
+                            is_external: false,
+                        });
+                }
+            }
+            let subgraph_names = sources
+                .iter()
+                .map(|(subgraph, _, _)| subgraph.name.as_str())
+                .collect::<Vec<_>>();
+            let mut arguments = Vec::new();
+            for (argument_name, argument_sources) in &argument_sources {
+                let argument_sources = argument_sources.iter().collect::<Vec<_>>();
+                let coordinate = format!("@{directive_name}({argument_name}:)");
+                if !self.merge_argument_presence(&coordinate, &argument_sources, &subgraph_names) {
+                    continue;
+                }
+                let Some((ty, _)) = self.merge_type_reference(
+                    supergraph,
+                    &argument_sources,
+                    true,
+                    &format!("argument \"{coordinate}\""),
+                    |message| SingleFederationError::FieldArgumentTypeMismatch { message },
+                    HintCode::InconsistentButCompatibleArgumentType,
+                ) else {
+                    continue;
+                };
+                let default_value =
+                    self.merge_default_value(&argument_sources, "Argument", &coordinate);
+                let mut argument_description = None;
+                for (subgraph, _, definition) in &sources {
+                    let Some(argument) = definition.argument_by_name(argument_name) else {
+                        continue;
+                    };
+                    self.merge_descriptions(
+                        &mut argument_description,
+                        &argument.description,
+                        subgraph,
+                        &coordinate,
+                    );
+                }
+                arguments.push(Node::new(InputValueDefinition {
+                    description: argument_description,
+                    name: (*argument_name).clone(),
+                    ty: ty.into(),
+                    default_value,
+                    directives: Default::default(),
+                }));
+            }
+
+            supergraph.directive_definitions.insert(
+                directive_name.clone(),
+                Node::new(DirectiveDefinition {
+                    description,
+                    name: directive_name.clone(),
+                    arguments,
+                    repeatable,
+                    locations: directive_locations.into_iter().collect(),
+                }),
+            );
+        }
+    }
+
+    /// Checks that an argument is defined in every subgraph defining its parent element, returning
+    /// whether the argument is part of the supergraph. Optional arguments missing from some
+    /// subgraphs are left out of the supergraph, while required ones are an error.
+    // PORT_NOTE: Part of `addArgumentsShallow()` in the JS codebase.
+    fn merge_argument_presence(
+        &mut self,
+        coordinate: &str,
+        sources: &[&FieldSource],
+        parent_subgraphs: &[&str],
+    ) -> bool {
+        if sources.len() >= parent_subgraphs.len() {
+            return true;
+        }
+        let missing_subgraphs = parent_subgraphs
+            .iter()
+            .filter(|subgraph| {
+                !sources
+                    .iter()
+                    .any(|source| source.location.subgraph == **subgraph)
+            })
+            .copied();
+        let locations = sources
+            .iter()
+            .map(|source| source.location.clone())
+            .collect::<Vec<_>>();
+        let required_sources = sources
+            .iter()
+            .filter(|source| source.ty.is_non_null() && source.default_value.is_none())
+            .collect::<Vec<_>>();
+        if required_sources.is_empty() {
+            self.composition_hints.push(CompositionHint {
+                code: HintCode::InconsistentArgumentPresence,
+                message: format!(
+                    "Optional argument \"{coordinate}\" will not be included in the supergraph as it does not appear in all subgraphs: it is defined in {} but not in {}",
+                    print_subgraph_names(sources.iter().map(|source| source.location.subgraph.as_str())),
+                    print_subgraph_names(missing_subgraphs),
+                ),
+                locations,
+            });
+        } else {
+            self.errors.push(MergeError {
+                error: SingleFederationError::RequiredArgumentMissingInSomeSubgraph {
+                    message: format!(
+                        "Argument \"{coordinate}\" is required in some subgraphs but does not appear in all subgraphs: it is required in {} but does not appear in {}",
+                        print_subgraph_names(required_sources.iter().map(|source| source.location.subgraph.as_str())),
+                        print_subgraph_names(missing_subgraphs),
+                    ),
+                },
+                locations,
+            });
+        }
+        false
+    }
+
+    /// Sets the supergraph type of fields, arguments and input fields from the types they have in
+    /// subgraphs. Output positions use the most general of those types and input positions the
+    /// most restrictive one, so that the supergraph type is valid for every subgraph. When
+    /// subgraphs disagree, the type of each subgraph is recorded in `@join__field(type:)`.
+    fn merge_field_types(&mut self, supergraph: &mut Schema) {
+        let field_sources = std::mem::take(&mut self.field_sources);
+        for (field_pos, sources) in &field_sources {
+            // The type of external fields is validated against the non-external ones rather than
+            // merged.
+            let sources = sources
+                .iter()
+                .filter(|source| !source.is_external)
+                .collect::<Vec<_>>();
+            let Some((ty, has_subtypes)) = self.merge_type_reference(
+                supergraph,
+                &sources,
+                false,
+                &format!("field \"{field_pos}\""),
+                |message| SingleFederationError::FieldTypeMismatch { message },
+                HintCode::InconsistentButCompatibleFieldType,
+            ) else {
+                continue;
+            };
+            let Some(parent_type) = supergraph.types.get_mut(field_pos.type_name()) else {
+                continue;
+            };
+            let type_graph_count = parent_type
+                .directives()
+                .get_all("join__type")
+                .filter_map(|directive| directive_arg_value(directive, &name!("graph")))
+                .collect::<IndexSet<_>>()
+                .len();
+            let Some(field) = (match parent_type {
+                ExtendedType::Object(type_) => {
+                    type_.make_mut().fields.get_mut(field_pos.field_name())
+                }
+                ExtendedType::Interface(type_) => {
+                    type_.make_mut().fields.get_mut(field_pos.field_name())
+                }
+                _ => None,
+            }) else {
+                continue;
+            };
+            let field = field.make_mut();
+            field.ty = ty;
+            // Without `@join__field`, a field is assumed to be resolvable by every subgraph
+            // defining its parent type, so fields only some of those subgraphs define must list
+            // them explicitly, as must fields that already list some of them.
+            if field_sources[field_pos].len() < type_graph_count
+                || field.directives.has("join__field")
+            {
+                let graphs = field_sources[field_pos]
+                    .iter()
+                    .map(|source| source.graph.clone());
+                add_join_field_graphs(&mut field.directives, graphs);
+            }
+            if has_subtypes {
+                let sources = &field_sources[field_pos];
+                add_join_field_types(&mut field.directives, sources);
+            }
+        }
+
+        // Arguments are only kept in the supergraph when every subgraph resolving the field defines
+        // them, as the query planner may send them to any of those subgraphs.
+        let argument_sources = std::mem::take(&mut self.argument_sources);
+        for ((field_pos, argument_name), sources) in &argument_sources {
+            let sources = sources
+                .iter()
+                .filter(|source| !source.is_external)
+                .collect::<Vec<_>>();
+            let field_subgraphs = field_sources
+                .get(field_pos)
+                .into_iter()
+                .flatten()
+                .filter(|source| !source.is_external)
+                .map(|source| source.location.subgraph.as_str())
+                .collect::<Vec<_>>();
+            let coordinate = format!("{field_pos}({argument_name}:)");
+            let merged = if self.merge_argument_presence(&coordinate, &sources, &field_subgraphs) {
+                self.merge_type_reference(
+                    supergraph,
+                    &sources,
+                    true,
+                    &format!("argument \"{coordinate}\""),
+                    |message| SingleFederationError::FieldArgumentTypeMismatch { message },
+                    HintCode::InconsistentButCompatibleArgumentType,
+                )
+                .map(|(ty, _)| {
+                    let default_value = self.merge_default_value(&sources, "Argument", &coordinate);
+                    (ty, default_value)
+                })
+            } else {
+                None
+            };
+            let Some(field) = supergraph
+                .types
+                .get_mut(field_pos.type_name())
+                .and_then(|type_| match type_ {
+                    ExtendedType::Object(type_) => {
+                        type_.make_mut().fields.get_mut(field_pos.field_name())
+                    }
+                    ExtendedType::Interface(type_) => {
+                        type_.make_mut().fields.get_mut(field_pos.field_name())
+                    }
+                    _ => None,
+                })
+            else {
+                continue;
+            };
+            let arguments = &mut field.make_mut().arguments;
+            let Some((ty, default_value)) = merged else {
+                arguments.retain(|argument| argument.name != *argument_name);
+                continue;
+            };
+            let Some(argument) = arguments
+                .iter_mut()
+                .find(|argument| argument.name == *argument_name)
+            else {
+                continue;
+            };
+            let argument = argument.make_mut();
+            *argument.ty.make_mut() = ty;
+            argument.default_value = default_value;
+        }
+
+        let input_field_sources = std::mem::take(&mut self.input_field_sources);
+        for (field_pos, sources) in &input_field_sources {
+            let sources = sources.iter().collect::<Vec<_>>();
+            let Some((ty, has_subtypes)) = self.merge_type_reference(
+                supergraph,
+                &sources,
+                true,
+                &format!("input field \"{field_pos}\""),
+                |message| SingleFederationError::FieldTypeMismatch { message },
+                HintCode::InconsistentButCompatibleFieldType,
+            ) else {
+                continue;
+            };
+            let default_value =
+                self.merge_default_value(&sources, "Input field", &field_pos.to_string());
+            let Some(ExtendedType::InputObject(type_)) =
+                supergraph.types.get_mut(&field_pos.type_name)
+            else {
+                continue;
+            };
+            let Some(field) = type_.make_mut().fields.get_mut(&field_pos.field_name) else {
+                continue;
+            };
+            let field = field.make_mut();
+            *field.ty.make_mut() = ty;
+            field.default_value = default_value;
+            if has_subtypes {
+                add_join_field_types(&mut field.directives, &input_field_sources[field_pos]);
+            }
+        }
+    }
+
+    /// Merges the types of the given sources, returning the merged type and whether some sources
+    /// have a different (but compatible) type. Returns `None` when the types are incompatible, in
+    /// which case an error is recorded with the given error kind.
+    // PORT_NOTE: Named `mergeTypeReference()` in the JS codebase. The JS codebase doesn't
+    // distinguish between fields and arguments through parameters, but rather through the type of
+    // the destination element.
+    fn merge_type_reference(
+        &mut self,
+        supergraph: &Schema,
+        sources: &[&FieldSource],
+        is_input_position: bool,
+        element: &str,
+        mismatch_error: fn(String) -> SingleFederationError,
+        inconsistency_hint: HintCode,
+    ) -> Option<(Type, bool)> {
+        let (first, rest) = sources.split_first()?;
+        let mut merged = &first.ty;
+        let mut has_subtypes = false;
+        let mut has_incompatible = false;
+        for source in rest {
+            if source.ty == *merged {
+                continue;
+            }
+            if is_strict_subtype(supergraph, merged, &source.ty) {
+                has_subtypes = true;
+                if is_input_position {
+                    merged = &source.ty;
+                }
+            } else if is_strict_subtype(supergraph, &source.ty, merged) {
+                has_subtypes = true;
+                if !is_input_position {
+                    merged = &source.ty;
+                }
+            } else {
+                has_incompatible = true;
+            }
+        }
+
+        let locations = sources
+            .iter()
+            .map(|source| source.location.clone())
+            .collect::<Vec<_>>();
+        if has_incompatible {
+            let message = format!(
+                "Type of {element} is incompatible across subgraphs: it has {}",
+                describe_mismatch(sources, |source| Some(format!("type \"{}\"", source.ty))),
+            );
+            self.errors.push(MergeError {
+                error: mismatch_error(message),
+                locations,
+            });
+            return None;
+        }
+        if has_subtypes {
+            let (from, others): (Vec<&FieldSource>, Vec<&FieldSource>) =
+                sources.iter().partition(|source| source.ty == *merged);
+            let supertype_or_subtype = if is_input_position {
+                "supertype"
+            } else {
+                "subtype"
+            };
+            self.composition_hints.push(CompositionHint {
+                code: inconsistency_hint,
+                message: format!(
+                    "Type of {element} is inconsistent but compatible across subgraphs: will use type \"{merged}\" (from {}) in supergraph but it has {supertype_or_subtype} {}",
+                    print_subgraph_names(from.iter().map(|source| source.location.subgraph.as_str())),
+                    describe_mismatch(&others, |source| Some(format!("\"{}\"", source.ty))),
+                ),
+                locations,
+            });
+        }
+        Some((merged.clone(), has_subtypes))
+    }
+
+    /// Merges the default values of the given sources. Differing default values are an error,
+    /// while a default value defined in only some subgraphs is dropped from the supergraph.
+    // PORT_NOTE: Named `mergeDefaultValue()` in the JS codebase.
+    fn merge_default_value(
         &mut self,
-        types: &mut IndexMap<NamedType, ExtendedType>,
-        subgraph_name: Name,
-        input_object_name: NamedType,
-        input_object: &Node<InputObjectType>,
-    ) {
-        let existing_type = types
-            .entry(input_object_name.clone())
-            .or_insert(copy_input_object_type(input_object_name, input_object));
-        if let ExtendedType::InputObject(obj) = existing_type {
-            let join_type_directives =
-                join_type_applied_directive(subgraph_name, iter::empty(), false);
-            let mutable_object = obj.make_mut();
-            mutable_object.directives.extend(join_type_directives);
-
-            for (field_name, _field) in input_object.fields.iter() {
-                let existing_field = mutable_object.fields.entry(field_name.clone());
-                match existing_field {
-                    Vacant(_i) => {
-                        // TODO warning - mismatch on input fields
+        sources: &[&FieldSource],
+        kind: &str,
+        coordinate: &str,
+    ) -> Option<Node<Value>> {
+        let mut merged: Option<&Node<Value>> = None;
+        let mut is_inconsistent = false;
+        let mut is_incompatible = false;
+        for (index, source) in sources.iter().enumerate() {
+            match (merged, &source.default_value) {
+                (None, Some(default_value)) => {
+                    merged = Some(default_value);
+                    if index > 0 {
+                        is_inconsistent = true;
                     }
-                    Occupied(_i) => {
-                        // merge_options(&i.get_mut().description, &field.description);
-                        // TODO check description
-                        // TODO check type
-                        // TODO check default value
-                        // TODO process directives
+                }
+                (None, None) => {}
+                (Some(_), None) => is_inconsistent = true,
+                (Some(merged), Some(default_value)) => {
+                    if merged != default_value {
+                        is_inconsistent = true;
+                        is_incompatible = true;
                     }
                 }
             }
+        }
+
+        let locations = sources
+            .iter()
+            .map(|source| source.location.clone())
+            .collect::<Vec<_>>();
+        if is_incompatible {
+            let message = format!(
+                "{kind} \"{coordinate}\" has incompatible default values across subgraphs: it has {}",
+                describe_mismatch(sources, |source| {
+                    source
+                        .default_value
+                        .as_ref()
+                        .map(|value| format!("default value {value}"))
+                }),
+            );
+            let error = if kind == "Argument" {
+                SingleFederationError::FieldArgumentDefaultMismatch { message }
+            } else {
+                SingleFederationError::InputFieldDefaultMismatch { message }
+            };
+            self.errors.push(MergeError { error, locations });
+            merged.cloned()
+        } else if is_inconsistent {
+            let (with_default, without_default): (Vec<&FieldSource>, Vec<&FieldSource>) = sources
+                .iter()
+                .partition(|source| source.default_value.is_some());
+            self.composition_hints.push(CompositionHint {
+                code: HintCode::InconsistentDefaultValuePresence,
+                message: format!(
+                    "{kind} \"{coordinate}\" has a default value in only some subgraphs: will not use a default in the supergraph (there is no default in {}) but it has {}",
+                    print_subgraph_names(
+                        without_default
+                            .iter()
+                            .map(|source| source.location.subgraph.as_str())
+                    ),
+                    describe_mismatch(&with_default, |source| {
+                        source
+                            .default_value
+                            .as_ref()
+                            .map(|value| format!("default value {value}"))
+                    }),
+                ),
+                locations,
+            });
+            None
         } else {
-            // TODO conflict on type
+            merged.cloned()
         }
     }
 
-    fn merge_interface_type(
-        &mut self,
-        types: &mut IndexMap<NamedType, ExtendedType>,
-        subgraph_name: Name,
-        interface_name: NamedType,
-        interface: &Node<InterfaceType>,
-    ) {
-        let existing_type = types
-            .entry(interface_name.clone())
-            .or_insert(copy_interface_type(interface_name, interface));
-        if let ExtendedType::Interface(intf) = existing_type {
-            let key_directives = interface.directives.get_all("key");
-            let join_type_directives =
-                join_type_applied_directive(subgraph_name, key_directives, false);
-            let mutable_intf = intf.make_mut();
-            mutable_intf.directives.extend(join_type_directives);
+    /// Merges the values of enum types depending on how they're used in the supergraph. Enums
+    /// only used as output take the union of the values of all subgraphs, since a subgraph never
+    /// receives values it doesn't know. Enums used as input only take the values common to all
+    /// subgraphs, and enums used as both input and output must define the same values everywhere.
+    // PORT_NOTE: Named `mergeEnum()` in the JS codebase, where it's called after merging all the
+    // other types rather than as a separate pass.
+    fn merge_enum_values(&mut self, supergraph: &mut Schema) {
+        let enum_sources = std::mem::take(&mut self.enum_sources);
+        let usages = collect_enum_usages(supergraph);
+        for (enum_name, sources) in &enum_sources {
+            let Some(ExtendedType::Enum(enum_type)) = supergraph.types.get_mut(enum_name) else {
+                continue;
+            };
+            let locations = sources
+                .iter()
+                .map(|source| source.location.clone())
+                .collect::<Vec<_>>();
+            let usage = usages.get(enum_name);
+            let input_usage = usage.and_then(|usage| usage.input.as_ref());
+            let output_usage = usage.and_then(|usage| usage.output.as_ref());
+            if input_usage.is_none() && output_usage.is_none() {
+                self.composition_hints.push(CompositionHint {
+                    code: HintCode::UnusedEnumType,
+                    message: format!(
+                        "Enum type \"{enum_name}\" is defined but unused. It will be merged as if it was a type used as output."
+                    ),
+                    locations: locations.clone(),
+                });
+            }
 
-            for (field_name, field) in interface.fields.iter() {
-                let existing_field = mutable_intf.fields.entry(field_name.clone());
-                match existing_field {
-                    Vacant(i) => {
-                        // TODO warning mismatch missing fields
-                        i.insert(Component::new(FieldDefinition {
-                            name: field.name.clone(),
-                            description: field.description.clone(),
-                            arguments: vec![],
-                            ty: field.ty.clone(),
-                            directives: Default::default(),
-                        }));
+            let mut values_to_remove = Vec::new();
+            for value_name in enum_type.values.keys() {
+                let (defining, not_defining): (Vec<&EnumSource>, Vec<&EnumSource>) = sources
+                    .iter()
+                    .partition(|source| source.values.contains(value_name));
+                if not_defining.is_empty() {
+                    continue;
+                }
+                let inconsistency = format!(
+                    "it is defined in {} but not in {}",
+                    print_subgraph_names(
+                        defining
+                            .iter()
+                            .map(|source| source.location.subgraph.as_str())
+                    ),
+                    print_subgraph_names(
+                        not_defining
+                            .iter()
+                            .map(|source| source.location.subgraph.as_str())
+                    ),
+                );
+                match (input_usage, output_usage) {
+                    (Some(input_usage), Some(output_usage)) => {
+                        self.errors.push(MergeError {
+                            error: SingleFederationError::EnumValueMismatch {
+                                message: format!(
+                                    "Enum type \"{enum_name}\" is used as both input type (for example, as type of \"{input_usage}\") and output type (for example, as type of \"{output_usage}\"), but value \"{value_name}\" is not defined in all the subgraphs defining \"{enum_name}\": {inconsistency}"
+                                ),
+                            },
+                            locations: locations.clone(),
+                        });
                     }
-                    Occupied(_i) => {
-                        // TODO check description
-                        // TODO check type
-                        // TODO check default value
-                        // TODO process directives
+                    (Some(_), None) => {
+                        values_to_remove.push(value_name.clone());
+                        self.composition_hints.push(CompositionHint {
+                            code: HintCode::InconsistentEnumValueForInputEnum,
+                            message: format!(
+                                "Value \"{value_name}\" of enum type \"{enum_name}\" will not be part of the supergraph as it is not defined in all the subgraphs defining \"{enum_name}\": {inconsistency}"
+                            ),
+                            locations: locations.clone(),
+                        });
+                    }
+                    (None, _) => {
+                        self.composition_hints.push(CompositionHint {
+                            code: HintCode::InconsistentEnumValueForOutputEnum,
+                            message: format!(
+                                "Value \"{value_name}\" of enum type \"{enum_name}\" has been added to the supergraph but is only defined in a subset of the subgraphs defining \"{enum_name}\": {inconsistency}"
+                            ),
+                            locations: locations.clone(),
+                        });
                     }
                 }
             }
-        } else {
-            // TODO conflict on type
+
+            if !values_to_remove.is_empty() {
+                let enum_type = enum_type.make_mut();
+                for value_name in values_to_remove {
+                    enum_type.values.shift_remove(&value_name);
+                }
+                if enum_type.values.is_empty() {
+                    self.errors.push(MergeError {
+                        error: SingleFederationError::EmptyMergedEnumType {
+                            message: format!(
+                                "None of the values of enum type \"{enum_name}\" are defined consistently in all the subgraphs defining that type. As only values common to all subgraphs are merged, this would result in an empty type."
+                            ),
+                        },
+                        locations,
+                    });
+                }
+            }
         }
     }
 
-    fn merge_object_type(
+    fn merge_scalar_type(
         &mut self,
         types: &mut IndexMap<NamedType, ExtendedType>,
+        subgraph: &ValidSubgraph,
         subgraph_name: Name,
-        object_name: NamedType,
-        object: &Node<ObjectType>,
+        scalar_name: NamedType,
+        scalar: &Node<ScalarType>,
     ) {
-        let is_interface_object = object.directives.has("interfaceObject");
         let existing_type = types
-            .entry(object_name.clone())
-            .or_insert(copy_object_type_stub(
-                object_name.clone(),
-                object,
-                is_interface_object,
-            ));
-        if let ExtendedType::Object(obj) = existing_type {
-            let key_fields: HashSet<&str> = parse_keys(object.directives.get_all("key"));
-            let is_join_field = !key_fields.is_empty() || object_name == "Query";
-            let key_directives = object.directives.get_all("key");
+            .entry(scalar_name.clone())
+            .or_insert(copy_scalar_type(scalar_name.clone(), scalar));
+        if let ExtendedType::Scalar(s) = existing_type {
             let join_type_directives =
-                join_type_applied_directive(subgraph_name.clone(), key_directives, false);
-            let mutable_object = obj.make_mut();
-            mutable_object.directives.extend(join_type_directives);
-            self.merge_descriptions(&mut mutable_object.description, &object.description);
-            object.implements_interfaces.iter().for_each(|intf_name| {
-                // IndexSet::insert deduplicates
-                mutable_object
-                    .implements_interfaces
-                    .insert(intf_name.clone());
-                let join_implements_directive =
-                    join_type_implements(subgraph_name.clone(), intf_name);
-                mutable_object.directives.push(join_implements_directive);
-            });
+                join_type_applied_directive(subgraph_name, iter::empty(), false);
+            s.make_mut().directives.extend(join_type_directives);
+            self.merge_descriptions(
+                &mut s.make_mut().description,
+                &scalar.description,
+                subgraph,
+                &scalar_name,
+            );
+            self.add_access_control_sources(
+                subgraph,
+                &scalar_name,
+                None,
+                scalar.directives.iter().map(|d| d.as_ref()),
+            );
+        } else {
+            // TODO conflict on type
+        }
+    }
 
-            for (field_name, field) in object.fields.iter() {
-                // skip federation built-in queries
-                if field_name == "_service" || field_name == "_entities" {
-                    continue;
+    /// Records the `@authenticated`, `@requiresScopes` and `@policy` applications of a subgraph
+    /// type or field, to be merged by `merge_access_control_directives()`.
+    fn add_access_control_sources<'a>(
+        &mut self,
+        subgraph: &ValidSubgraph,
+        type_name: &NamedType,
+        field_name: Option<&Name>,
+        directives: impl Iterator<Item = &'a Directive>,
+    ) {
+        let Some(names) = self.access_control_directive_names.get(&subgraph.name) else {
+            return;
+        };
+        for application in directives {
+            let Some((directive, _)) = names.iter().find(|(_, name)| *name == application.name)
+            else {
+                continue;
+            };
+            let values = self
+                .access_control_sources
+                .entry((type_name.clone(), field_name.cloned()))
+                .or_default()
+                .entry(*directive)
+                .or_default();
+            if let Some((argument_name, _)) = directive.argument() {
+                if let Some(value) = application.argument_by_name(&argument_name) {
+                    values.push(value.as_ref().clone());
                 }
+            }
+        }
+    }
 
-                let existing_field = mutable_object.fields.entry(field_name.clone());
-                let supergraph_field = match existing_field {
-                    Occupied(f) => {
-                        // check description
-                        // check type
-                        // check args
-                        f.into_mut()
-                    }
-                    Vacant(f) => f.insert(Component::new(FieldDefinition {
-                        name: field.name.clone(),
-                        description: field.description.clone(),
-                        arguments: vec![],
-                        directives: Default::default(),
-                        ty: field.ty.clone(),
-                    })),
-                };
-                self.merge_descriptions(
-                    &mut supergraph_field.make_mut().description,
-                    &field.description,
-                );
-                for arg in field.arguments.iter() {
-                    if let Some(_existing_arg) = supergraph_field.argument_by_name(&arg.name) {
-                    } else {
-                        // TODO mismatch no args
+    /// Applies the access-control directives recorded by `add_access_control_sources()` to the
+    /// supergraph, and links the specs of the directives that are used. An element requires the
+    /// union of the scopes or policies it requires in each subgraph.
+    fn merge_access_control_directives(&mut self, supergraph: &mut Schema) {
+        let mut used_directives = IndexSet::new();
+        for ((type_name, field_name), sources) in &self.access_control_sources {
+            let directives = AccessControlDirective::ALL
+                .into_iter()
+                .filter_map(|directive| {
+                    let values = sources.get(&directive)?;
+                    let arguments = match directive.argument() {
+                        Some((argument_name, _)) => vec![Node::new(Argument {
+                            name: argument_name,
+                            value: Node::new(
+                                ArgumentCompositionStrategy::Union.merge_values(values),
+                            ),
+                        })],
+                        None => Vec::new(),
+                    };
+                    used_directives.insert(directive);
+                    Some(Directive {
+                        name: directive.name(),
+                        arguments,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let Some(type_) = supergraph.types.get_mut(type_name) else {
+                continue;
+            };
+            match (type_, field_name) {
+                (ExtendedType::Object(object), Some(field_name)) => {
+                    if let Some(field) = object.make_mut().fields.get_mut(field_name) {
+                        field
+                            .make_mut()
+                            .directives
+                            .extend(directives.into_iter().map(Node::new));
                     }
                 }
-
-                if is_join_field {
-                    let is_key_field = key_fields.contains(field_name.as_str());
-                    if !is_key_field {
-                        let requires_directive_option =
-                            Option::and_then(field.directives.get_all("requires").next(), |p| {
-                                let requires_fields =
-                                    directive_string_arg_value(p, &name!("fields")).unwrap();
-                                Some(requires_fields.as_str())
-                            });
-                        let provides_directive_option =
-                            Option::and_then(field.directives.get_all("provides").next(), |p| {
-                                let provides_fields =
-                                    directive_string_arg_value(p, &name!("fields")).unwrap();
-                                Some(provides_fields.as_str())
-                            });
-                        let external_field = field.directives.get_all("external").next().is_some();
-                        let join_field_directive = join_field_applied_directive(
-                            subgraph_name.clone(),
-                            requires_directive_option,
-                            provides_directive_option,
-                            external_field,
-                        );
-
-                        supergraph_field
+                (ExtendedType::Interface(intf), Some(field_name)) => {
+                    if let Some(field) = intf.make_mut().fields.get_mut(field_name) {
+                        field
                             .make_mut()
                             .directives
-                            .push(Node::new(join_field_directive));
+                            .extend(directives.into_iter().map(Node::new));
                     }
                 }
+                (ExtendedType::Object(object), None) => object
+                    .make_mut()
+                    .directives
+                    .extend(directives.into_iter().map(Component::new)),
+                (ExtendedType::Interface(intf), None) => intf
+                    .make_mut()
+                    .directives
+                    .extend(directives.into_iter().map(Component::new)),
+                (ExtendedType::Scalar(scalar), None) => scalar
+                    .make_mut()
+                    .directives
+                    .extend(directives.into_iter().map(Component::new)),
+                (ExtendedType::Enum(enum_), None) => enum_
+                    .make_mut()
+                    .directives
+                    .extend(directives.into_iter().map(Component::new)),
+                _ => {}
             }
-        } else if let ExtendedType::Interface(intf) = existing_type {
-            // TODO support interface object
-            let key_directives = object.directives.get_all("key");
-            let join_type_directives =
-                join_type_applied_directive(subgraph_name, key_directives, true);
-            intf.make_mut().directives.extend(join_type_directives);
-        };
-        // TODO merge fields
+        }
+        for directive in used_directives {
+            add_core_feature_access_control(supergraph, directive);
+        }
     }
 
     fn merge_union_type(
@@ -469,6 +2435,163 @@ impl Merger {
     }
 }
 
+/// An example of an input and of an output position in which an enum type is used.
+#[derive(Default)]
+struct EnumUsage {
+    input: Option<String>,
+    output: Option<String>,
+}
+
+fn collect_enum_usages(supergraph: &Schema) -> IndexMap<NamedType, EnumUsage> {
+    let mut usages: IndexMap<NamedType, EnumUsage> = IndexMap::new();
+    let mut add_usage = |ty: &Type, coordinate: &dyn Fn() -> String, is_input: bool| {
+        let type_name = ty.inner_named_type();
+        if !matches!(supergraph.types.get(type_name), Some(ExtendedType::Enum(_))) {
+            return;
+        }
+        let usage = usages.entry(type_name.clone()).or_default();
+        let example = if is_input {
+            &mut usage.input
+        } else {
+            &mut usage.output
+        };
+        if example.is_none() {
+            *example = Some(coordinate());
+        }
+    };
+    for (type_name, type_) in &supergraph.types {
+        let fields = match type_ {
+            ExtendedType::Object(type_) => &type_.fields,
+            ExtendedType::Interface(type_) => &type_.fields,
+            ExtendedType::InputObject(type_) => {
+                for (field_name, field) in &type_.fields {
+                    add_usage(&field.ty, &|| format!("{type_name}.{field_name}"), true);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        for (field_name, field) in fields {
+            add_usage(&field.ty, &|| format!("{type_name}.{field_name}"), false);
+            for argument in &field.arguments {
+                add_usage(
+                    &argument.ty,
+                    &|| format!("{type_name}.{field_name}({}:)", argument.name),
+                    true,
+                );
+            }
+        }
+    }
+    for (directive_name, directive) in &supergraph.directive_definitions {
+        for argument in &directive.arguments {
+            add_usage(
+                &argument.ty,
+                &|| format!("@{directive_name}({}:)", argument.name),
+                true,
+            );
+        }
+    }
+    usages
+}
+
+/// Describes the distinct values of the given sources along with the subgraphs having each of
+/// them, e.g. `type "Int" in subgraph "A" but type "String" in subgraphs "B" and "C"`. Sources
+/// for which `describe` returns `None` are skipped.
+fn describe_mismatch(
+    sources: &[&FieldSource],
+    describe: impl Fn(&FieldSource) -> Option<String>,
+) -> String {
+    describe_subgraph_mismatch(
+        sources
+            .iter()
+            .map(|source| (source.location.subgraph.as_str(), describe(source))),
+    )
+}
+
+/// Same as `describe_mismatch()`, from the subgraph and description of each element.
+fn describe_subgraph_mismatch<'a>(
+    descriptions: impl Iterator<Item = (&'a str, Option<String>)>,
+) -> String {
+    let mut subgraphs_by_description: IndexMap<String, Vec<&str>> = IndexMap::new();
+    for (subgraph, description) in descriptions {
+        if let Some(description) = description {
+            subgraphs_by_description
+                .entry(description)
+                .or_default()
+                .push(subgraph);
+        }
+    }
+    subgraphs_by_description
+        .into_iter()
+        .map(|(description, subgraphs)| {
+            format!(
+                "{description} in {}",
+                print_subgraph_names(subgraphs.into_iter())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" but ")
+}
+
+pub(crate) fn print_subgraph_names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    let names = names.map(|name| format!("\"{name}\"")).collect::<Vec<_>>();
+    match names.as_slice() {
+        [] => "no subgraph".to_owned(),
+        [name] => format!("subgraph {name}"),
+        [init @ .., last] => format!("subgraphs {} and {last}", init.join(", ")),
+    }
+}
+
+/// Adds a `@join__field` application for each of the given graphs that doesn't have one yet.
+fn add_join_field_graphs(directives: &mut DirectiveList, graphs: impl IntoIterator<Item = Name>) {
+    for graph in graphs {
+        let graph = Value::Enum(graph);
+        let has_join_field = directives.iter().any(|directive| {
+            directive.name == "join__field"
+                && directive_arg_value(directive, &name!("graph")) == Some(&graph)
+        });
+        if !has_join_field {
+            directives.push(Node::new(Directive {
+                name: name!("join__field"),
+                arguments: vec![Node::new(Argument {
+                    name: name!("graph"),
+                    value: Node::new(graph),
+                })],
+            }));
+        }
+    }
+}
+
+/// Adds the type each subgraph gives to an element to its `@join__field` applications, adding
+/// applications for subgraphs that don't have one yet.
+fn add_join_field_types(directives: &mut DirectiveList, sources: &[FieldSource]) {
+    for source in sources {
+        let type_argument = Node::new(Argument {
+            name: name!("type"),
+            value: Node::new(Value::String(NodeStr::new(&source.ty.to_string()))),
+        });
+        let existing = directives.iter_mut().find(|directive| {
+            directive.name == "join__field"
+                && directive_arg_value(directive, &name!("graph"))
+                    == Some(&Value::Enum(source.graph.clone()))
+        });
+        if let Some(directive) = existing {
+            directive.make_mut().arguments.push(type_argument);
+        } else {
+            directives.push(Node::new(Directive {
+                name: name!("join__field"),
+                arguments: vec![
+                    Node::new(Argument {
+                        name: name!("graph"),
+                        value: Node::new(Value::Enum(source.graph.clone())),
+                    }),
+                    type_argument,
+                ],
+            }));
+        }
+    }
+}
+
 const EXECUTABLE_DIRECTIVE_LOCATIONS: [DirectiveLocation; 8] = [
     DirectiveLocation::Query,
     DirectiveLocation::Mutation,
@@ -496,6 +2619,14 @@ fn is_mergeable_type(type_name: &str) -> bool {
     !FEDERATION_TYPES.contains(&type_name)
 }
 
+fn copy_scalar_type(scalar_name: Name, scalar: &Node<ScalarType>) -> ExtendedType {
+    ExtendedType::Scalar(Node::new(ScalarType {
+        description: scalar.description.clone(),
+        name: scalar_name,
+        directives: Default::default(),
+    }))
+}
+
 fn copy_enum_type(enum_name: Name, enum_type: &Node<EnumType>) -> ExtendedType {
     ExtendedType::Enum(Node::new(EnumType {
         description: enum_type.description.clone(),
@@ -830,8 +2961,14 @@ fn link_purpose_enum_type() -> (Name, EnumType) {
 fn add_core_feature_join(
     supergraph: &mut Schema,
     subgraphs_and_enum_values: &Vec<(&ValidSubgraph, Name)>,
+    uses_override_labels: bool,
 ) {
     // @link(url: "https://specs.apollo.dev/join/v0.3", for: EXECUTION)
+    let join_url = if uses_override_labels {
+        "https://specs.apollo.dev/join/v0.4"
+    } else {
+        "https://specs.apollo.dev/join/v0.3"
+    };
     supergraph
         .schema_definition
         .make_mut()
@@ -841,9 +2978,7 @@ fn add_core_feature_join(
             arguments: vec![
                 Node::new(Argument {
                     name: name!("url"),
-                    value: Node::new(Value::String(NodeStr::new(
-                        "https://specs.apollo.dev/join/v0.3",
-                    ))),
+                    value: Node::new(Value::String(NodeStr::new(join_url))),
                 }),
                 Node::new(Argument {
                     name: name!("for"),
@@ -875,7 +3010,7 @@ fn add_core_feature_join(
         Node::new(join_type_directive_definition),
     );
 
-    let join_field_directive_definition = join_field_directive_definition();
+    let join_field_directive_definition = join_field_directive_definition(uses_override_labels);
     supergraph.directive_definitions.insert(
         join_field_directive_definition.name.clone(),
         Node::new(join_field_directive_definition),
@@ -903,6 +3038,80 @@ fn add_core_feature_join(
     supergraph.types.insert(name, join_graph_enum_type.into());
 }
 
+/// Links the spec of the given access-control directive and adds its definitions, e.g. for
+/// `@requiresScopes`:
+///
+/// @link(url: "https://specs.apollo.dev/requiresScopes/v0.1", for: SECURITY)
+/// directive @requiresScopes(scopes: [[requiresScopes__Scope!]!]!) on
+///   FIELD_DEFINITION | OBJECT | INTERFACE | SCALAR | ENUM
+/// scalar requiresScopes__Scope
+fn add_core_feature_access_control(supergraph: &mut Schema, directive: AccessControlDirective) {
+    let identity = directive.identity();
+    supergraph
+        .schema_definition
+        .make_mut()
+        .directives
+        .push(Component::new(Directive {
+            name: name!("link"),
+            arguments: vec![
+                Node::new(Argument {
+                    name: name!("url"),
+                    value: Node::new(Value::String(NodeStr::new(&format!(
+                        "{identity}/v{}",
+                        directive.version()
+                    )))),
+                }),
+                Node::new(Argument {
+                    name: name!("for"),
+                    value: Node::new(Value::Enum(name!("SECURITY"))),
+                }),
+            ],
+        }));
+
+    let mut arguments = Vec::new();
+    if let Some((argument_name, scalar_name)) = directive.argument() {
+        let scalar_name = Name::new(format!("{}__{scalar_name}", identity.name))
+            .expect("spec and scalar names form a valid name");
+        supergraph.types.insert(
+            scalar_name.clone(),
+            ExtendedType::Scalar(Node::new(ScalarType {
+                description: None,
+                name: scalar_name.clone(),
+                directives: Default::default(),
+            })),
+        );
+        arguments.push(Node::new(InputValueDefinition {
+            description: None,
+            name: argument_name,
+            ty: Type::Named(scalar_name)
+                .non_null()
+                .list()
+                .non_null()
+                .list()
+                .non_null()
+                .into(),
+            default_value: None,
+            directives: Default::default(),
+        }));
+    }
+    supergraph.directive_definitions.insert(
+        directive.name(),
+        Node::new(DirectiveDefinition {
+            description: None,
+            name: directive.name(),
+            arguments,
+            repeatable: false,
+            locations: vec![
+                DirectiveLocation::FieldDefinition,
+                DirectiveLocation::Object,
+                DirectiveLocation::Interface,
+                DirectiveLocation::Scalar,
+                DirectiveLocation::Enum,
+            ],
+        }),
+    );
+}
+
 /// directive @enumValue(graph: join__Graph!) repeatable on ENUM_VALUE
 fn join_enum_value_directive_definition() -> DirectiveDefinition {
     DirectiveDefinition {
@@ -927,10 +3136,11 @@ fn join_enum_value_directive_definition() -> DirectiveDefinition {
 ///   type: String,
 ///   external: Boolean,
 ///   override: String,
-///   usedOverridden: Boolean
+///   usedOverridden: Boolean,
+///   overrideLabel: String # join v0.4
 /// ) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
-fn join_field_directive_definition() -> DirectiveDefinition {
-    DirectiveDefinition {
+fn join_field_directive_definition(uses_override_labels: bool) -> DirectiveDefinition {
+    let mut definition = DirectiveDefinition {
         name: name!("join__field"),
         description: None,
         arguments: vec![
@@ -989,7 +3199,56 @@ fn join_field_directive_definition() -> DirectiveDefinition {
             DirectiveLocation::InputFieldDefinition,
         ],
         repeatable: true,
+    };
+    if uses_override_labels {
+        definition.arguments.push(Node::new(InputValueDefinition {
+            name: name!("overrideLabel"),
+            description: None,
+            directives: Default::default(),
+            ty: ty!(String).into(),
+            default_value: None,
+        }));
     }
+    definition
+}
+
+/// The `@join__field` application for a field of an entity (or root) type in the given subgraph.
+fn subgraph_join_field_directive(
+    subgraph_name: Name,
+    field: &FieldDefinition,
+    names: &FederationDirectiveNames,
+) -> Directive {
+    let requires_directive_option = Option::and_then(
+        field
+            .directives
+            .get_all(&names.requires_directive_name)
+            .next(),
+        |p| {
+            let requires_fields = directive_string_arg_value(p, &name!("fields")).unwrap();
+            Some(requires_fields.as_str())
+        },
+    );
+    let provides_directive_option = Option::and_then(
+        field
+            .directives
+            .get_all(&names.provides_directive_name)
+            .next(),
+        |p| {
+            let provides_fields = directive_string_arg_value(p, &name!("fields")).unwrap();
+            Some(provides_fields.as_str())
+        },
+    );
+    let external_field = field
+        .directives
+        .get_all(&names.external_directive_name)
+        .next()
+        .is_some();
+    join_field_applied_directive(
+        subgraph_name,
+        requires_directive_option,
+        provides_directive_option,
+        external_field,
+    )
 }
 
 fn join_field_applied_directive(
@@ -1204,6 +3463,93 @@ fn join_graph_enum_type(
     (join_graph_enum_name, join_graph_enum_type)
 }
 
+/// Whether the `fields` argument of the given `@key`, `@requires` or `@provides` application
+/// selects a field with the given name.
+// PORT_NOTE: The JS codebase collects the fields of the parsed field sets. Field sets are only split
+// into names here, so a nested selection of the same name also counts.
+fn field_set_selects(directive: &Directive, field_name: &Name) -> bool {
+    directive_string_arg_value(directive, &name!("fields")).is_some_and(|fields| {
+        fields
+            .split(|c: char| c.is_whitespace() || c == '{' || c == '}')
+            .any(|name| name == field_name.as_str())
+    })
+}
+
+/// Whether the given field of an object type is used by the `@key`, `@requires` or `@provides`
+/// applications of the subgraph, or to implement one of the interfaces of the type.
+// PORT_NOTE: Named `isFieldUsed()` in the JS codebase.
+fn is_field_used(
+    subgraph: &ValidSubgraph,
+    object_name: &Name,
+    object: &ObjectType,
+    field_name: &Name,
+    names: &FederationDirectiveNames,
+) -> bool {
+    let used_in_type = object
+        .directives
+        .get_all(&names.key_directive_name)
+        .any(|key| field_set_selects(key, field_name))
+        || object
+            .fields
+            .values()
+            .flat_map(|field| field.directives.get_all(&names.requires_directive_name))
+            .any(|requires| field_set_selects(requires, field_name));
+    let used_by_interface = object.implements_interfaces.iter().any(|interface_name| {
+        matches!(
+            subgraph.schema.types.get(interface_name.as_str()),
+            Some(ExtendedType::Interface(interface)) if interface.fields.contains_key(field_name)
+        )
+    });
+    used_in_type || used_by_interface || is_field_provided(subgraph, object_name, field_name, names)
+}
+
+/// Whether the given field of an object type is selected by a `@provides` of the subgraph.
+fn is_field_provided(
+    subgraph: &ValidSubgraph,
+    object_name: &Name,
+    field_name: &Name,
+    names: &FederationDirectiveNames,
+) -> bool {
+    subgraph.schema.types.values().any(|type_| {
+        let fields = match type_ {
+            ExtendedType::Object(type_) => &type_.fields,
+            ExtendedType::Interface(type_) => &type_.fields,
+            _ => return false,
+        };
+        fields
+            .values()
+            .filter(|field| field.ty.inner_named_type() == object_name)
+            .flat_map(|field| field.directives.get_all(&names.provides_directive_name))
+            .any(|provides| field_set_selects(provides, field_name))
+    })
+}
+
+/// The object types a value of the given type can be in the subgraph, if the type is abstract.
+fn possible_runtime_types(subgraph: &ValidSubgraph, type_name: &Name) -> Option<IndexSet<Name>> {
+    match subgraph.schema.types.get(type_name)? {
+        ExtendedType::Union(union_) => Some(
+            union_
+                .members
+                .iter()
+                .map(|member| member.name.clone())
+                .collect(),
+        ),
+        ExtendedType::Interface(_) => Some(
+            subgraph
+                .schema
+                .types
+                .iter()
+                .filter(|(_, type_)| {
+                    matches!(type_, ExtendedType::Object(object)
+                        if object.implements_interfaces.contains(type_name))
+                })
+                .map(|(name, _)| name.clone())
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
 // TODO use apollo_compiler::executable::FieldSet
 fn parse_keys<'a>(
     directives: impl Iterator<Item = &'a Component<Directive>> + Sized,
@@ -1217,12 +3563,3 @@ fn parse_keys<'a>(
             .collect::<Vec<&str>>(),
     )
 }
-
-fn merge_directive(
-    supergraph_directives: &mut IndexMap<Name, Node<DirectiveDefinition>>,
-    directive: &Node<DirectiveDefinition>,
-) {
-    if !supergraph_directives.contains_key(&directive.name.clone()) {
-        supergraph_directives.insert(directive.name.clone(), directive.clone());
-    }
-}
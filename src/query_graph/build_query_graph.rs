@@ -4,7 +4,8 @@ use crate::link::federation_spec_definition::{
 };
 use crate::query_graph::extract_subgraphs_from_supergraph::extract_subgraphs_from_supergraph;
 use crate::query_graph::{
-    QueryGraph, QueryGraphEdge, QueryGraphEdgeTransition, QueryGraphNode, QueryGraphNodeType,
+    OverrideCondition, QueryGraph, QueryGraphEdge, QueryGraphEdgeTransition, QueryGraphNode,
+    QueryGraphNodeType,
 };
 use crate::query_plan::operation::{
     merge_selection_sets, NormalizedSelection, NormalizedSelectionSet,
@@ -124,6 +125,7 @@ impl BaseQueryGraphBuilder {
             QueryGraphEdge {
                 transition,
                 conditions,
+                override_condition: None,
             },
         );
         let head_weight = self.query_graph.node_weight(head)?;
@@ -968,6 +970,9 @@ impl FederatedQueryGraphBuilder {
         // Note that @provides must be handled last when building since it requires copying nodes
         // and their edges, and it's easier to reason about this if we know previous
         self.handle_provides()?;
+        // Progressive @override is handled after @provides, so that the field edges of copied
+        // @provides nodes also get their override conditions.
+        self.handle_progressive_override()?;
         // The exception to the above rule is @interaceObject handling, where we explicitly don't
         // want to add self-edges for copied @provides nodes. (See the comments in this method for
         // more details).
@@ -1344,6 +1349,89 @@ impl FederatedQueryGraphBuilder {
         Ok(())
     }
 
+    /// Handle progressive @override (i.e. @override applications with a `label` argument) by adding
+    /// override conditions to the field-collecting edges of the overridden field. The edges in the
+    /// subgraph doing the overriding get a condition of `true`, while the edges in the subgraph
+    /// being overridden get a condition of `false`.
+    fn handle_progressive_override(&mut self) -> Result<(), FederationError> {
+        let mut to_edges = Vec::new();
+        // Maps the (from subgraph, type name, field name) of each progressive override to its label.
+        let mut overridden_fields = IndexMap::new();
+        for edge in self.base.query_graph.graph.edge_indices() {
+            let edge_weight = self.base.query_graph.edge_weight(edge)?;
+            let QueryGraphEdgeTransition::FieldCollection {
+                source,
+                field_definition_position,
+                ..
+            } = &edge_weight.transition
+            else {
+                continue;
+            };
+            if *source == self.base.query_graph.current_source {
+                continue;
+            }
+            let schema = self.base.query_graph.schema_by_source(source)?;
+            let subgraph_data = self.subgraphs.get(source)?;
+            let field = field_definition_position.get(schema.schema())?;
+            let Some(application) = field
+                .directives
+                .get(&subgraph_data.override_directive_definition_name)
+            else {
+                continue;
+            };
+            let application = subgraph_data
+                .federation_spec_definition
+                .override_directive_arguments(application)?;
+            let Some(label) = application.label else {
+                continue;
+            };
+            overridden_fields.insert(
+                (
+                    application.from,
+                    field_definition_position.type_name().clone(),
+                    field_definition_position.field_name().clone(),
+                ),
+                label.clone(),
+            );
+            to_edges.push((edge, label));
+        }
+        if to_edges.is_empty() {
+            return Ok(());
+        }
+
+        let mut from_edges = Vec::new();
+        for edge in self.base.query_graph.graph.edge_indices() {
+            let edge_weight = self.base.query_graph.edge_weight(edge)?;
+            let QueryGraphEdgeTransition::FieldCollection {
+                source,
+                field_definition_position,
+                ..
+            } = &edge_weight.transition
+            else {
+                continue;
+            };
+            let key = (
+                source.clone(),
+                field_definition_position.type_name().clone(),
+                field_definition_position.field_name().clone(),
+            );
+            if let Some(label) = overridden_fields.get(&key) {
+                from_edges.push((edge, label.clone()));
+            }
+        }
+
+        for (edges, condition) in [(to_edges, true), (from_edges, false)] {
+            for (edge, label) in edges {
+                let edge_weight_mut = self.base.query_graph.edge_weight_mut(edge)?;
+                edge_weight_mut.override_condition = Some(OverrideCondition {
+                    label: label.to_string(),
+                    condition,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Handle @provides by copying the appropriate nodes/edges.
     fn handle_provides(&mut self) -> Result<(), FederationError> {
         let mut provide_id = 0;
@@ -1946,6 +2034,10 @@ impl FederatedQueryGraphBuilderSubgraphs {
                 .provides_directive_definition(schema)?
                 .name
                 .clone();
+            let override_directive_definition_name = federation_spec_definition
+                .override_directive_definition(schema)?
+                .name
+                .clone();
             let interface_object_directive_definition_name = federation_spec_definition
                 .interface_object_directive_definition(schema)?
                 .map(|d| d.name.clone())
@@ -1966,6 +2058,7 @@ impl FederatedQueryGraphBuilderSubgraphs {
                     key_directive_definition_name,
                     requires_directive_definition_name,
                     provides_directive_definition_name,
+                    override_directive_definition_name,
                     interface_object_directive_definition_name,
                 },
             );
@@ -1991,6 +2084,7 @@ struct FederatedQueryGraphBuilderSubgraphData {
     key_directive_definition_name: Name,
     requires_directive_definition_name: Name,
     provides_directive_definition_name: Name,
+    override_directive_definition_name: Name,
     interface_object_directive_definition_name: Name,
 }
 
@@ -7,6 +7,7 @@ use crate::link::join_spec_definition::{
 };
 use crate::link::spec::{Identity, Version};
 use crate::link::spec_definition::SpecDefinition;
+use crate::schema::definitions::is_subtype;
 use crate::schema::field_set::parse_field_set_without_normalization;
 use crate::schema::position::{
     is_graphql_reserved_name, CompositeTypeDefinitionPosition, DirectiveDefinitionPosition,
@@ -66,8 +67,14 @@ pub(crate) fn extract_subgraphs_from_supergraph(
         }
     }
     if is_fed_1 {
-        // Handle Fed 1 supergraphs eventually, the extraction logic is gnarly
-        todo!()
+        extract_subgraphs_from_fed_1_supergraph(
+            supergraph_schema,
+            &mut subgraphs,
+            &graph_enum_value_name_to_subgraph_name,
+            &federation_spec_definitions,
+            join_spec_definition,
+            &filtered_types,
+        )?;
     } else {
         extract_subgraphs_from_fed_2_supergraph(
             supergraph_schema,
@@ -101,8 +108,16 @@ pub(crate) fn extract_subgraphs_from_supergraph(
                 Err((schema, error)) => {
                     subgraph.schema = schema;
                     if is_fed_1 {
-                        // See message above about Fed 1 supergraphs
-                        todo!()
+                        // Fed 2 validation is stricter than Fed 1 validation, so this can happen if
+                        // the supergraph was composed by Fed 1 from subgraphs with errors that it
+                        // mistakenly ignored.
+                        let message = format!(
+                            "Error extracting subgraph \"{}\" from the supergraph: this might be due to errors in subgraphs that were mistakenly ignored by federation 0.x versions but are rejected by federation 2.\nPlease try composing your subgraphs with federation 2: this should help precisely pinpoint the problems and, once fixed, generate a correct federation 2 supergraph.\n\nDetails:\n{error}",
+                            subgraph.name,
+                        );
+                        return Err(
+                            SingleFederationError::InvalidFederationSupergraph { message }.into(),
+                        );
                     } else {
                         let mut message = format!(
                                     "Unexpected error extracting {} from the supergraph: this is either a bug, or the supergraph has been corrupted.\n\nDetails:\n{error}",
@@ -192,7 +207,7 @@ pub(crate) fn new_empty_fed_2_subgraph_schema() -> Result<FederationSchema, Fede
         r#"
     extend schema
         @link(url: "https://specs.apollo.dev/link/v1.0")
-        @link(url: "https://specs.apollo.dev/federation/v2.5")
+        @link(url: "https://specs.apollo.dev/federation/v2.7")
 
     directive @link(url: String, as: String, for: link__Purpose, import: [link__Import]) repeatable on SCHEMA
 
@@ -226,7 +241,7 @@ pub(crate) fn new_empty_fed_2_subgraph_schema() -> Result<FederationSchema, Fede
 
     directive @federation__inaccessible on FIELD_DEFINITION | OBJECT | INTERFACE | UNION | ARGUMENT_DEFINITION | SCALAR | ENUM | ENUM_VALUE | INPUT_OBJECT | INPUT_FIELD_DEFINITION
 
-    directive @federation__override(from: String!) on FIELD_DEFINITION
+    directive @federation__override(from: String!, label: String) on FIELD_DEFINITION
 
     directive @federation__composeDirective(name: String) repeatable on SCHEMA
 
@@ -236,9 +251,13 @@ pub(crate) fn new_empty_fed_2_subgraph_schema() -> Result<FederationSchema, Fede
 
     directive @federation__requiresScopes(scopes: [[federation__Scope!]!]!) on FIELD_DEFINITION | OBJECT | INTERFACE | SCALAR | ENUM
 
+    directive @federation__policy(policies: [[federation__Policy!]!]!) on FIELD_DEFINITION | OBJECT | INTERFACE | SCALAR | ENUM
+
     scalar federation__FieldSet
 
     scalar federation__Scope
+
+    scalar federation__Policy
     "#,
         "subgraph.graphql",
     );
@@ -320,6 +339,612 @@ fn extract_subgraphs_from_fed_2_supergraph(
         &input_object_types,
     )?;
 
+    let all_executable_directive_definitions =
+        get_all_executable_directive_definitions(supergraph_schema);
+    for subgraph in subgraphs.subgraphs.values_mut() {
+        remove_inactive_requires_and_provides_from_subgraph(&mut subgraph.schema)?;
+        remove_unused_types_from_subgraph(&mut subgraph.schema)?;
+        for definition in all_executable_directive_definitions.iter() {
+            let pos = DirectiveDefinitionPosition {
+                directive_name: definition.name.clone(),
+            };
+            pos.pre_insert(&mut subgraph.schema)?;
+            pos.insert(&mut subgraph.schema, definition.clone())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn extract_subgraphs_from_fed_1_supergraph(
+    supergraph_schema: &FederationSchema,
+    subgraphs: &mut FederationSubgraphs,
+    graph_enum_value_name_to_subgraph_name: &IndexMap<Name, NodeStr>,
+    federation_spec_definitions: &IndexMap<Name, &'static FederationSpecDefinition>,
+    join_spec_definition: &'static JoinSpecDefinition,
+    filtered_types: &Vec<TypeDefinitionPosition>,
+) -> Result<(), FederationError> {
+    let TypeInfos {
+        object_types,
+        interface_types,
+        union_types,
+        enum_types,
+        input_object_types,
+    } = add_all_empty_subgraph_types(
+        supergraph_schema,
+        subgraphs,
+        graph_enum_value_name_to_subgraph_name,
+        federation_spec_definitions,
+        join_spec_definition,
+        filtered_types,
+    )?;
+
+    extract_fed_1_object_or_interface_type_content(
+        supergraph_schema,
+        subgraphs,
+        graph_enum_value_name_to_subgraph_name,
+        federation_spec_definitions,
+        join_spec_definition,
+        &object_types,
+        &interface_types,
+    )?;
+    // Union members, enum values and input fields have no join 0.1 directives, so the fed2 logic
+    // (which falls back to adding them to every subgraph having the type) applies as is.
+    extract_union_type_content(
+        supergraph_schema,
+        subgraphs,
+        graph_enum_value_name_to_subgraph_name,
+        join_spec_definition,
+        &union_types,
+    )?;
+    extract_enum_type_content(
+        supergraph_schema,
+        subgraphs,
+        graph_enum_value_name_to_subgraph_name,
+        join_spec_definition,
+        &enum_types,
+    )?;
+    extract_input_object_type_content(
+        supergraph_schema,
+        subgraphs,
+        graph_enum_value_name_to_subgraph_name,
+        join_spec_definition,
+        &input_object_types,
+    )?;
+
+    let all_executable_directive_definitions =
+        get_all_executable_directive_definitions(supergraph_schema);
+    for (graph_enum_value, subgraph_name) in graph_enum_value_name_to_subgraph_name {
+        let federation_spec_definition = federation_spec_definitions
+            .get(graph_enum_value)
+            .ok_or_else(|| SingleFederationError::InvalidFederationSupergraph {
+                message: "Subgraph unexpectedly does not use federation spec".to_owned(),
+            })?;
+        let subgraph =
+            subgraphs
+                .get_mut(subgraph_name)
+                .ok_or_else(|| SingleFederationError::Internal {
+                    message:
+                        "All subgraphs should have been created by \"collect_empty_subgraphs()\""
+                            .to_owned(),
+                })?;
+        add_external_fields(subgraph, supergraph_schema, federation_spec_definition)?;
+        remove_inactive_requires_and_provides_from_subgraph(&mut subgraph.schema)?;
+        remove_unused_types_from_subgraph(&mut subgraph.schema)?;
+        for definition in all_executable_directive_definitions.iter() {
+            let pos = DirectiveDefinitionPosition {
+                directive_name: definition.name.clone(),
+            };
+            pos.pre_insert(&mut subgraph.schema)?;
+            pos.insert(&mut subgraph.schema, definition.clone())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Extracts the fields and implemented interfaces of object and interface types from a join v0.1
+/// supergraph. Fed 1 supergraphs only record which subgraph "owns" an entity (@join__owner) and
+/// which subgraphs contribute fields to it through type extensions (@join__field), so everything
+/// else has to be inferred.
+fn extract_fed_1_object_or_interface_type_content(
+    supergraph_schema: &FederationSchema,
+    subgraphs: &mut FederationSubgraphs,
+    graph_enum_value_name_to_subgraph_name: &IndexMap<Name, NodeStr>,
+    federation_spec_definitions: &IndexMap<Name, &'static FederationSpecDefinition>,
+    join_spec_definition: &JoinSpecDefinition,
+    object_types: &[TypeInfo],
+    interface_types: &[TypeInfo],
+) -> Result<(), FederationError> {
+    let field_directive_definition =
+        join_spec_definition.field_directive_definition(supergraph_schema)?;
+    // join__owner only exists in join 0.1, and this method only runs for join 0.1, so it should be
+    // defined.
+    let owner_directive_definition = join_spec_definition
+        .owner_directive_definition(supergraph_schema)?
+        .ok_or_else(|| SingleFederationError::InvalidFederationSupergraph {
+            message: "@join__owner should exist for a fed1 supergraph".to_owned(),
+        })?;
+
+    let type_infos = object_types
+        .iter()
+        .map(|info| {
+            let pos: ObjectOrInterfaceTypeDefinitionPosition = ObjectTypeDefinitionPosition {
+                type_name: info.name.clone(),
+            }
+            .into();
+            (pos, info)
+        })
+        .chain(interface_types.iter().map(|info| {
+            let pos: ObjectOrInterfaceTypeDefinitionPosition = InterfaceTypeDefinitionPosition {
+                type_name: info.name.clone(),
+            }
+            .into();
+            (pos, info)
+        }));
+    for (
+        pos,
+        TypeInfo {
+            name: type_name,
+            subgraph_info,
+        },
+    ) in type_infos
+    {
+        let (directives, implements_interfaces, fields) = match supergraph_schema
+            .schema()
+            .types
+            .get(type_name)
+        {
+            Some(ExtendedType::Object(type_)) => (
+                &type_.directives,
+                &type_.implements_interfaces,
+                &type_.fields,
+            ),
+            Some(ExtendedType::Interface(type_)) => (
+                &type_.directives,
+                &type_.implements_interfaces,
+                &type_.fields,
+            ),
+            _ => {
+                return Err(SingleFederationError::Internal {
+                        message: format!(
+                            "Type \"{}\" unexpectedly not an object or interface type in the supergraph",
+                            type_name,
+                        ),
+                    }
+                    .into());
+            }
+        };
+        let is_object_type = matches!(pos, ObjectOrInterfaceTypeDefinitionPosition::Object(_));
+
+        // There is no @join__implements in join 0.1, so we add the interface to every subgraph
+        // having both the type and the interface.
+        for interface_name in implements_interfaces {
+            for graph_enum_value in subgraph_info.keys() {
+                let subgraph = get_subgraph(
+                    subgraphs,
+                    graph_enum_value_name_to_subgraph_name,
+                    graph_enum_value,
+                )?;
+                if !subgraph
+                    .schema
+                    .schema()
+                    .types
+                    .contains_key(&interface_name.name)
+                {
+                    continue;
+                }
+                match &pos {
+                    ObjectOrInterfaceTypeDefinitionPosition::Object(pos) => {
+                        pos.insert_implements_interface(
+                            &mut subgraph.schema,
+                            interface_name.clone(),
+                        )?;
+                    }
+                    ObjectOrInterfaceTypeDefinitionPosition::Interface(pos) => {
+                        pos.insert_implements_interface(
+                            &mut subgraph.schema,
+                            interface_name.clone(),
+                        )?;
+                    }
+                }
+            }
+        }
+
+        let owner = directives
+            .get(&owner_directive_definition.name)
+            .map(|directive| join_spec_definition.owner_directive_arguments(directive))
+            .transpose()?;
+
+        for (field_name, field) in fields.iter() {
+            let field_pos = pos.field(field_name.clone());
+            let mut field_directive_applications = Vec::new();
+            for directive in field.directives.get_all(&field_directive_definition.name) {
+                field_directive_applications
+                    .push(join_spec_definition.field_directive_arguments(directive)?);
+            }
+            if field_directive_applications.is_empty() {
+                let graph_enum_values = if let Some(owner) = &owner {
+                    // In a fed1 supergraph, no @join__field on an entity means the field is only
+                    // in the owner subgraph.
+                    vec![&owner.graph]
+                } else {
+                    // Otherwise this is a value type, whose fields are in all the subgraphs in
+                    // which the type is. As we don't know which subgraphs actually had the type,
+                    // we skip subgraphs lacking the field's type, as they clearly didn't.
+                    let field_base_type = field.ty.inner_named_type();
+                    let mut graph_enum_values = Vec::new();
+                    for graph_enum_value in subgraph_info.keys() {
+                        let subgraph = get_subgraph(
+                            subgraphs,
+                            graph_enum_value_name_to_subgraph_name,
+                            graph_enum_value,
+                        )?;
+                        if subgraph.schema.schema().types.contains_key(field_base_type) {
+                            graph_enum_values.push(graph_enum_value);
+                        }
+                    }
+                    graph_enum_values
+                };
+                // Fed 1 did not require @shareable on value type fields, so we add it to make the
+                // subgraphs valid fed2 subgraphs.
+                let is_shareable = is_object_type && graph_enum_values.len() > 1;
+                for graph_enum_value in graph_enum_values {
+                    let subgraph = get_subgraph(
+                        subgraphs,
+                        graph_enum_value_name_to_subgraph_name,
+                        graph_enum_value,
+                    )?;
+                    let federation_spec_definition = federation_spec_definitions
+                        .get(graph_enum_value)
+                        .ok_or_else(|| SingleFederationError::InvalidFederationSupergraph {
+                            message: "Subgraph unexpectedly does not use federation spec"
+                                .to_owned(),
+                        })?;
+                    add_subgraph_field(
+                        field_pos.clone(),
+                        field,
+                        subgraph,
+                        federation_spec_definition,
+                        is_shareable,
+                        None,
+                    )?;
+                }
+            } else {
+                let is_shareable = is_object_type && field_directive_applications.len() > 1;
+                for field_directive_application in &field_directive_applications {
+                    let Some(graph_enum_value) = &field_directive_application.graph else {
+                        continue;
+                    };
+                    if !subgraph_info.contains_key(graph_enum_value) {
+                        return Err(
+                            SingleFederationError::InvalidFederationSupergraph {
+                                message: format!(
+                                    "@join__field cannot exist on {}.{} for subgraph {} without type-level @join__type",
+                                    type_name,
+                                    field_name,
+                                    graph_enum_value,
+                                ),
+                            }.into()
+                        );
+                    }
+                    let subgraph = get_subgraph(
+                        subgraphs,
+                        graph_enum_value_name_to_subgraph_name,
+                        graph_enum_value,
+                    )?;
+                    let federation_spec_definition = federation_spec_definitions
+                        .get(graph_enum_value)
+                        .ok_or_else(|| SingleFederationError::InvalidFederationSupergraph {
+                            message: "Subgraph unexpectedly does not use federation spec"
+                                .to_owned(),
+                        })?;
+                    add_subgraph_field(
+                        field_pos.clone(),
+                        field,
+                        subgraph,
+                        federation_spec_definition,
+                        is_shareable,
+                        Some(field_directive_application),
+                    )?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fed 1 supergraphs don't record @external fields, so this adds back to the subgraph any field
+/// that is used in a @key, @requires or @provides but isn't defined in the subgraph, along with
+/// any interface field missing from an implementation.
+///
+/// Historically, fed1 required @external on the key fields of type extensions, but also required
+/// the subgraph to resolve those fields. So key fields are added (or kept) as non-external.
+fn add_external_fields(
+    subgraph: &mut FederationSubgraph,
+    supergraph_schema: &FederationSchema,
+    federation_spec_definition: &'static FederationSpecDefinition,
+) -> Result<(), FederationError> {
+    let key_directive_definition_name = federation_spec_definition
+        .key_directive_definition(&subgraph.schema)?
+        .name
+        .clone();
+    let requires_directive_definition_name = federation_spec_definition
+        .requires_directive_definition(&subgraph.schema)?
+        .name
+        .clone();
+    let provides_directive_definition_name = federation_spec_definition
+        .provides_directive_definition(&subgraph.schema)?
+        .name
+        .clone();
+    let external_directive_definition_name = federation_spec_definition
+        .external_directive_definition(&subgraph.schema)?
+        .name
+        .clone();
+
+    // Collect (parent type name, field set, force non-external) first, as adding fields mutates
+    // the schema.
+    let mut field_sets: Vec<(NamedType, NodeStr, bool)> = Vec::new();
+    for (type_name, type_) in subgraph.schema.schema().types.iter() {
+        let (directives, fields) = match type_ {
+            ExtendedType::Object(type_) => (&type_.directives, &type_.fields),
+            ExtendedType::Interface(type_) => (&type_.directives, &type_.fields),
+            _ => continue,
+        };
+        for directive in directives.get_all(&key_directive_definition_name) {
+            let arguments = federation_spec_definition.key_directive_arguments(directive)?;
+            field_sets.push((type_name.clone(), arguments.fields, true));
+        }
+        for field in fields.values() {
+            for directive in field
+                .directives
+                .get_all(&requires_directive_definition_name)
+            {
+                let arguments =
+                    federation_spec_definition.requires_directive_arguments(directive)?;
+                field_sets.push((type_name.clone(), arguments.fields, false));
+            }
+            for directive in field
+                .directives
+                .get_all(&provides_directive_definition_name)
+            {
+                let arguments =
+                    federation_spec_definition.provides_directive_arguments(directive)?;
+                field_sets.push((field.ty.inner_named_type().clone(), arguments.fields, false));
+            }
+        }
+    }
+
+    let valid_supergraph_schema = Valid::assume_valid_ref(supergraph_schema.schema());
+    for (parent_type_name, fields, force_non_external) in field_sets {
+        // We ignore field sets that don't parse, as subgraph validation will report them with
+        // better error messages.
+        let Ok(selection_set) = parse_field_set_without_normalization(
+            valid_supergraph_schema,
+            parent_type_name,
+            fields,
+        ) else {
+            continue;
+        };
+        add_external_fields_from_selection_set(
+            subgraph,
+            supergraph_schema,
+            federation_spec_definition,
+            &external_directive_definition_name,
+            &selection_set,
+            force_non_external,
+        )?;
+    }
+
+    add_external_fields_from_interfaces(
+        subgraph,
+        supergraph_schema,
+        federation_spec_definition,
+        &external_directive_definition_name,
+    )
+}
+
+fn add_external_fields_from_selection_set(
+    subgraph: &mut FederationSubgraph,
+    supergraph_schema: &FederationSchema,
+    federation_spec_definition: &'static FederationSpecDefinition,
+    external_directive_definition_name: &Name,
+    selection_set: &SelectionSet,
+    force_non_external: bool,
+) -> Result<(), FederationError> {
+    let parent_type_pos: ObjectOrInterfaceTypeDefinitionPosition =
+        match subgraph.schema.try_get_type(selection_set.ty.clone()) {
+            Some(TypeDefinitionPosition::Object(pos)) => pos.into(),
+            Some(TypeDefinitionPosition::Interface(pos)) => pos.into(),
+            // If the subgraph lacks the type, the field set can't be valid in that subgraph, and
+            // subgraph validation will report it.
+            _ => return Ok(()),
+        };
+    for selection in &selection_set.selections {
+        match selection {
+            Selection::Field(field) => {
+                if field.name == "__typename" {
+                    continue;
+                }
+                let field_pos = parent_type_pos.field(field.name.clone());
+                if let Some(subgraph_field) = field_pos.try_get(subgraph.schema.schema()) {
+                    if force_non_external
+                        && subgraph_field
+                            .directives
+                            .has(external_directive_definition_name)
+                    {
+                        field_pos.remove_directive_name(
+                            &mut subgraph.schema,
+                            external_directive_definition_name,
+                        );
+                    }
+                } else {
+                    let supergraph_field = parent_type_pos
+                        .field(field.name.clone())
+                        .get(supergraph_schema.schema())?;
+                    add_subgraph_field(
+                        field_pos,
+                        supergraph_field,
+                        subgraph,
+                        federation_spec_definition,
+                        false,
+                        Some(&FieldDirectiveArguments {
+                            graph: None,
+                            requires: None,
+                            provides: None,
+                            type_: None,
+                            external: Some(!force_non_external),
+                            override_: None,
+                            user_overridden: None,
+                            override_label: None,
+                        }),
+                    )?;
+                }
+                add_external_fields_from_selection_set(
+                    subgraph,
+                    supergraph_schema,
+                    federation_spec_definition,
+                    external_directive_definition_name,
+                    &field.selection_set,
+                    force_non_external,
+                )?;
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                add_external_fields_from_selection_set(
+                    subgraph,
+                    supergraph_schema,
+                    federation_spec_definition,
+                    external_directive_definition_name,
+                    &inline_fragment.selection_set,
+                    force_non_external,
+                )?;
+            }
+            Selection::FragmentSpread(_) => {
+                return Err(SingleFederationError::Internal {
+                    message: "Unexpectedly found named fragment in FieldSet scalar".to_owned(),
+                }
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn add_external_fields_from_interfaces(
+    subgraph: &mut FederationSubgraph,
+    supergraph_schema: &FederationSchema,
+    federation_spec_definition: &'static FederationSpecDefinition,
+    external_directive_definition_name: &Name,
+) -> Result<(), FederationError> {
+    // Collect the interface fields first, as adding fields mutates the schema.
+    let mut interface_fields = Vec::new();
+    for (type_name, type_) in subgraph.schema.schema().types.iter() {
+        let (pos, implements_interfaces): (ObjectOrInterfaceTypeDefinitionPosition, _) = match type_
+        {
+            ExtendedType::Object(type_) => (
+                ObjectTypeDefinitionPosition {
+                    type_name: type_name.clone(),
+                }
+                .into(),
+                &type_.implements_interfaces,
+            ),
+            ExtendedType::Interface(type_) => (
+                InterfaceTypeDefinitionPosition {
+                    type_name: type_name.clone(),
+                }
+                .into(),
+                &type_.implements_interfaces,
+            ),
+            _ => continue,
+        };
+        for interface_name in implements_interfaces {
+            let Some(ExtendedType::Interface(interface)) =
+                subgraph.schema.schema().types.get(&interface_name.name)
+            else {
+                continue;
+            };
+            for (field_name, field) in interface.fields.iter() {
+                interface_fields.push((pos.field(field_name.clone()), field.node.clone()));
+            }
+        }
+    }
+    for (field_pos, interface_field) in interface_fields {
+        // The same field may be provided by several interfaces, so whether the field exists is
+        // checked as we go.
+        match field_pos.try_get(subgraph.schema.schema()) {
+            None => add_subgraph_field(
+                field_pos,
+                &interface_field,
+                subgraph,
+                federation_spec_definition,
+                false,
+                Some(&FieldDirectiveArguments {
+                    graph: None,
+                    requires: None,
+                    provides: None,
+                    type_: None,
+                    external: Some(true),
+                    override_: None,
+                    user_overridden: None,
+                    override_label: None,
+                }),
+            )?,
+            Some(field) if field.directives.has(external_directive_definition_name) => {
+                maybe_update_field_for_interface(
+                    subgraph,
+                    supergraph_schema,
+                    field_pos,
+                    interface_field.ty.clone(),
+                )?
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// A type may implement several interfaces providing a given field, with a different type in
+/// each of them. As fed1 supergraphs don't record the type of @external fields, this makes sure
+/// the field gets the most specific of those types, so that the type is a valid implementation of
+/// all its interfaces.
+fn maybe_update_field_for_interface(
+    subgraph: &mut FederationSubgraph,
+    supergraph_schema: &FederationSchema,
+    field_pos: ObjectOrInterfaceFieldDefinitionPosition,
+    interface_field_type: Type,
+) -> Result<(), FederationError> {
+    let field = field_pos.get(subgraph.schema.schema())?;
+    if is_subtype(supergraph_schema.schema(), &interface_field_type, &field.ty) {
+        return Ok(());
+    }
+    if !is_subtype(supergraph_schema.schema(), &field.ty, &interface_field_type) {
+        return Err(SingleFederationError::Internal {
+            message: format!(
+                "For {}, expected {} and {} to be in a subtyping relationship",
+                field_pos, interface_field_type, field.ty,
+            ),
+        }
+        .into());
+    }
+    let mut field = field.clone();
+    field.make_mut().ty = interface_field_type;
+    match field_pos {
+        ObjectOrInterfaceFieldDefinitionPosition::Object(pos) => {
+            pos.remove(&mut subgraph.schema)?;
+            pos.insert(&mut subgraph.schema, field)?;
+        }
+        ObjectOrInterfaceFieldDefinitionPosition::Interface(pos) => {
+            pos.remove(&mut subgraph.schema)?;
+            pos.insert(&mut subgraph.schema, field)?;
+        }
+    }
+    Ok(())
+}
+
+fn get_all_executable_directive_definitions(
+    supergraph_schema: &FederationSchema,
+) -> Vec<Node<DirectiveDefinition>> {
     // We add all the "executable" directive definitions from the supergraph to each subgraphs, as
     // those may be part of a query and end up in any subgraph fetches. We do this "last" to make
     // sure that if one of the directives uses a type for an argument, that argument exists. Note
@@ -327,7 +952,7 @@ fn extract_subgraphs_from_fed_2_supergraph(
     // don't extract their applications. It might become something we need later, but we don't so
     // far. Accordingly, we skip any potentially applied directives in the argument of the copied
     // definition, because we haven't copied type-system directives.
-    let all_executable_directive_definitions = supergraph_schema
+    supergraph_schema
         .schema()
         .directive_definitions
         .values()
@@ -361,19 +986,7 @@ fn extract_subgraphs_from_fed_2_supergraph(
                 locations: executable_locations,
             }))
         })
-        .collect::<Vec<_>>();
-    for subgraph in subgraphs.subgraphs.values_mut() {
-        remove_inactive_requires_and_provides_from_subgraph(&mut subgraph.schema)?;
-        remove_unused_types_from_subgraph(&mut subgraph.schema)?;
-        for definition in all_executable_directive_definitions.iter() {
-            DirectiveDefinitionPosition {
-                directive_name: definition.name.clone(),
-            }
-            .insert(&mut subgraph.schema, definition.clone())?;
-        }
-    }
-
-    Ok(())
+        .collect::<Vec<_>>()
 }
 
 fn add_all_empty_subgraph_types(
@@ -400,6 +1013,24 @@ fn add_all_empty_subgraph_types(
             type_directive_applications
                 .push(join_spec_definition.type_directive_arguments(directive)?);
         }
+        if type_directive_applications.is_empty()
+            && *join_spec_definition.version() == (Version { major: 0, minor: 1 })
+        {
+            // Fed 1 supergraphs only have @join__type on entities, so for other types we don't
+            // know which subgraphs define them. We add those types to all subgraphs; this is
+            // harmless for subgraphs that didn't have the type, as the type will either be
+            // unreachable or be removed for being empty.
+            type_directive_applications = graph_enum_value_name_to_subgraph_name
+                .keys()
+                .map(|graph_enum_value| TypeDirectiveArguments {
+                    graph: graph_enum_value.clone(),
+                    key: None,
+                    extension: false,
+                    resolvable: true,
+                    is_interface_object: false,
+                })
+                .collect();
+        }
         let types_mut = match &type_definition_position {
             TypeDefinitionPosition::Scalar(pos) => {
                 // Scalar are a bit special in that they don't have any sub-component, so we don't
@@ -1265,6 +1896,7 @@ fn add_subgraph_field(
             external: None,
             override_: None,
             user_overridden: None,
+            override_label: None,
         });
     let subgraph_field_type = match &field_directive_application.type_ {
         Some(t) => decode_type(t)?,
@@ -1315,9 +1947,13 @@ fn add_subgraph_field(
         ));
     }
     if let Some(override_) = &field_directive_application.override_ {
-        subgraph_field.directives.push(Node::new(
-            federation_spec_definition.override_directive(&subgraph.schema, override_.clone())?,
-        ));
+        subgraph_field
+            .directives
+            .push(Node::new(federation_spec_definition.override_directive(
+                &subgraph.schema,
+                override_.clone(),
+                field_directive_application.override_label.clone(),
+            )?));
     }
     if is_shareable && !external && !user_overridden {
         subgraph_field.directives.push(Node::new(
@@ -1352,6 +1988,7 @@ fn add_subgraph_input_field(
             external: None,
             override_: None,
             user_overridden: None,
+            override_label: None,
         });
     let subgraph_input_field_type = match &field_directive_application.type_ {
         Some(t) => Node::new(decode_type(t)?),
@@ -2053,10 +2690,83 @@ fn maybe_dump_subgraph_schema(subgraph: FederationSubgraph, message: &mut String
     };
 }
 
+/// A fed1 supergraph with an interface, a value type, and `@requires`/`@provides`, shared by the
+/// tests of fed1 supergraph extraction and planning.
+#[cfg(test)]
+pub(crate) const FED_1_SUPERGRAPH: &str = r#"
+schema
+  @core(feature: "https://specs.apollo.dev/core/v0.2")
+  @core(feature: "https://specs.apollo.dev/join/v0.1", for: EXECUTION)
+{
+  query: Query
+}
+
+directive @core(as: String, feature: String!, for: core__Purpose) repeatable on SCHEMA
+
+directive @join__field(graph: join__Graph, provides: join__FieldSet, requires: join__FieldSet) on FIELD_DEFINITION
+
+directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+directive @join__owner(graph: join__Graph!) on INTERFACE | OBJECT
+
+directive @join__type(graph: join__Graph!, key: join__FieldSet) repeatable on INTERFACE | OBJECT
+
+interface Node {
+  id: ID!
+}
+
+type Location {
+  lat: Float
+  long: Float
+}
+
+type Product implements Node
+  @join__owner(graph: PRODUCTS)
+  @join__type(graph: PRODUCTS, key: "id")
+  @join__type(graph: INVENTORY, key: "id")
+  @join__type(graph: REVIEWS, key: "id")
+{
+  id: ID!
+  price: Int
+  weight: Int
+  shippingEstimate: Int @join__field(graph: INVENTORY, requires: "price weight")
+  warehouse: Location @join__field(graph: INVENTORY)
+}
+
+type Query {
+  products: [Product] @join__field(graph: PRODUCTS)
+  reviews: [Review] @join__field(graph: REVIEWS)
+}
+
+type Review implements Node
+  @join__owner(graph: REVIEWS)
+  @join__type(graph: REVIEWS, key: "id")
+{
+  id: ID!
+  body: String
+  location: Location
+  product: Product @join__field(graph: REVIEWS, provides: "price")
+}
+
+enum core__Purpose {
+  EXECUTION
+  SECURITY
+}
+
+scalar join__FieldSet
+
+enum join__Graph {
+  INVENTORY @join__graph(name: "inventory", url: "http://inventory")
+  PRODUCTS @join__graph(name: "products", url: "http://products")
+  REVIEWS @join__graph(name: "reviews", url: "http://reviews")
+}
+"#;
+
 #[cfg(test)]
 mod tests {
     use apollo_compiler::{name, Schema};
 
+    use super::FED_1_SUPERGRAPH;
     use crate::{schema::FederationSchema, ValidFederationSubgraphs};
 
     // JS PORT NOTE: these tests were ported from
@@ -2666,4 +3376,275 @@ mod tests {
         let user_type = subgraph.schema.schema().get_object("User");
         assert!(user_type.is_none());
     }
+
+    #[test]
+    fn extracts_fed_1_supergraphs() {
+        let supergraph = r#"
+            schema
+              @core(feature: "https://specs.apollo.dev/core/v0.2")
+              @core(feature: "https://specs.apollo.dev/join/v0.1", for: EXECUTION)
+            {
+              query: Query
+            }
+
+            directive @core(as: String, feature: String!, for: core__Purpose) repeatable on SCHEMA
+
+            directive @join__field(graph: join__Graph, provides: join__FieldSet, requires: join__FieldSet) on FIELD_DEFINITION
+
+            directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+            directive @join__owner(graph: join__Graph!) on INTERFACE | OBJECT
+
+            directive @join__type(graph: join__Graph!, key: join__FieldSet) repeatable on INTERFACE | OBJECT
+
+            type Metadata {
+              key: String
+              value: String
+            }
+
+            type Product
+              @join__owner(graph: PRODUCTS)
+              @join__type(graph: PRODUCTS, key: "upc")
+              @join__type(graph: REVIEWS, key: "upc")
+            {
+              upc: String!
+              name: String
+              metadata: [Metadata]
+              reviews: [Review] @join__field(graph: REVIEWS)
+            }
+
+            type Query {
+              topProducts: [Product] @join__field(graph: PRODUCTS)
+              topReviews: [Review] @join__field(graph: REVIEWS)
+            }
+
+            type Review
+              @join__owner(graph: REVIEWS)
+              @join__type(graph: REVIEWS, key: "id")
+            {
+              id: ID!
+              body: String
+              metadata: [Metadata]
+              product: Product @join__field(graph: REVIEWS, provides: "name")
+            }
+
+            enum core__Purpose {
+              EXECUTION
+              SECURITY
+            }
+
+            scalar join__FieldSet
+
+            enum join__Graph {
+              PRODUCTS @join__graph(name: "products", url: "http://products")
+              REVIEWS @join__graph(name: "reviews", url: "http://reviews")
+            }
+      "#;
+
+        let schema = Schema::parse(supergraph, "supergraph.graphql").unwrap();
+        let ValidFederationSubgraphs { subgraphs } = super::extract_subgraphs_from_supergraph(
+            &FederationSchema::new(schema).unwrap(),
+            Some(true),
+        )
+        .unwrap();
+
+        let products = subgraphs.get("products").unwrap().schema.schema();
+        let product = products.get_object("Product").unwrap();
+        assert!(!product.fields.contains_key("reviews"));
+        assert!(products.get_object("Review").is_none());
+        let metadata = products.get_object("Metadata").unwrap();
+        assert!(metadata.fields["key"]
+            .directives
+            .has("federation__shareable"));
+
+        let reviews = subgraphs.get("reviews").unwrap().schema.schema();
+        let product = reviews.get_object("Product").unwrap();
+        // Key fields are resolvable by the subgraph, so they aren't @external.
+        assert!(!product.fields["upc"].directives.has("federation__external"));
+        // Provided fields get re-added as @external.
+        assert!(product.fields["name"]
+            .directives
+            .has("federation__external"));
+        assert!(!product.fields.contains_key("metadata"));
+        assert!(product.fields["reviews"].directives.is_empty());
+        let metadata = reviews.get_object("Metadata").unwrap();
+        assert!(metadata.fields["value"]
+            .directives
+            .has("federation__shareable"));
+    }
+
+    #[test]
+    fn extracts_fed_1_requires_and_provides() {
+        let schema = Schema::parse(FED_1_SUPERGRAPH, "supergraph.graphql").unwrap();
+        let ValidFederationSubgraphs { subgraphs } = super::extract_subgraphs_from_supergraph(
+            &FederationSchema::new(schema).unwrap(),
+            Some(true),
+        )
+        .unwrap();
+
+        let inventory = subgraphs.get("inventory").unwrap().schema.schema();
+        let product = inventory.get_object("Product").unwrap();
+        assert_eq!(
+            product.fields["shippingEstimate"]
+                .directives
+                .get("federation__requires")
+                .unwrap()
+                .to_string(),
+            r#"@federation__requires(fields: "price weight")"#,
+        );
+        // Required fields get re-added as @external.
+        assert!(product.fields["price"]
+            .directives
+            .has("federation__external"));
+        assert!(product.fields["weight"]
+            .directives
+            .has("federation__external"));
+
+        let reviews = subgraphs.get("reviews").unwrap().schema.schema();
+        let review = reviews.get_object("Review").unwrap();
+        assert_eq!(
+            review.fields["product"]
+                .directives
+                .get("federation__provides")
+                .unwrap()
+                .to_string(),
+            r#"@federation__provides(fields: "price")"#,
+        );
+        let product = reviews.get_object("Product").unwrap();
+        assert!(product.fields["price"]
+            .directives
+            .has("federation__external"));
+        assert!(!product.fields.contains_key("weight"));
+
+        // The owner of the fields doesn't mark them @external.
+        let products = subgraphs.get("products").unwrap().schema.schema();
+        let product = products.get_object("Product").unwrap();
+        assert!(product.fields["price"].directives.is_empty());
+        assert!(!product.fields.contains_key("shippingEstimate"));
+    }
+
+    #[test]
+    fn extracts_fed_1_interfaces_and_value_types() {
+        let schema = Schema::parse(FED_1_SUPERGRAPH, "supergraph.graphql").unwrap();
+        let ValidFederationSubgraphs { subgraphs } = super::extract_subgraphs_from_supergraph(
+            &FederationSchema::new(schema).unwrap(),
+            Some(true),
+        )
+        .unwrap();
+
+        for subgraph in ["inventory", "products", "reviews"] {
+            let schema = subgraphs.get(subgraph).unwrap().schema.schema();
+            // There is no @join__implements in fed1 supergraphs, so implementations are added to
+            // every subgraph having both the type and the interface.
+            let node = schema.get_interface("Node").unwrap();
+            assert!(node.fields["id"].directives.is_empty());
+            let product = schema.get_object("Product").unwrap();
+            assert!(product.implements_interfaces.contains("Node"));
+        }
+
+        let reviews = subgraphs.get("reviews").unwrap().schema.schema();
+        let review = reviews.get_object("Review").unwrap();
+        assert!(review.implements_interfaces.contains("Node"));
+        assert!(subgraphs
+            .get("inventory")
+            .unwrap()
+            .schema
+            .schema()
+            .get_object("Review")
+            .is_none());
+
+        // Value type fields are in every subgraph having the type, and are @shareable as fed1
+        // didn't require it.
+        for subgraph in ["inventory", "reviews"] {
+            let schema = subgraphs.get(subgraph).unwrap().schema.schema();
+            let location = schema.get_object("Location").unwrap();
+            assert!(location.fields["lat"]
+                .directives
+                .has("federation__shareable"));
+            assert!(location.fields["long"]
+                .directives
+                .has("federation__shareable"));
+        }
+    }
+
+    #[test]
+    fn extracts_fed_1_external_fields_with_the_most_specific_interface_type() {
+        let supergraph = r#"
+            schema
+              @core(feature: "https://specs.apollo.dev/core/v0.2")
+              @core(feature: "https://specs.apollo.dev/join/v0.1", for: EXECUTION)
+            {
+              query: Query
+            }
+
+            directive @core(as: String, feature: String!, for: core__Purpose) repeatable on SCHEMA
+
+            directive @join__field(graph: join__Graph, provides: join__FieldSet, requires: join__FieldSet) on FIELD_DEFINITION
+
+            directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+            directive @join__owner(graph: join__Graph!) on INTERFACE | OBJECT
+
+            directive @join__type(graph: join__Graph!, key: join__FieldSet) repeatable on INTERFACE | OBJECT
+
+            interface HasNode {
+              item: Node
+            }
+
+            interface HasProduct {
+              item: Product
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type Product implements Node
+              @join__owner(graph: A)
+              @join__type(graph: A, key: "id")
+              @join__type(graph: B, key: "id")
+            {
+              id: ID!
+            }
+
+            type Query {
+              thing: Thing @join__field(graph: A)
+            }
+
+            type Thing implements HasNode & HasProduct
+              @join__owner(graph: A)
+              @join__type(graph: A, key: "id")
+              @join__type(graph: B, key: "id")
+            {
+              id: ID!
+              item: Product
+            }
+
+            enum core__Purpose {
+              EXECUTION
+              SECURITY
+            }
+
+            scalar join__FieldSet
+
+            enum join__Graph {
+              A @join__graph(name: "a", url: "http://a")
+              B @join__graph(name: "b", url: "http://b")
+            }
+      "#;
+
+        let schema = Schema::parse(supergraph, "supergraph.graphql").unwrap();
+        let ValidFederationSubgraphs { subgraphs } = super::extract_subgraphs_from_supergraph(
+            &FederationSchema::new(schema).unwrap(),
+            Some(true),
+        )
+        .unwrap();
+
+        // `Thing.item` is only in "a", so it's re-added to "b" as @external from its interfaces,
+        // with the type of `HasProduct.item` as `HasNode.item` is less specific.
+        let b = subgraphs.get("b").unwrap().schema.schema();
+        let item = &b.get_object("Thing").unwrap().fields["item"];
+        assert_eq!(item.ty.to_string(), "Product");
+        assert!(item.directives.has("federation__external"));
+    }
 }
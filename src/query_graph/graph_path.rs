@@ -9,11 +9,13 @@ use crate::query_graph::condition_resolver::{
     ConditionResolution, ConditionResolver, UnsatisfiedConditionReason,
 };
 use crate::query_graph::path_tree::OpPathTree;
-use crate::query_graph::{QueryGraph, QueryGraphEdgeTransition, QueryGraphNodeType};
+use crate::query_graph::{
+    EnabledOverrideConditions, QueryGraph, QueryGraphEdgeTransition, QueryGraphNodeType,
+};
 use crate::query_plan::operation::{
     NormalizedField, NormalizedFieldData, NormalizedFieldSelection, NormalizedInlineFragment,
     NormalizedInlineFragmentData, NormalizedInlineFragmentSelection, NormalizedSelection,
-    NormalizedSelectionSet, SelectionId,
+    NormalizedSelectionSet, RebaseErrorHandlingOption, SelectionId,
 };
 use crate::query_plan::{FetchDataPathElement, QueryPathElement, QueryPlanCost};
 use crate::schema::position::{
@@ -120,6 +122,9 @@ where
     /// This array stores the IDs of paths that override this one. (See docs for `own_path_ids` for
     /// more info).
     overriding_path_ids: Arc<IndexSet<OverrideId>>,
+    /// The progressive @override labels that are enabled for the query plan this path is part of.
+    /// Field edges whose override condition doesn't match these are never taken.
+    override_conditions: Arc<EnabledOverrideConditions>,
     /// Names of all the possible runtime types the tail of the path can be.
     runtime_types_of_tail: Arc<IndexSet<ObjectTypeDefinitionPosition>>,
     /// If the last edge in the `edges` array was a `DownCast` transition, then the runtime types
@@ -151,6 +156,7 @@ where
             last_subgraph_entering_edge_info,
             own_path_ids,
             overriding_path_ids,
+            override_conditions,
             runtime_types_of_tail,
             runtime_types_before_tail_if_last_is_cast,
             defer_on_tail,
@@ -168,6 +174,7 @@ where
             )
             .field("own_path_ids", own_path_ids)
             .field("overriding_path_ids", overriding_path_ids)
+            .field("override_conditions", override_conditions)
             .field("runtime_types_of_tail", runtime_types_of_tail)
             .field(
                 "runtime_types_before_tail_if_last_is_cast",
@@ -290,6 +297,46 @@ impl OpPathElement {
         }
     }
 
+    /// Returns the type of the selection set that would follow this element, or `None` if the
+    /// element is a leaf field.
+    pub(crate) fn sub_selection_type_position(
+        &self,
+    ) -> Result<Option<CompositeTypeDefinitionPosition>, FederationError> {
+        match self {
+            OpPathElement::Field(field) => {
+                let data = field.data();
+                let definition = data.field_position.get(data.schema.schema())?;
+                let base_type = data
+                    .schema
+                    .get_type(definition.ty.inner_named_type().clone())?;
+                Ok(CompositeTypeDefinitionPosition::try_from(base_type).ok())
+            }
+            OpPathElement::InlineFragment(inline) => Ok(Some(inline.data().casted_type())),
+        }
+    }
+
+    /// Returns this element "rebased" on the provided parent type (which is usually from a
+    /// different schema than the one of this element), erroring if that's not possible.
+    pub(crate) fn rebase_on(
+        &self,
+        parent_type: &CompositeTypeDefinitionPosition,
+        schema: &ValidFederationSchema,
+    ) -> Result<OpPathElement, FederationError> {
+        let rebased = match self {
+            OpPathElement::Field(field) => field
+                .rebase_on(parent_type, schema, RebaseErrorHandlingOption::ThrowError)?
+                .map(OpPathElement::Field),
+            OpPathElement::InlineFragment(inline) => inline
+                .rebase_on(parent_type, schema, RebaseErrorHandlingOption::ThrowError)?
+                .map(OpPathElement::InlineFragment),
+        };
+        rebased.ok_or_else(|| {
+            FederationError::internal(format!(
+                "Cannot rebase operation element {self} on type \"{parent_type}\""
+            ))
+        })
+    }
+
     pub(crate) fn extract_operation_conditionals(
         &self,
     ) -> Result<Vec<OperationConditional>, FederationError> {
@@ -368,7 +415,8 @@ impl OpPathElement {
                 let updated_directives: DirectiveList = inline_fragment
                     .data()
                     .directives
-                    .get_all("defer")
+                    .iter()
+                    .filter(|directive| directive.name != "defer")
                     .cloned()
                     .collect();
                 if inline_fragment.data().type_condition_position.is_none()
@@ -771,7 +819,11 @@ where
     TEdge: Copy + Into<Option<EdgeIndex>>,
     EdgeIndex: Into<TEdge>,
 {
-    pub(crate) fn new(graph: Arc<QueryGraph>, head: NodeIndex) -> Result<Self, FederationError> {
+    pub(crate) fn new(
+        graph: Arc<QueryGraph>,
+        head: NodeIndex,
+        override_conditions: Arc<EnabledOverrideConditions>,
+    ) -> Result<Self, FederationError> {
         let mut path = Self {
             graph,
             head,
@@ -782,6 +834,7 @@ where
             last_subgraph_entering_edge_info: None,
             own_path_ids: Arc::new(IndexSet::new()),
             overriding_path_ids: Arc::new(IndexSet::new()),
+            override_conditions,
             runtime_types_of_tail: Arc::new(IndexSet::new()),
             runtime_types_before_tail_if_last_is_cast: None,
             defer_on_tail: None,
@@ -848,6 +901,7 @@ where
                 },
                 own_path_ids: self.own_path_ids.clone(),
                 overriding_path_ids: self.overriding_path_ids.clone(),
+                override_conditions: self.override_conditions.clone(),
                 runtime_types_of_tail: Arc::new(
                     self.graph
                         .advance_possible_runtime_types(&self.runtime_types_of_tail, None)?,
@@ -978,6 +1032,7 @@ where
                                             .clone(),
                                         own_path_ids: self.own_path_ids.clone(),
                                         overriding_path_ids: self.overriding_path_ids.clone(),
+                                        override_conditions: self.override_conditions.clone(),
                                         runtime_types_of_tail: Arc::new(new_runtime_types_of_tail),
                                         runtime_types_before_tail_if_last_is_cast: self
                                             .runtime_types_before_tail_if_last_is_cast
@@ -1046,6 +1101,7 @@ where
                     },
                     own_path_ids: self.own_path_ids.clone(),
                     overriding_path_ids: self.overriding_path_ids.clone(),
+                    override_conditions: self.override_conditions.clone(),
                     runtime_types_of_tail: Arc::new(self.graph.advance_possible_runtime_types(
                         &self.runtime_types_of_tail,
                         Some(new_edge),
@@ -1081,6 +1137,7 @@ where
             },
             own_path_ids: self.own_path_ids.clone(),
             overriding_path_ids: self.overriding_path_ids.clone(),
+            override_conditions: self.override_conditions.clone(),
             runtime_types_of_tail: Arc::new(
                 self.graph
                     .advance_possible_runtime_types(&self.runtime_types_of_tail, Some(new_edge))?,
@@ -1784,7 +1841,8 @@ where
 
 impl OpGraphPath {
     fn next_edge_for_field(&self, field: &NormalizedField) -> Option<EdgeIndex> {
-        self.graph.edge_for_field(self.tail, field)
+        self.graph
+            .edge_for_field(self.tail, field, &self.override_conditions)
     }
 
     fn next_edge_for_inline_fragment(
@@ -1963,7 +2021,10 @@ impl OpGraphPath {
             directives: Arc::new(Default::default()),
             sibling_typename: None,
         });
-        let Some(edge) = self.graph.edge_for_field(path.tail, &typename_field) else {
+        let Some(edge) =
+            self.graph
+                .edge_for_field(path.tail, &typename_field, &self.override_conditions)
+        else {
             return Err(FederationError::internal(
                 "Unexpectedly missing edge for __typename field",
             ));
@@ -2001,7 +2062,11 @@ impl OpGraphPath {
         let Some(last_edge_index) = last_edge_index else {
             // PORT_NOTE: The JS codebase just returns the same path if all edges are downcast or
             // `None` edges. This is likely a bug, so we instead return the empty path here.
-            return OpGraphPath::new(self.graph.clone(), self.head);
+            return OpGraphPath::new(
+                self.graph.clone(),
+                self.head,
+                self.override_conditions.clone(),
+            );
         };
         let prefix_length = last_edge_index + 1;
         if prefix_length == self.edges.len() {
@@ -2023,6 +2088,7 @@ impl OpGraphPath {
             last_subgraph_entering_edge_info: self.last_subgraph_entering_edge_info.clone(),
             own_path_ids: self.own_path_ids.clone(),
             overriding_path_ids: self.overriding_path_ids.clone(),
+            override_conditions: self.override_conditions.clone(),
             runtime_types_of_tail: last_runtime_types,
             runtime_types_before_tail_if_last_is_cast: None,
             // TODO: The JS codebase copied this from the current path, which seems like a bug.
@@ -2172,10 +2238,14 @@ impl OpGraphPath {
     // PORT_NOTE: In the JS code, this method was a free-standing function called "anImplementationIsEntityWithFieldShareable".
     fn has_an_entity_implementation_with_shareable_field(
         &self,
-        _source: &NodeStr,
+        source: &NodeStr,
         itf: InterfaceFieldDefinitionPosition,
     ) -> Result<bool, FederationError> {
-        let valid_schema = self.graph.schema()?;
+        // `__typename` is never declared on the implementations, and can't be `@shareable` anyway.
+        if itf.is_introspection_typename_field() {
+            return Ok(false);
+        }
+        let valid_schema = self.graph.schema_by_source(source)?;
         let schema = valid_schema.schema();
         let fed_spec = get_federation_spec_definition_from_subgraph(valid_schema)?;
         let key_directive = fed_spec.key_directive_definition(valid_schema)?;
@@ -3133,7 +3203,8 @@ impl SimultaneousPathsWithLazyIndirectPaths {
         path_index: usize,
         condition_resolver: &mut impl ConditionResolver,
     ) -> Result<OpIndirectPaths, FederationError> {
-        self.paths.0[path_index].advance_with_non_collecting_and_type_preserving_transitions(
+        let path = &self.paths.0[path_index];
+        path.advance_with_non_collecting_and_type_preserving_transitions(
             &self.context,
             condition_resolver,
             &self.excluded_destinations,
@@ -3142,7 +3213,9 @@ impl SimultaneousPathsWithLazyIndirectPaths {
             // the trigger is the context (which is really a hack to provide context information for
             // keys during fetch dependency graph updating).
             |_, context| OpGraphPathTrigger::Context(context.clone()),
-            |graph, node, trigger| graph.edge_for_op_graph_path_trigger(node, trigger),
+            |graph, node, trigger| {
+                graph.edge_for_op_graph_path_trigger(node, trigger, &path.override_conditions)
+            },
         )
     }
 
@@ -3351,11 +3424,9 @@ impl SimultaneousPathsWithLazyIndirectPaths {
 
 // PORT_NOTE: JS passes a ConditionResolver here, we do not: see port note for
 // `SimultaneousPathsWithLazyIndirectPaths`
-// TODO(@goto-bus-stop): JS passes `override_conditions` here and maintains stores
-// references to it in the created paths. AFAICT override conditions
-// are shared mutable state among different query graphs, so having references to
-// it in many structures would require synchronization. We should likely pass it as
-// an argument to exactly the functionality that uses it.
+// PORT_NOTE: JS passes `override_conditions` here and stores references to it in the created
+// paths. Here, the enabled override conditions are instead part of `initial_path`, and are
+// immutable for the duration of query planning, so paths can share them without synchronization.
 pub fn create_initial_options(
     initial_path: GraphPath<OpGraphPathTrigger, Option<EdgeIndex>>,
     initial_type: &QueryGraphNodeType,
@@ -3473,6 +3544,13 @@ impl OpPath {
         new.push(element);
         Self(new)
     }
+
+    // PORT_NOTE: Named `pathHasOnlyFragments()` in the JS codebase.
+    pub(crate) fn has_only_fragments(&self) -> bool {
+        self.0
+            .iter()
+            .all(|element| matches!(**element, OpPathElement::InlineFragment(_)))
+    }
 }
 
 impl TryFrom<&'_ OpPath> for Vec<QueryPathElement> {
@@ -3532,7 +3610,8 @@ mod tests {
         let schema = ValidFederationSchema::new(schema).unwrap();
         let name = NodeStr::new("S1");
         let graph = build_query_graph(name, schema.clone()).unwrap();
-        let path = OpGraphPath::new(Arc::new(graph), NodeIndex::new(0)).unwrap();
+        let path =
+            OpGraphPath::new(Arc::new(graph), NodeIndex::new(0), Default::default()).unwrap();
         assert_eq!(path.to_string(), "_");
         let pos = ObjectFieldDefinitionPosition {
             type_name: Name::new("T").unwrap(),
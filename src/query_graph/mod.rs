@@ -107,6 +107,26 @@ pub(crate) struct QueryGraphEdge {
     ///
     /// Outside of keys, @requires edges also rely on conditions.
     pub(crate) conditions: Option<Arc<NormalizedSelectionSet>>,
+    /// Edges can require that an override condition (provided during query planning) be met in
+    /// order to be taken. This is used for progressive @override, where (at least) 2 subgraphs can
+    /// resolve the same field, but one of them has an @override with a label. If the override
+    /// condition matches the query plan parameters, this edge can be taken.
+    pub(crate) override_condition: Option<OverrideCondition>,
+}
+
+impl QueryGraphEdge {
+    /// Returns whether this edge can be taken given the override conditions enabled for the
+    /// current query plan.
+    pub(crate) fn satisfies_override_conditions(
+        &self,
+        conditions_to_check: &EnabledOverrideConditions,
+    ) -> bool {
+        if let Some(override_condition) = &self.override_condition {
+            override_condition.condition == conditions_to_check.contains(&override_condition.label)
+        } else {
+            true
+        }
+    }
 }
 
 impl Display for QueryGraphEdge {
@@ -119,10 +139,49 @@ impl Display for QueryGraphEdge {
             return Ok(());
         }
         if let Some(conditions) = &self.conditions {
-            write!(f, "{} ⊢ {}", conditions, self.transition)
+            write!(f, "{} ⊢ {}", conditions, self.transition)?;
         } else {
-            self.transition.fmt(f)
+            self.transition.fmt(f)?;
         }
+        if let Some(override_condition) = &self.override_condition {
+            write!(f, " ({})", override_condition)?;
+        }
+        Ok(())
+    }
+}
+
+/// The condition attached to a field edge by a progressive @override application (i.e. one with
+/// a `label` argument).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OverrideCondition {
+    pub(crate) label: String,
+    /// `true` for the edge in the subgraph doing the overriding, and `false` for the edge in the
+    /// subgraph being overridden.
+    pub(crate) condition: bool,
+}
+
+impl Display for OverrideCondition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.label, self.condition)
+    }
+}
+
+/// The set of progressive @override labels that are enabled when building a query plan.
+// PORT_NOTE: In the JS codebase, this is a map from every label in the supergraph to a boolean,
+// where missing labels are initialized to `false`. Here, presence in the set means `true` and
+// absence means `false`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct EnabledOverrideConditions(IndexSet<String>);
+
+impl EnabledOverrideConditions {
+    pub(crate) fn contains(&self, label: &str) -> bool {
+        self.0.contains(label)
+    }
+}
+
+impl FromIterator<String> for EnabledOverrideConditions {
+    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
     }
 }
 
@@ -526,6 +585,7 @@ impl QueryGraph {
         &self,
         node: NodeIndex,
         field: &NormalizedField,
+        override_conditions: &EnabledOverrideConditions,
     ) -> Option<EdgeIndex> {
         let mut candidates = self.out_edges(node).filter_map(|edge_ref| {
             let edge_weight = edge_ref.weight();
@@ -536,6 +596,9 @@ impl QueryGraph {
             else {
                 return None;
             };
+            if !edge_weight.satisfies_override_conditions(override_conditions) {
+                return None;
+            }
             // We explicitly avoid comparing parent type's here, to allow interface object
             // fields to match operation fields with the same name but differing types.
             if field.data().field_position.field_name() == field_definition_position.field_name() {
@@ -602,12 +665,15 @@ impl QueryGraph {
         &self,
         node: NodeIndex,
         op_graph_path_trigger: &OpGraphPathTrigger,
+        override_conditions: &EnabledOverrideConditions,
     ) -> Option<Option<EdgeIndex>> {
         let OpGraphPathTrigger::OpPathElement(op_path_element) = op_graph_path_trigger else {
             return None;
         };
         match op_path_element {
-            OpPathElement::Field(field) => self.edge_for_field(node, field).map(Some),
+            OpPathElement::Field(field) => self
+                .edge_for_field(node, field, override_conditions)
+                .map(Some),
             OpPathElement::InlineFragment(inline_fragment) => {
                 if inline_fragment.data().type_condition_position.is_some() {
                     self.edge_for_inline_fragment(node, inline_fragment)
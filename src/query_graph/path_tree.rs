@@ -343,6 +343,30 @@ where
             childs,
         })
     }
+
+    /// Appends the children of `other` to the children of this path tree. Contrarily to `merge`,
+    /// children with the same edge and trigger are kept separate.
+    pub(crate) fn concat(self: &Arc<Self>, other: &Arc<Self>) -> Arc<Self> {
+        assert!(
+            Arc::ptr_eq(&self.graph, &other.graph),
+            "Cannot concat path tree build on another graph"
+        );
+        assert_eq!(
+            self.node, other.node,
+            "Cannot concat path trees rooted different nodes"
+        );
+        Arc::new(Self {
+            graph: self.graph.clone(),
+            node: self.node,
+            local_selection_sets: self
+                .local_selection_sets
+                .iter()
+                .chain(&other.local_selection_sets)
+                .cloned()
+                .collect(),
+            childs: self.childs.iter().chain(&other.childs).cloned().collect(),
+        })
+    }
 }
 
 fn merge_conditions(
@@ -404,7 +428,8 @@ mod tests {
     ) -> Result<OpGraphPath, FederationError> {
         let nodes_by_kind = query_graph.root_kinds_to_nodes()?;
         let root_node_idx = nodes_by_kind[&op_kind];
-        let mut graph_path = OpGraphPath::new(query_graph.clone(), root_node_idx)?;
+        let mut graph_path =
+            OpGraphPath::new(query_graph.clone(), root_node_idx, Default::default())?;
         let mut curr_node_idx = root_node_idx;
         for field_name in path.iter() {
             // find the edge that matches `field_name`
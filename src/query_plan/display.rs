@@ -160,9 +160,7 @@ impl ConditionNode {
                 state.indent()?;
                 if_clause.write_indented(state)?;
                 state.dedent()?;
-                state.write("}")?;
-
-                state.write("Else {")?;
+                state.write("} Else {")?;
                 state.indent()?;
                 else_clause.write_indented(state)?;
                 state.dedent()?;
@@ -286,6 +284,10 @@ impl DeferredDeferBlock {
 
             if let Some(sub_selection) = sub_selection {
                 write_selections(state, &sub_selection.selections)?;
+                if node.is_some() {
+                    state.write(":")?;
+                    state.new_line()?;
+                }
             }
             if let Some(node) = node {
                 node.write_indented(state)?;
@@ -5,7 +5,7 @@ use crate::query_graph::graph_path::{
     selection_of_element, OpGraphPathContext, OpGraphPathTrigger, OpPath, OpPathElement,
 };
 use crate::query_graph::path_tree::{OpPathTree, PathTreeChild};
-use crate::query_graph::{QueryGraph, QueryGraphEdgeTransition};
+use crate::query_graph::{QueryGraph, QueryGraphEdgeTransition, QueryGraphNodeType};
 use crate::query_plan::conditions::{remove_conditions_from_selection_set, Conditions};
 use crate::query_plan::fetch_dependency_graph_processor::FetchDependencyGraphProcessor;
 use crate::query_plan::operation::{
@@ -17,14 +17,15 @@ use crate::query_plan::FetchDataPathElement;
 use crate::query_plan::{FetchDataRewrite, FetchDataValueSetter, QueryPlanCost};
 use crate::schema::position::{
     CompositeTypeDefinitionPosition, FieldDefinitionPosition, ObjectTypeDefinitionPosition,
-    SchemaRootDefinitionKind,
+    OutputTypeDefinitionPosition, SchemaRootDefinitionKind,
 };
 use crate::schema::ValidFederationSchema;
 use crate::subgraph::spec::{ANY_SCALAR_NAME, ENTITIES_QUERY};
 use apollo_compiler::ast::{Argument, Directive, OperationType, Type};
 use apollo_compiler::executable::{self, VariableDefinition};
 use apollo_compiler::schema::{self, Name};
-use apollo_compiler::{name, Node, NodeStr};
+use apollo_compiler::validation::Valid;
+use apollo_compiler::{name, ExecutableDocument, Node, NodeStr};
 use indexmap::{IndexMap, IndexSet};
 use petgraph::stable_graph::{EdgeIndex, NodeIndex, StableDiGraph};
 use petgraph::visit::EdgeRef;
@@ -102,6 +103,11 @@ impl FetchIdGenerator {
     pub fn next_id(&self) -> u64 {
         self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
     }
+
+    /// Returns the ID that will be generated next, without generating it.
+    pub fn peek_next_id(&self) -> u64 {
+        self.next.load(std::sync::atomic::Ordering::Relaxed)
+    }
 }
 
 impl Clone for FetchIdGenerator {
@@ -281,6 +287,18 @@ impl DeferContext {
             is_part_of_query: self.is_part_of_query,
         }
     }
+
+    /// The defer context to use when computing the nodes for the conditions of a `@requires`.
+    // PORT_NOTE: This corresponds to `deferContextForConditions` in the JS codebase.
+    fn for_conditions(&self) -> Self {
+        Self {
+            current_defer_ref: self.active_defer_ref.clone(),
+            is_part_of_query: false,
+            // Clone the rest as-is
+            path_to_defer_parent: self.path_to_defer_parent.clone(),
+            active_defer_ref: self.active_defer_ref.clone(),
+        }
+    }
 }
 
 impl Default for DeferContext {
@@ -491,6 +509,18 @@ impl FetchDependencyGraph {
         Ok(node)
     }
 
+    /// The subgraphs of the root fetch nodes of this graph.
+    pub(crate) fn root_subgraphs(&self) -> impl Iterator<Item = &NodeStr> {
+        self.root_nodes_by_subgraph.keys()
+    }
+
+    /// The fetch ID at which a fetch dependency graph planned after this one should start.
+    // PORT_NOTE: The JS codebase allocates an ID for each node when it is created, while we
+    // allocate them lazily. So we reserve an ID for every node that may still need one.
+    pub(crate) fn next_fetch_id(&self) -> u64 {
+        self.fetch_id_generation.peek_next_id() + self.graph.node_count() as u64
+    }
+
     fn new_root_type_node(
         &mut self,
         subgraph_name: NodeStr,
@@ -733,6 +763,52 @@ impl FetchDependencyGraph {
             })
     }
 
+    /// Returns the relation of `child_id` to the given parent, if `parent_id` is a parent of it.
+    fn parent_relation(&self, child_id: NodeIndex, parent_id: NodeIndex) -> Option<ParentRelation> {
+        self.parents_relations_of(child_id)
+            .find(|relation| relation.parent_node_id == parent_id)
+    }
+
+    fn remove_child_edge(&mut self, node_id: NodeIndex, child_id: NodeIndex) {
+        let edges = self
+            .graph
+            .edges_connecting(node_id, child_id)
+            .map(|edge| edge.id())
+            .collect::<Vec<_>>();
+        if !edges.is_empty() {
+            self.on_modification();
+        }
+        for edge in edges {
+            self.graph.remove_edge(edge);
+        }
+    }
+
+    /// Computes the "path in parent" of a node created after `condition_node_id`, when the new
+    /// node would otherwise be a child of `node_id` at `path_in_node`.
+    ///
+    /// If `condition_node_id` parent is `node_id`, that is the same as the new node's parent, then
+    /// we can infer the path of the new node into that condition node by looking at the paths of
+    /// each to their common parent. But otherwise, we cannot have a proper "path in parent".
+    fn path_in_condition_node(
+        &self,
+        condition_node_id: NodeIndex,
+        node_id: NodeIndex,
+        path_in_node: &OpPath,
+    ) -> Option<Arc<OpPath>> {
+        let mut iter = self.parents_relations_of(condition_node_id);
+        let (Some(condition_node_parent), None) = (iter.next(), iter.next()) else {
+            return None;
+        };
+        // There is exactly one parent
+        if condition_node_parent.parent_node_id != node_id {
+            return None;
+        }
+        condition_node_parent
+            .path_in_parent?
+            .strip_prefix(path_in_node)
+            .map(Arc::new)
+    }
+
     fn type_for_fetch_inputs(
         &self,
         type_name: &Name,
@@ -742,12 +818,269 @@ impl FetchDependencyGraph {
             .try_into()
     }
 
+    /// Whether `child_id` is a child of `maybe_parent_id` but doesn't truly depend on it, meaning
+    /// that all of its inputs are already the inputs of `maybe_parent_id` (or that it has no
+    /// inputs at all).
+    fn is_child_of_with_artificial_dependency(
+        &self,
+        child_id: NodeIndex,
+        maybe_parent_id: NodeIndex,
+    ) -> Result<bool, FederationError> {
+        // To be a child with an artificial dependency, it needs to be a child first, and the
+        // "path in parent" should be known.
+        let Some(path_in_parent) = self
+            .parent_relation(child_id, maybe_parent_id)
+            .and_then(|relation| relation.path_in_parent)
+        else {
+            return Ok(false);
+        };
+        let child = self.node_weight(child_id)?;
+        // Then, if we have no inputs, we know we don't depend on anything from the parent no
+        // matter what.
+        let Some(child_inputs) = &child.inputs else {
+            return Ok(true);
+        };
+        // If we do have inputs, then we first look at the path to `maybe_parent_id` which needs
+        // to be "essentially empty". "essentially" is because path can sometimes have some
+        // leading fragment(s) and those are fine to ignore. But if the path has some field, then
+        // this implies that the inputs of the child are based on something at a deeper level than
+        // those of `maybe_parent_id`, and the "contains" comparison we do below would not make
+        // sense.
+        if path_in_parent
+            .0
+            .iter()
+            .any(|element| matches!(**element, OpPathElement::Field(_)))
+        {
+            return Ok(false);
+        }
+        // In theory, the most general test we could have here is to check if the child's inputs
+        // are fetched by the parent, but that's a bit complex to do, so we check if the parent's
+        // inputs contain the child's inputs instead.
+        let maybe_parent = self.node_weight(maybe_parent_id)?;
+        Ok(maybe_parent
+            .inputs
+            .as_ref()
+            .is_some_and(|parent_inputs| parent_inputs.contains(child_inputs)))
+    }
+
+    fn can_merge_child_in(
+        &self,
+        node_id: NodeIndex,
+        child_id: NodeIndex,
+    ) -> Result<bool, FederationError> {
+        let node = self.node_weight(node_id)?;
+        let child = self.node_weight(child_id)?;
+        Ok(node.defer_ref == child.defer_ref
+            && self
+                .parent_relation(child_id, node_id)
+                .is_some_and(|relation| relation.path_in_parent.is_some()))
+    }
+
+    /// Merges a child of `node_id` into it.
+    ///
+    /// Note that it is up to the caller to know that doing such a merge is reasonable in the
+    /// first place, which generally means knowing that 1) `child.inputs` are included in
+    /// `node.selection` and 2) all of `child.selection` can safely be queried on the
+    /// `node.subgraph_name` subgraph.
+    fn merge_child_in(
+        &mut self,
+        node_id: NodeIndex,
+        child_id: NodeIndex,
+    ) -> Result<(), FederationError> {
+        let Some(path_in_parent) = self
+            .parent_relation(child_id, node_id)
+            .and_then(|relation| relation.path_in_parent)
+        else {
+            return Err(FederationError::internal(format!(
+                "Cannot merge node {} into {}: it is not a child with a known path",
+                child_id.index(),
+                node_id.index(),
+            )));
+        };
+        self.merge_in_internal(node_id, child_id, &path_in_parent)
+    }
+
+    /// We only allow merging sibling on the same subgraph, same "merge_at" and when the common
+    /// parent is their only parent:
+    /// - there is no reason merging siblings of different subgraphs could ever make sense.
+    /// - same "merge_at" is so that our sibling selection can be merged at the same level.
+    /// - only one parent: we don't want to merge a node that depends on 2 different parents.
+    fn can_merge_sibling_in(
+        &self,
+        node_id: NodeIndex,
+        sibling_id: NodeIndex,
+    ) -> Result<bool, FederationError> {
+        let node = self.node_weight(node_id)?;
+        let sibling = self.node_weight(sibling_id)?;
+        if node.defer_ref != sibling.defer_ref
+            || node.subgraph_name != sibling.subgraph_name
+            || node.merge_at != sibling.merge_at
+        {
+            return Ok(false);
+        }
+        let mut own_parents = self.parents_of(node_id);
+        let mut sibling_parents = self.parents_of(sibling_id);
+        Ok(
+            match (
+                own_parents.next(),
+                own_parents.next(),
+                sibling_parents.next(),
+                sibling_parents.next(),
+            ) {
+                (Some(own_parent), None, Some(sibling_parent), None) => {
+                    own_parent == sibling_parent
+                }
+                _ => false,
+            },
+        )
+    }
+
+    fn merge_sibling_in(
+        &mut self,
+        node_id: NodeIndex,
+        sibling_id: NodeIndex,
+    ) -> Result<(), FederationError> {
+        let sibling = self.node_weight(sibling_id)?.clone();
+        let node = FetchDependencyGraph::node_weight_mut(&mut self.graph, node_id)?;
+        node.copy_inputs(&self.supergraph_schema, &sibling)?;
+        self.merge_in_internal(node_id, sibling_id, &OpPath::default())
+    }
+
+    fn can_merge_grand_child_in(
+        &self,
+        node_id: NodeIndex,
+        grand_child_id: NodeIndex,
+    ) -> Result<bool, FederationError> {
+        let mut grand_child_parents = self.parents_relations_of(grand_child_id);
+        let (Some(grand_child_parent), None) =
+            (grand_child_parents.next(), grand_child_parents.next())
+        else {
+            return Ok(false);
+        };
+        let node = self.node_weight(node_id)?;
+        let grand_child = self.node_weight(grand_child_id)?;
+        Ok(node.defer_ref == grand_child.defer_ref
+            && grand_child_parent.path_in_parent.is_some()
+            && self
+                .parent_relation(grand_child_parent.parent_node_id, node_id)
+                .is_some_and(|relation| relation.path_in_parent.is_some()))
+    }
+
+    /// Merges a grand child of `node_id` into it.
+    ///
+    /// Note that it is up to the caller to know that doing such a merge is reasonable in the
+    /// first place, which generally means knowing that 1) `grand_child.inputs` are included in
+    /// `node.selection` and 2) all of `grand_child.selection` can safely be queried on the
+    /// `node.subgraph_name` subgraph (the grand child does not depend on the parent in the
+    /// middle, say).
+    fn merge_grand_child_in(
+        &mut self,
+        node_id: NodeIndex,
+        grand_child_id: NodeIndex,
+    ) -> Result<(), FederationError> {
+        let mut grand_child_parents = self.parents_relations_of(grand_child_id);
+        let (Some(grand_child_parent), None) =
+            (grand_child_parents.next(), grand_child_parents.next())
+        else {
+            return Err(FederationError::internal(format!(
+                "Cannot merge node {} as grand child of {}: it should have exactly one parent",
+                grand_child_id.index(),
+                node_id.index(),
+            )));
+        };
+        drop(grand_child_parents);
+        let path = concat_paths_in_parents(
+            &self
+                .parent_relation(grand_child_parent.parent_node_id, node_id)
+                .and_then(|relation| relation.path_in_parent),
+            &grand_child_parent.path_in_parent,
+        )
+        .ok_or_else(|| {
+            FederationError::internal(format!(
+                "Cannot merge node {} into {}: the path to the grand child is unknown",
+                grand_child_id.index(),
+                node_id.index(),
+            ))
+        })?;
+        self.merge_in_internal(node_id, grand_child_id, &path)
+    }
+
+    fn merge_in_internal(
+        &mut self,
+        node_id: NodeIndex,
+        merged_id: NodeIndex,
+        path: &OpPath,
+    ) -> Result<(), FederationError> {
+        let merged = self.node_weight(merged_id)?.clone();
+        if merged.is_top_level() {
+            return Err(FederationError::internal(
+                "Shouldn't remove top level nodes",
+            ));
+        }
+        // PORT_NOTE: The JS codebase additionally removes the top-level @include/@skip of the
+        // merged selection that are already part of the path. Keeping them is redundant but
+        // harmless.
+        let node = FetchDependencyGraph::node_weight_mut(&mut self.graph, node_id)?;
+        node.selection_set_mut()
+            .add_at_path(path, Some(&merged.selection_set.selection_set))?;
+        if merged.must_preserve_selection_set {
+            node.must_preserve_selection_set = true;
+        }
+        self.relocate_children_on_merged_in(node_id, merged_id, path);
+        self.remove_node(merged_id);
+        Ok(())
+    }
+
+    fn relocate_children_on_merged_in(
+        &mut self,
+        merged_into_id: NodeIndex,
+        merged_id: NodeIndex,
+        path_in_merged_into: &OpPath,
+    ) {
+        let mut new_parent_relations = Vec::new();
+        for child_id in self.children_of(merged_id) {
+            // This could already be a child of `merged_into`. Typically, we can have case where
+            // we have:
+            //     1
+            //   /  \
+            //  0    3
+            //   \  /
+            //     2
+            // and we can merge siblings 2 into 1.
+            if self.graph.contains_edge(merged_into_id, child_id) {
+                continue;
+            }
+            let path_in_merged = self
+                .parent_relation(child_id, merged_id)
+                .and_then(|relation| relation.path_in_parent);
+            new_parent_relations.push((
+                child_id,
+                ParentRelation {
+                    parent_node_id: merged_into_id,
+                    path_in_parent: concat_paths_in_parents(
+                        &Some(Arc::new(path_in_merged_into.clone())),
+                        &path_in_merged,
+                    )
+                    .map(Arc::new),
+                },
+            ));
+        }
+        for (child_id, parent_relation) in new_parent_relations {
+            self.add_parent(child_id, parent_relation);
+        }
+    }
+
+    fn remove_node(&mut self, node_id: NodeIndex) {
+        self.on_modification();
+        self.graph.remove_node(node_id);
+    }
+
     /// Find redundant edges coming out of a node. See `remove_redundant_edges`.
     fn collect_redundant_edges(&self, node_index: NodeIndex, acc: &mut HashSet<EdgeIndex>) {
         for start_index in self.children_of(node_index) {
             let mut stack = self.children_of(start_index).collect::<Vec<_>>();
             while let Some(v) = stack.pop() {
-                for edge in self.graph.edges_connecting(start_index, v) {
+                for edge in self.graph.edges_connecting(node_index, v) {
                     acc.insert(edge.id());
                 }
 
@@ -795,14 +1128,83 @@ impl FetchDependencyGraph {
     ///     was the one with the require and that forced some dependencies. Those fetch should have
     ///     no dependents and we can just remove them.
     ///  2) fetches that are made in parallel to the same subgraph and the same path, and merge those.
-    fn reduce_and_optimize(&mut self) {
+    fn reduce_and_optimize(&mut self) -> Result<(), FederationError> {
         if std::mem::replace(&mut self.is_optimized, true) {
-            return;
+            return Ok(());
         }
 
         self.reduce();
 
-        // TODO Optimize: FED-55
+        let root_node_ids = self
+            .root_nodes_by_subgraph
+            .values()
+            .copied()
+            .collect::<Vec<_>>();
+        for &node_id in &root_node_ids {
+            self.remove_empty_nodes(node_id)?;
+        }
+        for &node_id in &root_node_ids {
+            self.remove_useless_nodes(node_id)?;
+        }
+
+        // TODO Optimize: FED-55 (merge the fetches to the same subgraph and path)
+        Ok(())
+    }
+
+    /// Removes the non-top-level nodes with an empty selection. Those are usually temporary nodes
+    /// created while handling `@requires` that ended up not being needed.
+    // PORT_NOTE: Named `removeEmptyGroups()` in the JS codebase. Note that with `@defer`, the root
+    // node itself may be empty if everything is deferred, in which case it's kept and recognized
+    // when processing the nodes.
+    fn remove_empty_nodes(&mut self, node_id: NodeIndex) -> Result<(), FederationError> {
+        let children = self.children_of(node_id).collect::<Vec<_>>();
+        let node = self.node_weight(node_id)?;
+        if node.selection_set.selection_set.selections.is_empty() && !node.is_top_level() {
+            self.remove_node(node_id);
+        }
+        for child_id in children {
+            // A child may have several parents, and so may have been removed already.
+            if self.graph.contains_node(child_id) {
+                self.remove_empty_nodes(child_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the nodes that fetch nothing more than their inputs.
+    ///
+    /// Removing a node requires relocating its children, which is only done when that's simple:
+    /// either the node has no children, or it has a single parent and its path in that parent is
+    /// known. Other useless nodes are kept.
+    // PORT_NOTE: Named `removeUselessGroups()` in the JS codebase.
+    fn remove_useless_nodes(&mut self, node_id: NodeIndex) -> Result<(), FederationError> {
+        // Recursing first, this makes it a bit easier to reason about.
+        for child_id in self.children_of(node_id).collect::<Vec<_>>() {
+            if self.graph.contains_node(child_id) {
+                self.remove_useless_nodes(child_id)?;
+            }
+        }
+
+        if !FetchDependencyGraph::node_weight_mut(&mut self.graph, node_id)?.is_useless() {
+            return Ok(());
+        }
+        if self.children_of(node_id).next().is_none() {
+            self.remove_node(node_id);
+            return Ok(());
+        }
+        let mut parents = self.parents_relations_of(node_id);
+        let (Some(parent), None) = (parents.next(), parents.next()) else {
+            return Ok(());
+        };
+        drop(parents);
+        let Some(path_in_parent) = &parent.path_in_parent else {
+            return Ok(());
+        };
+        // The node fetches nothing, so its children can depend on its parent directly.
+        self.remove_child_edge(parent.parent_node_id, node_id);
+        self.relocate_children_on_merged_in(parent.parent_node_id, node_id, path_in_parent);
+        self.remove_node(node_id);
+        Ok(())
     }
 
     fn extract_children_and_deferred_dependencies(
@@ -823,10 +1225,11 @@ impl FetchDependencyGraph {
             if node.defer_ref == child.defer_ref {
                 children.push(child_index);
             } else {
-                let parent_defer_ref = node.defer_ref.as_ref().unwrap();
                 let Some(child_defer_ref) = &child.defer_ref else {
-                    panic!("{} has defer_ref `{parent_defer_ref}`, so its child {} cannot have a top-level defer_ref.",
+                    panic!(
+                        "{} has defer_ref `{}`, so its child {} cannot have a top-level defer_ref.",
                         node.display(node_index),
+                        node.defer_ref.as_deref().unwrap_or_default(),
                         child.display(child_index),
                     );
                 };
@@ -1100,7 +1503,7 @@ impl FetchDependencyGraph {
         mut processor: impl FetchDependencyGraphProcessor<TProcessed, TDeferred>,
         root_kind: SchemaRootDefinitionKind,
     ) -> Result<(TProcessed, Vec<TDeferred>), FederationError> {
-        self.reduce_and_optimize();
+        self.reduce_and_optimize()?;
 
         let (main_sequence, deferred) = self.process_root_nodes(
             &mut processor,
@@ -1213,6 +1616,86 @@ impl FetchDependencyGraphNode {
         }
     }
 
+    /// Copies the inputs (and input rewrites) of `other` into this node.
+    fn copy_inputs(
+        &mut self,
+        supergraph_schema: &ValidFederationSchema,
+        other: &FetchDependencyGraphNode,
+    ) -> Result<(), FederationError> {
+        if let Some(other_inputs) = &other.inputs {
+            let inputs = self
+                .inputs
+                .get_or_insert_with(|| Arc::new(FetchInputs::empty(supergraph_schema.clone())));
+            Arc::make_mut(inputs).add_all(other_inputs)?;
+            self.on_inputs_updated();
+            Arc::make_mut(&mut self.input_rewrites).extend(other.input_rewrites.iter().cloned());
+        }
+        Ok(())
+    }
+
+    /// Removes the inputs of this node from its selection. This is used to check whether the
+    /// node fetches anything beyond what it takes as input.
+    fn remove_inputs_from_selection(&mut self) -> Result<(), FederationError> {
+        let Some(inputs) = self.inputs.clone() else {
+            return Ok(());
+        };
+        let mut selection_set = (*self.selection_set.selection_set).clone();
+        for input_selection_set in inputs.selection_sets_per_parent_type.values() {
+            selection_set = selection_set.minus(input_selection_set)?;
+        }
+        let selection_set = Arc::new(selection_set);
+        let conditions = selection_set.conditions()?;
+        *self.selection_set_mut() = FetchSelectionSet {
+            selection_set,
+            conditions,
+        };
+        Ok(())
+    }
+
+    /// Whether this node fetches nothing more than its inputs, in which case it can be removed.
+    // PORT_NOTE: The JS codebase additionally handles `@interfaceObject` nodes, whose inputs are
+    // on the implementation types while the selection is on the interface type. Those nodes are
+    // never considered useless here.
+    fn is_useless(&mut self) -> bool {
+        if self.is_known_useful || self.must_preserve_selection_set {
+            return false;
+        }
+        let Some(inputs) = &self.inputs else {
+            return false;
+        };
+        let is_useless = self
+            .selection_set
+            .selection_set
+            .selections
+            .values()
+            .all(|selection| {
+                let NormalizedSelection::InlineFragment(fragment) = selection else {
+                    return false;
+                };
+                let Some(type_condition) = &fragment.inline_fragment.data().type_condition_position
+                else {
+                    return false;
+                };
+                inputs
+                    .selection_sets_per_parent_type
+                    .get(type_condition)
+                    .is_some_and(|input_selection_set| {
+                        input_selection_set
+                            .selections
+                            .values()
+                            .any(|input_selection| input_selection.contains(selection))
+                    })
+            });
+        self.is_known_useful = !is_useless;
+        is_useless
+    }
+
+    /// Whether this is a top-level fetch, that is, a fetch whose result is not merged at some
+    /// path of the response of a previous fetch.
+    fn is_top_level(&self) -> bool {
+        self.merge_at.is_none()
+    }
+
     pub(crate) fn cost(&mut self) -> Result<QueryPlanCost, FederationError> {
         if self.cached_cost.is_none() {
             self.cached_cost = Some(self.selection_set.selection_set.cost(1)?)
@@ -1227,6 +1710,7 @@ impl FetchDependencyGraphNode {
         variable_definitions: &[Node<VariableDefinition>],
         fragments: Option<&mut RebasedFragments>,
         operation_name: Option<NodeStr>,
+        subgraph_graphql_validation: bool,
     ) -> Result<Option<super::PlanNode>, FederationError> {
         if self.selection_set.selection_set.selections.is_empty() {
             return Ok(None);
@@ -1264,14 +1748,37 @@ impl FetchDependencyGraphNode {
         };
         let fragments = fragments
             .map(|rebased| rebased.for_subgraph(self.subgraph_name.clone(), subgraph_schema));
-        operation.optimize(fragments, Default::default());
-        let operation_document = operation.try_into()?;
+        operation.optimize(fragments, Default::default())?;
+        let operation_document: Valid<ExecutableDocument> = operation.try_into()?;
+        if subgraph_graphql_validation {
+            // The printed operation is validated (rather than the document), as that's what the
+            // subgraph receives and what the locations of the errors should point into.
+            let operation = operation_document.to_string();
+            if let Err(invalid) = ExecutableDocument::parse_and_validate(
+                subgraph_schema.schema(),
+                &operation,
+                "operation.graphql",
+            ) {
+                return Err(SingleFederationError::InvalidSubgraphOperation {
+                    subgraph: self.subgraph_name.to_string(),
+                    errors: invalid
+                        .errors
+                        .iter()
+                        .map(|diagnostic| diagnostic.to_json())
+                        .collect(),
+                    operation,
+                }
+                .into());
+            }
+        }
 
         let node = super::PlanNode::Fetch(Box::new(super::FetchNode {
             subgraph_name: self.subgraph_name.clone(),
             id: self.id.get().copied(),
             variable_usages,
-            requires: input_nodes.map(|sel| executable::SelectionSet::from(sel).selections),
+            requires: input_nodes
+                .map(|sel| executable::SelectionSet::try_from(&sel).map(|set| set.selections))
+                .transpose()?,
             operation_document,
             operation_name,
             operation_kind: self.root_kind.into(),
@@ -1578,7 +2085,7 @@ impl FetchSelectionSet {
         path_in_node: &OpPath,
         selection_set: Option<&Arc<NormalizedSelectionSet>>,
     ) -> Result<(), FederationError> {
-        Arc::make_mut(&mut self.selection_set).add_at_path(path_in_node, selection_set);
+        Arc::make_mut(&mut self.selection_set).add_at_path(path_in_node, selection_set)?;
         // TODO: when calling this multiple times, maybe only re-compute conditions at the end?
         // Or make it lazily-initialized and computed on demand?
         self.conditions = self.selection_set.conditions()?;
@@ -1612,6 +2119,18 @@ impl FetchInputs {
         // PORT_NOTE: `onUpdateCallback` call is moved to `FetchDependencyGraphNode::on_inputs_updated`.
     }
 
+    /// Returns true if all the inputs in `other` are included in these inputs.
+    fn contains(&self, other: &Self) -> bool {
+        other
+            .selection_sets_per_parent_type
+            .iter()
+            .all(|(parent_type, other_selection_set)| {
+                self.selection_sets_per_parent_type
+                    .get(parent_type)
+                    .is_some_and(|selection_set| selection_set.contains(other_selection_set))
+            })
+    }
+
     fn add_all(&mut self, other: &Self) -> Result<(), FederationError> {
         for selections in other.selection_sets_per_parent_type.values() {
             self.add(selections)?;
@@ -1684,10 +2203,10 @@ impl DeferTracking {
         defer_args: &DeferDirectiveArguments,
         path: FetchDependencyGraphNodePath,
         parent_type: CompositeTypeDefinitionPosition,
-    ) {
+    ) -> Result<(), FederationError> {
         // Having the primary selection undefined means that @defer handling is actually disabled, so there's no need to track anything.
         let Some(primary_selection) = self.primary_selection.as_mut() else {
-            return;
+            return Ok(());
         };
 
         let label = defer_args
@@ -1710,10 +2229,10 @@ impl DeferTracking {
             parent_info.deferred.insert(label.clone());
             parent_info
                 .sub_selection
-                .add_at_path(&defer_context.path_to_defer_parent, None);
+                .add_at_path(&defer_context.path_to_defer_parent, None)
         } else {
             self.top_level_deferred.insert(label.clone());
-            primary_selection.add_at_path(&defer_context.path_to_defer_parent, None);
+            primary_selection.add_at_path(&defer_context.path_to_defer_parent, None)
         }
     }
 
@@ -1721,12 +2240,12 @@ impl DeferTracking {
         &mut self,
         defer_context: &DeferContext,
         selection_set: Option<&Arc<NormalizedSelectionSet>>,
-    ) {
+    ) -> Result<(), FederationError> {
         if !defer_context.is_part_of_query {
-            return;
+            return Ok(());
         }
         let Some(primary_selection) = &mut self.primary_selection else {
-            return;
+            return Ok(());
         };
         if let Some(parent_ref) = &defer_context.current_defer_ref {
             self.deferred[parent_ref]
@@ -1789,6 +2308,48 @@ impl DeferredInfo {
     }
 }
 
+/// Computes the fetch nodes for a path tree that starts at the federated root of the query graph,
+/// creating (or reusing) a root fetch node for each subgraph entered by the tree.
+pub(crate) fn compute_root_fetch_nodes(
+    root_kind: SchemaRootDefinitionKind,
+    dependency_graph: &mut FetchDependencyGraph,
+    path_tree: &OpPathTree,
+) -> Result<(), FederationError> {
+    // The root of the pathTree is one of the "fake" root of the subgraphs graph,
+    // which belongs to no subgraph but points to each ones.
+    // So we "unpack" the first level of the tree to find out our top level groups
+    // (and initialize our stack).
+    // Note that we can safely ignore the triggers of that first level
+    // as it will all be free transition, and we know we cannot have conditions.
+    for child in &path_tree.childs {
+        let edge = child.edge.expect("The root edge should not be None");
+        let (_source_node, target_node) = path_tree.graph.edge_endpoints(edge)?;
+        let target_node = path_tree.graph.node_weight(target_node)?;
+        let subgraph_name = &target_node.source;
+        let root_type = match &target_node.type_ {
+            QueryGraphNodeType::SchemaType(OutputTypeDefinitionPosition::Object(object)) => {
+                object.clone().into()
+            }
+            ty => {
+                return Err(FederationError::internal(format!(
+                    "expected an object type for the root of a subgraph, found {ty}"
+                )))
+            }
+        };
+        let fetch_dependency_node =
+            dependency_graph.get_or_create_root_node(subgraph_name, root_kind, root_type)?;
+        compute_nodes_for_tree(
+            dependency_graph,
+            &child.tree,
+            fetch_dependency_node,
+            Default::default(),
+            Default::default(),
+            &Default::default(),
+        )?;
+    }
+    Ok(())
+}
+
 struct ComputeNodesStackItem<'a> {
     tree: &'a OpPathTree,
     node_id: NodeIndex,
@@ -1821,14 +2382,14 @@ pub(crate) fn compute_nodes_for_tree(
                 .add_at_path(&stack_item.node_path.path_in_node, Some(selection_set))?;
             dependency_graph
                 .defer_tracking
-                .update_subselection(&stack_item.defer_context, Some(selection_set));
+                .update_subselection(&stack_item.defer_context, Some(selection_set))?;
         }
         if stack_item.tree.is_leaf() {
             node.selection_set_mut()
                 .add_at_path(&stack_item.node_path.path_in_node, None)?;
             dependency_graph
                 .defer_tracking
-                .update_subselection(&stack_item.defer_context, None);
+                .update_subselection(&stack_item.defer_context, None)?;
             continue;
         }
         // We want to preserve the order of the elements in the child,
@@ -1914,7 +2475,7 @@ fn compute_nodes_for_key_resolution<'a>(
         conditions,
         stack_item.node_id,
         stack_item.node_path.clone(),
-        stack_item.defer_context.clone(),
+        stack_item.defer_context.for_conditions(),
         &Default::default(),
     )?;
     created_nodes.extend(conditions_nodes.iter().copied());
@@ -1954,22 +2515,11 @@ fn compute_nodes_for_key_resolution<'a>(
     )?;
     created_nodes.insert(new_node_id);
     for condition_node in conditions_nodes {
-        // If `condition_node` parent is `node_id`,
-        // that is the same as `new_node_id` current parent,
-        // then we can infer the path of `new_node_id` into that condition node
-        // by looking at the paths of each to their common parent.
-        // But otherwise, we cannot have a proper "path in parent".
-        let mut path = None;
-        let mut iter = dependency_graph.parents_relations_of(condition_node);
-        if let (Some(condition_node_parent), None) = (iter.next(), iter.next()) {
-            // There is exactly one parent
-            if condition_node_parent.parent_node_id == stack_item.node_id {
-                if let Some(condition_path) = condition_node_parent.path_in_parent {
-                    path = condition_path.strip_prefix(path_in_parent).map(Arc::new)
-                }
-            }
-        }
-        drop(iter);
+        let path = dependency_graph.path_in_condition_node(
+            condition_node,
+            stack_item.node_id,
+            path_in_parent,
+        );
         dependency_graph.add_parent(
             new_node_id,
             ParentRelation {
@@ -1983,7 +2533,7 @@ fn compute_nodes_for_key_resolution<'a>(
     // (and so no one subgraph has a type definition with all the proper fields,
     // only the supergraph does).
     let input_type = dependency_graph.type_for_fetch_inputs(source_type.type_name())?;
-    let mut input_selections = NormalizedSelectionSet::empty(
+    let mut input_selections = NormalizedSelectionSet::for_composite_type(
         dependency_graph.supergraph_schema.clone(),
         input_type.clone(),
     );
@@ -1993,7 +2543,7 @@ fn compute_nodes_for_key_resolution<'a>(
             "missing expected edge conditions",
         ));
     };
-    input_selections.merge_into(std::iter::once(edge_conditions.as_ref()))?;
+    input_selections.add_at_path(&Default::default(), Some(edge_conditions))?;
     let new_node =
         &mut FetchDependencyGraph::node_weight_mut(&mut dependency_graph.graph, new_node_id)?;
     new_node.add_inputs(
@@ -2232,7 +2782,7 @@ fn compute_nodes_for_op_path_element<'a>(
                 ..stack_item.defer_context.clone()
             },
             None,
-        )
+        )?;
     }
     let Ok((Some(updated_operation), updated_defer_context)) = extract_defer_from_operation(
         dependency_graph,
@@ -2510,7 +3060,7 @@ fn extract_defer_from_operation(
         &defer_args,
         node_path.clone(),
         operation.parent_type_position(),
-    );
+    )?;
 
     let updated_context = DeferContext {
         current_defer_ref: Some(updated_defer_ref.into()),
@@ -2523,15 +3073,530 @@ fn extract_defer_from_operation(
 }
 
 fn handle_requires(
-    _dependency_graph: &mut FetchDependencyGraph,
-    _edge_id: EdgeIndex,
-    _requires_conditions: &OpPathTree,
-    (_node_id, _node_path): (NodeIndex, &FetchDependencyGraphNodePath),
-    _context: &OpGraphPathContext,
-    _defer_context: &DeferContext,
-    _created_nodes: &mut IndexSet<NodeIndex>,
+    dependency_graph: &mut FetchDependencyGraph,
+    edge_id: EdgeIndex,
+    requires_conditions: &OpPathTree,
+    (node_id, node_path): (NodeIndex, &FetchDependencyGraphNodePath),
+    context: &OpGraphPathContext,
+    defer_context: &DeferContext,
+    created_nodes: &mut IndexSet<NodeIndex>,
 ) -> Result<(NodeIndex, FetchDependencyGraphNodePath), FederationError> {
     // PORT_NOTE: instead of returing IDs of created nodes they should be inserted directly
     // in the `created_nodes` set passed by mutable reference.
-    todo!() // Port `handleRequires` (FED-25)
+
+    // @requires should be on an entity type, and we only support object types right now
+    let (edge_head, _) = dependency_graph
+        .federated_query_graph
+        .edge_endpoints(edge_id)?;
+    let head = dependency_graph
+        .federated_query_graph
+        .node_weight(edge_head)?;
+    let entity_type_schema = dependency_graph
+        .federated_query_graph
+        .schema_by_source(&head.source)?
+        .clone();
+    let QueryGraphNodeType::SchemaType(OutputTypeDefinitionPosition::Object(entity_type_position)) =
+        head.type_.clone()
+    else {
+        return Err(FederationError::internal(format!(
+            "@requires applied on non-entity object type {}",
+            head.type_
+        )));
+    };
+
+    // In theory, for requires, we need to fetch the key of the entity and the requirements, and
+    // pass them to a new node where we'll fetch the field with the requires. But if we do that
+    // naively, we may end up fetching fields with requires in separate nodes when they could be
+    // fetched in the same node (and we may end up with an overly complex plan).
+    //
+    // So we first try to see if the requirements can be fetched directly from the current node's
+    // parent. To be "safe", we only do this when the current node has a single parent and we're
+    // at the "top" of the current node (the path in the node has no fields), and this is mostly
+    // for when the node is a "key" node (as in, created only to fetch the key of some entity).
+    let node = dependency_graph.node_weight(node_id)?.clone();
+    let mut parents = dependency_graph.parents_relations_of(node_id);
+    let single_parent = match (parents.next(), parents.next()) {
+        (Some(parent), None) => Some(parent),
+        _ => None,
+    };
+    drop(parents);
+    let single_parent = single_parent.filter(|_| node_path.path_in_node.has_only_fragments());
+    if let (Some(parent), Some(merge_at)) = (single_parent, &node.merge_at) {
+        // We start by computing the nodes for the conditions. We do this using a copy of the
+        // current node (with only the inputs) as that allows to modify this copy without modifying
+        // `node`.
+        let new_node_id = dependency_graph.new_key_node(
+            &node.subgraph_name,
+            merge_at.clone(),
+            node.defer_ref.clone(),
+        )?;
+        dependency_graph.add_parent(new_node_id, parent.clone());
+        FetchDependencyGraph::node_weight_mut(&mut dependency_graph.graph, new_node_id)?
+            .copy_inputs(&dependency_graph.supergraph_schema, &node)?;
+        let newly_created_nodes = compute_nodes_for_tree(
+            dependency_graph,
+            requires_conditions,
+            new_node_id,
+            node_path.clone(),
+            defer_context.for_conditions(),
+            &Default::default(),
+        )?;
+        if newly_created_nodes.is_empty() {
+            // All conditions were local. Just merge the newly created node back in the current
+            // node (we didn't need it) and continue.
+            if !dependency_graph.can_merge_sibling_in(node_id, new_node_id)? {
+                return Err(FederationError::internal(format!(
+                    "We should be able to merge {} into {} by construction",
+                    new_node_id.index(),
+                    node_id.index(),
+                )));
+            }
+            dependency_graph.merge_sibling_in(node_id, new_node_id)?;
+            return Ok((node_id, node_path.clone()));
+        }
+
+        // We know the @requires needs `newly_created_nodes`. We do want to know however if any of
+        // the conditions was fetched from our `new_node`. If not, then this means that
+        // `newly_created_nodes` don't really depend on the current `node`, but can be
+        // dependencies of the parent (or even merged into this parent).
+        //
+        // So we want to know if anything in `new_node` selection cannot be fetched directly from
+        // the parent. For that, we first remove any of `new_node` inputs from its selection: in
+        // most case, `new_node` will just contain the key needed to jump back to its parent, and
+        // those would usually be the same as the inputs. And since by definition we know
+        // `new_node`'s inputs are already fetched, we know they are not things that we need. Then,
+        // we check if what remains (often empty) can be directly fetched from the parent. If it
+        // can, then we can just merge `new_node` into that parent. Otherwise, we will have to
+        // "keep it".
+        //
+        // Note: it is to be sure this test is not polluted by other things in `node` that we
+        // created `new_node`.
+        FetchDependencyGraph::node_weight_mut(&mut dependency_graph.graph, new_node_id)?
+            .remove_inputs_from_selection()?;
+        let new_node_is_unneeded = match &parent.path_in_parent {
+            Some(parent_path) => {
+                let parent_selection = &dependency_graph
+                    .node_weight(parent.parent_node_id)?
+                    .selection_set
+                    .selection_set;
+                let parent_type_at_path = type_at_path(
+                    &parent_selection.schema,
+                    &parent_selection.type_position,
+                    parent_path,
+                )?;
+                dependency_graph
+                    .node_weight(new_node_id)?
+                    .selection_set
+                    .selection_set
+                    .can_rebase_on(&parent_type_at_path, &parent_selection.schema)
+            }
+            None => false,
+        };
+
+        let mut unmerged_node_ids = Vec::new();
+        if new_node_is_unneeded {
+            // Up to this point, `new_node` had no parent, so let's first merge `new_node` to the
+            // parent, thus "rooting" its children to it. Note that we just checked that `new_node`
+            // selection was just its inputs, so we know that merging it to the parent is mostly a
+            // no-op from that POV, except maybe for requesting a few addition `__typename` we
+            // didn't before (due to the exclusion of `__typename` in the `new_node_is_unneeded`
+            // check).
+            dependency_graph.merge_child_in(parent.parent_node_id, new_node_id)?;
+
+            // Now, all created nodes are going to be descendant of the parent. But some of them
+            // may actually be mergeable into it.
+            for created_node_id in newly_created_nodes {
+                // Note that `created_node_id` will not be a direct child of the parent, but
+                // `can_merge_child_in` just returns `false` in that case, so it's fine to test it.
+                let created_node = dependency_graph.node_weight(created_node_id)?;
+                let parent_node = dependency_graph.node_weight(parent.parent_node_id)?;
+                if created_node.subgraph_name == parent_node.subgraph_name
+                    && dependency_graph
+                        .can_merge_child_in(parent.parent_node_id, created_node_id)?
+                {
+                    dependency_graph.merge_child_in(parent.parent_node_id, created_node_id)?;
+                    continue;
+                }
+
+                unmerged_node_ids.push(created_node_id);
+                // `created_node` cannot be merged into the parent, which may happen because we've
+                // have a non-local condition. But even if that's the case, it's possible that
+                // `created_node` parent is the parent, as `created_node` might have been done as
+                // a child of `new_node`, and the merge of `new_node` into the parent would have
+                // re-parented `created_node`. So we want to check this and if it's the case, try
+                // to give `created_node` a better "path in parent", by moving it up to the
+                // grand parent(s) as long as it doesn't truly depend on its current parent.
+                let mut current_parent = Some(parent.clone());
+                while let Some(current) = current_parent.take() {
+                    if dependency_graph
+                        .node_weight(current.parent_node_id)?
+                        .is_top_level()
+                        || !dependency_graph.is_child_of_with_artificial_dependency(
+                            created_node_id,
+                            current.parent_node_id,
+                        )?
+                    {
+                        break;
+                    }
+                    dependency_graph.remove_child_edge(current.parent_node_id, created_node_id);
+                    let mut grand_parents = dependency_graph
+                        .parents_relations_of(current.parent_node_id)
+                        .collect::<Vec<_>>();
+                    if grand_parents.is_empty() {
+                        return Err(FederationError::internal(format!(
+                            "Fetch node {} is not top-level, so it should have parents",
+                            current.parent_node_id.index(),
+                        )));
+                    }
+                    for grand_parent in &grand_parents {
+                        dependency_graph.add_parent(
+                            created_node_id,
+                            ParentRelation {
+                                parent_node_id: grand_parent.parent_node_id,
+                                path_in_parent: concat_paths_in_parents(
+                                    &grand_parent.path_in_parent,
+                                    &current.path_in_parent,
+                                )
+                                .map(Arc::new),
+                            },
+                        );
+                    }
+                    if grand_parents.len() == 1 {
+                        current_parent = grand_parents.pop();
+                    }
+                }
+            }
+        } else {
+            // We cannot merge `new_node` to the parent, either because it fetches some things
+            // necessary to the @requires, or because we had more than one parent and don't know
+            // how to handle this (unsure if the later can actually happen at this point tbh (?)).
+            // But there is no reason not to merge `new_node` back to `node` so we do that first.
+            if !dependency_graph.can_merge_sibling_in(node_id, new_node_id)? {
+                return Err(FederationError::internal(format!(
+                    "We should be able to merge {} into {} by construction",
+                    new_node_id.index(),
+                    node_id.index(),
+                )));
+            }
+            dependency_graph.merge_sibling_in(node_id, new_node_id)?;
+
+            // The created nodes depend on `node` and the dependency cannot be moved to the parent
+            // in this case. However, we might still be able to merge some created nodes directly
+            // in the parent. But for this to be true, we should essentially make sure that the
+            // dependency on `node` is not a "true" dependency. That is, if the created node inputs
+            // are the same as `node` inputs (and said created node is the same subgraph as the
+            // parent of `node`), then it means we depend only on values that are already in the
+            // parent and can merge the node.
+            for created_node_id in newly_created_nodes {
+                let created_node = dependency_graph.node_weight(created_node_id)?;
+                let parent_node = dependency_graph.node_weight(parent.parent_node_id)?;
+                let inputs_are_contained = match (&node.inputs, &created_node.inputs) {
+                    (Some(node_inputs), Some(created_node_inputs)) => {
+                        node_inputs.contains(created_node_inputs)
+                    }
+                    (_, None) => true,
+                    (None, Some(_)) => false,
+                };
+                if parent.path_in_parent.is_some()
+                    && created_node.subgraph_name == parent_node.subgraph_name
+                    && created_node.merge_at == node.merge_at
+                    && inputs_are_contained
+                    && dependency_graph
+                        .can_merge_grand_child_in(parent.parent_node_id, created_node_id)?
+                {
+                    dependency_graph
+                        .merge_grand_child_in(parent.parent_node_id, created_node_id)?;
+                } else {
+                    unmerged_node_ids.push(created_node_id);
+                }
+            }
+        }
+
+        // If we've merged all the created nodes, then all the "requires" are handled _before_ we
+        // get to the current node, so we can "continue" with the current node.
+        if unmerged_node_ids.is_empty() {
+            // We still need to add the stuffs we require though (but `node` already has a key in
+            // its inputs, we don't need one).
+            let (inputs, _) = inputs_for_require(
+                dependency_graph,
+                &entity_type_position,
+                &entity_type_schema,
+                edge_id,
+                context,
+                false,
+            )?;
+            FetchDependencyGraph::node_weight_mut(&mut dependency_graph.graph, node_id)?
+                .add_inputs(&dependency_graph.supergraph_schema, &inputs, [])?;
+            return Ok((node_id, node_path.clone()));
+        }
+
+        // If we get here, it means that @requires needs the information from `unmerged_nodes`
+        // (plus whatever has been merged before) _and_ those rely on some information from the
+        // current `node` (if they hadn't, we would have been able to merge `new_node` to `node`'s
+        // parent). So the node we should return, which is the node where the "post-@requires"
+        // fields will be added, needs to a be a new node that depends on all those
+        // `unmerged_nodes`.
+        let post_require_node_id = dependency_graph.new_key_node(
+            &node.subgraph_name,
+            merge_at.clone(),
+            node.defer_ref.clone(),
+        )?;
+        // Note that `post_require_node` cannot generally be merged in any of the `unmerged_nodes`
+        // and we don't provide a `path`.
+        for unmerged_node_id in &unmerged_node_ids {
+            dependency_graph.add_parent(
+                post_require_node_id,
+                ParentRelation {
+                    parent_node_id: *unmerged_node_id,
+                    path_in_parent: None,
+                },
+            );
+        }
+        // That node also need, in general, to depend on the current `node`. That said, if we
+        // detected that the @requires didn't need anything of said `node` (if
+        // `new_node_is_unneeded`), then we can depend on the parent instead.
+        if new_node_is_unneeded {
+            dependency_graph.add_parent(post_require_node_id, parent);
+        } else {
+            dependency_graph.add_parent(
+                post_require_node_id,
+                ParentRelation {
+                    parent_node_id: node_id,
+                    path_in_parent: Some(Default::default()),
+                },
+            );
+        }
+
+        let (inputs, key_inputs) = inputs_for_require(
+            dependency_graph,
+            &entity_type_position,
+            &entity_type_schema,
+            edge_id,
+            context,
+            true,
+        )?;
+        FetchDependencyGraph::node_weight_mut(&mut dependency_graph.graph, post_require_node_id)?
+            .add_inputs(&dependency_graph.supergraph_schema, &inputs, [])?;
+        if let Some(key_inputs) = key_inputs {
+            // It could be the key used to resume fetching after the @requires is already fetched
+            // in the original node, but we cannot guarantee it, so we add it now (and if it was
+            // already selected, this is a no-op).
+            FetchDependencyGraph::node_weight_mut(&mut dependency_graph.graph, node_id)?
+                .selection_set_mut()
+                .add_at_path(&node_path.path_in_node, Some(&Arc::new(key_inputs)))?;
+        }
+        created_nodes.extend(unmerged_node_ids);
+        created_nodes.insert(post_require_node_id);
+        let initial_fetch_path = create_fetch_initial_path(
+            &dependency_graph.supergraph_schema,
+            &entity_type_position.into(),
+            context,
+        )?;
+        return Ok((
+            post_require_node_id,
+            node_path.for_new_key_fetch(initial_fetch_path),
+        ));
+    }
+
+    // We're in the somewhat simpler case where a @requires happens somewhere in the middle of a
+    // subgraph query (so, not just after having jumped to that subgraph). In that case, there
+    // isn't tons of optimisation we can do: we have to see what satisfying the @requires
+    // necessitates, and if it needs anything from another subgraph, we have to stop the current
+    // subgraph fetch there, get the requirements from other subgraphs, and then resume the query
+    // of that particular subgraph.
+    let newly_created_nodes = compute_nodes_for_tree(
+        dependency_graph,
+        requires_conditions,
+        node_id,
+        node_path.clone(),
+        defer_context.for_conditions(),
+        &Default::default(),
+    )?;
+    // If we didn't create any node, that means the whole condition was fetched from the current
+    // node and we're good.
+    if newly_created_nodes.is_empty() {
+        return Ok((node_id, node_path.clone()));
+    }
+
+    // We need to create a new node, on the same subgraph as `node`, where we resume fetching the
+    // field for which we handle the @requires _after_ we've dealt with the `newly_created_nodes`.
+    // Note that we know the conditions will include a key for our node so we can resume properly.
+    let new_node_id = dependency_graph.new_key_node(
+        &node.subgraph_name,
+        node_path.response_path.clone(),
+        node.defer_ref.clone(),
+    )?;
+    dependency_graph.add_parent(
+        new_node_id,
+        ParentRelation {
+            parent_node_id: node_id,
+            path_in_parent: Some(Arc::clone(&node_path.path_in_node)),
+        },
+    );
+    for created_node_id in &newly_created_nodes {
+        // Usually, the created nodes will be children of `node`, but not always: some of them
+        // can be descendants of such children. In any case, the new node needs to be fetched
+        // after all of them.
+        let path = dependency_graph.path_in_condition_node(
+            *created_node_id,
+            node_id,
+            &node_path.path_in_node,
+        );
+        dependency_graph.add_parent(
+            new_node_id,
+            ParentRelation {
+                parent_node_id: *created_node_id,
+                path_in_parent: path,
+            },
+        );
+    }
+
+    let (inputs, key_inputs) = inputs_for_require(
+        dependency_graph,
+        &entity_type_position,
+        &entity_type_schema,
+        edge_id,
+        context,
+        true,
+    )?;
+    FetchDependencyGraph::node_weight_mut(&mut dependency_graph.graph, new_node_id)?.add_inputs(
+        &dependency_graph.supergraph_schema,
+        &inputs,
+        [],
+    )?;
+    if let Some(key_inputs) = key_inputs {
+        // And we make sure the key inputs are fetched in the original node.
+        FetchDependencyGraph::node_weight_mut(&mut dependency_graph.graph, node_id)?
+            .selection_set_mut()
+            .add_at_path(&node_path.path_in_node, Some(&Arc::new(key_inputs)))?;
+    }
+    created_nodes.extend(newly_created_nodes);
+    created_nodes.insert(new_node_id);
+    let initial_fetch_path = create_fetch_initial_path(
+        &dependency_graph.supergraph_schema,
+        &entity_type_position.into(),
+        context,
+    )?;
+    Ok((new_node_id, node_path.for_new_key_fetch(initial_fetch_path)))
+}
+
+/// Computes the inputs of the fetch for a field with a `@requires`, that is the requirements
+/// themselves along with (if `include_key_inputs` is true) a key of the entity. The key inputs
+/// are also returned separately (based on `entity_type_schema`), so that the caller can ensure
+/// they are fetched by the node preceding the requires.
+fn inputs_for_require(
+    dependency_graph: &FetchDependencyGraph,
+    entity_type_position: &ObjectTypeDefinitionPosition,
+    entity_type_schema: &ValidFederationSchema,
+    edge_id: EdgeIndex,
+    context: &OpGraphPathContext,
+    include_key_inputs: bool,
+) -> Result<(NormalizedSelectionSet, Option<NormalizedSelectionSet>), FederationError> {
+    // This method is actually called for to handle conditions of @requires, but also to fetch
+    // `__typename` in the case of "fake downcast on an @interfaceObject". In that later case, once
+    // we fetched that `__typename`, we want to wrap the input into the "downcasted" type, not the
+    // @interfaceObject one, so that we don't end up querying some fields in the @interfaceObject
+    // subgraph for entities that we know won't match a type condition of the query.
+    let edge = dependency_graph
+        .federated_query_graph
+        .edge_weight(edge_id)?;
+    let input_type_name = match &edge.transition {
+        QueryGraphEdgeTransition::InterfaceObjectFakeDownCast { to_type_name, .. } => to_type_name,
+        _ => &entity_type_position.type_name,
+    };
+    let input_type = dependency_graph.type_for_fetch_inputs(input_type_name)?;
+    let Some(edge_conditions) = &edge.conditions else {
+        return Err(FederationError::internal(format!(
+            "Requires edge {edge} should have some conditions"
+        )));
+    };
+
+    let mut full_selection_set = NormalizedSelectionSet::for_composite_type(
+        dependency_graph.supergraph_schema.clone(),
+        input_type.clone(),
+    );
+    let mut key_inputs = None;
+    if include_key_inputs {
+        let (edge_head, _) = dependency_graph
+            .federated_query_graph
+            .edge_endpoints(edge_id)?;
+        let Some(key_condition) = dependency_graph
+            .federated_query_graph
+            .get_locally_satisfiable_key(edge_head)?
+        else {
+            return Err(FederationError::internal(format!(
+                "Due to @requires, validation should have required a key to be present for {edge}"
+            )));
+        };
+        let key_condition = Arc::new(key_condition);
+        full_selection_set.add_at_path(&Default::default(), Some(&key_condition))?;
+        let mut key_selection_set = NormalizedSelectionSet::for_composite_type(
+            entity_type_schema.clone(),
+            entity_type_position.clone().into(),
+        );
+        key_selection_set.add_at_path(&Default::default(), Some(&key_condition))?;
+        key_inputs = Some(key_selection_set);
+    }
+    full_selection_set.add_at_path(&Default::default(), Some(edge_conditions))?;
+
+    let inputs = wrap_input_selections(
+        &dependency_graph.supergraph_schema,
+        &input_type,
+        full_selection_set,
+        context,
+    );
+    Ok((inputs, key_inputs))
+}
+
+/// Returns the type reached by following `path` from `parent_type` in the given schema.
+fn type_at_path(
+    schema: &ValidFederationSchema,
+    parent_type: &CompositeTypeDefinitionPosition,
+    path: &OpPath,
+) -> Result<CompositeTypeDefinitionPosition, FederationError> {
+    let mut type_ = parent_type.clone();
+    for element in &path.0 {
+        match &**element {
+            OpPathElement::Field(field) => {
+                let field_position = type_.field(field.data().name().clone())?;
+                let field_definition = field_position.get(schema.schema())?;
+                type_ = schema
+                    .get_type(field_definition.ty.inner_named_type().clone())?
+                    .try_into()
+                    .map_err(|_| {
+                        FederationError::internal(format!(
+                            "Invalid call from {path} starting at {parent_type}: \
+                             {field_position} is not composite"
+                        ))
+                    })?;
+            }
+            OpPathElement::InlineFragment(fragment) => {
+                if let Some(type_condition) = &fragment.data().type_condition_position {
+                    type_ = schema
+                        .get_type(type_condition.type_name().clone())?
+                        .try_into()
+                        .map_err(|_| {
+                            FederationError::internal(format!(
+                                "Invalid call from {path} starting at {parent_type}: \
+                                 {type_condition} is not composite"
+                            ))
+                        })?;
+                }
+            }
+        }
+    }
+    Ok(type_)
+}
+
+/// Concatenates two "paths in parent", which is only possible if both are known.
+fn concat_paths_in_parents(
+    first: &Option<Arc<OpPath>>,
+    second: &Option<Arc<OpPath>>,
+) -> Option<OpPath> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(OpPath(
+            first.0.iter().chain(second.0.iter()).cloned().collect(),
+        )),
+        _ => None,
+    }
 }
@@ -48,6 +48,8 @@ pub(crate) struct FetchDependencyGraphToQueryPlanProcessor {
     operation_name: Option<Name>,
     assigned_defer_labels: Option<HashSet<NodeStr>>,
     counter: u32,
+    /// Whether to validate the generated subgraph operations against the subgraph schemas.
+    subgraph_graphql_validation: bool,
 }
 
 /// Computes the cost of a Plan.
@@ -244,6 +246,7 @@ impl FetchDependencyGraphToQueryPlanProcessor {
         fragments: Option<RebasedFragments>,
         operation_name: Option<Name>,
         assigned_defer_labels: Option<HashSet<NodeStr>>,
+        subgraph_graphql_validation: bool,
     ) -> Self {
         Self {
             variable_definitions,
@@ -251,6 +254,7 @@ impl FetchDependencyGraphToQueryPlanProcessor {
             operation_name,
             assigned_defer_labels,
             counter: 0,
+            subgraph_graphql_validation,
         }
     }
 }
@@ -276,6 +280,7 @@ impl FetchDependencyGraphProcessor<Option<PlanNode>, DeferredDeferBlock>
             &self.variable_definitions,
             self.fragments.as_mut(),
             op_name,
+            self.subgraph_graphql_validation,
         )
     }
 
@@ -0,0 +1,859 @@
+//! (De)serialization of query plans in the JSON format of the `@apollo/query-planner` JS package.
+//!
+//! Query plans are converted to and from a mirror of the JS `QueryPlan` types, in which the GraphQL
+//! parts of a plan (fetch operations, `@defer` sub-selections and query paths) are strings. Reading
+//! those strings back requires the schemas the plan was built for, which is why plans are read
+//! through [`QueryPlanner::deserialize_query_plan`](super::query_planner::QueryPlanner) rather than
+//! through a `Deserialize` implementation.
+use super::*;
+use crate::schema::ValidFederationSchema;
+use apollo_compiler::executable::FieldSet;
+use apollo_compiler::schema::NamedType;
+use apollo_compiler::Schema;
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum QueryPlanJson {
+    QueryPlan {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        node: Option<Box<PlanNodeJson>>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum PlanNodeJson {
+    Fetch(Box<FetchNodeJson>),
+    Sequence {
+        nodes: Vec<PlanNodeJson>,
+    },
+    Parallel {
+        nodes: Vec<PlanNodeJson>,
+    },
+    Flatten {
+        path: Vec<FetchDataPathElement>,
+        node: Box<PlanNodeJson>,
+    },
+    Defer {
+        primary: PrimaryDeferBlockJson,
+        deferred: Vec<DeferredDeferBlockJson>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Condition {
+        condition: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        if_clause: Option<Box<PlanNodeJson>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        else_clause: Option<Box<PlanNodeJson>>,
+    },
+    Subscription {
+        primary: Box<PlanNodeJson>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rest: Option<Box<PlanNodeJson>>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FetchNodeJson {
+    service_name: String,
+    // PORT_NOTE: Fetch IDs are strings in the JS codebase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    variable_usages: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    requires: Option<Vec<SelectionJson>>,
+    operation: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    operation_name: Option<String>,
+    operation_kind: OperationKindJson,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    input_rewrites: Vec<FetchDataRewrite>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    output_rewrites: Vec<FetchDataRewrite>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OperationKindJson {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+/// The JS `QueryPlanSelectionNode`, used for the `requires` of fetch nodes.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum SelectionJson {
+    Field {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        alias: Option<String>,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selections: Option<Vec<SelectionJson>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    InlineFragment {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        type_condition: Option<String>,
+        selections: Vec<SelectionJson>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct PrimaryDeferBlockJson {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subselection: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    node: Option<Box<PlanNodeJson>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeferredDeferBlockJson {
+    depends: Vec<DeferredDependencyJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    query_path: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subselection: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    node: Option<Box<PlanNodeJson>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DeferredDependencyJson {
+    id: String,
+}
+
+impl From<OperationType> for OperationKindJson {
+    fn from(operation_type: OperationType) -> Self {
+        match operation_type {
+            OperationType::Query => Self::Query,
+            OperationType::Mutation => Self::Mutation,
+            OperationType::Subscription => Self::Subscription,
+        }
+    }
+}
+
+impl From<OperationKindJson> for OperationType {
+    fn from(kind: OperationKindJson) -> Self {
+        match kind {
+            OperationKindJson::Query => Self::Query,
+            OperationKindJson::Mutation => Self::Mutation,
+            OperationKindJson::Subscription => Self::Subscription,
+        }
+    }
+}
+
+impl Serialize for QueryPlan {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let node = self
+            .node
+            .as_ref()
+            .map(|node| node.to_json().map(Box::new))
+            .transpose()
+            .map_err(serde::ser::Error::custom)?;
+        QueryPlanJson::QueryPlan { node }.serialize(serializer)
+    }
+}
+
+impl Serialize for TopLevelPlanNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl Serialize for PlanNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl Serialize for FetchNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PlanNodeJson::Fetch(Box::new(self.to_json().map_err(serde::ser::Error::custom)?))
+            .serialize(serializer)
+    }
+}
+
+impl Serialize for FetchDataPathElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Key(key) => serializer.serialize_str(key),
+            Self::AnyIndex => serializer.serialize_str("@"),
+            Self::TypenameEquals(type_name) => {
+                serializer.serialize_str(&format!("... on {type_name}"))
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FetchDataPathElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let element = String::deserialize(deserializer)?;
+        Ok(if element == "@" {
+            Self::AnyIndex
+        } else if let Some(type_name) = element.strip_prefix("... on ") {
+            Self::TypenameEquals(type_name.into())
+        } else {
+            Self::Key(element.into())
+        })
+    }
+}
+
+fn node_to_json(node: &Option<Box<PlanNode>>) -> Result<Option<Box<PlanNodeJson>>, String> {
+    node.as_deref()
+        .map(|node| node.to_json().map(Box::new))
+        .transpose()
+}
+
+impl TopLevelPlanNode {
+    fn to_json(&self) -> Result<PlanNodeJson, String> {
+        match self {
+            Self::Subscription(node) => node.to_json(),
+            Self::Fetch(node) => Ok(PlanNodeJson::Fetch(Box::new(node.to_json()?))),
+            Self::Sequence(node) => node.to_json(),
+            Self::Parallel(node) => node.to_json(),
+            Self::Flatten(node) => node.to_json(),
+            Self::Defer(node) => node.to_json(),
+            Self::Condition(node) => node.to_json(),
+        }
+    }
+}
+
+impl PlanNode {
+    fn to_json(&self) -> Result<PlanNodeJson, String> {
+        match self {
+            Self::Fetch(node) => Ok(PlanNodeJson::Fetch(Box::new(node.to_json()?))),
+            Self::Sequence(node) => node.to_json(),
+            Self::Parallel(node) => node.to_json(),
+            Self::Flatten(node) => node.to_json(),
+            Self::Defer(node) => node.to_json(),
+            Self::Condition(node) => node.to_json(),
+        }
+    }
+}
+
+impl SubscriptionNode {
+    fn to_json(&self) -> Result<PlanNodeJson, String> {
+        Ok(PlanNodeJson::Subscription {
+            primary: Box::new(PlanNodeJson::Fetch(Box::new(self.primary.to_json()?))),
+            rest: node_to_json(&self.rest)?,
+        })
+    }
+}
+
+impl FetchNode {
+    fn to_json(&self) -> Result<FetchNodeJson, String> {
+        Ok(FetchNodeJson {
+            service_name: self.subgraph_name.to_string(),
+            id: self.id.map(|id| id.to_string()),
+            variable_usages: self
+                .variable_usages
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            requires: self
+                .requires
+                .as_deref()
+                .map(selections_to_json)
+                .transpose()?,
+            operation: strip_ignored_characters(
+                &self.operation_document.serialize().no_indent().to_string(),
+            ),
+            operation_name: self.operation_name.as_ref().map(|name| name.to_string()),
+            operation_kind: self.operation_kind.into(),
+            input_rewrites: self
+                .input_rewrites
+                .iter()
+                .map(|rewrite| FetchDataRewrite::clone(rewrite))
+                .collect(),
+            output_rewrites: self
+                .output_rewrites
+                .iter()
+                .map(|rewrite| FetchDataRewrite::clone(rewrite))
+                .collect(),
+        })
+    }
+}
+
+impl SequenceNode {
+    fn to_json(&self) -> Result<PlanNodeJson, String> {
+        Ok(PlanNodeJson::Sequence {
+            nodes: self
+                .nodes
+                .iter()
+                .map(PlanNode::to_json)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl ParallelNode {
+    fn to_json(&self) -> Result<PlanNodeJson, String> {
+        Ok(PlanNodeJson::Parallel {
+            nodes: self
+                .nodes
+                .iter()
+                .map(PlanNode::to_json)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl FlattenNode {
+    fn to_json(&self) -> Result<PlanNodeJson, String> {
+        Ok(PlanNodeJson::Flatten {
+            path: self.path.clone(),
+            node: Box::new(self.node.to_json()?),
+        })
+    }
+}
+
+impl DeferNode {
+    fn to_json(&self) -> Result<PlanNodeJson, String> {
+        Ok(PlanNodeJson::Defer {
+            primary: PrimaryDeferBlockJson {
+                subselection: self
+                    .primary
+                    .sub_selection
+                    .as_ref()
+                    .map(sub_selection_to_json),
+                node: node_to_json(&self.primary.node)?,
+            },
+            deferred: self
+                .deferred
+                .iter()
+                .map(|block| {
+                    Ok(DeferredDeferBlockJson {
+                        depends: block
+                            .depends
+                            .iter()
+                            .map(|dependency| DeferredDependencyJson {
+                                id: dependency.id.to_string(),
+                            })
+                            .collect(),
+                        label: block.label.as_ref().map(|label| label.to_string()),
+                        // PORT_NOTE: The JS codebase skips inline fragments without type
+                        // conditions when printing query paths.
+                        query_path: block
+                            .query_path
+                            .iter()
+                            .filter(|element| {
+                                !matches!(
+                                    element,
+                                    QueryPathElement::InlineFragment(inline)
+                                        if inline.type_condition.is_none()
+                                )
+                            })
+                            .map(|element| element.to_string())
+                            .collect(),
+                        subselection: block.sub_selection.as_ref().map(sub_selection_to_json),
+                        node: node_to_json(&block.node)?,
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+        })
+    }
+}
+
+impl ConditionNode {
+    fn to_json(&self) -> Result<PlanNodeJson, String> {
+        Ok(PlanNodeJson::Condition {
+            condition: self.condition_variable.to_string(),
+            if_clause: node_to_json(&self.if_clause)?,
+            else_clause: node_to_json(&self.else_clause)?,
+        })
+    }
+}
+
+fn selections_to_json(selections: &[Selection]) -> Result<Vec<SelectionJson>, String> {
+    selections.iter().map(selection_to_json).collect()
+}
+
+fn selection_to_json(selection: &Selection) -> Result<SelectionJson, String> {
+    match selection {
+        Selection::Field(field) => Ok(SelectionJson::Field {
+            alias: field.alias.as_ref().map(|alias| alias.to_string()),
+            name: field.name.to_string(),
+            selections: if field.selection_set.selections.is_empty() {
+                None
+            } else {
+                Some(selections_to_json(&field.selection_set.selections)?)
+            },
+        }),
+        Selection::InlineFragment(inline) => Ok(SelectionJson::InlineFragment {
+            type_condition: inline.type_condition.as_ref().map(|name| name.to_string()),
+            selections: selections_to_json(&inline.selection_set.selections)?,
+        }),
+        Selection::FragmentSpread(spread) => Err(format!(
+            "Unexpected fragment spread \"...{}\" in fetch requirements",
+            spread.fragment_name
+        )),
+    }
+}
+
+/// Prints a `@defer` sub-selection the way the JS `SelectionSet.toString()` does, on a single
+/// line and with its outer brackets.
+fn sub_selection_to_json(sub_selection: &SelectionSet) -> String {
+    sub_selection.serialize().no_indent().to_string()
+}
+
+/// Removes the characters that are insignificant to the GraphQL lexer from `source`, keeping the
+/// single spaces needed to separate consecutive names, numbers and strings.
+// PORT_NOTE: This is `stripIgnoredCharacters()` from graphql-js, which the JS query planner uses to
+// print fetch operations. Unlike that function, this doesn't re-indent block strings.
+fn strip_ignored_characters(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    // Whether the last token written is a name, number or string, which can't directly be
+    // followed by another such token (or a spread) without a separator.
+    let mut last_is_non_punctuator = false;
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' | ',' | '\u{feff}' => {}
+            '#' => while chars.next_if(|c| *c != '\n' && *c != '\r').is_some() {},
+            '"' => {
+                if last_is_non_punctuator {
+                    stripped.push(' ');
+                }
+                stripped.push(c);
+                let is_block_string = chars.next_if_eq(&'"').is_some();
+                if is_block_string && chars.next_if_eq(&'"').is_none() {
+                    // This was an empty string.
+                    stripped.push('"');
+                } else if is_block_string {
+                    stripped.push_str("\"\"");
+                    let mut quotes = 0;
+                    while let Some(c) = chars.next() {
+                        stripped.push(c);
+                        match c {
+                            '"' => quotes += 1,
+                            '\\' => {
+                                quotes = 0;
+                                if let Some(escaped) = chars.next() {
+                                    stripped.push(escaped);
+                                }
+                                continue;
+                            }
+                            _ => quotes = 0,
+                        }
+                        if quotes == 3 {
+                            break;
+                        }
+                    }
+                } else {
+                    while let Some(c) = chars.next() {
+                        stripped.push(c);
+                        match c {
+                            '"' => break,
+                            '\\' => {
+                                if let Some(escaped) = chars.next() {
+                                    stripped.push(escaped);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                last_is_non_punctuator = true;
+            }
+            '.' => {
+                if last_is_non_punctuator {
+                    stripped.push(' ');
+                }
+                stripped.push(c);
+                last_is_non_punctuator = false;
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '-' => {
+                if last_is_non_punctuator {
+                    stripped.push(' ');
+                }
+                stripped.push(c);
+                let is_number = c.is_ascii_digit() || c == '-';
+                while let Some(c) = chars.next_if(|c| {
+                    c.is_ascii_alphanumeric()
+                        || *c == '_'
+                        || (is_number && matches!(c, '.' | '+' | '-'))
+                }) {
+                    stripped.push(c);
+                }
+                last_is_non_punctuator = true;
+            }
+            _ => {
+                stripped.push(c);
+                last_is_non_punctuator = false;
+            }
+        }
+    }
+    stripped
+}
+
+/// Reads a query plan in the JSON format of the JS query planner, using the schemas of the
+/// supergraph and subgraphs the plan was built for to read its GraphQL parts.
+pub(crate) fn deserialize_query_plan<'de, D: Deserializer<'de>>(
+    supergraph_schema: &ValidFederationSchema,
+    subgraph_schemas: &IndexMap<NodeStr, ValidFederationSchema>,
+    deserializer: D,
+) -> Result<QueryPlan, D::Error> {
+    let QueryPlanJson::QueryPlan { node } = QueryPlanJson::deserialize(deserializer)?;
+    let node = node
+        .map(|node| {
+            let root_kind = node.operation_kind().unwrap_or(OperationKindJson::Query);
+            let root_type = supergraph_schema
+                .schema()
+                .root_operation(root_kind.into())
+                .ok_or("The supergraph has no root type for the plan's operation")?;
+            let reader = PlanReader {
+                supergraph_schema: supergraph_schema.schema(),
+                subgraph_schemas,
+            };
+            reader.top_level_node(*node, root_type)
+        })
+        .transpose()
+        .map_err(serde::de::Error::custom)?;
+    Ok(QueryPlan {
+        node,
+        statistics: Default::default(),
+    })
+}
+
+impl PlanNodeJson {
+    /// The kind of the operation of the first fetch of the plan, which is the kind of the planned
+    /// operation.
+    fn operation_kind(&self) -> Option<OperationKindJson> {
+        match self {
+            Self::Fetch(fetch) => Some(fetch.operation_kind),
+            Self::Sequence { nodes } | Self::Parallel { nodes } => {
+                nodes.iter().find_map(Self::operation_kind)
+            }
+            Self::Flatten { node, .. } => node.operation_kind(),
+            Self::Defer { primary, deferred } => primary
+                .node
+                .iter()
+                .chain(deferred.iter().flat_map(|block| &block.node))
+                .find_map(|node| node.operation_kind()),
+            Self::Condition {
+                if_clause,
+                else_clause,
+                ..
+            } => if_clause
+                .iter()
+                .chain(else_clause)
+                .find_map(|node| node.operation_kind()),
+            Self::Subscription { primary, .. } => primary.operation_kind(),
+        }
+    }
+}
+
+struct PlanReader<'a> {
+    supergraph_schema: &'a Valid<Schema>,
+    subgraph_schemas: &'a IndexMap<NodeStr, ValidFederationSchema>,
+}
+
+fn name(value: &str) -> Result<Name, String> {
+    Name::new(value).map_err(|_| format!("\"{value}\" is not a valid GraphQL name"))
+}
+
+impl PlanReader<'_> {
+    fn top_level_node(
+        &self,
+        node: PlanNodeJson,
+        root_type: &NamedType,
+    ) -> Result<TopLevelPlanNode, String> {
+        Ok(match node {
+            PlanNodeJson::Subscription { primary, rest } => {
+                TopLevelPlanNode::Subscription(SubscriptionNode {
+                    primary: Box::new(self.fetch_node(*primary)?),
+                    rest: self.optional_node(rest, root_type)?,
+                })
+            }
+            node => match self.node(node, root_type)? {
+                PlanNode::Fetch(node) => TopLevelPlanNode::Fetch(node),
+                PlanNode::Sequence(node) => TopLevelPlanNode::Sequence(node),
+                PlanNode::Parallel(node) => TopLevelPlanNode::Parallel(node),
+                PlanNode::Flatten(node) => TopLevelPlanNode::Flatten(node),
+                PlanNode::Defer(node) => TopLevelPlanNode::Defer(node),
+                PlanNode::Condition(node) => TopLevelPlanNode::Condition(node),
+            },
+        })
+    }
+
+    /// Reads a plan node, where `defer_type` is the type that the sub-selections and query paths
+    /// of its `@defer`s start from.
+    fn node(&self, node: PlanNodeJson, defer_type: &NamedType) -> Result<PlanNode, String> {
+        Ok(match node {
+            PlanNodeJson::Fetch(fetch) => PlanNode::Fetch(Box::new(self.fetch(*fetch)?)),
+            PlanNodeJson::Sequence { nodes } => PlanNode::Sequence(SequenceNode {
+                nodes: self.nodes(nodes, defer_type)?,
+            }),
+            PlanNodeJson::Parallel { nodes } => PlanNode::Parallel(ParallelNode {
+                nodes: self.nodes(nodes, defer_type)?,
+            }),
+            PlanNodeJson::Flatten { path, node } => PlanNode::Flatten(FlattenNode {
+                path,
+                node: Box::new(self.node(*node, defer_type)?),
+            }),
+            PlanNodeJson::Defer { primary, deferred } => PlanNode::Defer(DeferNode {
+                primary: PrimaryDeferBlock {
+                    sub_selection: primary
+                        .subselection
+                        .map(|sub_selection| self.sub_selection(defer_type, sub_selection))
+                        .transpose()?,
+                    node: self.optional_node(primary.node, defer_type)?,
+                },
+                deferred: deferred
+                    .into_iter()
+                    .map(|block| self.deferred_block(block, defer_type))
+                    .collect::<Result<_, _>>()?,
+            }),
+            PlanNodeJson::Condition {
+                condition,
+                if_clause,
+                else_clause,
+            } => PlanNode::Condition(Box::new(ConditionNode {
+                condition_variable: name(&condition)?,
+                if_clause: self.optional_node(if_clause, defer_type)?,
+                else_clause: self.optional_node(else_clause, defer_type)?,
+            })),
+            PlanNodeJson::Subscription { .. } => {
+                return Err("Subscription nodes can only be at the top of a query plan".to_owned())
+            }
+        })
+    }
+
+    fn nodes(
+        &self,
+        nodes: Vec<PlanNodeJson>,
+        defer_type: &NamedType,
+    ) -> Result<Vec<PlanNode>, String> {
+        nodes
+            .into_iter()
+            .map(|node| self.node(node, defer_type))
+            .collect()
+    }
+
+    fn optional_node(
+        &self,
+        node: Option<Box<PlanNodeJson>>,
+        defer_type: &NamedType,
+    ) -> Result<Option<Box<PlanNode>>, String> {
+        node.map(|node| self.node(*node, defer_type).map(Box::new))
+            .transpose()
+    }
+
+    fn fetch_node(&self, node: PlanNodeJson) -> Result<FetchNode, String> {
+        match node {
+            PlanNodeJson::Fetch(fetch) => self.fetch(*fetch),
+            _ => Err("The primary node of a subscription must be a fetch node".to_owned()),
+        }
+    }
+
+    fn fetch(&self, fetch: FetchNodeJson) -> Result<FetchNode, String> {
+        let subgraph_schema = self
+            .subgraph_schemas
+            .get(fetch.service_name.as_str())
+            .ok_or_else(|| format!("Unknown subgraph \"{}\"", fetch.service_name))?;
+        let operation_document = ExecutableDocument::parse_and_validate(
+            subgraph_schema.schema(),
+            fetch.operation,
+            "operation.graphql",
+        )
+        .map_err(|invalid| {
+            format!(
+                "Invalid operation for subgraph \"{}\": {}",
+                fetch.service_name, invalid.errors
+            )
+        })?;
+        Ok(FetchNode {
+            subgraph_name: fetch.service_name.as_str().into(),
+            id: fetch
+                .id
+                .map(|id| {
+                    id.parse()
+                        .map_err(|_| format!("\"{id}\" is not a valid fetch id"))
+                })
+                .transpose()?,
+            variable_usages: fetch
+                .variable_usages
+                .iter()
+                .map(|variable| name(variable))
+                .collect::<Result<_, _>>()?,
+            requires: fetch
+                .requires
+                .map(|selections| {
+                    selections
+                        .into_iter()
+                        .map(|selection| self.selection(None, selection))
+                        .collect::<Result<_, _>>()
+                })
+                .transpose()?,
+            operation_document,
+            operation_name: fetch.operation_name.map(Into::into),
+            operation_kind: fetch.operation_kind.into(),
+            input_rewrites: Arc::new(fetch.input_rewrites.into_iter().map(Arc::new).collect()),
+            output_rewrites: fetch.output_rewrites.into_iter().map(Arc::new).collect(),
+        })
+    }
+
+    /// Reads a selection of the `requires` of a fetch, which is looked up in the supergraph schema
+    /// like the requirements computed by the query planner.
+    fn selection(
+        &self,
+        parent_type: Option<&NamedType>,
+        selection: SelectionJson,
+    ) -> Result<Selection, String> {
+        Ok(match selection {
+            SelectionJson::Field {
+                alias,
+                name: field_name,
+                selections,
+            } => {
+                let parent_type = parent_type.ok_or_else(|| {
+                    format!("Field \"{field_name}\" has no parent type in fetch requirements")
+                })?;
+                let definition = self
+                    .supergraph_schema
+                    .type_field(parent_type, &field_name)
+                    .map_err(|_| format!("Cannot query field \"{parent_type}.{field_name}\""))?;
+                let mut field = Field::new(name(&field_name)?, definition.node.clone())
+                    .with_opt_alias(alias.as_deref().map(name).transpose()?);
+                let field_type = field.selection_set.ty.clone();
+                for selection in selections.into_iter().flatten() {
+                    field
+                        .selection_set
+                        .push(self.selection(Some(&field_type), selection)?);
+                }
+                field.into()
+            }
+            SelectionJson::InlineFragment {
+                type_condition,
+                selections,
+            } => {
+                let mut inline = match type_condition {
+                    Some(type_condition) => {
+                        InlineFragment::with_type_condition(name(&type_condition)?)
+                    }
+                    None => InlineFragment::without_type_condition(
+                        parent_type
+                            .ok_or("Inline fragment has no type in fetch requirements")?
+                            .clone(),
+                    ),
+                };
+                let inline_type = inline.selection_set.ty.clone();
+                for selection in selections {
+                    inline
+                        .selection_set
+                        .push(self.selection(Some(&inline_type), selection)?);
+                }
+                inline.into()
+            }
+        })
+    }
+
+    fn deferred_block(
+        &self,
+        block: DeferredDeferBlockJson,
+        defer_type: &NamedType,
+    ) -> Result<DeferredDeferBlock, String> {
+        let mut block_type = defer_type.clone();
+        let mut query_path = Vec::with_capacity(block.query_path.len());
+        for element in &block.query_path {
+            if let Some(type_condition) = element.strip_prefix("... on ") {
+                let inline = InlineFragment::with_type_condition(name(type_condition)?);
+                block_type = inline.selection_set.ty.clone();
+                query_path.push(QueryPathElement::InlineFragment(inline));
+            } else {
+                // PORT_NOTE: The JS codebase prints the response names of fields in query paths,
+                // so aliased fields can't be read back.
+                let definition = self
+                    .supergraph_schema
+                    .type_field(&block_type, element)
+                    .map_err(|_| format!("Cannot query field \"{block_type}.{element}\""))?;
+                let field = Field::new(name(element)?, definition.node.clone());
+                block_type = field.selection_set.ty.clone();
+                query_path.push(QueryPathElement::Field(field));
+            }
+        }
+        Ok(DeferredDeferBlock {
+            depends: block
+                .depends
+                .into_iter()
+                .map(|dependency| DeferredDependency {
+                    id: dependency.id.into(),
+                })
+                .collect(),
+            label: block.label.map(Into::into),
+            query_path,
+            sub_selection: block
+                .subselection
+                .map(|sub_selection| self.sub_selection(&block_type, sub_selection))
+                .transpose()?,
+            node: self.optional_node(block.node, &block_type)?,
+        })
+    }
+
+    fn sub_selection(&self, ty: &NamedType, sub_selection: String) -> Result<SelectionSet, String> {
+        FieldSet::parse(
+            self.supergraph_schema,
+            ty.clone(),
+            sub_selection,
+            "subselection.graphql",
+        )
+        .map(|field_set| field_set.selection_set)
+        .map_err(|invalid| format!("Invalid @defer sub-selection: {}", invalid.errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_rewrites_with_js_path_elements() {
+        let rewrites = vec![
+            FetchDataRewrite::ValueSetter(FetchDataValueSetter {
+                path: vec![
+                    FetchDataPathElement::TypenameEquals("Book".into()),
+                    FetchDataPathElement::Key("__typename".into()),
+                ],
+                set_value_to: "Product".into(),
+            }),
+            FetchDataRewrite::KeyRenamer(FetchDataKeyRenamer {
+                path: vec![
+                    FetchDataPathElement::Key("items".into()),
+                    FetchDataPathElement::AnyIndex,
+                    FetchDataPathElement::Key("name__alias_0".into()),
+                ],
+                rename_key_to: "name".into(),
+            }),
+        ];
+        let json = serde_json::to_string(&rewrites).unwrap();
+        insta::assert_snapshot!(json, @r###"
+        [{"kind":"ValueSetter","path":["... on Book","__typename"],"setValueTo":"Product"},{"kind":"KeyRenamer","path":["items","@","name__alias_0"],"renameKeyTo":"name"}]
+        "###);
+
+        let read_rewrites: Vec<FetchDataRewrite> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&read_rewrites).unwrap(), json);
+    }
+
+    #[test]
+    fn strips_ignored_characters_like_graphql_js() {
+        let operation = r#"query Op($id: ID!, $n: Int = -1) {
+          product(id: $id, names: ["a b", """block "quoted" text"""]) {
+            __typename
+            ... on Book { id pages(first: 1.5e3) }
+          }
+        }"#;
+        insta::assert_snapshot!(strip_ignored_characters(operation), @r###"
+        query Op($id:ID!$n:Int=-1){product(id:$id names:["a b" """block "quoted" text"""]){__typename ...on Book{id pages(first:1.5e3)}}}
+        "###);
+    }
+}
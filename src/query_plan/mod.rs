@@ -11,7 +11,10 @@ pub(crate) mod display;
 pub(crate) mod fetch_dependency_graph;
 pub(crate) mod fetch_dependency_graph_processor;
 pub mod generate;
+#[cfg(feature = "serde")]
+pub(crate) mod json;
 pub mod operation;
+pub mod parity;
 pub mod query_planner;
 pub(crate) mod query_planning_traversal;
 
@@ -20,7 +23,7 @@ pub type QueryPlanCost = i64;
 #[derive(Debug, Default)]
 pub struct QueryPlan {
     pub node: Option<TopLevelPlanNode>,
-    statistics: QueryPlanningStatistics,
+    pub statistics: QueryPlanningStatistics,
 }
 
 #[derive(Debug, derive_more::From)]
@@ -68,9 +71,9 @@ pub struct FetchNode {
     // PORT_NOTE: This was its own type in the JS codebase, but it's likely simpler to just have the
     // constraint be implicit for router instead of creating a new type.
     pub requires: Option<Vec<Selection>>,
-    // PORT_NOTE: We don't serialize the "operation" string in this struct, as these query plan
-    // nodes are meant for direct consumption by router (without any serdes), so we leave the
-    // question of whether it needs to be serialized to router.
+    // PORT_NOTE: We don't store the "operation" string in this struct, as these query plan nodes
+    // are meant for direct consumption by router. With the `serde` feature, the string is printed
+    // from this document when serializing the node.
     pub operation_document: Valid<ExecutableDocument>,
     pub operation_name: Option<NodeStr>,
     pub operation_kind: OperationType,
@@ -185,6 +188,11 @@ pub struct ConditionNode {
 /// A rewrite usually identifies some sub-part of the data and some action to perform on that
 /// sub-part.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind")
+)]
 pub enum FetchDataRewrite {
     ValueSetter(FetchDataValueSetter),
     KeyRenamer(FetchDataKeyRenamer),
@@ -192,6 +200,11 @@ pub enum FetchDataRewrite {
 
 /// A rewrite that sets a value at the provided path of the data it is applied to.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct FetchDataValueSetter {
     /// Path to the value that is set by this "rewrite".
     pub path: Vec<FetchDataPathElement>,
@@ -202,6 +215,11 @@ pub struct FetchDataValueSetter {
 
 /// A rewrite that renames the key at the provided path of the data it is applied to.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
 pub struct FetchDataKeyRenamer {
     /// Path to the key that is renamed by this "rewrite".
     pub path: Vec<FetchDataPathElement>,
@@ -199,24 +199,96 @@ impl NormalizedOperation {
         })
     }
 
-    // PORT_NOTE(@goto-bus-stop): It might make sense for the returned data structure to *be* the
-    // `DeferNormalizer` from the JS side
-    pub(crate) fn with_normalized_defer(self) -> NormalizedDefer {
-        todo!()
+    /// Normalizes the `@defer` applications of this operation: unlabelled `@defer`s are assigned a
+    /// label, `@defer(if: true)` loses its `if` argument and `@defer(if: false)` is removed.
+    /// Additionally collects, for each variable used as a `@defer` condition, the labels of the
+    /// `@defer`s it conditions.
+    pub(crate) fn with_normalized_defer(mut self) -> Result<NormalizedDefer, FederationError> {
+        let mut normalizer = DeferNormalizer::new(&self.selection_set)?;
+        if normalizer.has_non_labelled_or_conditional_defers {
+            self.selection_set = self.selection_set.with_normalized_defer(&mut normalizer)?;
+        }
+        Ok(NormalizedDefer {
+            operation: self,
+            has_defers: normalizer.has_defers,
+            assigned_defer_labels: normalizer.assigned_labels,
+            defer_conditions: normalizer.conditions,
+        })
     }
 
-    pub(crate) fn without_defer(self) -> Self {
-        if self.selection_set.has_defer()
-            || self
-                .named_fragments
-                .fragments
-                .values()
-                .any(|f| f.has_defer())
-        {
-            todo!("@defer not implemented");
+    /// Removes the `@defer` applications with the given labels from this operation, or all of them
+    /// if `labels_to_remove` is `None`.
+    pub(crate) fn without_defer(
+        mut self,
+        labels_to_remove: Option<&IndexSet<String>>,
+    ) -> Result<Self, FederationError> {
+        if self.selection_set.has_defer() {
+            self.selection_set = self.selection_set.without_defer(labels_to_remove)?;
         }
+        Ok(self)
+    }
+}
+
+/// Keeps track of the labels and conditions of `@defer` applications while normalizing them.
+// PORT_NOTE: This corresponds to `DeferNormalizer` in the JS codebase.
+struct DeferNormalizer {
+    used_labels: HashSet<NodeStr>,
+    assigned_labels: HashSet<NodeStr>,
+    conditions: IndexMap<String, IndexSet<String>>,
+    label_offset: usize,
+    has_defers: bool,
+    has_non_labelled_or_conditional_defers: bool,
+}
+
+impl DeferNormalizer {
+    fn new(selection_set: &NormalizedSelectionSet) -> Result<Self, FederationError> {
+        let mut digest = Self {
+            used_labels: HashSet::new(),
+            assigned_labels: HashSet::new(),
+            conditions: IndexMap::new(),
+            label_offset: 0,
+            has_defers: false,
+            has_non_labelled_or_conditional_defers: false,
+        };
+        let mut stack = selection_set.selections.values().collect::<Vec<_>>();
+        while let Some(selection) = stack.pop() {
+            if let NormalizedSelection::InlineFragment(inline) = selection {
+                if let Some(args) = inline.inline_fragment.data().defer_directive_arguments()? {
+                    digest.has_defers = true;
+                    if args.label().is_none() || args.if_().is_some() {
+                        digest.has_non_labelled_or_conditional_defers = true;
+                    }
+                    if let Some(label) = args.label() {
+                        digest.used_labels.insert(label.clone());
+                    }
+                }
+            }
+            if let Some(selection_set) = selection.selection_set()? {
+                stack.extend(selection_set.selections.values());
+            }
+        }
+        Ok(digest)
+    }
 
-        self
+    fn new_label(&mut self) -> NodeStr {
+        // It's unlikely that an auto-generated label would conflict with an existing one, but we
+        // don't take any chances.
+        let label = loop {
+            let candidate = NodeStr::new(&format!("qp__{}", self.label_offset));
+            self.label_offset += 1;
+            if !self.used_labels.contains(&candidate) {
+                break candidate;
+            }
+        };
+        self.assigned_labels.insert(label.clone());
+        label
+    }
+
+    fn register_condition(&mut self, label: &NodeStr, condition: &Name) {
+        self.conditions
+            .entry(condition.to_string())
+            .or_default()
+            .insert(label.to_string());
     }
 }
 
@@ -1506,15 +1578,20 @@ impl NormalizedFragmentSpreadData {
 
 mod normalized_inline_fragment_selection {
     use crate::error::FederationError;
-    use crate::link::graphql_definition::{defer_directive_arguments, DeferDirectiveArguments};
+    use crate::link::graphql_definition::{
+        defer_directive_arguments, BooleanOrVariable, DeferDirectiveArguments,
+    };
+    use crate::query_graph::graph_path::OpPathElement;
     use crate::query_plan::operation::{
         directives_with_sorted_arguments, is_deferred_selection, runtime_types_intersect,
-        HasNormalizedSelectionKey, NormalizedSelectionKey, NormalizedSelectionSet, SelectionId,
+        DeferNormalizer, HasNormalizedSelectionKey, NormalizedSelectionKey, NormalizedSelectionSet,
+        SelectionId,
     };
     use crate::query_plan::FetchDataPathElement;
     use crate::schema::position::CompositeTypeDefinitionPosition;
     use crate::schema::ValidFederationSchema;
-    use apollo_compiler::ast::{DirectiveList, Name};
+    use apollo_compiler::ast::{Argument, DirectiveList, Name, Value};
+    use apollo_compiler::{name, Node};
     use std::sync::Arc;
 
     use super::normalized_field_selection::collect_variables_from_directive;
@@ -1598,6 +1675,73 @@ mod normalized_inline_fragment_selection {
             Self::new(data)
         }
 
+        /// Normalizes the `@defer` application of this fragment, if any (see
+        /// `NormalizedOperation::with_normalized_defer`). Returns `None` if the fragment carries
+        /// no information anymore once normalized.
+        pub(super) fn with_normalized_defer(
+            &self,
+            normalizer: &mut DeferNormalizer,
+        ) -> Result<Option<Self>, FederationError> {
+            let Some(args) = self.data().defer_directive_arguments()? else {
+                return Ok(Some(self.clone()));
+            };
+            let mut remove_condition = false;
+            let mut condition_variable = None;
+            match args.if_() {
+                // Hardcoded `if: true`, remove the `if`.
+                Some(BooleanOrVariable::Boolean(true)) => remove_condition = true,
+                // Hardcoded `if: false`, remove the @defer altogether.
+                Some(BooleanOrVariable::Boolean(false)) => {
+                    let element = OpPathElement::InlineFragment(self.clone());
+                    return Ok(match element.without_defer() {
+                        Some(OpPathElement::InlineFragment(inline_fragment)) => {
+                            Some(inline_fragment)
+                        }
+                        _ => None,
+                    });
+                }
+                Some(BooleanOrVariable::Variable(variable)) => condition_variable = Some(variable),
+                None => {}
+            }
+            let new_label = match args.label() {
+                Some(_) => None,
+                None => Some(normalizer.new_label()),
+            };
+            // Now that we are sure to have a label, if we had a (non-trivial) condition, associate
+            // it to that label.
+            if let Some(variable) = condition_variable {
+                let label = new_label.as_ref().or(args.label()).ok_or_else(|| {
+                    FederationError::internal("@defer should have a label at this point")
+                })?;
+                normalizer.register_condition(label, variable);
+            }
+            if !remove_condition && new_label.is_none() {
+                return Ok(Some(self.clone()));
+            }
+            let directives = self
+                .data()
+                .directives
+                .iter()
+                .map(|directive| {
+                    if directive.name != "defer" {
+                        return directive.clone();
+                    }
+                    let mut directive = directive.as_ref().clone();
+                    if remove_condition {
+                        directive.arguments.retain(|argument| argument.name != "if");
+                    }
+                    if let Some(label) = &new_label {
+                        directive.arguments.push(Node::new(Argument {
+                            name: name!("label"),
+                            value: Node::new(Value::String(label.clone())),
+                        }));
+                    }
+                    Node::new(directive)
+                })
+                .collect();
+            Ok(Some(self.with_updated_directives(directives)))
+        }
+
         pub(crate) fn as_path_element(&self) -> Option<FetchDataPathElement> {
             let condition = self.data().type_condition_position.clone()?;
 
@@ -1657,7 +1801,7 @@ mod normalized_inline_fragment_selection {
             }
         }
 
-        fn casted_type(&self) -> CompositeTypeDefinitionPosition {
+        pub(crate) fn casted_type(&self) -> CompositeTypeDefinitionPosition {
             self.type_condition_position
                 .clone()
                 .unwrap_or_else(|| self.parent_type_position.clone())
@@ -1708,23 +1852,852 @@ pub(crate) use normalized_inline_fragment_selection::NormalizedInlineFragmentSel
 
 // TODO(@goto-bus-stop): merge this with the other NormalizedOperation impl block.
 impl NormalizedOperation {
+    /// Optimizes this operation by re-using the provided named fragments wherever they match a
+    /// sub-selection of the operation.
+    ///
+    /// Fragments that end up being used fewer than `min_usages_to_optimize` times (2 by default)
+    /// are expanded back, and only the fragments that remain in use are kept on the operation.
     pub(crate) fn optimize(
         &mut self,
         fragments: Option<&NamedFragments>,
         min_usages_to_optimize: Option<u32>,
-    ) {
+    ) -> Result<(), FederationError> {
         let min_usages_to_optimize = min_usages_to_optimize.unwrap_or(2);
-        let Some(fragments) = fragments else { return };
+        let Some(fragments) = fragments else {
+            return Ok(());
+        };
         if fragments.is_empty() {
-            return;
+            return Ok(());
         }
         assert!(
             min_usages_to_optimize >= 1,
             "Expected 'min_usages_to_optimize' to be at least 1, but got {min_usages_to_optimize}"
         );
 
-        todo!(); // TODO: port JS `Operation.optimize` from `operations.ts`
+        let mut optimized_selection = self.selection_set.optimize(fragments)?;
+        if optimized_selection == self.selection_set {
+            return Ok(());
+        }
+
+        let mut final_fragments =
+            compute_fragments_to_keep(&optimized_selection, fragments, min_usages_to_optimize)?;
+
+        // If we're not keeping all the fragments, we need to expand the spreads of those we drop.
+        if final_fragments.size() != fragments.size() {
+            // Note that optimizing all fragments to potentially re-expand some is not entirely
+            // optimal, but it's unclear how to do otherwise, and it probably doesn't matter too
+            // much in practice (we only call this optimization on the final computed query plan,
+            // so it's not a very hot path).
+            optimized_selection = optimized_selection.expand_fragments(&final_fragments)?;
+
+            // Expanding fragments could create some "inefficiencies" that we wouldn't have if we
+            // hadn't re-optimized the fragments to de-optimize it later, so we do a final
+            // "normalize" pass to remove those.
+            let parent_type = optimized_selection.type_position.clone();
+            optimized_selection = optimized_selection.normalize(
+                &parent_type,
+                &final_fragments,
+                &self.schema,
+                NormalizeSelectionOption::NormalizeRecursively,
+            )?;
+
+            // And if we've expanded some fragments but kept others, then it's not 100% impossible
+            // that some fragment was used multiple times in some expanded fragment(s), but that
+            // post-expansion all of its usages are "dead" branches that are removed by the final
+            // `normalize`. In that case though, we need to ensure we don't include the now-unused
+            // fragment in the final list of fragments. Note that removing a fragment might lead
+            // to another fragment being unused, so we iterate until there is nothing more to
+            // remove.
+            loop {
+                let mut usages = HashMap::new();
+                optimized_selection.collect_used_fragment_names(&mut usages);
+                final_fragments.collect_used_fragment_names(&mut usages);
+                let before_removal = final_fragments.size();
+                final_fragments = final_fragments.filter(|fragment| {
+                    usages.get(&fragment.name).is_some_and(|count| *count > 0)
+                })?;
+                if final_fragments.size() == before_removal {
+                    break;
+                }
+            }
+        }
+
+        self.selection_set = optimized_selection;
+        self.named_fragments = final_fragments;
+        Ok(())
+    }
+}
+
+/// Computes which of the `fragments` are worth keeping once `selection_set` has been optimized
+/// with them, that is the fragments that are still used at least `min_usages_to_optimize` times.
+///
+/// A kept fragment is defined once in the final document, so the fragments it uses are only used
+/// once more, but an expanded fragment is inlined at each of its usages, and so are the fragments
+/// it uses.
+fn compute_fragments_to_keep(
+    selection_set: &NormalizedSelectionSet,
+    fragments: &NamedFragments,
+    min_usages_to_optimize: u32,
+) -> Result<NamedFragments, FederationError> {
+    let mut direct_usages = HashMap::new();
+    selection_set.collect_used_fragment_names(&mut direct_usages);
+    // If we have no fragment in the selection set, then it's simple, we just don't keep any.
+    if direct_usages.is_empty() {
+        return Ok(NamedFragments::default());
+    }
+
+    let min_usages = i32::try_from(min_usages_to_optimize).unwrap_or(i32::MAX);
+    let mut kept: HashSet<Name> = fragments.fragments.keys().cloned().collect();
+    loop {
+        // Fragments are stored in dependency order (a fragment only uses fragments defined before
+        // it), so iterating in reverse order guarantees that all the usages of a fragment are
+        // known by the time we reach it.
+        let mut usages = direct_usages.clone();
+        for fragment in fragments.fragments.values().rev() {
+            let count = usages.get(&fragment.name).copied().unwrap_or(0);
+            let multiplier = if kept.contains(&fragment.name) {
+                count.min(1)
+            } else {
+                count
+            };
+            if multiplier == 0 {
+                continue;
+            }
+            for (name, used) in fragment.fragment_usages() {
+                *usages.entry(name).or_default() += multiplier * used;
+            }
+        }
+
+        let before_removal = kept.len();
+        kept.retain(|name| usages.get(name).copied().unwrap_or(0) >= min_usages);
+        // Removing a fragment can only lower the usages of the fragments it uses, so we
+        // iterate until nothing more is removed.
+        if kept.len() == before_removal {
+            break;
+        }
+    }
+
+    fragments.filter(|fragment| kept.contains(&fragment.name))
+}
+
+impl NormalizedFragment {
+    /// Whether this fragment may be spread directly within a selection set of the provided type,
+    /// that is, whether its type condition covers all the possible runtime types of `ty`.
+    fn can_apply_directly_at_type(
+        &self,
+        ty: &CompositeTypeDefinitionPosition,
+    ) -> Result<bool, FederationError> {
+        if self.type_condition_position == *ty {
+            return Ok(true);
+        }
+        // No point computing runtime types if the condition is an object (it can never cover all
+        // of the runtimes of `ty` unless it's the same type, which is already covered).
+        if !self.type_condition_position.is_abstract_type() {
+            return Ok(false);
+        }
+        let condition_runtimes = self
+            .schema
+            .possible_runtime_types(self.type_condition_position.clone())?;
+        let type_runtimes = self.schema.possible_runtime_types(ty.clone())?;
+        Ok(condition_runtimes.len() >= type_runtimes.len()
+            && type_runtimes
+                .iter()
+                .all(|runtime| condition_runtimes.contains(runtime)))
+    }
+
+    /// The selection set of this fragment with all nested fragments expanded.
+    fn expanded_selection_set(&self) -> Result<NormalizedSelectionSet, FederationError> {
+        self.selection_set.expand_all_fragments()?.normalize(
+            &self.type_condition_position,
+            &Default::default(),
+            &self.schema,
+            NormalizeSelectionOption::NormalizeRecursively,
+        )
+    }
+
+    /// Computes the expanded selection set of this fragment once it is spread at the provided
+    /// type, alongside a validator for the parts of the fragment that get trimmed away by that
+    /// restriction.
+    // PORT_NOTE: The JS codebase caches this on the fragment definition.
+    fn expanded_selection_set_at_type(
+        &self,
+        ty: &CompositeTypeDefinitionPosition,
+    ) -> Result<FragmentRestrictionAtType, FederationError> {
+        let expanded_selection_set = self.expanded_selection_set()?;
+        // Note that what we want is to get any simplification coming from updating the parent
+        // type to `ty` (for instance, if the fragment type is an interface and `ty` is an
+        // implementation of it).
+        let mut selection_set = expanded_selection_set.normalize(
+            ty,
+            &Default::default(),
+            &self.schema,
+            NormalizeSelectionOption::NormalizeRecursively,
+        )?;
+        selection_set.type_position = ty.clone();
+
+        if !self.type_condition_position.is_object_type() {
+            // When the type condition of the fragment is not an object type, the
+            // `FieldsInSetCanMerge` rule is more restrictive and any fields can create conflicts,
+            // so we have to use the full validator in this case.
+            let validator = FieldsConflictValidator::from_selection_set(&expanded_selection_set)?;
+            return Ok(FragmentRestrictionAtType {
+                selection_set,
+                validator: Some(Arc::new(validator)),
+            });
+        }
+
+        // Note that `trimmed` is the difference of 2 selections that may not have been normalized
+        // on the same parent type, so in practice, it is possible that `trimmed` contains some of
+        // the selections that `selection_set` contains, but that they have been simplified in
+        // `selection_set` in such a way that `minus` does not see it. This is fine: the validator
+        // only exists because the trimmed parts may conflict with other fields, and it is ok if
+        // it ends up bigger than it theoretically could be.
+        let trimmed = expanded_selection_set.minus(&selection_set)?;
+        let validator = if trimmed.is_empty() {
+            None
+        } else {
+            Some(Arc::new(FieldsConflictValidator::from_selection_set(
+                &trimmed,
+            )?))
+        };
+        Ok(FragmentRestrictionAtType {
+            selection_set,
+            validator,
+        })
+    }
+
+    /// Whether this fragment directly spreads the fragment named `other_fragment_name` at its
+    /// top-level.
+    fn includes(&self, other_fragment_name: &Name) -> bool {
+        if self.name == *other_fragment_name {
+            return false;
+        }
+        self.selection_set.selections.values().any(|selection| {
+            matches!(
+                selection,
+                NormalizedSelection::FragmentSpread(spread)
+                    if spread.spread.data().fragment_name == *other_fragment_name
+            )
+        })
+    }
+}
+
+/// The selection set of a fragment once spread at a given type.
+// PORT_NOTE: In JS, this is `FragmentRestrictionAtType`.
+struct FragmentRestrictionAtType {
+    /// The expanded selection set of the fragment, normalized for the type it is spread at.
+    selection_set: NormalizedSelectionSet,
+    /// A validator for the parts of the fragment that are trimmed away when spreading it at this
+    /// type. When `None`, nothing is trimmed and the fragment cannot introduce field conflicts.
+    validator: Option<Arc<FieldsConflictValidator>>,
+}
+
+impl FragmentRestrictionAtType {
+    /// It's possible that while a fragment technically applies at some type, its restriction to
+    /// that type is empty or contains only `__typename`. For instance, with a union `U = A | B | C`
+    /// and a fragment `fragment F on U { ... on A { x } ... on B { y } }`, `F` can be applied at
+    /// `C`, but that ends up selecting nothing at all. Using the fragment in those cases, while not
+    /// incorrect, is not productive.
+    fn is_useless(&self) -> bool {
+        match self.selection_set.selections.first() {
+            None => true,
+            Some((key, _)) => self.selection_set.selections.len() == 1 && key.is_typename_field(),
+        }
+    }
+}
+
+/// The result of trying to optimize a sub-selection with fragments: either the sub-selection with
+/// some of its parts replaced by fragment spreads, or a single fragment that matches the whole
+/// sub-selection.
+enum SelectionSetOrFragment {
+    SelectionSet(NormalizedSelectionSet),
+    Fragment(Node<NormalizedFragment>),
+}
+
+/// Checks that reusing fragments doesn't introduce field conflicts (see
+/// [FieldsInSetCanMerge](https://spec.graphql.org/draft/#FieldsInSetCanMerge())).
+///
+/// When a fragment is reused at some type, the parts of the fragment that do not apply at that
+/// type are technically included in the query as well, and they may conflict with other fields of
+/// the selection even though they never select anything.
+#[derive(Debug)]
+struct FieldsConflictValidator {
+    by_response_name: IndexMap<Name, Vec<FieldWithValidator>>,
+}
+
+/// A field alongside the validator of its sub-selection, if it has one.
+type FieldWithValidator = (NormalizedField, Option<Arc<FieldsConflictValidator>>);
+
+impl FieldsConflictValidator {
+    fn from_selection_set(selection_set: &NormalizedSelectionSet) -> Result<Self, FederationError> {
+        Self::for_level(
+            selection_set
+                .fields_in_set()
+                .into_iter()
+                .map(|collected| collected.field),
+        )
+    }
+
+    fn for_level(
+        level: impl IntoIterator<Item = Arc<NormalizedFieldSelection>>,
+    ) -> Result<Self, FederationError> {
+        // For each response name, the distinct fields with that response name, alongside the
+        // fields of their (merged) sub-selections.
+        type FieldWithSubFields = (NormalizedField, Option<Vec<Arc<NormalizedFieldSelection>>>);
+        let mut at_level: IndexMap<Name, Vec<FieldWithSubFields>> = IndexMap::new();
+        for field_selection in level {
+            let at_response_name = at_level
+                .entry(field_selection.field.data().response_name())
+                .or_default();
+            let sub_fields = field_selection.selection_set.as_ref().map(|selection_set| {
+                selection_set
+                    .fields_in_set()
+                    .into_iter()
+                    .map(|collected| collected.field)
+                    .collect::<Vec<_>>()
+            });
+            match at_response_name
+                .iter_mut()
+                .find(|(field, _)| *field == field_selection.field)
+            {
+                Some((_, existing)) => {
+                    if let (Some(existing), Some(sub_fields)) = (existing, sub_fields) {
+                        existing.extend(sub_fields);
+                    }
+                }
+                None => at_response_name.push((field_selection.field.clone(), sub_fields)),
+            }
+        }
+
+        let mut by_response_name = IndexMap::new();
+        for (response_name, fields) in at_level {
+            let mut at_response_name = Vec::new();
+            for (field, sub_fields) in fields {
+                let validator = sub_fields
+                    .map(|sub_fields| Self::for_level(sub_fields).map(Arc::new))
+                    .transpose()?;
+                at_response_name.push((field, validator));
+            }
+            by_response_name.insert(response_name, at_response_name);
+        }
+        Ok(Self { by_response_name })
+    }
+
+    /// The validators for the sub-selections of the fields with the same response name as the
+    /// provided field.
+    fn for_field(&self, field: &NormalizedField) -> Vec<Arc<FieldsConflictValidator>> {
+        self.by_response_name
+            .get(&field.data().response_name())
+            .into_iter()
+            .flatten()
+            .filter_map(|(_, validator)| validator.clone())
+            .collect()
+    }
+
+    /// Whether the fields of this validator can be merged with those of `other`, knowing that the
+    /// fields of each validator can already be merged together.
+    fn do_merge_with(&self, other: &FieldsConflictValidator) -> Result<bool, FederationError> {
+        for (response_name, self_fields) in &self.by_response_name {
+            let Some(other_fields) = other.by_response_name.get(response_name) else {
+                continue;
+            };
+            for (self_field, self_validator) in self_fields {
+                for (other_field, other_validator) in other_fields {
+                    // The `SameResponseShape` test that all fields must pass.
+                    if !fields_types_can_be_merged(self_field, other_field)? {
+                        return Ok(false);
+                    }
+
+                    let self_parent = self_field.data().field_position.parent();
+                    let other_parent = other_field.data().field_position.parent();
+                    if self_parent == other_parent
+                        || !self_parent.is_object_type()
+                        || !other_parent.is_object_type()
+                    {
+                        // Additional checks of `FieldsInSetCanMerge` when the parent types are the
+                        // same, or one of them isn't an object type.
+                        if self_field.data().name() != other_field.data().name()
+                            || !same_arguments(
+                                &self_field.data().arguments,
+                                &other_field.data().arguments,
+                            )
+                        {
+                            return Ok(false);
+                        }
+                        if let (Some(self_validator), Some(other_validator)) =
+                            (self_validator, other_validator)
+                        {
+                            if !self_validator.do_merge_with(other_validator)? {
+                                return Ok(false);
+                            }
+                        }
+                    } else if let (Some(self_validator), Some(other_validator)) =
+                        (self_validator, other_validator)
+                    {
+                        // Otherwise, the sub-selections must pass `SameResponseShape`.
+                        if !self_validator.has_same_response_shape_as(other_validator)? {
+                            return Ok(false);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn has_same_response_shape_as(
+        &self,
+        other: &FieldsConflictValidator,
+    ) -> Result<bool, FederationError> {
+        for (response_name, self_fields) in &self.by_response_name {
+            let Some(other_fields) = other.by_response_name.get(response_name) else {
+                continue;
+            };
+            for (self_field, self_validator) in self_fields {
+                for (other_field, other_validator) in other_fields {
+                    if !fields_types_can_be_merged(self_field, other_field)? {
+                        return Ok(false);
+                    }
+                    if let (Some(self_validator), Some(other_validator)) =
+                        (self_validator, other_validator)
+                    {
+                        if !self_validator.has_same_response_shape_as(other_validator)? {
+                            return Ok(false);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn fields_types_can_be_merged(
+    field1: &NormalizedField,
+    field2: &NormalizedField,
+) -> Result<bool, FederationError> {
+    let schema = field1.data().schema.schema();
+    let type1 = &field1.data().field_position.get(schema)?.ty;
+    let type2 = &field2.data().field_position.get(schema)?.ty;
+    types_can_be_merged(type1, type2, schema)
+}
+
+/// Tracks, for a given level of the selection being optimized, the validators of all the "branches"
+/// of the original selection that end up at that level, as well as the trimmed parts of the
+/// fragments that have been reused at that level so far.
+struct FieldsConflictMultiBranchValidator {
+    validators: Vec<Arc<FieldsConflictValidator>>,
+    used_spread_trimmed_part_at_level: Vec<Arc<FieldsConflictValidator>>,
+}
+
+impl FieldsConflictMultiBranchValidator {
+    fn new(validators: Vec<Arc<FieldsConflictValidator>>) -> Self {
+        Self {
+            validators,
+            used_spread_trimmed_part_at_level: Vec::new(),
+        }
+    }
+
+    fn from_initial(validator: FieldsConflictValidator) -> Self {
+        Self::new(vec![Arc::new(validator)])
+    }
+
+    fn for_field(&self, field: &NormalizedField) -> Self {
+        Self::new(
+            self.validators
+                .iter()
+                .flat_map(|validator| validator.for_field(field))
+                .collect(),
+        )
+    }
+
+    /// At this point, we know that the fragment, restricted to the current parent type, matches a
+    /// sub-selection of the optimized selection set. We need to make sure that the fragment's
+    /// trimmed parts don't conflict with fields of the selection set, nor with the trimmed parts of
+    /// the fragments we've already reused at this level.
+    fn check_can_reuse_fragment_and_track_it(
+        &mut self,
+        fragment_restriction: &FragmentRestrictionAtType,
+    ) -> Result<bool, FederationError> {
+        // No validator means that everything in the fragment selection was part of the selection
+        // we're optimizing away (by using the fragment), and we know the original selection was
+        // ok, so there is nothing to check.
+        let Some(validator) = &fragment_restriction.validator else {
+            return Ok(true);
+        };
+
+        for existing in &self.validators {
+            if !existing.do_merge_with(validator)? {
+                return Ok(false);
+            }
+        }
+        // Note that this means that if 2 fragments conflict on their "trimmed" parts, then the
+        // choice of which is used depends on the fragment and selection order, which may not be
+        // optimal. This feels niche enough that we keep it simple for now.
+        for used in &self.used_spread_trimmed_part_at_level {
+            if !validator.do_merge_with(used)? {
+                return Ok(false);
+            }
+        }
+
+        self.used_spread_trimmed_part_at_level
+            .push(validator.clone());
+        Ok(true)
+    }
+}
+
+impl NormalizedSelectionSet {
+    /// Replaces the parts of this selection set that match one of the provided fragments by a
+    /// spread of that fragment.
+    fn optimize(&self, fragments: &NamedFragments) -> Result<Self, FederationError> {
+        if fragments.is_empty() {
+            return Ok(self.clone());
+        }
+
+        // Calling `optimize_selections` will not match a fragment that would have expanded at
+        // top-level. That is, `optimize_selections` will never replace the whole selection set
+        // by a fragment spread (so `{ ... on Query { a b } }` would not be replaced by `{ ...F }`
+        // where `fragment F on Query { a b }`). To handle this case, we wrap the selection set in
+        // an inline fragment and optimize that.
+        let wrapped = NormalizedInlineFragmentSelection {
+            inline_fragment: NormalizedInlineFragment::new(NormalizedInlineFragmentData {
+                schema: self.schema.clone(),
+                parent_type_position: self.type_position.clone(),
+                type_condition_position: Some(self.type_position.clone()),
+                directives: Default::default(),
+                selection_id: SelectionId::new(),
+            }),
+            selection_set: self.clone(),
+        };
+        let mut validator = FieldsConflictMultiBranchValidator::from_initial(
+            FieldsConflictValidator::from_selection_set(self)?,
+        );
+        // Now, it's possible we matched a full fragment, in which case `optimized` is just the
+        // fragment spread, and we return a selection set with just that spread. Otherwise, it's
+        // our wrapping inline fragment with optimized sub-selections, and we return those.
+        match wrapped.optimize(fragments, &mut validator)? {
+            spread @ NormalizedSelection::FragmentSpread(_) => Ok(
+                NormalizedSelectionSet::from_selection(self.type_position.clone(), spread),
+            ),
+            NormalizedSelection::InlineFragment(inline_fragment) => {
+                Ok(inline_fragment.selection_set.clone())
+            }
+            NormalizedSelection::Field(_) => Err(FederationError::internal(
+                "Optimizing an inline fragment should not result in a field",
+            )),
+        }
+    }
+
+    /// Tries to match fragments inside each selection of this selection set, recursively.
+    fn optimize_selections(
+        &self,
+        fragments: &NamedFragments,
+        validator: &mut FieldsConflictMultiBranchValidator,
+    ) -> Result<Self, FederationError> {
+        let mut optimized_selections = Vec::with_capacity(self.selections.len());
+        for selection in self.selections.values() {
+            optimized_selections.push(selection.optimize(fragments, validator)?);
+        }
+        let mut optimized =
+            NormalizedSelectionSet::empty(self.schema.clone(), self.type_position.clone());
+        optimized.merge_selections_into(optimized_selections.iter())?;
+        Ok(optimized)
+    }
+
+    /// Returns the selections that are both in this selection set and in `other`.
+    pub(crate) fn intersection(
+        &self,
+        other: &NormalizedSelectionSet,
+    ) -> Result<NormalizedSelectionSet, FederationError> {
+        if self.is_empty() {
+            return Ok(self.clone());
+        }
+        if other.is_empty() {
+            return Ok(other.clone());
+        }
+
+        let mut selections = NormalizedSelectionMap::new();
+        for (key, selection) in self.selections.iter() {
+            let Some(other_selection) = other.selections.get(key) else {
+                continue;
+            };
+            if let (Some(self_sub_selection), Some(other_sub_selection)) =
+                (selection.selection_set()?, other_selection.selection_set()?)
+            {
+                let sub_selection_intersection =
+                    self_sub_selection.intersection(other_sub_selection)?;
+                if !sub_selection_intersection.is_empty() {
+                    selections.insert(
+                        selection.with_updated_selection_set(Some(sub_selection_intersection))?,
+                    );
+                }
+            } else {
+                selections.insert(selection.clone());
+            }
+        }
+        Ok(NormalizedSelectionSet {
+            schema: self.schema.clone(),
+            type_position: self.type_position.clone(),
+            selections: Arc::new(selections),
+        })
+    }
+}
+
+impl NormalizedSelection {
+    fn optimize(
+        &self,
+        fragments: &NamedFragments,
+        validator: &mut FieldsConflictMultiBranchValidator,
+    ) -> Result<NormalizedSelection, FederationError> {
+        match self {
+            NormalizedSelection::Field(field) => field.optimize(fragments, validator),
+            // Spreads come from a previous optimization, so there is nothing more to do.
+            NormalizedSelection::FragmentSpread(_) => Ok(self.clone()),
+            NormalizedSelection::InlineFragment(inline_fragment) => {
+                inline_fragment.optimize(fragments, validator)
+            }
+        }
+    }
+}
+
+impl NormalizedFieldSelection {
+    fn optimize(
+        &self,
+        fragments: &NamedFragments,
+        validator: &mut FieldsConflictMultiBranchValidator,
+    ) -> Result<NormalizedSelection, FederationError> {
+        let Some(selection_set) = &self.selection_set else {
+            return Ok(NormalizedSelection::Field(Arc::new(self.clone())));
+        };
+        let field_base_type = selection_set.type_position.clone();
+        let mut field_validator = validator.for_field(&self.field);
+
+        // First, see if we can reuse fragments for the selection of this field.
+        let optimized = try_optimize_subselection_with_fragments(
+            &field_base_type,
+            selection_set,
+            fragments,
+            &mut field_validator,
+            |fragment| fragment.directives.is_empty(),
+        )?;
+        let optimized = match optimized {
+            SelectionSetOrFragment::Fragment(fragment) => NormalizedSelectionSet::from_selection(
+                field_base_type,
+                fragment_spread_selection(&fragment, Default::default()),
+            ),
+            SelectionSetOrFragment::SelectionSet(optimized) => optimized,
+        };
+
+        // Then, recurse inside the field sub-selection (note that if we matched some fragments
+        // above, this recursion will "ignore" those as optimizing a spread is a no-op).
+        let optimized = optimized.optimize_selections(fragments, &mut field_validator)?;
+        Ok(if optimized == *selection_set {
+            NormalizedSelection::Field(Arc::new(self.clone()))
+        } else {
+            NormalizedSelection::Field(Arc::new(self.with_updated_selection_set(Some(optimized))))
+        })
+    }
+}
+
+impl NormalizedInlineFragmentSelection {
+    fn optimize(
+        &self,
+        fragments: &NamedFragments,
+        validator: &mut FieldsConflictMultiBranchValidator,
+    ) -> Result<NormalizedSelection, FederationError> {
+        let data = self.inline_fragment.data();
+        let mut optimized = self.selection_set.clone();
+
+        // First, see if we can reuse fragments for the selection of this inline fragment.
+        if let Some(type_condition) = &data.type_condition_position {
+            let same_directive = |left: &Node<Directive>, right: &Node<Directive>| {
+                left.name == right.name && same_arguments(&left.arguments, &right.arguments)
+            };
+            let result = try_optimize_subselection_with_fragments(
+                type_condition,
+                &self.selection_set,
+                fragments,
+                validator,
+                |fragment| {
+                    // To be able to use a matching fragment, it needs to have either no directives,
+                    // or if it has some, then:
+                    //  1. all its directives should also be on the current element.
+                    //  2. the directives of this element should be the fragment condition.
+                    // because if those 2 conditions are true, we can replace the whole current
+                    // inline fragment with the matching spread and directives will still match.
+                    fragment.directives.is_empty()
+                        || (fragment.type_condition_position == *type_condition
+                            && fragment.directives.iter().all(|fragment_directive| {
+                                data.directives
+                                    .iter()
+                                    .any(|directive| same_directive(fragment_directive, directive))
+                            }))
+                },
+            )?;
+            match result {
+                SelectionSetOrFragment::Fragment(fragment) => {
+                    // We're fully matching the sub-selection. If the fragment condition is also
+                    // this element condition, then we can replace the whole element by the spread
+                    // (not just the sub-selection).
+                    if fragment.type_condition_position == *type_condition {
+                        // Note that `can_use_full_matching_fragment` above guarantees us that this
+                        // element's directives are a superset of the fragment directives. But
+                        // there can be additional directives, and in that case they should be
+                        // kept on the spread.
+                        let spread_directives = DirectiveList(
+                            data.directives
+                                .iter()
+                                .filter(|directive| {
+                                    !fragment.directives.iter().any(|fragment_directive| {
+                                        same_directive(fragment_directive, directive)
+                                    })
+                                })
+                                .cloned()
+                                .collect(),
+                        );
+                        return Ok(fragment_spread_selection(&fragment, spread_directives));
+                    }
+                    // Otherwise, we keep this element and use a sub-selection with just the spread.
+                    optimized = NormalizedSelectionSet::from_selection(
+                        type_condition.clone(),
+                        fragment_spread_selection(&fragment, Default::default()),
+                    );
+                }
+                SelectionSetOrFragment::SelectionSet(selection_set) => optimized = selection_set,
+            }
+        }
+
+        // Then, recurse inside the sub-selection (note that if we matched some fragments above,
+        // this recursion will "ignore" those as optimizing a spread is a no-op).
+        let optimized = optimized.optimize_selections(fragments, validator)?;
+        Ok(if optimized == self.selection_set {
+            NormalizedSelection::InlineFragment(Arc::new(self.clone()))
+        } else {
+            NormalizedSelection::InlineFragment(Arc::new(
+                self.with_updated_selection_set(Some(optimized)),
+            ))
+        })
+    }
+}
+
+fn fragment_spread_selection(
+    fragment: &Node<NormalizedFragment>,
+    spread_directives: DirectiveList,
+) -> NormalizedSelection {
+    NormalizedSelection::FragmentSpread(Arc::new(NormalizedFragmentSpreadSelection {
+        spread: NormalizedFragmentSpread::new(NormalizedFragmentSpreadData::from_fragment(
+            fragment,
+            &spread_directives,
+        )),
+        selection_set: fragment.selection_set.clone(),
+    }))
+}
+
+/// Tries to replace the provided sub-selection, or some parts of it, by spreads of the fragments
+/// that apply at `parent_type`.
+///
+/// If a fragment matches the whole sub-selection and `can_use_full_matching_fragment` accepts it,
+/// that fragment is returned. Otherwise, we return the sub-selection where the fragments that
+/// match a subset of it are spread.
+// PORT_NOTE: In JS, this is `tryOptimizeSubselectionWithFragments` on `AbstractSelection`.
+fn try_optimize_subselection_with_fragments(
+    parent_type: &CompositeTypeDefinitionPosition,
+    sub_selection: &NormalizedSelectionSet,
+    fragments: &NamedFragments,
+    validator: &mut FieldsConflictMultiBranchValidator,
+    can_use_full_matching_fragment: impl Fn(&NormalizedFragment) -> bool,
+) -> Result<SelectionSetOrFragment, FederationError> {
+    let candidates = fragments.maybe_applying_directly_at_type(parent_type)?;
+    if candidates.is_empty() {
+        return Ok(SelectionSetOrFragment::SelectionSet(sub_selection.clone()));
+    }
+
+    // First, we check which of the candidates do apply inside `sub_selection`, if any. If we find
+    // a candidate that applies to the whole `sub_selection`, then we stop and only return that
+    // one candidate. Otherwise, we accumulate in `applying_fragments` the list of fragments that
+    // apply to a subset of `sub_selection`.
+    let mut applying_fragments = Vec::new();
+    for candidate in candidates {
+        let at_type = candidate.expanded_selection_set_at_type(parent_type)?;
+        if at_type.is_useless() {
+            continue;
+        }
+
+        let containment = sub_selection.containment(
+            &at_type.selection_set,
+            ContainmentOptions {
+                ignore_missing_typename: true,
+            },
+        );
+        match containment {
+            Containment::Equal => {
+                if can_use_full_matching_fragment(&candidate) {
+                    if !validator.check_can_reuse_fragment_and_track_it(&at_type)? {
+                        // We cannot use it at all, so no point in adding it to
+                        // `applying_fragments`.
+                        continue;
+                    }
+                    return Ok(SelectionSetOrFragment::Fragment(candidate));
+                }
+                // If we're not going to replace the full thing, then same reasoning as below.
+                if candidate.directives.is_empty() {
+                    applying_fragments.push((candidate, at_type));
+                }
+            }
+            // Note that if a fragment applies to only a subset of the sub-selection, then we
+            // really only can use it if that fragment is defined _without_ directives.
+            Containment::StrictlyContained if candidate.directives.is_empty() => {
+                applying_fragments.push((candidate, at_type));
+            }
+            _ => {}
+        }
+    }
+
+    if applying_fragments.is_empty() {
+        return Ok(SelectionSetOrFragment::SelectionSet(sub_selection.clone()));
+    }
+
+    // We have found the list of fragments that apply to some subset of the sub-selection. We now
+    // produce the selection set with spreads for those fragments plus any selection that is not
+    // covered by any of the fragments. For instance, if `sub_selection` is `{ a b c d e }` and
+    // both `fragment F1 on X { a b c }` and `fragment F2 on X { c d }` apply, then we generate
+    // `{ ...F1 ...F2 e }`.
+    //
+    // A special case of this is if a fragment is entirely included in another one, for instance
+    // `fragment F1 on X { a ...F2 }` and `fragment F2 on X { b c }`. Both fragments apply, but as
+    // `F1` already includes `F2`, we really want to only use `F1`. So we filter away any fragment
+    // that is spread at the top-level of another fragment that applies. This does not catch all
+    // forms of inclusion, but it is cheap to check and likely the most common case.
+    let filtered_applying_fragments = applying_fragments
+        .iter()
+        .filter(|(fragment, _)| {
+            !applying_fragments
+                .iter()
+                .any(|(other, _)| other.includes(&fragment.name))
+        })
+        .collect::<Vec<_>>();
+
+    let mut not_covered_so_far = sub_selection.clone();
+    let mut optimized_selections = Vec::new();
+    for (fragment, at_type) in filtered_applying_fragments {
+        if !validator.check_can_reuse_fragment_and_track_it(at_type)? {
+            continue;
+        }
+        let not_covered = sub_selection.minus(&at_type.selection_set)?;
+        not_covered_so_far = not_covered_so_far.intersection(&not_covered)?;
+        optimized_selections.push(fragment_spread_selection(fragment, Default::default()));
     }
+
+    let mut optimized = NormalizedSelectionSet::empty(
+        sub_selection.schema.clone(),
+        sub_selection.type_position.clone(),
+    );
+    optimized.merge_selections_into(
+        optimized_selections
+            .iter()
+            .chain(not_covered_so_far.selections.values()),
+    )?;
+    Ok(SelectionSetOrFragment::SelectionSet(optimized))
 }
 
 impl NormalizedSelectionSet {
@@ -1739,6 +2712,26 @@ impl NormalizedSelectionSet {
         }
     }
 
+    /// Creates a selection set for the given composite type that only selects `__typename`.
+    // PORT_NOTE: This corresponds to `newCompositeTypeSelectionSet` in the JS codebase.
+    pub(crate) fn for_composite_type(
+        schema: ValidFederationSchema,
+        type_position: CompositeTypeDefinitionPosition,
+    ) -> Self {
+        let typename_field = NormalizedField::new(NormalizedFieldData {
+            schema,
+            field_position: type_position.introspection_typename_field(),
+            alias: None,
+            arguments: Default::default(),
+            directives: Default::default(),
+            sibling_typename: None,
+        });
+        Self::from_selection(
+            type_position,
+            NormalizedSelection::from_normalized_field(typename_field, None),
+        )
+    }
+
     pub(crate) fn from_selection(
         type_position: CompositeTypeDefinitionPosition,
         selection: NormalizedSelection,
@@ -1757,6 +2750,128 @@ impl NormalizedSelectionSet {
         self.selections.is_empty()
     }
 
+    /// Removes the `@defer` applications with the given labels from this selection set, or all of
+    /// them if `labels_to_remove` is `None`.
+    pub(crate) fn without_defer(
+        &self,
+        labels_to_remove: Option<&IndexSet<String>>,
+    ) -> Result<NormalizedSelectionSet, FederationError> {
+        let mut selections = vec![];
+        for selection in self.selections.values() {
+            match selection {
+                NormalizedSelection::Field(field) => match &field.selection_set {
+                    Some(selection_set) => selections.push(selection.with_updated_selection_set(
+                        Some(selection_set.without_defer(labels_to_remove)?),
+                    )?),
+                    None => selections.push(selection.clone()),
+                },
+                NormalizedSelection::InlineFragment(inline) => {
+                    let selection_set = inline.selection_set.without_defer(labels_to_remove)?;
+                    let should_remove =
+                        match inline.inline_fragment.data().defer_directive_arguments()? {
+                            // If there is no @defer, or it's not one of the labels we remove, we keep
+                            // the fragment as is.
+                            None => false,
+                            Some(args) => match labels_to_remove {
+                                None => true,
+                                Some(labels) => args
+                                    .label()
+                                    .is_some_and(|label| labels.contains(label.as_str())),
+                            },
+                        };
+                    if !should_remove {
+                        selections.push(selection.with_updated_selection_set(Some(selection_set))?);
+                        continue;
+                    }
+                    let element = OpPathElement::InlineFragment(inline.inline_fragment.clone());
+                    match element.without_defer() {
+                        Some(element) => selections.push(NormalizedSelection::from_element(
+                            element,
+                            Some(selection_set),
+                        )?),
+                        // The fragment carries no information anymore, so we hoist its selections.
+                        None => selections.extend(selection_set.selections.values().cloned()),
+                    }
+                }
+                NormalizedSelection::FragmentSpread(_) => {
+                    return Err(FederationError::internal("unexpected fragment spread"))
+                }
+            }
+        }
+        let mut without_defer =
+            NormalizedSelectionSet::empty(self.schema.clone(), self.type_position.clone());
+        without_defer.merge_selections_into(selections.iter())?;
+        Ok(without_defer)
+    }
+
+    fn with_normalized_defer(
+        &self,
+        normalizer: &mut DeferNormalizer,
+    ) -> Result<NormalizedSelectionSet, FederationError> {
+        let mut selections = vec![];
+        for selection in self.selections.values() {
+            match selection {
+                NormalizedSelection::Field(field) => match &field.selection_set {
+                    Some(selection_set) => selections.push(selection.with_updated_selection_set(
+                        Some(selection_set.with_normalized_defer(normalizer)?),
+                    )?),
+                    None => selections.push(selection.clone()),
+                },
+                NormalizedSelection::InlineFragment(inline) => {
+                    let selection_set = inline.selection_set.with_normalized_defer(normalizer)?;
+                    match inline.inline_fragment.with_normalized_defer(normalizer)? {
+                        Some(inline_fragment) => {
+                            selections.push(NormalizedSelection::from_normalized_inline_fragment(
+                                inline_fragment,
+                                selection_set,
+                            ))
+                        }
+                        None => selections.extend(selection_set.selections.values().cloned()),
+                    }
+                }
+                NormalizedSelection::FragmentSpread(_) => {
+                    return Err(FederationError::internal("unexpected fragment spread"))
+                }
+            }
+        }
+        let mut normalized =
+            NormalizedSelectionSet::empty(self.schema.clone(), self.type_position.clone());
+        normalized.merge_selections_into(selections.iter())?;
+        Ok(normalized)
+    }
+
+    /// Splits this selection set into one selection set per top-level field, preserving their
+    /// order. Fragments are traversed, so each resulting selection set selects a single field,
+    /// nested within the fragments that it was selected through.
+    // PORT_NOTE: This corresponds to `splitTopLevelFields` in the JS codebase.
+    pub(crate) fn split_top_level_fields(
+        &self,
+    ) -> Result<Vec<NormalizedSelectionSet>, FederationError> {
+        let mut split = vec![];
+        for selection in self.selections.values() {
+            if let NormalizedSelection::Field(_) = selection {
+                split.push(NormalizedSelectionSet::from_selection(
+                    self.type_position.clone(),
+                    selection.clone(),
+                ));
+                continue;
+            }
+            let element = selection.element()?;
+            let Some(sub_selection_set) = selection.selection_set()? else {
+                return Err(FederationError::internal(
+                    "Fragment selections should have a selection set",
+                ));
+            };
+            for fields in sub_selection_set.split_top_level_fields()? {
+                split.push(NormalizedSelectionSet::from_selection(
+                    self.type_position.clone(),
+                    NormalizedSelection::from_element(element.clone(), Some(fields))?,
+                ));
+            }
+        }
+        Ok(split)
+    }
+
     pub(crate) fn contains_top_level_field(
         &self,
         field: &NormalizedField,
@@ -2036,8 +3151,20 @@ impl NormalizedSelectionSet {
     }
 
     pub(crate) fn expand_all_fragments(&self) -> Result<NormalizedSelectionSet, FederationError> {
+        self.expand_fragments(&Default::default())
+    }
+
+    /// Expands the spreads of all the fragments that are not part of `fragments_to_keep`.
+    pub(crate) fn expand_fragments(
+        &self,
+        fragments_to_keep: &NamedFragments,
+    ) -> Result<NormalizedSelectionSet, FederationError> {
         let mut expanded_selections = vec![];
-        NormalizedSelectionSet::expand_selection_set(&mut expanded_selections, self)?;
+        NormalizedSelectionSet::expand_selection_set(
+            &mut expanded_selections,
+            self,
+            fragments_to_keep,
+        )?;
 
         let mut expanded = NormalizedSelectionSet {
             schema: self.schema.clone(),
@@ -2051,12 +3178,13 @@ impl NormalizedSelectionSet {
     fn expand_selection_set(
         destination: &mut Vec<NormalizedSelection>,
         selection_set: &NormalizedSelectionSet,
+        fragments_to_keep: &NamedFragments,
     ) -> Result<(), FederationError> {
         for (_, value) in selection_set.selections.iter() {
             match value {
                 NormalizedSelection::Field(field_selection) => {
                     let selections = match &field_selection.selection_set {
-                        Some(s) => Some(s.expand_all_fragments()?),
+                        Some(s) => Some(s.expand_fragments(fragments_to_keep)?),
                         None => None,
                     };
                     let expanded_selection = NormalizedFieldSelection {
@@ -2067,20 +3195,25 @@ impl NormalizedSelectionSet {
                 }
                 NormalizedSelection::FragmentSpread(spread_selection) => {
                     let fragment_spread_data = spread_selection.spread.data();
-                    // We can hoist/collapse named fragments if their type condition is on the
-                    // parent type and they don't have any directives.
-                    if fragment_spread_data.type_condition_position == selection_set.type_position
+                    if fragments_to_keep.contains(&fragment_spread_data.fragment_name) {
+                        destination.push(value.clone());
+                    } else if fragment_spread_data.type_condition_position
+                        == selection_set.type_position
                         && fragment_spread_data.directives.is_empty()
                     {
+                        // We can hoist/collapse named fragments if their type condition is on the
+                        // parent type and they don't have any directives.
                         NormalizedSelectionSet::expand_selection_set(
                             destination,
                             &spread_selection.selection_set,
+                            fragments_to_keep,
                         )?;
                     } else {
                         // convert to inline fragment
                         let expanded =
                             NormalizedInlineFragmentSelection::from_fragment_spread_selection(
                                 spread_selection,
+                                fragments_to_keep,
                             )?;
                         destination.push(NormalizedSelection::InlineFragment(Arc::new(expanded)));
                     }
@@ -2088,7 +3221,9 @@ impl NormalizedSelectionSet {
                 NormalizedSelection::InlineFragment(inline_selection) => {
                     let expanded_selection = NormalizedInlineFragmentSelection {
                         inline_fragment: inline_selection.inline_fragment.clone(),
-                        selection_set: inline_selection.selection_set.expand_all_fragments()?,
+                        selection_set: inline_selection
+                            .selection_set
+                            .expand_fragments(fragments_to_keep)?,
                     };
                     destination.push(NormalizedSelection::InlineFragment(Arc::new(
                         expanded_selection,
@@ -2319,12 +3454,83 @@ impl NormalizedSelectionSet {
         self.selections.contains_key(key)
     }
 
+    /// Adds a path, and optional some selections following that path, to this selection set.
+    ///
+    /// The final selections are optional (for instance, if `path` ends on a leaf field,
+    /// then no followup selections would make sense),
+    /// but when some are provided, uncesssary fragments will be automaticaly removed
+    /// at the junction between the path and those final selections.
+    /// For instance, suppose that we have:
+    ///  - a `path` argument that is `a::b::c`,
+    ///    where the type of the last field `c` is some object type `C`.
+    ///  - a `selections` argument that is `{ ... on C { d } }`.
+    /// Then the resulting built selection set will be: `{ a { b { c { d } } }`,
+    /// and in particular the `... on C` fragment will be eliminated since it is unecesasry
+    /// (since again, `c` is of type `C`).
+    ///
+    /// Both the path elements and the selections are rebased on the schema of this selection set.
+    // PORT_NOTE: The JS codebase accumulates updates in a `SelectionSetUpdates` structure and
+    // builds the selection set lazily. Here we instead build the selection for the path and
+    // directly merge it into this selection set.
     pub(crate) fn add_at_path(
         &mut self,
         path: &OpPath,
         selection_set: Option<&Arc<NormalizedSelectionSet>>,
-    ) {
-        Arc::make_mut(&mut self.selections).add_at_path(path, selection_set)
+    ) -> Result<(), FederationError> {
+        self.add_at_path_elements(&path.0, selection_set)
+    }
+
+    fn add_at_path_elements(
+        &mut self,
+        path: &[Arc<OpPathElement>],
+        selection_set: Option<&Arc<NormalizedSelectionSet>>,
+    ) -> Result<(), FederationError> {
+        let Some((element, remaining_path)) = path.split_first() else {
+            let Some(selection_set) = selection_set else {
+                return Ok(());
+            };
+            let rebased = selection_set.rebase_on(
+                &self.type_position,
+                &Default::default(),
+                &self.schema,
+                RebaseErrorHandlingOption::ThrowError,
+            )?;
+            let mut selections = vec![];
+            for selection in rebased.selections.values() {
+                match selection {
+                    NormalizedSelection::InlineFragment(inline)
+                        if inline.inline_fragment.data().directives.is_empty()
+                            && inline.casted_type() == &self.type_position =>
+                    {
+                        selections.extend(inline.selection_set.selections.values().cloned())
+                    }
+                    _ => selections.push(selection.clone()),
+                }
+            }
+            return self.merge_selections_into(selections.iter());
+        };
+        let element = element.rebase_on(&self.type_position, &self.schema)?;
+        if let OpPathElement::InlineFragment(inline) = &element {
+            // A fragment that doesn't change the type and has no directives is a no-op, so we
+            // skip it rather than creating a useless nested fragment (this happens when
+            // concatenating paths that both start with the same type condition).
+            if inline.data().directives.is_empty()
+                && inline.data().casted_type() == self.type_position
+            {
+                return self.add_at_path_elements(remaining_path, selection_set);
+            }
+        }
+        let sub_selection_set = match element.sub_selection_type_position()? {
+            Some(sub_selection_type) => {
+                let mut sub_selection_set =
+                    NormalizedSelectionSet::empty(self.schema.clone(), sub_selection_type);
+                sub_selection_set.add_at_path_elements(remaining_path, selection_set)?;
+                Some(sub_selection_set)
+            }
+            None => None,
+        };
+        let selection = NormalizedSelection::from_element(element, sub_selection_set)?;
+        self.merge_selections_into(std::iter::once(&selection))
     }
 
     fn collect_used_fragment_names(&self, aggregator: &mut HashMap<Name, i32>) {
@@ -2352,8 +3558,8 @@ impl NormalizedSelectionSet {
             rebased_selections.insert(rebased.clone());
         }
         Ok(NormalizedSelectionSet {
-            schema: self.schema.clone(),
-            type_position: self.type_position.clone(),
+            schema: schema.clone(),
+            type_position: parent_type.clone(),
             selections: Arc::new(rebased_selections),
         })
     }
@@ -2461,10 +3667,45 @@ impl NormalizedSelectionSet {
         })
     }
 
-    pub(crate) fn can_rebase_on(&self, parent_type: &CompositeTypeDefinitionPosition) -> bool {
+    pub(crate) fn can_rebase_on(
+        &self,
+        parent_type: &CompositeTypeDefinitionPosition,
+        parent_schema: &ValidFederationSchema,
+    ) -> bool {
         self.selections
             .values()
-            .all(|sel| sel.can_add_to(parent_type, &self.schema))
+            .all(|sel| sel.can_add_to(parent_type, parent_schema))
+    }
+
+    /// Returns the selections of this selection set that are not in `other`.
+    ///
+    /// Selections are matched by key, and for selections with sub-selections, only the
+    /// sub-selections that are not in the corresponding selection of `other` are kept.
+    pub(crate) fn minus(
+        &self,
+        other: &NormalizedSelectionSet,
+    ) -> Result<NormalizedSelectionSet, FederationError> {
+        let mut selections = NormalizedSelectionMap::new();
+        for (key, selection) in self.selections.iter() {
+            let Some(other_selection) = other.selections.get(key) else {
+                selections.insert(selection.clone());
+                continue;
+            };
+            if let (Some(self_sub_selection), Some(other_sub_selection)) =
+                (selection.selection_set()?, other_selection.selection_set()?)
+            {
+                let updated_sub_selection = self_sub_selection.minus(other_sub_selection)?;
+                if !updated_sub_selection.is_empty() {
+                    selections
+                        .insert(selection.with_updated_selection_set(Some(updated_sub_selection))?);
+                }
+            }
+        }
+        Ok(NormalizedSelectionSet {
+            schema: self.schema.clone(),
+            type_position: self.type_position.clone(),
+            selections: Arc::new(selections),
+        })
     }
 
     fn has_defer(&self) -> bool {
@@ -2595,8 +3836,26 @@ impl NormalizedSelectionSet {
                     path: Vec::new(),
                     field: field.clone(),
                 }),
-                NormalizedSelection::FragmentSpread(_fragment) => {
-                    todo!()
+                NormalizedSelection::FragmentSpread(fragment_spread) => {
+                    let header = vec![FetchDataPathElement::TypenameEquals(
+                        fragment_spread
+                            .spread
+                            .data()
+                            .type_condition_position
+                            .type_name()
+                            .clone()
+                            .into(),
+                    )];
+                    for CollectedFieldInSet { path, field } in
+                        fragment_spread.selection_set.fields_in_set().into_iter()
+                    {
+                        let mut new_path = header.clone();
+                        new_path.extend(path);
+                        fields.push(CollectedFieldInSet {
+                            path: new_path,
+                            field,
+                        })
+                    }
                 }
                 NormalizedSelection::InlineFragment(inline_fragment) => {
                     let condition = inline_fragment
@@ -2948,35 +4207,6 @@ pub(crate) fn subselection_type_if_abstract(
     }
 }
 
-impl From<NormalizedSelectionSet> for executable::SelectionSet {
-    fn from(_value: NormalizedSelectionSet) -> Self {
-        todo!()
-    }
-}
-
-impl NormalizedSelectionMap {
-    /// Adds a path, and optional some selections following that path, to those updates.
-    ///
-    /// The final selections are optional (for instance, if `path` ends on a leaf field,
-    /// then no followup selections would make sense),
-    /// but when some are provided, uncesssary fragments will be automaticaly removed
-    /// at the junction between the path and those final selections.
-    /// For instance, suppose that we have:
-    ///  - a `path` argument that is `a::b::c`,
-    ///    where the type of the last field `c` is some object type `C`.
-    ///  - a `selections` argument that is `{ ... on C { d } }`.
-    /// Then the resulting built selection set will be: `{ a { b { c { d } } }`,
-    /// and in particular the `... on C` fragment will be eliminated since it is unecesasry
-    /// (since again, `c` is of type `C`).
-    pub(crate) fn add_at_path(
-        &mut self,
-        _path: &OpPath,
-        _selection_set: Option<&Arc<NormalizedSelectionSet>>,
-    ) {
-        // TODO: port a `SelectionSetUpdates` data structure or mutate directly?
-    }
-}
-
 impl NormalizedFieldSelection {
     /// Normalize this field selection (merging selections with the same keys), with the following
     /// additional transformations:
@@ -3490,6 +4720,7 @@ impl NormalizedInlineFragmentSelection {
 
     pub(crate) fn from_fragment_spread_selection(
         fragment_spread_selection: &Arc<NormalizedFragmentSpreadSelection>,
+        fragments_to_keep: &NamedFragments,
     ) -> Result<NormalizedInlineFragmentSelection, FederationError> {
         let fragment_spread_data = fragment_spread_selection.spread.data();
         Ok(NormalizedInlineFragmentSelection {
@@ -3502,7 +4733,7 @@ impl NormalizedInlineFragmentSelection {
             }),
             selection_set: fragment_spread_selection
                 .selection_set
-                .expand_all_fragments()?,
+                .expand_fragments(fragments_to_keep)?,
         })
     }
 
@@ -3904,7 +5135,7 @@ impl NormalizedInlineFragment {
         schema: &ValidFederationSchema,
         error_handling: RebaseErrorHandlingOption,
     ) -> Result<Option<NormalizedInlineFragment>, FederationError> {
-        if &self.data().parent_type_position == parent_type {
+        if &self.data().parent_type_position == parent_type && &self.data().schema == schema {
             return Ok(Some(self.clone()));
         }
 
@@ -3938,6 +5169,8 @@ impl NormalizedInlineFragment {
             }
         } else {
             let mut rebased_fragment_data = self.data().clone();
+            rebased_fragment_data.schema = schema.clone();
+            rebased_fragment_data.parent_type_position = parent_type.clone();
             rebased_fragment_data.type_condition_position = rebased_condition;
             Ok(Some(NormalizedInlineFragment::new(rebased_fragment_data)))
         }
@@ -4077,6 +5310,72 @@ impl NamedFragments {
         }
     }
 
+    /// Returns the fragments that may be spread directly within a selection set of the provided
+    /// type.
+    fn maybe_applying_directly_at_type(
+        &self,
+        ty: &CompositeTypeDefinitionPosition,
+    ) -> Result<Vec<Node<NormalizedFragment>>, FederationError> {
+        let mut applying = Vec::new();
+        for fragment in self.fragments.values() {
+            if fragment.can_apply_directly_at_type(ty)? {
+                applying.push(fragment.clone());
+            }
+        }
+        Ok(applying)
+    }
+
+    /// Returns the fragments for which `predicate` is true. The fragments that are kept but use
+    /// some of the removed fragments get the spreads of those removed fragments expanded.
+    // PORT_NOTE: In JS, this returns `undefined` when no fragments are kept.
+    pub(crate) fn filter(
+        &self,
+        mut predicate: impl FnMut(&NormalizedFragment) -> bool,
+    ) -> Result<NamedFragments, FederationError> {
+        let mut filtered = NamedFragments::default();
+        // Fragments are in dependency order, so any fragment used by a kept fragment has already
+        // been filtered when we reach that kept fragment.
+        for fragment in self.fragments.values() {
+            if !predicate(fragment) {
+                continue;
+            }
+            let selection_set = fragment.selection_set.expand_fragments(&filtered)?;
+            filtered.insert(NormalizedFragment {
+                selection_set,
+                ..fragment.deref().clone()
+            });
+        }
+        Ok(filtered)
+    }
+
+    /// Adds `__typename` to the selections of abstract types within those fragments, as is done
+    /// for the selections of subgraph fetches (see
+    /// `NormalizedSelectionSet::add_typename_field_for_abstract_types`), so that those fragments
+    /// can still be reused in those fetches.
+    ///
+    /// Nested fragments make this a bit tricky: if we were to naively add `__typename` to a
+    /// fragment that spreads another fragment whose selection already contains `__typename`, we
+    /// would end up with an extra `__typename` next to the spread, and it would become hard to
+    /// recognize the updated fragment in a selection where the inner fragment has already been
+    /// reused. So for every fragment, we expand its nested fragments, add `__typename` to that
+    /// expanded selection, and then re-optimize it with the previously updated fragments.
+    pub(crate) fn add_typename_field_for_abstract_types_in_named_fragments(
+        &self,
+    ) -> Result<NamedFragments, FederationError> {
+        let mut updated = NamedFragments::default();
+        for fragment in self.fragments.values() {
+            let expanded_selection_set = fragment.expanded_selection_set()?;
+            let selection_set = expanded_selection_set
+                .add_typename_field_for_abstract_types(None, &None)?
+                .optimize(&updated)?;
+            updated.insert(NormalizedFragment {
+                selection_set,
+                ..fragment.deref().clone()
+            });
+        }
+        Ok(updated)
+    }
+
     /// JS PORT NOTE: In JS implementation this method was named mapInDependencyOrder and accepted a lambda to
     /// apply transformation on the fragments. It was called when rebasing/filtering/expanding selection sets.
     /// JS PORT NOTE: In JS implementation this method was potentially returning `undefined`. In order to simplify the code
@@ -4416,8 +5715,21 @@ impl From<&NormalizedFragmentSpreadSelection> for FragmentSpread {
 impl TryFrom<NormalizedOperation> for Valid<executable::ExecutableDocument> {
     type Error = FederationError;
 
-    fn try_from(_value: NormalizedOperation) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: NormalizedOperation) -> Result<Self, Self::Error> {
+        let operation = Operation::try_from(&value)?;
+        let fragments = value
+            .named_fragments
+            .fragments
+            .iter()
+            .map(|(name, fragment)| Ok((name.clone(), Node::new(Fragment::try_from(&**fragment)?))))
+            .collect::<Result<IndexMap<_, _>, FederationError>>()?;
+
+        let mut document = executable::ExecutableDocument::new();
+        document.fragments = fragments;
+        document.insert_operation(operation);
+        // PORT_NOTE: Like the JS codebase, we don't validate generated subgraph operations by
+        // default, as they are built from already-validated selections.
+        Ok(Valid::assume_valid(document))
     }
 }
 
@@ -6470,4 +7782,164 @@ type T {
         }
         "###);
     }
+
+    fn optimize_operation(schema_and_operation: &str) -> String {
+        let (schema, executable_document) = parse_schema_and_operation(schema_and_operation);
+        let operation = executable_document.anonymous_operation.as_ref().unwrap();
+        let mut normalized_operation = normalize_operation(
+            operation,
+            &executable_document.fragments,
+            &schema,
+            &IndexSet::new(),
+        )
+        .unwrap();
+        let fragments = normalized_operation.named_fragments.clone();
+        normalized_operation
+            .optimize(Some(&fragments), None)
+            .unwrap();
+        let mut printed = normalized_operation.to_string();
+        for fragment in normalized_operation.named_fragments.fragments.values() {
+            printed.push_str(&format!("\n\n{fragment}"));
+        }
+        printed
+    }
+
+    const OPTIMIZE_SCHEMA: &str = r#"
+type Query {
+  t1: T
+  t2: T
+}
+
+type T {
+  a: Int
+  b: Int
+  c: Int
+  u: U
+}
+
+type U {
+  x: Int
+  y: Int
+}
+"#;
+
+    #[test]
+    fn optimize_reuses_fragments() {
+        let optimized = optimize_operation(&format!(
+            r#"{OPTIMIZE_SCHEMA}
+{{
+  t1 {{
+    ...F
+    c
+  }}
+  t2 {{
+    ...F
+  }}
+}}
+
+fragment F on T {{
+  a
+  b
+}}
+"#
+        ));
+        insta::assert_snapshot!(optimized, @r###"
+        {
+          t1 {
+            ...F
+            c
+          }
+          t2 {
+            ...F
+          }
+        }
+
+        fragment F on T {
+          a
+          b
+        }
+        "###);
+    }
+
+    #[test]
+    fn optimize_expands_fragments_used_once() {
+        let optimized = optimize_operation(&format!(
+            r#"{OPTIMIZE_SCHEMA}
+{{
+  t1 {{
+    ...F
+  }}
+  t2 {{
+    c
+  }}
+}}
+
+fragment F on T {{
+  a
+  b
+}}
+"#
+        ));
+        insta::assert_snapshot!(optimized, @r###"
+        {
+          t1 {
+            a
+            b
+          }
+          t2 {
+            c
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn optimize_keeps_nested_fragments_used_enough() {
+        let optimized = optimize_operation(&format!(
+            r#"{OPTIMIZE_SCHEMA}
+{{
+  t1 {{
+    ...Outer
+  }}
+  t2 {{
+    u {{
+      ...Inner
+    }}
+  }}
+}}
+
+fragment Outer on T {{
+  a
+  u {{
+    ...Inner
+  }}
+}}
+
+fragment Inner on U {{
+  x
+  y
+}}
+"#
+        ));
+        insta::assert_snapshot!(optimized, @r###"
+        {
+          t1 {
+            a
+            u {
+              ...Inner
+            }
+          }
+          t2 {
+            u {
+              ...Inner
+            }
+          }
+        }
+
+        fragment Inner on U {
+          x
+          y
+        }
+        "###);
+    }
 }
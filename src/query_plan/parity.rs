@@ -0,0 +1,383 @@
+//! Comparison of the plans of this query planner with the ones of the JS query planner.
+//!
+//! A plan fixture holds a supergraph (or the name of a file holding it, so that fixtures can share
+//! one), an operation and the plan that the JS query planner generates for them, as printed by its
+//! `prettyFormatQueryPlan()`. The plan generated by [`QueryPlanner`] is printed with its `Display`
+//! implementation, which uses the same format, and both plans are compared structurally so that
+//! differences in the order of parallel nodes and selections, and in whitespace, are ignored.
+use crate::error::FederationError;
+use crate::query_plan::query_planner::{QueryPlanner, QueryPlannerConfig};
+use crate::{ApiSchemaOptions, Supergraph};
+use apollo_compiler::ExecutableDocument;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+const SECTION_PREFIX: &str = "---";
+const SUPERGRAPH_SECTION: &str = "supergraph";
+const OPERATION_SECTION: &str = "operation";
+const PLAN_SECTION: &str = "plan";
+
+/// A query planning test case, along with the plan the JS query planner generates for it.
+///
+/// In fixture files, each part is in a section starting with a `--- <section>` line, where the
+/// section is `supergraph`, `operation` or `plan`. Lines before the first section are ignored,
+/// which can be used to describe the fixture. Instead of holding the supergraph, the `supergraph`
+/// section can name the file holding it, as in `--- supergraph test_supergraph.graphql`, in which
+/// case the file is read relatively to the fixture by [`PlanFixture::read`].
+#[derive(Debug, Clone)]
+pub struct PlanFixture {
+    pub name: String,
+    pub supergraph: String,
+    pub operation: String,
+    pub expected_plan: String,
+}
+
+impl PlanFixture {
+    /// Reads the fixture file at the given path, naming the fixture after the file, along with the
+    /// supergraph file it references, if any.
+    pub fn read(path: &Path) -> Result<Self, FederationError> {
+        let read_to_string = |path: &Path| {
+            fs::read_to_string(path).map_err(|error| {
+                FederationError::internal(format!("Could not read \"{}\": {error}", path.display()))
+            })
+        };
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self::parse_with_supergraph_files(name, &read_to_string(path)?, |file| {
+            read_to_string(&path.with_file_name(file))
+        })
+    }
+
+    /// Parses a fixture whose supergraph is inline.
+    pub fn parse(name: impl Into<String>, source: &str) -> Result<Self, FederationError> {
+        let name = name.into();
+        let supergraph_file_error = FederationError::internal(format!(
+            "The supergraph of plan fixture \"{name}\" must be inline"
+        ));
+        Self::parse_with_supergraph_files(name, source, |_| Err(supergraph_file_error))
+    }
+
+    fn parse_with_supergraph_files(
+        name: String,
+        source: &str,
+        read_supergraph_file: impl FnOnce(&str) -> Result<String, FederationError>,
+    ) -> Result<Self, FederationError> {
+        let mut supergraph_file = None;
+        let mut sections: [(&str, Option<String>); 3] = [
+            (SUPERGRAPH_SECTION, None),
+            (OPERATION_SECTION, None),
+            (PLAN_SECTION, None),
+        ];
+        let mut current_section = None;
+        for line in source.lines() {
+            if let Some(section) = line.strip_prefix(SECTION_PREFIX) {
+                let (section, file) = match section.trim().split_once(char::is_whitespace) {
+                    Some((section, file)) => (section, Some(file.trim())),
+                    None => (section.trim(), None),
+                };
+                let index = sections
+                    .iter()
+                    .position(|(section_name, _)| *section_name == section)
+                    .ok_or_else(|| {
+                        FederationError::internal(format!(
+                            "Unknown section \"{section}\" in plan fixture \"{name}\""
+                        ))
+                    })?;
+                if sections[index].1.replace(String::new()).is_some() {
+                    return Err(FederationError::internal(format!(
+                        "Duplicate section \"{section}\" in plan fixture \"{name}\""
+                    )));
+                }
+                current_section = Some(index);
+                if let Some(file) = file {
+                    if section != SUPERGRAPH_SECTION {
+                        return Err(FederationError::internal(format!(
+                            "Section \"{section}\" of plan fixture \"{name}\" cannot name a file"
+                        )));
+                    }
+                    supergraph_file = Some(file.to_owned());
+                    current_section = None;
+                }
+            } else if let Some(index) = current_section {
+                let content = sections[index].1.get_or_insert_with(String::new);
+                content.push_str(line);
+                content.push('\n');
+            }
+        }
+        let [supergraph, operation, expected_plan] = sections.map(|(section, content)| {
+            content.ok_or_else(|| {
+                FederationError::internal(format!(
+                    "Missing section \"{section}\" in plan fixture \"{name}\""
+                ))
+            })
+        });
+        let supergraph = match supergraph_file {
+            Some(file) => read_supergraph_file(&file)?,
+            None => supergraph?,
+        };
+        Ok(Self {
+            supergraph,
+            operation: operation?,
+            expected_plan: expected_plan?,
+            name,
+        })
+    }
+
+    /// Plans the operation of this fixture, and returns the differences between the generated plan
+    /// and the expected one.
+    pub fn run(&self, config: QueryPlannerConfig) -> Result<Vec<PlanDifference>, FederationError> {
+        let supergraph = Supergraph::new(&self.supergraph)?;
+        let api_schema = supergraph.to_api_schema(ApiSchemaOptions {
+            include_defer: true,
+            include_stream: false,
+        })?;
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            self.operation.as_str(),
+            "operation.graphql",
+        )?;
+        let planner = QueryPlanner::new(&supergraph, config)?;
+        let plan = planner.build_query_plan(&document, None, Default::default())?;
+        Ok(diff_query_plans(&self.expected_plan, &plan.to_string()))
+    }
+}
+
+/// A difference between an expected query plan and an actual one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanDifference {
+    /// The headers of the blocks (e.g. `Sequence` or `Fetch(service: "products")`) enclosing the
+    /// difference.
+    pub path: Vec<String>,
+    /// The expected part of the plan, if any.
+    pub expected: Option<String>,
+    /// The actual part of the plan, if any.
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for PlanDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            writeln!(f, "At the top of the plan:")?;
+        } else {
+            writeln!(f, "In {}:", self.path.join(" > "))?;
+        }
+        for (prefix, part) in [("-", &self.expected), ("+", &self.actual)] {
+            for line in part.iter().flat_map(|part| part.lines()) {
+                writeln!(f, "{prefix} {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares two query plans printed in the format of the JS `prettyFormatQueryPlan()`, ignoring
+/// whitespace as well as the order of the nodes of `Parallel` blocks and of selections.
+pub fn diff_query_plans(expected: &str, actual: &str) -> Vec<PlanDifference> {
+    let expected = PlanTree::parse(expected).normalized(false);
+    let actual = PlanTree::parse(actual).normalized(false);
+    let mut differences = Vec::new();
+    expected.diff_children(&actual, &mut Vec::new(), &mut differences);
+    differences
+}
+
+/// A printed query plan, as a tree of the lines of its blocks.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct PlanTree {
+    /// The line opening the block (e.g. `Sequence {`), or the line itself for leaves.
+    header: String,
+    children: Vec<PlanTree>,
+    /// What follows the closing bracket of the block, e.g. the `=>` after the `requires` of a fetch.
+    footer: String,
+    /// Whether the order of the children is insignificant, in which case they are sorted.
+    unordered_children: bool,
+}
+
+impl PlanTree {
+    fn new(header: impl Into<String>) -> Self {
+        Self {
+            header: header.into(),
+            children: Vec::new(),
+            footer: String::new(),
+            unordered_children: false,
+        }
+    }
+
+    fn opens_block(line: &str) -> bool {
+        line.ends_with('{') || line.ends_with('[')
+    }
+
+    /// Parses the lines of a printed plan into a tree whose root contains the top-level lines.
+    fn parse(plan: &str) -> Self {
+        fn close_block(stack: &mut Vec<PlanTree>) -> Option<&mut PlanTree> {
+            if stack.len() < 2 {
+                return None;
+            }
+            let closed = stack.pop()?;
+            let parent = stack.last_mut()?;
+            parent.children.push(closed);
+            parent.children.last_mut()
+        }
+
+        let mut stack = vec![Self::new("")];
+        for line in plan.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let mut line = line;
+            // A closing bracket ends the current block, and can be followed by a footer and by the
+            // header of a new block, like in `} Else {` or `} => {`.
+            if let Some(rest) = line.strip_prefix(['}', ']']) {
+                let header = rest.trim_start_matches([',', ':', '=', '>', ' ']);
+                let (footer, header) = if Self::opens_block(header) {
+                    (&rest[..rest.len() - header.len()], header)
+                } else {
+                    (rest, "")
+                };
+                if let Some(closed) = close_block(&mut stack) {
+                    // Commas between blocks are insignificant.
+                    closed.footer = footer.replace(',', "").trim().to_owned();
+                }
+                line = header;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            if Self::opens_block(line) {
+                stack.push(Self::new(line));
+            } else if let Some(block) = stack.last_mut() {
+                block.children.push(Self::new(line));
+            }
+        }
+        // Close the blocks of truncated plans.
+        while close_block(&mut stack).is_some() {}
+        stack.pop().unwrap_or_else(|| Self::new(""))
+    }
+
+    /// Sorts the children of the blocks whose order is insignificant: the nodes of `Parallel`
+    /// blocks, and the selections of the GraphQL parts of the plan (fetch operations and `@defer`
+    /// sub-selections), which are in `Fetch` blocks or in blocks without headers.
+    fn normalized(mut self, in_selections: bool) -> Self {
+        if self.header.starts_with("Flatten(") {
+            // PORT_NOTE: The JS codebase prints `@` for the array elements of flatten paths, while
+            // the `Display` implementation of `FetchDataPathElement` prints `*`.
+            self.header = self.header.replace('@', "*");
+        }
+        let in_selections =
+            in_selections || self.header == "{" || self.header.starts_with("Fetch(");
+        self.children = self
+            .children
+            .into_iter()
+            .map(|child| child.normalized(in_selections))
+            .collect();
+        self.unordered_children = in_selections || self.header.starts_with("Parallel");
+        if self.unordered_children {
+            self.children.sort();
+        }
+        self
+    }
+
+    /// The header without its opening bracket, except for blocks without other header.
+    fn path_segment(&self) -> String {
+        match self.header.trim_end_matches(['{', '[']).trim_end() {
+            "" => self.header.clone(),
+            segment => segment.to_owned(),
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut rendered = String::new();
+        self.render_into(&mut rendered, 0);
+        rendered
+    }
+
+    fn render_into(&self, rendered: &mut String, indent_level: usize) {
+        let indent = "  ".repeat(indent_level);
+        rendered.push_str(&indent);
+        rendered.push_str(&self.header);
+        rendered.push('\n');
+        for child in &self.children {
+            child.render_into(rendered, indent_level + 1);
+        }
+        if Self::opens_block(&self.header) {
+            rendered.push_str(&indent);
+            rendered.push(if self.header.ends_with('[') { ']' } else { '}' });
+            if !self.footer.is_empty() {
+                if !self.footer.starts_with(':') {
+                    rendered.push(' ');
+                }
+                rendered.push_str(&self.footer);
+            }
+            rendered.push('\n');
+        }
+    }
+
+    fn difference(
+        path: &[String],
+        expected: Option<&Self>,
+        actual: Option<&Self>,
+    ) -> PlanDifference {
+        PlanDifference {
+            path: path.to_vec(),
+            expected: expected.map(Self::render),
+            actual: actual.map(Self::render),
+        }
+    }
+
+    fn diff(&self, actual: &Self, path: &mut Vec<String>, differences: &mut Vec<PlanDifference>) {
+        if self == actual {
+            return;
+        }
+        if self.header != actual.header || self.footer != actual.footer {
+            differences.push(Self::difference(path, Some(self), Some(actual)));
+            return;
+        }
+        path.push(self.path_segment());
+        self.diff_children(actual, path, differences);
+        path.pop();
+    }
+
+    fn diff_children(
+        &self,
+        actual: &Self,
+        path: &mut Vec<String>,
+        differences: &mut Vec<PlanDifference>,
+    ) {
+        if !self.unordered_children {
+            for index in 0..self.children.len().max(actual.children.len()) {
+                match (self.children.get(index), actual.children.get(index)) {
+                    (Some(expected_child), Some(actual_child)) => {
+                        expected_child.diff(actual_child, path, differences)
+                    }
+                    (expected_child, actual_child) => {
+                        differences.push(Self::difference(path, expected_child, actual_child))
+                    }
+                }
+            }
+            return;
+        }
+        // Identical children match each other, and the remaining ones are compared to a child with
+        // the same header, if any.
+        let mut unexpected: Vec<&Self> = actual.children.iter().collect();
+        let mut missing = Vec::new();
+        for expected_child in &self.children {
+            match unexpected.iter().position(|child| *child == expected_child) {
+                Some(index) => {
+                    unexpected.remove(index);
+                }
+                None => missing.push(expected_child),
+            }
+        }
+        for expected_child in missing {
+            match unexpected
+                .iter()
+                .position(|child| child.header == expected_child.header)
+            {
+                Some(index) => expected_child.diff(unexpected.remove(index), path, differences),
+                None => differences.push(Self::difference(path, Some(expected_child), None)),
+            }
+        }
+        for actual_child in unexpected {
+            differences.push(Self::difference(path, None, Some(actual_child)));
+        }
+    }
+}
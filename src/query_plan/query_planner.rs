@@ -5,17 +5,20 @@ use crate::link::federation_spec_definition::FEDERATION_INTERFACEOBJECT_DIRECTIV
 use crate::link::spec::Identity;
 use crate::query_graph::build_federated_query_graph;
 use crate::query_graph::QueryGraph;
+use crate::query_plan::fetch_dependency_graph::compute_root_fetch_nodes;
 use crate::query_plan::fetch_dependency_graph::FetchDependencyGraph;
 use crate::query_plan::fetch_dependency_graph_processor::FetchDependencyGraphProcessor;
 use crate::query_plan::fetch_dependency_graph_processor::FetchDependencyGraphToCostProcessor;
 use crate::query_plan::fetch_dependency_graph_processor::FetchDependencyGraphToQueryPlanProcessor;
 use crate::query_plan::operation::normalize_operation;
 use crate::query_plan::operation::NormalizedDefer;
+use crate::query_plan::operation::NormalizedOperation;
 use crate::query_plan::operation::NormalizedSelectionSet;
 use crate::query_plan::operation::RebasedFragments;
 use crate::query_plan::query_planning_traversal::BestQueryPlanInfo;
 use crate::query_plan::query_planning_traversal::QueryPlanningParameters;
 use crate::query_plan::query_planning_traversal::QueryPlanningTraversal;
+use crate::query_plan::ConditionNode;
 use crate::query_plan::FetchNode;
 use crate::query_plan::PlanNode;
 use crate::query_plan::QueryPlan;
@@ -36,8 +39,12 @@ use apollo_compiler::ExecutableDocument;
 use apollo_compiler::NodeStr;
 use indexmap::IndexMap;
 use indexmap::IndexSet;
+use std::cell::RefCell;
 use std::num::NonZeroU32;
+use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 #[derive(Debug, Clone)]
 pub struct QueryPlannerConfig {
@@ -148,10 +155,51 @@ impl Default for QueryPlannerDebugConfig {
     }
 }
 
-// PORT_NOTE: renamed from PlanningStatistics in the JS codebase.
+/// Options for planning a single operation.
+// PORT_NOTE: Named `PlanningOptions` in the JS codebase.
+#[derive(Debug, Clone, Default)]
+pub struct QueryPlanOptions {
+    /// The labels of the progressive @override conditions (i.e. the `label` arguments of
+    /// `@override` applications) that are enabled for this operation. For a field overridden with
+    /// an enabled label, the plan fetches the field from the overriding subgraph, and otherwise it
+    /// fetches it from the subgraph being overridden.
+    ///
+    /// Typically, the router decides which labels are enabled for a given request (e.g. for
+    /// percentage-based labels like `percent(10)`, it enables the label for roughly 10% of
+    /// requests).
+    pub override_conditions: Vec<String>,
+}
+
+/// Statistics about the planning of an operation, e.g. to monitor the operations that are costly
+/// to plan.
+// PORT_NOTE: renamed from PlanningStatistics in the JS codebase, which only has the number of
+// evaluated plans.
 #[derive(Debug, Default, Clone)]
-pub(crate) struct QueryPlanningStatistics {
-    pub(crate) evaluated_plan_count: usize,
+pub struct QueryPlanningStatistics {
+    /// The number of complete plans whose cost was computed to pick the best one.
+    pub evaluated_plan_count: usize,
+    /// The number of branches (i.e. the options for a leaf field of the operation) closed by the
+    /// planning traversals, including the ones resolving conditions like `@key`s and `@requires`.
+    pub closed_branch_count: usize,
+    /// The number of options of closed branches that were discarded before evaluating plans,
+    /// either because another option is known to be better, or to stay under
+    /// `debug.max_evaluated_plans`.
+    pub pruned_option_count: usize,
+    /// Whether the number of possible plans exceeded `debug.max_evaluated_plans`, in which case
+    /// the returned plan may not be the best one.
+    pub max_evaluated_plans_reached: bool,
+    /// The time spent normalizing the operation (including the handling of `@defer`), if it got
+    /// normalized.
+    pub normalization_duration: Option<Duration>,
+    /// The time spent by the planning traversals finding the best plans, if any ran.
+    pub traversal_duration: Option<Duration>,
+    /// The time spent processing the fetch dependency graphs of the best plans into plan nodes, if
+    /// any got processed.
+    pub processing_duration: Option<Duration>,
+    /// Whether an option of a path of the operation exceeded `debug.paths_limit`, in which case
+    /// planning fails with a [`SingleFederationError::PathsLimitExceeded`] error (see
+    /// [`QueryPlanner::build_query_plan_with_statistics`] to get these statistics along with it).
+    pub paths_limit_reached: bool,
 }
 
 impl QueryPlannerConfig {
@@ -163,6 +211,14 @@ impl QueryPlannerConfig {
     }
 }
 
+/// The error of a failed planning, along with the statistics of the planning up to the failure.
+#[derive(Debug, thiserror::Error)]
+#[error("{error}")]
+pub struct QueryPlanningFailure {
+    pub error: FederationError,
+    pub statistics: Box<QueryPlanningStatistics>,
+}
+
 pub struct QueryPlanner {
     config: QueryPlannerConfig,
     federated_query_graph: Arc<QueryGraph>,
@@ -270,8 +326,9 @@ impl QueryPlanner {
 
         // PORT_NOTE: JS prepares a map of override conditions here, which is
         // a map where the keys are all `@join__field(overrideLabel:)` argument values
-        // and the values are all initialised to `false`. Instead of doing that, we should
-        // be able to use a Set where presence means `true` and absence means `false`.
+        // and the values are all initialised to `false`. Instead of doing that, we
+        // use a Set where presence means `true` and absence means `false` (see
+        // `EnabledOverrideConditions`), which is built from `QueryPlanOptions` for each plan.
 
         Ok(Self {
             config,
@@ -290,11 +347,55 @@ impl QueryPlanner {
         &self.federated_query_graph.sources
     }
 
+    /// Reads a query plan serialized in the JSON format of the JS query planner, like the one
+    /// produced by serializing a [`QueryPlan`]. The GraphQL parts of the plan are parsed against
+    /// the schemas of this planner.
+    #[cfg(feature = "serde")]
+    pub fn deserialize_query_plan<'de, D: serde::Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<QueryPlan, D::Error> {
+        super::json::deserialize_query_plan(
+            &self.supergraph_schema,
+            self.subgraph_schemas(),
+            deserializer,
+        )
+    }
+
     // PORT_NOTE: this receives an `Operation` object in JS which is a concept that doesn't exist in apollo-rs.
     pub fn build_query_plan(
         &self,
         document: &Valid<ExecutableDocument>,
         operation_name: Option<Name>,
+        options: QueryPlanOptions,
+    ) -> Result<QueryPlan, FederationError> {
+        self.build_query_plan_with_statistics(document, operation_name, options)
+            .map_err(|failure| failure.error)
+    }
+
+    /// Same as [`QueryPlanner::build_query_plan`], but also returns the statistics of the planning
+    /// when it fails, e.g. to monitor the operations reaching `debug.paths_limit`. The statistics
+    /// of successful plannings are in [`QueryPlan::statistics`].
+    pub fn build_query_plan_with_statistics(
+        &self,
+        document: &Valid<ExecutableDocument>,
+        operation_name: Option<Name>,
+        options: QueryPlanOptions,
+    ) -> Result<QueryPlan, QueryPlanningFailure> {
+        let statistics = Rc::new(RefCell::new(QueryPlanningStatistics::default()));
+        self.plan(document, operation_name, options, &statistics)
+            .map_err(|error| QueryPlanningFailure {
+                error,
+                statistics: Box::new(statistics.take()),
+            })
+    }
+
+    fn plan(
+        &self,
+        document: &Valid<ExecutableDocument>,
+        operation_name: Option<Name>,
+        options: QueryPlanOptions,
+        statistics: &Rc<RefCell<QueryPlanningStatistics>>,
     ) -> Result<QueryPlan, FederationError> {
         let operation = document
             .get_operation(operation_name.as_ref().map(|name| name.as_str()))
@@ -311,10 +412,6 @@ impl QueryPlanner {
 
         let is_subscription = operation.is_subscription();
 
-        let statistics = QueryPlanningStatistics {
-            evaluated_plan_count: 0,
-        };
-
         if self.config.debug.bypass_planner_for_single_subgraph {
             // A federated query graph always have 1 more sources than there is subgraph, because the root vertices
             // belong to no subgraphs and use a special source named '_'. So we skip that "fake" source.
@@ -341,21 +438,11 @@ impl QueryPlanner {
                     output_rewrites: Default::default(),
                 };
 
-                return Ok(QueryPlan::new(node, statistics));
+                return Ok(QueryPlan::new(node, Default::default()));
             }
         }
 
-        let reuse_query_fragments = self.config.reuse_query_fragments;
-        if reuse_query_fragments && !document.fragments.is_empty() {
-            // For all subgraph fetches we query `__typename` on every abstract types (see `FetchDependencyGraphNode::to_plan_node`)
-            // so if we want to have a chance to reuse fragments, we should make sure those fragments also query `__typename` for
-            // every abstract type.
-            //
-            // TODO: FED-165
-            //
-            // JS: fragments = addTypenameFieldForAbstractTypesInNamedFragments(fragments);
-        }
-
+        let normalization_start = Instant::now();
         let normalized_operation = normalize_operation(
             operation,
             &document.fragments,
@@ -370,7 +457,7 @@ impl QueryPlanner {
                     assigned_defer_labels,
                     defer_conditions,
                     has_defers,
-                } = normalized_operation.with_normalized_defer();
+                } = normalized_operation.with_normalized_defer()?;
                 if has_defers && is_subscription {
                     return Err(SingleFederationError::DeferredSubscriptionUnsupported.into());
                 }
@@ -384,11 +471,15 @@ impl QueryPlanner {
                 // If defer is not enabled, we remove all @defer from the query. This feels cleaner do this once here than
                 // having to guard all the code dealing with defer later, and is probably less error prone too (less likely
                 // to end up passing through a @defer to a subgraph by mistake).
-                (normalized_operation.without_defer(), None, None, false)
+                (normalized_operation.without_defer(None)?, None, None, false)
             };
+        statistics.borrow_mut().normalization_duration = Some(normalization_start.elapsed());
 
         if normalized_operation.selection_set.selections.is_empty() {
-            return Ok(QueryPlan::default());
+            return Ok(QueryPlan {
+                node: None,
+                statistics: statistics.take(),
+            });
         }
 
         let Some(root) = self
@@ -402,11 +493,26 @@ impl QueryPlanner {
             );
         };
 
+        let fragments = if self.config.reuse_query_fragments
+            && !normalized_operation.named_fragments.is_empty()
+        {
+            // For all subgraph fetches we query `__typename` on every abstract types (see `FetchDependencyGraphNode::to_plan_node`)
+            // so if we want to have a chance to reuse fragments, we should make sure those fragments also query `__typename` for
+            // every abstract type.
+            Some(RebasedFragments::new(
+                &normalized_operation
+                    .named_fragments
+                    .add_typename_field_for_abstract_types_in_named_fragments()?,
+            ))
+        } else {
+            None
+        };
         let processor = FetchDependencyGraphToQueryPlanProcessor::new(
             operation.variables.clone(),
-            Some(RebasedFragments::new(&normalized_operation.named_fragments)),
+            fragments,
             operation_name.clone(),
             assigned_defer_labels,
+            self.config.subgraph_graphql_validation,
         );
         let mut parameters = QueryPlanningParameters {
             supergraph_schema: self.supergraph_schema.clone(),
@@ -417,13 +523,13 @@ impl QueryPlanner {
             // PORT_NOTE(@goto-bus-stop): In JS, `root` is a `RootVertex`, which is dynamically
             // checked at various points in query planning. This is our Rust equivalent of that.
             head_must_be_root: true,
-            statistics,
+            statistics: statistics.clone(),
             abstract_types_with_inconsistent_runtime_types: self
                 .abstract_types_with_inconsistent_runtime_types
                 .clone()
                 .into(),
             config: self.config.clone(),
-            // PORT_NOTE: JS provides `override_conditions` here: see port note in `QueryPlanner::new`.
+            override_conditions: Arc::new(options.override_conditions.into_iter().collect()),
         };
 
         let root_node = match defer_conditions {
@@ -476,16 +582,103 @@ impl QueryPlanner {
 
         Ok(QueryPlan {
             node: root_node,
-            statistics: parameters.statistics,
+            statistics: parameters.statistics.take(),
         })
     }
 }
 
 fn compute_root_serial_dependency_graph(
-    _parameters: &QueryPlanningParameters,
-    _has_defers: bool,
+    parameters: &QueryPlanningParameters,
+    has_defers: bool,
 ) -> Result<Vec<FetchDependencyGraph>, FederationError> {
-    todo!("FED-127")
+    let QueryPlanningParameters {
+        supergraph_schema,
+        federated_query_graph,
+        operation,
+        ..
+    } = parameters;
+    let root_type = if has_defers {
+        supergraph_schema
+            .schema()
+            .root_operation(operation.root_kind.into())
+            .cloned()
+            // A root operation type has to be an object type
+            .map(|type_name| ObjectTypeDefinitionPosition { type_name }.into())
+    } else {
+        None
+    };
+    // We have to serially compute a plan for each top-level selection.
+    let mut split_roots = operation
+        .selection_set
+        .split_top_level_fields()?
+        .into_iter();
+    let mut digest = Vec::new();
+    let selection_set = split_roots
+        .next()
+        .ok_or_else(|| FederationError::internal("Empty top level fields"))?;
+    let BestQueryPlanInfo {
+        mut fetch_dependency_graph,
+        path_tree: mut prev_path,
+        ..
+    } = compute_root_parallel_best_plan(parameters, selection_set, 0, has_defers)?;
+    let mut prev_subgraph = only_root_subgraph(&fetch_dependency_graph)?;
+    let mut starting_fetch_id = 0;
+    for selection_set in split_roots {
+        let BestQueryPlanInfo {
+            fetch_dependency_graph: new_dep_graph,
+            path_tree: new_path,
+            ..
+        } = compute_root_parallel_best_plan(
+            parameters,
+            selection_set,
+            fetch_dependency_graph.next_fetch_id(),
+            has_defers,
+        )?;
+        let new_subgraph = only_root_subgraph(&new_dep_graph)?;
+        if new_subgraph == prev_subgraph {
+            // The new operation (think 'mutation' operation) is on the same subgraph than the previous one, so we can concatenate them in a single fetch
+            // and rely on the subgraph to enforce seriability. Do note that we need to `concat()` and not `merge()` because if we have
+            // mutation Mut {
+            //    mut1 {...}
+            //    mut2 {...}
+            //    mut1 {...}
+            // }
+            // then we should _not_ merge the 2 `mut1` fields (contrarily to what happens on queried fields).
+            prev_path = prev_path.concat(&new_path);
+            fetch_dependency_graph = FetchDependencyGraph::new(
+                supergraph_schema.clone(),
+                federated_query_graph.clone(),
+                root_type.clone(),
+                starting_fetch_id,
+            );
+            compute_root_fetch_nodes(operation.root_kind, &mut fetch_dependency_graph, &prev_path)?;
+        } else {
+            starting_fetch_id = fetch_dependency_graph.next_fetch_id();
+            digest.push(std::mem::replace(
+                &mut fetch_dependency_graph,
+                new_dep_graph,
+            ));
+            prev_path = new_path;
+            prev_subgraph = new_subgraph;
+        }
+    }
+    digest.push(fetch_dependency_graph);
+    Ok(digest)
+}
+
+fn only_root_subgraph(graph: &FetchDependencyGraph) -> Result<NodeStr, FederationError> {
+    let mut iter = graph.root_subgraphs();
+    let (Some(subgraph), None) = (iter.next(), iter.next()) else {
+        return Err(FederationError::internal(format!(
+            "{graph} should have only one root, but has [{}]",
+            graph
+                .root_subgraphs()
+                .map(|subgraph| subgraph.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )));
+    };
+    Ok(subgraph.clone())
 }
 
 fn compute_root_parallel_dependency_graph(
@@ -493,28 +686,37 @@ fn compute_root_parallel_dependency_graph(
     has_defers: bool,
 ) -> Result<FetchDependencyGraph, FederationError> {
     let selection_set = parameters.operation.selection_set.clone();
-    let best_plan = compute_root_parallel_best_plan(parameters, selection_set, has_defers)?;
+    let best_plan = compute_root_parallel_best_plan(parameters, selection_set, 0, has_defers)?;
     Ok(best_plan.fetch_dependency_graph)
 }
 
 fn compute_root_parallel_best_plan(
     parameters: &QueryPlanningParameters,
     selection: NormalizedSelectionSet,
+    starting_fetch_id: u64,
     has_defers: bool,
 ) -> Result<BestQueryPlanInfo, FederationError> {
-    let planning_traversal = QueryPlanningTraversal::new(
+    let traversal_start = Instant::now();
+    let best_plan = QueryPlanningTraversal::new(
         parameters,
         selection,
+        starting_fetch_id,
         has_defers,
         parameters.operation.root_kind,
         FetchDependencyGraphToCostProcessor,
-    )?;
+    )
+    .and_then(|planning_traversal| planning_traversal.find_best_plan());
+    // Recorded before bailing out so that the statistics of failed plannings are complete too.
+    *parameters
+        .statistics
+        .borrow_mut()
+        .traversal_duration
+        .get_or_insert(Duration::ZERO) += traversal_start.elapsed();
+    let best_plan = best_plan?;
 
     // Getting no plan means the query is essentially unsatisfiable (it's a valid query, but we can prove it will never return a result),
     // so we just return an empty plan.
-    Ok(planning_traversal
-        .find_best_plan()?
-        .unwrap_or_else(|| BestQueryPlanInfo::empty(parameters)))
+    Ok(best_plan.unwrap_or_else(|| BestQueryPlanInfo::empty(parameters)))
 }
 
 fn compute_plan_internal(
@@ -529,8 +731,14 @@ fn compute_plan_internal(
         let mut deferred = vec![];
         let mut primary_selection = None::<NormalizedSelectionSet>;
         for mut dependency_graph in dependency_graphs {
+            let processing_start = Instant::now();
             let (local_main, local_deferred) =
                 dependency_graph.process(&mut parameters.processor, root_kind)?;
+            *parameters
+                .statistics
+                .borrow_mut()
+                .processing_duration
+                .get_or_insert(Duration::ZERO) += processing_start.elapsed();
             main = match main {
                 Some(unlocal_main) => parameters
                     .processor
@@ -548,7 +756,13 @@ fn compute_plan_internal(
     } else {
         let mut dependency_graph = compute_root_parallel_dependency_graph(parameters, has_defers)?;
 
+        let processing_start = Instant::now();
         let (main, deferred) = dependency_graph.process(&mut parameters.processor, root_kind)?;
+        *parameters
+            .statistics
+            .borrow_mut()
+            .processing_duration
+            .get_or_insert(Duration::ZERO) += processing_start.elapsed();
         // XXX(@goto-bus-stop) Maybe `.defer_tracking` should be on the return value of `process()`..?
         let primary_selection = dependency_graph.defer_tracking.primary_selection;
 
@@ -568,15 +782,57 @@ fn compute_plan_internal(
 }
 
 fn compute_plan_for_defer_conditionals(
-    _parameters: &mut QueryPlanningParameters,
-    _defer_conditions: IndexMap<String, IndexSet<String>>,
+    parameters: &mut QueryPlanningParameters,
+    defer_conditions: IndexMap<String, IndexSet<String>>,
+) -> Result<Option<PlanNode>, FederationError> {
+    generate_condition_nodes(
+        parameters.operation.clone(),
+        defer_conditions.iter(),
+        &mut |op| {
+            parameters.operation = op;
+            compute_plan_internal(parameters, true)
+        },
+    )
+}
+
+fn generate_condition_nodes<'a>(
+    op: Arc<NormalizedOperation>,
+    mut conditions: impl Clone + Iterator<Item = (&'a String, &'a IndexSet<String>)>,
+    on_final_operation: &mut impl FnMut(
+        Arc<NormalizedOperation>,
+    ) -> Result<Option<PlanNode>, FederationError>,
 ) -> Result<Option<PlanNode>, FederationError> {
-    todo!("FED-95")
+    match conditions.next() {
+        None => on_final_operation(op),
+        Some((cond, labels)) => {
+            let else_op = Arc::new(op.as_ref().clone().without_defer(Some(labels))?);
+            let if_op = op;
+            // Note that we're not 100% sure that the if/else branches will be different. For
+            // instance, if the @defer is not in the query plan (because it was on a field for
+            // which we have no plan), then the if/else would be the same. But that's ok, it'll
+            // just be a bit more verbose.
+            let node = ConditionNode {
+                condition_variable: Name::new(cond)?,
+                if_clause: generate_condition_nodes(if_op, conditions.clone(), on_final_operation)?
+                    .map(Box::new),
+                else_clause: generate_condition_nodes(
+                    else_op,
+                    conditions.clone(),
+                    on_final_operation,
+                )?
+                .map(Box::new),
+            };
+            Ok(Some(PlanNode::Condition(Box::new(node))))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::query_graph::extract_subgraphs_from_supergraph::FED_1_SUPERGRAPH;
     use crate::subgraph::Subgraph;
+    use apollo_compiler::ast::InputValueDefinition;
+    use apollo_compiler::{name, ty, Node};
 
     use super::*;
 
@@ -719,7 +975,6 @@ type User
     "#;
 
     #[test]
-    #[allow(unused)] // remove when build_query_plan() can run without panicking
     fn it_does_not_crash() {
         let supergraph = Supergraph::new(TEST_SUPERGRAPH).unwrap();
         let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
@@ -738,60 +993,1228 @@ type User
             "operation.graphql",
         )
         .unwrap();
-        // let plan = planner.build_query_plan(&document, None).unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Fetch(service: "accounts") {
+            {
+                    userById(id: 1) {
+                name
+                email
+              }
+            }
+          }
+        }
+        "###);
     }
 
     #[test]
-    fn bypass_planner_for_single_subgraph() {
-        let a = Subgraph::parse_and_expand(
-            "A",
-            "https://A",
+    fn plan_requires_after_root_fetch() {
+        let supergraph = Supergraph::new(TEST_SUPERGRAPH).unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            {
+                productById(id: 1) {
+                    ... on Book {
+                        avg_rating
+                    }
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Sequence {
+            Fetch(service: "products") {
+              {
+                        productById(id: 1) {
+                  ... on Book {
+                    id
+                    __typename
+                  }
+                }
+              }
+            }
+            Flatten(path: "productById") {
+              Fetch(service: "reviews") {
+                {
+                            ... on Book {
+                    __typename
+                    id
+                  }
+                } => {
+                            ... on Book {
+                    reviews {
+                      rating
+                    }
+                  }
+                }
+              }
+            }
+            Flatten(path: "productById") {
+              Fetch(service: "products") {
+                {
+                            ... on Book {
+                    __typename
+                    id
+                    reviews {
+                      rating
+                    }
+                  }
+                } => {
+                            ... on Book {
+                    avg_rating
+                  }
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    /// A supergraph with `@requires` on both a field of an entity type reached from the root and a
+    /// field of an entity type reached through another entity.
+    fn requires_supergraph() -> Supergraph {
+        let books = Subgraph::parse_and_expand(
+            "books",
+            "https://books",
             r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
             type Query {
-                a: A
+                books: [Book]
+                authors: [Author]
             }
-            type A {
-                b: B
+
+            type Book @key(fields: "id") {
+                id: ID!
+                title: String
             }
-            type B {
-                x: Int
-                y: String
+
+            type Author @key(fields: "id") {
+                id: ID!
             }
-        "#,
+            "#,
         )
         .unwrap();
-        let subgraphs = vec![&a];
-        let supergraph = Supergraph::compose(subgraphs).unwrap();
+        let reviews = Subgraph::parse_and_expand(
+            "reviews",
+            "https://reviews",
+            r#"
+            extend schema
+                @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@external", "@requires"])
+
+            type Book @key(fields: "id") {
+                id: ID!
+                author: Author
+            }
+
+            type Author @key(fields: "id") {
+                id: ID!
+                name: String @external
+                greeting: String @requires(fields: "name")
+            }
+            "#,
+        )
+        .unwrap();
+        let authors = Subgraph::parse_and_expand(
+            "authors",
+            "https://authors",
+            r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
+            type Author @key(fields: "id") {
+                id: ID!
+                name: String
+            }
+            "#,
+        )
+        .unwrap();
+        Supergraph::compose(vec![&books, &reviews, &authors]).unwrap()
+    }
+
+    #[test]
+    fn plan_requires_of_entity_without_useless_fetches() {
+        let supergraph = requires_supergraph();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            {
+                authors {
+                    greeting
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Sequence {
+            Fetch(service: "books") {
+              {
+                        authors {
+                  id
+                  __typename
+                }
+              }
+            }
+            Flatten(path: "authors.*") {
+              Fetch(service: "authors") {
+                {
+                            ... on Author {
+                    __typename
+                    id
+                  }
+                } => {
+                            ... on Author {
+                    name
+                  }
+                }
+              }
+            }
+            Flatten(path: "authors.*") {
+              Fetch(service: "reviews") {
+                {
+                            ... on Author {
+                    __typename
+                    id
+                    name
+                  }
+                } => {
+                            ... on Author {
+                    greeting
+                  }
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn plan_requires_nested_in_entity_fetch() {
+        let supergraph = requires_supergraph();
         let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
 
         let document = ExecutableDocument::parse_and_validate(
             api_schema.schema(),
             r#"
             {
-                a {
-                    b {
-                        x
-                        y
+                books {
+                    author {
+                        greeting
                     }
                 }
             }
             "#,
-            "",
+            "operation.graphql",
         )
         .unwrap();
-
-        let mut config = QueryPlannerConfig::default();
-        config.debug.bypass_planner_for_single_subgraph = true;
-        let planner = QueryPlanner::new(&supergraph, config).unwrap();
-        let plan = planner.build_query_plan(&document, None).unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
         insta::assert_snapshot!(plan, @r###"
         QueryPlan {
-          Fetch(service: "A") {
+          Sequence {
+            Fetch(service: "books") {
+              {
+                        books {
+                  id
+                  __typename
+                }
+              }
+            }
+            Flatten(path: "books.*") {
+              Fetch(service: "reviews") {
+                {
+                            ... on Book {
+                    __typename
+                    id
+                  }
+                } => {
+                            ... on Book {
+                    author {
+                      id
+                      __typename
+                    }
+                  }
+                }
+              }
+            }
+            Flatten(path: "books.*.author") {
+              Fetch(service: "authors") {
+                {
+                            ... on Author {
+                    __typename
+                    id
+                  }
+                } => {
+                            ... on Author {
+                    name
+                  }
+                }
+              }
+            }
+            Flatten(path: "books.*.author") {
+              Fetch(service: "reviews") {
+                {
+                            ... on Author {
+                    __typename
+                    id
+                    name
+                  }
+                } => {
+                            ... on Author {
+                    greeting
+                  }
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn plan_with_subgraph_graphql_validation() {
+        let supergraph = Supergraph::new(TEST_SUPERGRAPH).unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
             {
-                    a {
-                b {
-                  x
-                  y
+                productById(id: 1) {
+                    ... on Book {
+                        avg_rating
+                    }
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        let config = QueryPlannerConfig {
+            subgraph_graphql_validation: true,
+            ..Default::default()
+        };
+        let validating_planner = QueryPlanner::new(&supergraph, config).unwrap();
+        let validated_plan = validating_planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        assert_eq!(validated_plan.to_string(), plan.to_string());
+    }
+
+    #[test]
+    fn plan_fails_subgraph_graphql_validation() {
+        let supergraph = Supergraph::new(TEST_SUPERGRAPH).unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            "{ me { name } }",
+            "operation.graphql",
+        )
+        .unwrap();
+
+        let config = QueryPlannerConfig {
+            subgraph_graphql_validation: true,
+            ..Default::default()
+        };
+        let mut planner = QueryPlanner::new(&supergraph, config).unwrap();
+        // Make the subgraph schema of "accounts" mismatch the supergraph by adding a required
+        // argument to `User.name`.
+        let query_graph = Arc::get_mut(&mut planner.federated_query_graph).unwrap();
+        let mut schema = query_graph.sources["accounts"]
+            .schema()
+            .clone()
+            .into_inner();
+        let Some(ExtendedType::Object(user)) = schema.types.get_mut("User") else {
+            panic!("expected a User object type");
+        };
+        user.make_mut().fields["name"]
+            .make_mut()
+            .arguments
+            .push(Node::new(InputValueDefinition {
+                description: None,
+                name: name!("format"),
+                ty: ty!(String!).into(),
+                default_value: None,
+                directives: Default::default(),
+            }));
+        query_graph.sources.insert(
+            NodeStr::new("accounts"),
+            ValidFederationSchema::new(Valid::assume_valid(schema)).unwrap(),
+        );
+
+        let error = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap_err();
+        let FederationError::SingleFederationError(
+            SingleFederationError::InvalidSubgraphOperation {
+                subgraph,
+                operation,
+                errors,
+            },
+        ) = error
+        else {
+            panic!("expected an invalid subgraph operation error, got: {error}");
+        };
+        assert_eq!(subgraph, "accounts");
+        insta::assert_snapshot!(operation, @r###"
+        {
+          me {
+            name
+          }
+        }
+        "###);
+        insta::assert_debug_snapshot!(errors, @r###"
+        [
+            GraphQLError {
+                message: "the required argument `User.name(format:)` is not provided",
+                locations: [
+                    GraphQLLocation {
+                        line: 3,
+                        column: 5,
+                    },
+                ],
+                path: [],
+                extensions: {},
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn plan_requires_fetched_from_parent() {
+        let supergraph = Supergraph::new(TEST_SUPERGRAPH).unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            {
+                bestRatedProducts {
+                    ... on Book {
+                        avg_rating
+                    }
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Sequence {
+            Fetch(service: "reviews") {
+              {
+                        bestRatedProducts {
+                  ... on Book {
+                    id
+                    __typename
+                    reviews {
+                      rating
+                    }
+                  }
+                }
+              }
+            }
+            Flatten(path: "bestRatedProducts.*") {
+              Fetch(service: "products") {
+                {
+                            ... on Book {
+                    __typename
+                    id
+                    reviews {
+                      rating
+                    }
+                  }
+                } => {
+                            ... on Book {
+                    avg_rating
+                  }
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn plan_fed_1_supergraph() {
+        let supergraph = Supergraph::new(FED_1_SUPERGRAPH).unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            {
+                products {
+                    shippingEstimate
+                }
+                reviews {
+                    product {
+                        price
+                        weight
+                    }
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Parallel {
+            Sequence {
+              Fetch(service: "products") {
+                {
+                            products {
+                    id
+                    __typename
+                    price
+                    weight
+                  }
+                }
+              }
+              Flatten(path: "products.*") {
+                Fetch(service: "inventory") {
+                  {
+                                ... on Product {
+                      __typename
+                      id
+                      price
+                      weight
+                    }
+                  } => {
+                                ... on Product {
+                      shippingEstimate
+                    }
+                  }
+                }
+              }
+            }
+            Sequence {
+              Fetch(service: "reviews") {
+                {
+                            reviews {
+                    product {
+                      id
+                      __typename
+                      price
+                    }
+                  }
+                }
+              }
+              Flatten(path: "reviews.*.product") {
+                Fetch(service: "products") {
+                  {
+                                ... on Product {
+                      __typename
+                      id
+                    }
+                  } => {
+                                ... on Product {
+                      weight
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn plan_statistics() {
+        let supergraph = Supergraph::new(TEST_SUPERGRAPH).unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            "{ me { id } }",
+            "operation.graphql",
+        )
+        .unwrap();
+
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        let statistics = &plan.statistics;
+        assert_eq!(statistics.evaluated_plan_count, 2);
+        assert_eq!(statistics.closed_branch_count, 1);
+        assert_eq!(statistics.pruned_option_count, 0);
+        assert!(!statistics.max_evaluated_plans_reached);
+
+        let mut config = QueryPlannerConfig::default();
+        config.debug.max_evaluated_plans = NonZeroU32::new(1).unwrap();
+        let planner = QueryPlanner::new(&supergraph, config).unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        let statistics = &plan.statistics;
+        assert_eq!(statistics.evaluated_plan_count, 1);
+        assert_eq!(statistics.pruned_option_count, 1);
+        assert!(statistics.max_evaluated_plans_reached);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn plan_round_trips_through_js_json() {
+        let supergraph = Supergraph::new(TEST_SUPERGRAPH).unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            {
+                bestRatedProducts {
+                    ... on Book {
+                        avg_rating
+                    }
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        let json = serde_json::to_string_pretty(&plan).unwrap();
+        insta::assert_snapshot!(json, @r###"
+        {
+          "kind": "QueryPlan",
+          "node": {
+            "kind": "Sequence",
+            "nodes": [
+              {
+                "kind": "Fetch",
+                "serviceName": "reviews",
+                "variableUsages": [],
+                "operation": "{bestRatedProducts{...on Book{id __typename reviews{rating}}}}",
+                "operationKind": "query"
+              },
+              {
+                "kind": "Flatten",
+                "path": [
+                  "bestRatedProducts",
+                  "@"
+                ],
+                "node": {
+                  "kind": "Fetch",
+                  "serviceName": "products",
+                  "variableUsages": [],
+                  "requires": [
+                    {
+                      "kind": "InlineFragment",
+                      "typeCondition": "Book",
+                      "selections": [
+                        {
+                          "kind": "Field",
+                          "name": "__typename"
+                        },
+                        {
+                          "kind": "Field",
+                          "name": "id"
+                        },
+                        {
+                          "kind": "Field",
+                          "name": "reviews",
+                          "selections": [
+                            {
+                              "kind": "Field",
+                              "name": "rating"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ],
+                  "operation": "query($representations:[_Any!]!){_entities(representations:$representations){...on Book{avg_rating}}}",
+                  "operationKind": "query"
+                }
+              }
+            ]
+          }
+        }
+        "###);
+
+        let read_plan = planner
+            .deserialize_query_plan(&mut serde_json::Deserializer::from_str(&json))
+            .unwrap();
+        assert_eq!(read_plan.to_string(), plan.to_string());
+        assert_eq!(serde_json::to_string_pretty(&read_plan).unwrap(), json);
+    }
+
+    #[test]
+    fn bypass_planner_for_single_subgraph() {
+        let a = Subgraph::parse_and_expand(
+            "A",
+            "https://A",
+            r#"
+            type Query {
+                a: A
+            }
+            type A {
+                b: B
+            }
+            type B {
+                x: Int
+                y: String
+            }
+        "#,
+        )
+        .unwrap();
+        let subgraphs = vec![&a];
+        let supergraph = Supergraph::compose(subgraphs).unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            {
+                a {
+                    b {
+                        x
+                        y
+                    }
+                }
+            }
+            "#,
+            "",
+        )
+        .unwrap();
+
+        let mut config = QueryPlannerConfig::default();
+        config.debug.bypass_planner_for_single_subgraph = true;
+        let planner = QueryPlanner::new(&supergraph, config).unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Fetch(service: "A") {
+            {
+                    a {
+                b {
+                  x
+                  y
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn plan_mutation_fields_in_order() {
+        let supergraph = Supergraph::new(
+            r#"
+schema @link(url: "https://specs.apollo.dev/link/v1.0") @link(url: "https://specs.apollo.dev/join/v0.3", for: EXECUTION) {
+  query: Query
+  mutation: Mutation
+}
+
+directive @link(url: String, as: String, for: link__Purpose, import: [link__Import]) repeatable on SCHEMA
+
+directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+directive @join__type(graph: join__Graph!, key: join__FieldSet, extension: Boolean! = false, resolvable: Boolean! = true, isInterfaceObject: Boolean! = false) repeatable on ENUM | INPUT_OBJECT | INTERFACE | OBJECT | SCALAR | UNION
+
+directive @join__field(graph: join__Graph, requires: join__FieldSet, provides: join__FieldSet, type: String, external: Boolean, override: String, usedOverridden: Boolean) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+
+directive @join__implements(graph: join__Graph!, interface: String!) repeatable on INTERFACE | OBJECT
+
+directive @join__unionMember(graph: join__Graph!, member: String!) repeatable on UNION
+
+directive @join__enumValue(graph: join__Graph!) repeatable on ENUM_VALUE
+
+enum link__Purpose {
+  """
+  SECURITY features provide metadata necessary to securely resolve fields.
+  """
+  SECURITY
+  """EXECUTION features provide metadata necessary for operation execution."""
+  EXECUTION
+}
+
+scalar link__Import
+
+scalar join__FieldSet
+
+enum join__Graph {
+  A @join__graph(name: "A", url: "https://A")
+  B @join__graph(name: "B", url: "https://B")
+}
+
+type Query @join__type(graph: A) @join__type(graph: B) {
+  a: Int @join__field(graph: A)
+  b: Int @join__field(graph: B)
+}
+
+type Mutation @join__type(graph: A) @join__type(graph: B) {
+  m1: Int @join__field(graph: A)
+  m3: Int @join__field(graph: A)
+  m4: T @join__field(graph: A)
+  m2: Int @join__field(graph: B)
+}
+
+type T @join__type(graph: A, key: "id") @join__type(graph: B, key: "id") {
+  id: ID!
+  x: Int @join__field(graph: A)
+  y: Int @join__field(graph: B)
+}
+            "#,
+        )
+        .unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            mutation {
+                m1
+                m2
+                m3
+                m4 {
+                    x
+                    y
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Sequence {
+            Fetch(service: "A") {
+                    mutation {
+                m1
+              }
+            }
+            Fetch(service: "B") {
+                    mutation {
+                m2
+              }
+            }
+            Fetch(service: "A") {
+                    mutation {
+                m3
+                m4 {
+                  id
+                  __typename
+                  x
+                }
+              }
+            }
+            Flatten(path: "m4") {
+              Fetch(service: "B") {
+                {
+                            ... on T {
+                    __typename
+                    id
+                  }
+                } => {
+                            ... on T {
+                    y
+                  }
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn plan_defer_with_condition() {
+        let supergraph = Supergraph::new(TEST_SUPERGRAPH).unwrap();
+        let mut config = QueryPlannerConfig::default();
+        config.incremental_delivery.enable_defer = true;
+        let planner = QueryPlanner::new(&supergraph, config).unwrap();
+        let api_schema = supergraph
+            .to_api_schema(ApiSchemaOptions {
+                include_defer: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            query($shouldDefer: Boolean!) {
+                userById(id: 1) {
+                    name
+                    ... @defer(if: $shouldDefer) {
+                        email
+                    }
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Condition(if: $shouldDefer) {
+            Then {
+              Defer {
+                Primary {
+                            {
+                    userById(id: 1) {
+                      name
+                    }
+                  }:
+                  Fetch(service: "accounts", id: 0) {
+                    {
+                                    userById(id: 1) {
+                        name
+                        id
+                        __typename
+                      }
+                    }
+                  }
+                }, [
+                  Deferred(depends: [0], path: "userById") {
+                    {
+                                    email
+                    }:
+                    Flatten(path: "userById") {
+                      Fetch(service: "accounts") {
+                        {
+                                            ... on User {
+                            __typename
+                            id
+                          }
+                        } => {
+                                            ... on User {
+                            email
+                          }
+                        }
+                      }
+                    }
+                  }
+                ]
+              }
+            } Else {
+              Fetch(service: "accounts") {
+                {
+                            userById(id: 1) {
+                    name
+                    email
+                  }
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn plan_reuses_named_fragments() {
+        let supergraph = Supergraph::new(
+            r#"
+schema @link(url: "https://specs.apollo.dev/link/v1.0") @link(url: "https://specs.apollo.dev/join/v0.3", for: EXECUTION) {
+  query: Query
+}
+
+directive @link(url: String, as: String, for: link__Purpose, import: [link__Import]) repeatable on SCHEMA
+
+directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+directive @join__type(graph: join__Graph!, key: join__FieldSet, extension: Boolean! = false, resolvable: Boolean! = true, isInterfaceObject: Boolean! = false) repeatable on ENUM | INPUT_OBJECT | INTERFACE | OBJECT | SCALAR | UNION
+
+directive @join__field(graph: join__Graph, requires: join__FieldSet, provides: join__FieldSet, type: String, external: Boolean, override: String, usedOverridden: Boolean) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+
+directive @join__implements(graph: join__Graph!, interface: String!) repeatable on INTERFACE | OBJECT
+
+directive @join__unionMember(graph: join__Graph!, member: String!) repeatable on UNION
+
+directive @join__enumValue(graph: join__Graph!) repeatable on ENUM_VALUE
+
+enum link__Purpose {
+  """
+  SECURITY features provide metadata necessary to securely resolve fields.
+  """
+  SECURITY
+  """EXECUTION features provide metadata necessary for operation execution."""
+  EXECUTION
+}
+
+scalar link__Import
+
+scalar join__FieldSet
+
+enum join__Graph {
+  A @join__graph(name: "A", url: "https://A")
+  B @join__graph(name: "B", url: "https://B")
+}
+
+type Query @join__type(graph: A) @join__type(graph: B) {
+  t1: T @join__field(graph: A)
+  t2: T @join__field(graph: A)
+}
+
+type T @join__type(graph: A, key: "id") @join__type(graph: B, key: "id") {
+  id: ID!
+  x: Int @join__field(graph: A)
+  y: Int @join__field(graph: A)
+  z: Int @join__field(graph: B)
+}
+            "#,
+        )
+        .unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            {
+                t1 {
+                    ...TFields
+                }
+                t2 {
+                    ...TFields
+                }
+            }
+
+            fragment TFields on T {
+                x
+                y
+                z
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Sequence {
+            Fetch(service: "A") {
+              {
+                        t1 {
+                  ...TFields
+                  id
+                  __typename
+                }
+                        t2 {
+                  ...TFields
+                  id
+                  __typename
+                }
+              }
+                    fragment TFields on T {
+                x
+                y
+              }
+            }
+            Parallel {
+              Flatten(path: "t2") {
+                Fetch(service: "B") {
+                  {
+                                ... on T {
+                      __typename
+                      id
+                    }
+                  } => {
+                                ... on T {
+                      z
+                    }
+                  }
+                }
+              }
+              Flatten(path: "t1") {
+                Fetch(service: "B") {
+                  {
+                                ... on T {
+                      __typename
+                      id
+                    }
+                  } => {
+                                ... on T {
+                      z
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn plan_with_progressive_override() {
+        let supergraph = Supergraph::new(
+            r#"
+schema @link(url: "https://specs.apollo.dev/link/v1.0") @link(url: "https://specs.apollo.dev/join/v0.4", for: EXECUTION) {
+  query: Query
+}
+
+directive @link(url: String, as: String, for: link__Purpose, import: [link__Import]) repeatable on SCHEMA
+
+directive @join__directive(graphs: [join__Graph!], name: String!, args: join__DirectiveArguments) repeatable on SCHEMA | OBJECT | INTERFACE | FIELD_DEFINITION
+
+directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+directive @join__type(graph: join__Graph!, key: join__FieldSet, extension: Boolean! = false, resolvable: Boolean! = true, isInterfaceObject: Boolean! = false) repeatable on ENUM | INPUT_OBJECT | INTERFACE | OBJECT | SCALAR | UNION
+
+directive @join__field(graph: join__Graph, requires: join__FieldSet, provides: join__FieldSet, type: String, external: Boolean, override: String, usedOverridden: Boolean, overrideLabel: String) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+
+directive @join__implements(graph: join__Graph!, interface: String!) repeatable on INTERFACE | OBJECT
+
+directive @join__unionMember(graph: join__Graph!, member: String!) repeatable on UNION
+
+directive @join__enumValue(graph: join__Graph!) repeatable on ENUM_VALUE
+
+enum link__Purpose {
+  """
+  SECURITY features provide metadata necessary to securely resolve fields.
+  """
+  SECURITY
+  """EXECUTION features provide metadata necessary for operation execution."""
+  EXECUTION
+}
+
+scalar link__Import
+
+scalar join__FieldSet
+
+scalar join__DirectiveArguments
+
+enum join__Graph {
+  A @join__graph(name: "A", url: "https://A")
+  B @join__graph(name: "B", url: "https://B")
+}
+
+type Query @join__type(graph: A) @join__type(graph: B) {
+  t: T @join__field(graph: A)
+}
+
+type T @join__type(graph: A, key: "id") @join__type(graph: B, key: "id") {
+  id: ID!
+  x: Int @join__field(graph: A)
+  y: Int @join__field(graph: A, override: "B", overrideLabel: "percent(50)") @join__field(graph: B, overrideLabel: "percent(50)")
+}
+            "#,
+        )
+        .unwrap();
+        let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+        let planner = QueryPlanner::new(&supergraph, Default::default()).unwrap();
+
+        let document = ExecutableDocument::parse_and_validate(
+            api_schema.schema(),
+            r#"
+            {
+                t {
+                    x
+                    y
+                }
+            }
+            "#,
+            "operation.graphql",
+        )
+        .unwrap();
+
+        let plan = planner
+            .build_query_plan(
+                &document,
+                None,
+                QueryPlanOptions {
+                    override_conditions: vec!["percent(50)".to_string()],
+                },
+            )
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Fetch(service: "A") {
+            {
+                    t {
+                x
+                y
+              }
+            }
+          }
+        }
+        "###);
+
+        let plan = planner
+            .build_query_plan(&document, None, Default::default())
+            .unwrap();
+        insta::assert_snapshot!(plan, @r###"
+        QueryPlan {
+          Sequence {
+            Fetch(service: "A") {
+              {
+                        t {
+                  id
+                  __typename
+                  x
+                }
+              }
+            }
+            Flatten(path: "t") {
+              Fetch(service: "B") {
+                {
+                            ... on T {
+                    __typename
+                    id
+                  }
+                } => {
+                            ... on T {
+                    y
+                  }
                 }
               }
             }
@@ -1,4 +1,5 @@
 use crate::error::FederationError;
+use crate::error::SingleFederationError;
 use crate::query_graph::condition_resolver::{
     ConditionResolution, ConditionResolutionCacheResult, ConditionResolver, ConditionResolverCache,
 };
@@ -8,8 +9,10 @@ use crate::query_graph::graph_path::{
     SimultaneousPathsWithLazyIndirectPaths,
 };
 use crate::query_graph::path_tree::OpPathTree;
-use crate::query_graph::{QueryGraph, QueryGraphNodeType};
-use crate::query_plan::fetch_dependency_graph::{compute_nodes_for_tree, FetchDependencyGraph};
+use crate::query_graph::{EnabledOverrideConditions, QueryGraph, QueryGraphNodeType};
+use crate::query_plan::fetch_dependency_graph::{
+    compute_nodes_for_tree, compute_root_fetch_nodes, FetchDependencyGraph,
+};
 use crate::query_plan::fetch_dependency_graph_processor::{
     FetchDependencyGraphProcessor, FetchDependencyGraphToCostProcessor,
     FetchDependencyGraphToQueryPlanProcessor,
@@ -21,12 +24,14 @@ use crate::query_plan::operation::{
 use crate::query_plan::query_planner::QueryPlannerConfig;
 use crate::query_plan::query_planner::QueryPlanningStatistics;
 use crate::query_plan::QueryPlanCost;
+use crate::schema::position::AbstractTypeDefinitionPosition;
 use crate::schema::position::ObjectTypeDefinitionPosition;
-use crate::schema::position::{AbstractTypeDefinitionPosition, OutputTypeDefinitionPosition};
 use crate::schema::position::{CompositeTypeDefinitionPosition, SchemaRootDefinitionKind};
 use crate::schema::ValidFederationSchema;
 use indexmap::IndexSet;
 use petgraph::graph::{EdgeIndex, NodeIndex};
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 
 // PORT_NOTE: Named `PlanningParameters` in the JS codebase, but there was no particular reason to
@@ -56,7 +61,12 @@ pub(crate) struct QueryPlanningParameters {
         Arc<IndexSet<AbstractTypeDefinitionPosition>>,
     /// The configuration for the query planner.
     pub(crate) config: QueryPlannerConfig,
-    pub(crate) statistics: QueryPlanningStatistics,
+    /// The statistics of the planning of the operation.
+    // PORT_NOTE: In the JS codebase, the statistics object is shared by reference between the
+    // parameters of a planning and the ones of the traversals it starts to resolve conditions.
+    pub(crate) statistics: Rc<RefCell<QueryPlanningStatistics>>,
+    /// The progressive @override labels that are enabled for this query plan.
+    pub(crate) override_conditions: Arc<EnabledOverrideConditions>,
 }
 
 pub(crate) struct QueryPlanningTraversal<'a> {
@@ -133,6 +143,7 @@ impl<'a> QueryPlanningTraversal<'a> {
         // refactored.
         parameters: &'a QueryPlanningParameters,
         selection_set: NormalizedSelectionSet,
+        starting_id_generation: u64,
         has_defers: bool,
         root_kind: SchemaRootDefinitionKind,
         cost_processor: FetchDependencyGraphToCostProcessor,
@@ -140,7 +151,7 @@ impl<'a> QueryPlanningTraversal<'a> {
         Self::new_inner(
             parameters,
             selection_set,
-            0,
+            starting_id_generation,
             has_defers,
             root_kind,
             cost_processor,
@@ -180,6 +191,7 @@ impl<'a> QueryPlanningTraversal<'a> {
         let initial_path = OpGraphPath::new(
             Arc::clone(&parameters.federated_query_graph),
             parameters.head,
+            parameters.override_conditions.clone(),
         )
         .unwrap();
         // In JS this is done *inside* create_initial_options, which would require awareness of the
@@ -281,11 +293,12 @@ impl<'a> QueryPlanningTraversal<'a> {
             new_options.extend(followups_for_option);
             if let Some(options_limit) = self.parameters.config.debug.paths_limit {
                 if new_options.len() > options_limit as usize {
-                    // TODO: Create a new error code for this error kind.
-                    return Err(FederationError::internal(format!(
-                        "Too many options generated for {}, reached the limit of {}.",
-                        selection, options_limit,
-                    )));
+                    self.parameters.statistics.borrow_mut().paths_limit_reached = true;
+                    return Err(SingleFederationError::PathsLimitExceeded {
+                        selection: selection.to_string(),
+                        limit: options_limit,
+                    }
+                    .into());
                 }
             }
         }
@@ -429,6 +442,7 @@ impl<'a> QueryPlanningTraversal<'a> {
 
     fn record_closed_branch(&mut self, closed_branch: ClosedBranch) -> Result<(), FederationError> {
         let maybe_trimmed = closed_branch.maybe_eliminate_strictly_more_costly_paths()?;
+        self.parameters.statistics.borrow_mut().closed_branch_count += 1;
         self.closed_branches.push(maybe_trimmed);
         Ok(())
     }
@@ -476,7 +490,12 @@ impl<'a> QueryPlanningTraversal<'a> {
                 }
                 QueryGraphNodeType::FederatedRootType(_) => return Ok(false),
             };
-            if n.has_reachable_cross_subgraph_edges || !selection.can_rebase_on(&parent_ty) {
+            let schema = self
+                .parameters
+                .federated_query_graph
+                .schema_by_source(&n.source)?;
+            if n.has_reachable_cross_subgraph_edges || !selection.can_rebase_on(&parent_ty, schema)
+            {
                 return Ok(false);
             }
         }
@@ -560,6 +579,7 @@ impl<'a> QueryPlanningTraversal<'a> {
             if first_group.is_empty() {
                 // Well, we have the only possible plan; it's also the best.
                 let cost = self.cost(&mut initial_dependency_graph)?;
+                self.parameters.statistics.borrow_mut().evaluated_plan_count += 1;
                 self.best_plan = BestQueryPlanInfo {
                     fetch_dependency_graph: initial_dependency_graph,
                     path_tree: initial_tree.into(),
@@ -611,6 +631,7 @@ impl<'a> QueryPlanningTraversal<'a> {
     /// So first, we check if we can preemptively prune some branches based on
     /// those branches having options that are known to be overriden by other ones.
     fn prune_closed_branches(&mut self) {
+        let mut pruned_option_count = 0;
         for branch in &mut self.closed_branches {
             if branch.0.len() <= 1 {
                 continue;
@@ -623,8 +644,10 @@ impl<'a> QueryPlanningTraversal<'a> {
                 }
             }
 
+            pruned_option_count += branch.0.len() - pruned.0.len();
             *branch = pruned
         }
+        self.parameters.statistics.borrow_mut().pruned_option_count += pruned_option_count;
     }
 
     fn option_is_overriden(
@@ -732,6 +755,9 @@ impl<'a> QueryPlanningTraversal<'a> {
             if plan_count <= max_evaluated_plans || first_branch_len <= 1 {
                 break;
             }
+            let mut statistics = self.parameters.statistics.borrow_mut();
+            statistics.max_evaluated_plans_reached = true;
+            statistics.pruned_option_count += 1;
             Self::prune_and_reorder_first_branch(&mut self.closed_branches);
             plan_count -= plan_count / first_branch_len;
 
@@ -815,41 +841,7 @@ impl<'a> QueryPlanningTraversal<'a> {
             QueryGraphNodeType::FederatedRootType(_)
         );
         if is_root_path_tree {
-            // The root of the pathTree is one of the "fake" root of the subgraphs graph,
-            // which belongs to no subgraph but points to each ones.
-            // So we "unpack" the first level of the tree to find out our top level groups
-            // (and initialize our stack).
-            // Note that we can safely ignore the triggers of that first level
-            // as it will all be free transition, and we know we cannot have conditions.
-            for child in &path_tree.childs {
-                let edge = child.edge.expect("The root edge should not be None");
-                let (_source_node, target_node) = path_tree.graph.edge_endpoints(edge)?;
-                let target_node = path_tree.graph.node_weight(target_node)?;
-                let subgraph_name = &target_node.source;
-                let root_type = match &target_node.type_ {
-                    QueryGraphNodeType::SchemaType(OutputTypeDefinitionPosition::Object(
-                        object,
-                    )) => object.clone().into(),
-                    ty => {
-                        return Err(FederationError::internal(format!(
-                            "expected an object type for the root of a subgraph, found {ty}"
-                        )))
-                    }
-                };
-                let fetch_dependency_node = dependency_graph.get_or_create_root_node(
-                    subgraph_name,
-                    self.root_kind,
-                    root_type,
-                )?;
-                compute_nodes_for_tree(
-                    dependency_graph,
-                    &child.tree,
-                    fetch_dependency_node,
-                    Default::default(),
-                    Default::default(),
-                    &Default::default(),
-                )?;
-            }
+            compute_root_fetch_nodes(self.root_kind, dependency_graph, path_tree)?;
         } else {
             let query_graph_node = path_tree.graph.node_weight(path_tree.node)?;
             let subgraph_name = &query_graph_node.source;
@@ -911,6 +903,7 @@ impl<'a> QueryPlanningTraversal<'a> {
                 .clone(),
             config: self.parameters.config.clone(),
             statistics: self.parameters.statistics.clone(),
+            override_conditions: self.parameters.override_conditions.clone(),
         };
         let best_plan_opt = QueryPlanningTraversal::new_inner(
             &parameters,
@@ -966,6 +959,8 @@ impl PlanBuilder<(FetchDependencyGraph, Arc<OpPathTree>), Arc<OpPathTree>>
         _cost: QueryPlanCost,
         _prev_cost: Option<QueryPlanCost>,
     ) {
+        // Only complete plans are counted, not the partial ones whose cost is computed on the way.
+        self.parameters.statistics.borrow_mut().evaluated_plan_count += 1;
         // debug log
         // if prev_cost.is_none() {
         //     print!("Computed plan with cost {}: {}", cost, plan_tree);
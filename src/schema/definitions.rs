@@ -1,4 +1,5 @@
 use apollo_compiler::ast::{NamedType, Type};
+use apollo_compiler::schema::ExtendedType;
 use apollo_compiler::Schema;
 
 use crate::error::{FederationError, SingleFederationError};
@@ -74,3 +75,46 @@ pub(crate) fn types_can_be_merged(
 
     Ok(n1 == n2)
 }
+
+/// Returns whether `maybe_subtype` is a subtype of `ty` (and not the same type). The named type
+/// hierarchy (interface implementations and union members) is taken from the given schema.
+// PORT_NOTE: The JS codebase allows configuring the subtyping rules; we always use its defaults,
+// which allow everything but "upgrading" a type to a list of that type.
+pub(crate) fn is_strict_subtype(schema: &Schema, ty: &Type, maybe_subtype: &Type) -> bool {
+    match maybe_subtype {
+        Type::List(maybe_subtype_item) => match ty {
+            Type::List(item) => is_subtype(schema, item, maybe_subtype_item),
+            _ => false,
+        },
+        Type::NonNullNamed(_) | Type::NonNullList(_) => {
+            let maybe_subtype = maybe_subtype.clone().nullable();
+            if ty.is_non_null() {
+                is_subtype(schema, &ty.clone().nullable(), &maybe_subtype)
+            } else {
+                is_subtype(schema, ty, &maybe_subtype)
+            }
+        }
+        Type::Named(maybe_subtype_name) => {
+            let Type::Named(name) = ty else {
+                return false;
+            };
+            match (schema.types.get(name), schema.types.get(maybe_subtype_name)) {
+                (Some(ExtendedType::Interface(_)), Some(ExtendedType::Object(maybe_subtype))) => {
+                    maybe_subtype.implements_interfaces.contains(name)
+                }
+                (
+                    Some(ExtendedType::Interface(_)),
+                    Some(ExtendedType::Interface(maybe_subtype)),
+                ) => maybe_subtype.implements_interfaces.contains(name),
+                (Some(ExtendedType::Union(union_)), Some(ExtendedType::Object(_))) => {
+                    union_.members.contains(maybe_subtype_name)
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+pub(crate) fn is_subtype(schema: &Schema, ty: &Type, maybe_subtype: &Type) -> bool {
+    ty == maybe_subtype || is_strict_subtype(schema, ty, maybe_subtype)
+}
@@ -178,8 +178,8 @@ pub(crate) fn add_interface_field_implementations(
 #[cfg(test)]
 mod tests {
     use crate::{
-        error::FederationError, query_graph::build_federated_query_graph, subgraph::Subgraph,
-        Supergraph,
+        error::FederationError, merge::merge_subgraphs, query_graph::build_federated_query_graph,
+        subgraph::Subgraph, Supergraph,
     };
     use apollo_compiler::schema::Name;
 
@@ -193,7 +193,8 @@ mod tests {
         "#;
 
         let subgraph = Subgraph::parse_and_expand("S1", "http://S1", sdl).unwrap();
-        let supergraph = Supergraph::compose([&subgraph].to_vec()).unwrap();
+        // Composition would reject the aliases, so only merge the subgraph.
+        let supergraph = Supergraph::from_schema(merge_subgraphs(vec![&subgraph]).unwrap().schema)?;
         let err = super::parse_field_set(
             &supergraph.schema,
             Name::new("Query").unwrap(),
@@ -221,7 +222,8 @@ mod tests {
         "#;
 
         let subgraph = Subgraph::parse_and_expand("S1", "http://S1", sdl).unwrap();
-        let supergraph = Supergraph::compose([&subgraph].to_vec()).unwrap();
+        // Composition would reject the aliases, so only merge the subgraph.
+        let supergraph = Supergraph::from_schema(merge_subgraphs(vec![&subgraph]).unwrap().schema)?;
         let api_schema = supergraph.to_api_schema(Default::default())?;
         // Testing via `build_federated_query_graph` function, which validates the @requires directive.
         let err = build_federated_query_graph(supergraph.schema, api_schema, None, None)
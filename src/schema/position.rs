@@ -693,6 +693,13 @@ impl ObjectOrInterfaceFieldDefinitionPosition {
         }
     }
 
+    pub(crate) fn try_get<'schema>(
+        &self,
+        schema: &'schema Schema,
+    ) -> Option<&'schema Component<FieldDefinition>> {
+        self.get(schema).ok()
+    }
+
     pub(crate) fn insert_directive(
         &self,
         schema: &mut FederationSchema,
@@ -708,6 +715,17 @@ impl ObjectOrInterfaceFieldDefinitionPosition {
         }
     }
 
+    pub(crate) fn remove_directive_name(&self, schema: &mut FederationSchema, name: &str) {
+        match self {
+            ObjectOrInterfaceFieldDefinitionPosition::Object(field) => {
+                field.remove_directive_name(schema, name)
+            }
+            ObjectOrInterfaceFieldDefinitionPosition::Interface(field) => {
+                field.remove_directive_name(schema, name)
+            }
+        }
+    }
+
     pub(crate) fn remove_directive(
         &self,
         schema: &mut FederationSchema,
@@ -13,13 +13,15 @@ use crate::link::LinkError;
 use crate::link::{Link, DEFAULT_LINK_NAME};
 use crate::subgraph::spec::{
     AppliedFederationLink, FederationSpecDefinitions, LinkSpecDefinitions, ANY_SCALAR_NAME,
-    ENTITIES_QUERY, ENTITY_UNION_NAME, FEDERATION_V2_DIRECTIVE_NAMES, KEY_DIRECTIVE_NAME,
-    SERVICE_SDL_QUERY, SERVICE_TYPE,
+    ENTITIES_QUERY, ENTITY_UNION_NAME, FEDERATION_V2_DIRECTIVE_NAMES, FEDERATION_V2_SCALAR_NAMES,
+    KEY_DIRECTIVE_NAME, SERVICE_SDL_QUERY, SERVICE_TYPE,
 };
+use crate::subgraph::validation::validate_federation_subgraph;
 use apollo_compiler::validation::Valid;
 
 mod database;
 pub mod spec;
+mod validation;
 
 pub struct Subgraph {
     pub name: String,
@@ -78,16 +80,19 @@ impl Subgraph {
         }
 
         // generate additional schema definitions
-        Self::populate_missing_type_definitions(
+        let is_fed_2 = imported_federation_definitions.is_some();
+        let fed_definitions = Self::populate_missing_type_definitions(
             &mut schema,
             imported_federation_definitions,
             imported_link_definitions,
         )?;
         let schema = schema.validate()?;
+        validate_federation_subgraph(name, &schema, &fed_definitions, is_fed_2)?;
         Ok(ValidSubgraph {
             name: name.to_owned(),
             url: url.to_owned(),
             schema,
+            is_fed_2,
         })
     }
 
@@ -95,7 +100,7 @@ impl Subgraph {
         schema: &mut Schema,
         imported_federation_definitions: Option<FederationSpecDefinitions>,
         imported_link_definitions: Option<LinkSpecDefinitions>,
-    ) -> Result<(), FederationError> {
+    ) -> Result<FederationSpecDefinitions, FederationError> {
         // populate @link spec definitions
         let link_spec_definitions = match imported_link_definitions {
             Some(definitions) => definitions,
@@ -128,7 +133,8 @@ impl Subgraph {
             }
         };
         Self::populate_missing_federation_directive_definitions(schema, &fed_definitions)?;
-        Self::populate_missing_federation_types(schema, &fed_definitions)
+        Self::populate_missing_federation_types(schema, &fed_definitions)?;
+        Ok(fed_definitions)
     }
 
     fn populate_missing_link_definitions(
@@ -173,7 +179,25 @@ impl Subgraph {
                     .into()
             });
 
+        for (scalar_name, _) in &FEDERATION_V2_SCALAR_NAMES {
+            if !fed_definitions.supports_scalar(scalar_name) {
+                continue;
+            }
+            let namespaced_scalar_name = fed_definitions.namespaced_type_name(scalar_name, false);
+            schema
+                .types
+                .entry(namespaced_scalar_name.clone())
+                .or_insert_with(|| {
+                    fed_definitions
+                        .scalar_definition(namespaced_scalar_name)
+                        .into()
+                });
+        }
+
         for directive_name in &FEDERATION_V2_DIRECTIVE_NAMES {
+            if !fed_definitions.supports_directive(directive_name) {
+                continue;
+            }
             let namespaced_directive_name =
                 fed_definitions.namespaced_type_name(directive_name, true);
             if let Entry::Vacant(entry) = schema
@@ -251,17 +275,19 @@ impl Subgraph {
         let mut entities = Vec::new();
         let immutable_type_map = schema.types.to_owned();
         for (named_type, extended_type) in immutable_type_map.iter() {
-            let is_entity = extended_type
-                .directives()
-                .iter()
-                .find(|d| {
-                    d.name
-                        == fed_definitions
-                            .namespaced_type_name(&KEY_DIRECTIVE_NAME, true)
-                            .as_str()
-                })
-                .map(|_| true)
-                .unwrap_or(false);
+            // Interfaces can have a `@key` too, but `_Entity` members must be object types.
+            let is_entity = extended_type.is_object()
+                && extended_type
+                    .directives()
+                    .iter()
+                    .find(|d| {
+                        d.name
+                            == fed_definitions
+                                .namespaced_type_name(&KEY_DIRECTIVE_NAME, true)
+                                .as_str()
+                    })
+                    .map(|_| true)
+                    .unwrap_or(false);
             if is_entity {
                 entities.push(named_type);
             }
@@ -308,6 +334,9 @@ pub struct ValidSubgraph {
     pub name: String,
     pub url: String,
     pub schema: Valid<Schema>,
+    /// Whether the subgraph links to the federation spec. Federation 1 subgraphs are expanded with
+    /// a default link, so this can't be told from the schema itself.
+    pub(crate) is_fed_2: bool,
 }
 
 impl std::fmt::Debug for ValidSubgraph {
@@ -31,7 +31,17 @@ pub const PROVIDES_DIRECTIVE_NAME: Name = name!("provides");
 pub const REQUIRES_DIRECTIVE_NAME: Name = name!("requires");
 pub const SHAREABLE_DIRECTIVE_NAME: Name = name!("shareable");
 pub const TAG_DIRECTIVE_NAME: Name = name!("tag");
+pub const AUTHENTICATED_DIRECTIVE_NAME: Name = name!("authenticated");
+pub const REQUIRES_SCOPES_DIRECTIVE_NAME: Name = name!("requiresScopes");
+pub const POLICY_DIRECTIVE_NAME: Name = name!("policy");
+pub const CONTEXT_DIRECTIVE_NAME: Name = name!("context");
+pub const FROM_CONTEXT_DIRECTIVE_NAME: Name = name!("fromContext");
+pub const COST_DIRECTIVE_NAME: Name = name!("cost");
+pub const LIST_SIZE_DIRECTIVE_NAME: Name = name!("listSize");
 pub const FIELDSET_SCALAR_NAME: Name = name!("FieldSet");
+pub const SCOPE_SCALAR_NAME: Name = name!("Scope");
+pub const POLICY_SCALAR_NAME: Name = name!("Policy");
+pub const CONTEXT_FIELD_VALUE_SCALAR_NAME: Name = name!("ContextFieldValue");
 
 // federated types
 pub const ANY_SCALAR_NAME: Name = name!("_Any");
@@ -49,7 +59,7 @@ pub const FEDERATION_V1_DIRECTIVE_NAMES: [Name; 5] = [
     REQUIRES_DIRECTIVE_NAME,
 ];
 
-pub const FEDERATION_V2_DIRECTIVE_NAMES: [Name; 11] = [
+pub const FEDERATION_V2_DIRECTIVE_NAMES: [Name; 18] = [
     COMPOSE_DIRECTIVE_NAME,
     KEY_DIRECTIVE_NAME,
     EXTENDS_DIRECTIVE_NAME,
@@ -61,6 +71,24 @@ pub const FEDERATION_V2_DIRECTIVE_NAMES: [Name; 11] = [
     REQUIRES_DIRECTIVE_NAME,
     SHAREABLE_DIRECTIVE_NAME,
     TAG_DIRECTIVE_NAME,
+    AUTHENTICATED_DIRECTIVE_NAME,
+    REQUIRES_SCOPES_DIRECTIVE_NAME,
+    POLICY_DIRECTIVE_NAME,
+    CONTEXT_DIRECTIVE_NAME,
+    FROM_CONTEXT_DIRECTIVE_NAME,
+    COST_DIRECTIVE_NAME,
+    LIST_SIZE_DIRECTIVE_NAME,
+];
+
+/// Scalars used by federation directive arguments other than `FieldSet`, along with the spec
+/// version that introduced them.
+pub const FEDERATION_V2_SCALAR_NAMES: [(Name, Version); 3] = [
+    (SCOPE_SCALAR_NAME, Version { major: 2, minor: 5 }),
+    (POLICY_SCALAR_NAME, Version { major: 2, minor: 6 }),
+    (
+        CONTEXT_FIELD_VALUE_SCALAR_NAME,
+        Version { major: 2, minor: 8 },
+    ),
 ];
 
 // This type and the subsequent IndexMap exist purely so we can use match with Names; see comment
@@ -77,6 +105,37 @@ enum FederationDirectiveName {
     Requires,
     Shareable,
     Tag,
+    Authenticated,
+    RequiresScopes,
+    Policy,
+    Context,
+    FromContext,
+    Cost,
+    ListSize,
+}
+
+impl FederationDirectiveName {
+    /// The federation spec version in which the directive was introduced.
+    fn added_in(&self) -> Version {
+        let minor = match self {
+            FederationDirectiveName::Key
+            | FederationDirectiveName::Extends
+            | FederationDirectiveName::External
+            | FederationDirectiveName::Inaccessible
+            | FederationDirectiveName::Override
+            | FederationDirectiveName::Provides
+            | FederationDirectiveName::Requires
+            | FederationDirectiveName::Shareable
+            | FederationDirectiveName::Tag => 0,
+            FederationDirectiveName::Compose => 1,
+            FederationDirectiveName::IntfObject => 3,
+            FederationDirectiveName::Authenticated | FederationDirectiveName::RequiresScopes => 5,
+            FederationDirectiveName::Policy => 6,
+            FederationDirectiveName::Context | FederationDirectiveName::FromContext => 8,
+            FederationDirectiveName::Cost | FederationDirectiveName::ListSize => 9,
+        };
+        Version { major: 2, minor }
+    }
 }
 
 lazy_static! {
@@ -99,12 +158,28 @@ lazy_static! {
             (REQUIRES_DIRECTIVE_NAME, FederationDirectiveName::Requires),
             (SHAREABLE_DIRECTIVE_NAME, FederationDirectiveName::Shareable),
             (TAG_DIRECTIVE_NAME, FederationDirectiveName::Tag),
+            (
+                AUTHENTICATED_DIRECTIVE_NAME,
+                FederationDirectiveName::Authenticated,
+            ),
+            (
+                REQUIRES_SCOPES_DIRECTIVE_NAME,
+                FederationDirectiveName::RequiresScopes,
+            ),
+            (POLICY_DIRECTIVE_NAME, FederationDirectiveName::Policy),
+            (CONTEXT_DIRECTIVE_NAME, FederationDirectiveName::Context),
+            (
+                FROM_CONTEXT_DIRECTIVE_NAME,
+                FederationDirectiveName::FromContext,
+            ),
+            (COST_DIRECTIVE_NAME, FederationDirectiveName::Cost),
+            (LIST_SIZE_DIRECTIVE_NAME, FederationDirectiveName::ListSize),
         ])
     };
 }
 
 const MIN_FEDERATION_VERSION: Version = Version { major: 2, minor: 0 };
-const MAX_FEDERATION_VERSION: Version = Version { major: 2, minor: 5 };
+const MAX_FEDERATION_VERSION: Version = Version { major: 2, minor: 9 };
 
 #[derive(Error, Debug, PartialEq)]
 pub enum FederationSpecError {
@@ -243,6 +318,25 @@ impl FederationSpecDefinitions {
         })
     }
 
+    /// The version of the federation spec linked by the subgraph.
+    pub(crate) fn version(&self) -> &Version {
+        &self.link.url.version
+    }
+
+    /// Whether the linked federation spec version defines the given directive.
+    pub fn supports_directive(&self, name: &Name) -> bool {
+        FEDERATION_DIRECTIVE_NAMES_TO_ENUM
+            .get(name)
+            .is_some_and(|enum_name| enum_name.added_in() <= *self.version())
+    }
+
+    /// Whether the linked federation spec version defines the given scalar.
+    pub fn supports_scalar(&self, name: &Name) -> bool {
+        FEDERATION_V2_SCALAR_NAMES
+            .iter()
+            .any(|(scalar_name, added_in)| scalar_name == name && added_in <= self.version())
+    }
+
     pub fn namespaced_type_name(&self, name: &Name, is_directive: bool) -> Name {
         if is_directive {
             self.link.directive_name_in_schema(name)
@@ -277,6 +371,17 @@ impl FederationSpecDefinitions {
             FederationDirectiveName::Requires => self.requires_directive_definition(alias)?,
             FederationDirectiveName::Shareable => self.shareable_directive_definition(alias),
             FederationDirectiveName::Tag => self.tag_directive_definition(alias),
+            FederationDirectiveName::Authenticated => {
+                self.authenticated_directive_definition(alias)
+            }
+            FederationDirectiveName::RequiresScopes => {
+                self.requires_scopes_directive_definition(alias)
+            }
+            FederationDirectiveName::Policy => self.policy_directive_definition(alias),
+            FederationDirectiveName::Context => self.context_directive_definition(alias),
+            FederationDirectiveName::FromContext => self.from_context_directive_definition(alias),
+            FederationDirectiveName::Cost => self.cost_directive_definition(alias),
+            FederationDirectiveName::ListSize => self.list_size_directive_definition(alias),
         })
     }
 
@@ -289,6 +394,15 @@ impl FederationSpecDefinitions {
         }
     }
 
+    /// scalar Scope, scalar Policy or scalar ContextFieldValue
+    pub fn scalar_definition(&self, name: Name) -> ScalarType {
+        ScalarType {
+            description: None,
+            name,
+            directives: Default::default(),
+        }
+    }
+
     fn fields_argument_definition(&self) -> Result<InputValueDefinition, FederationSpecError> {
         Ok(InputValueDefinition {
             description: None,
@@ -411,19 +525,34 @@ impl FederationSpecDefinitions {
         }
     }
 
-    /// directive @override(from: String!) on FIELD_DEFINITION
+    /// directive @override(from: String!, label: String) on FIELD_DEFINITION
+    ///
+    /// The `label` argument was added in federation v2.7.
     fn override_directive_definition(&self, alias: &Option<Name>) -> DirectiveDefinition {
+        let mut arguments = vec![InputValueDefinition {
+            description: None,
+            name: name!("from"),
+            ty: ty!(String!).into(),
+            default_value: None,
+            directives: Default::default(),
+        }
+        .into()];
+        if *self.version() >= (Version { major: 2, minor: 7 }) {
+            arguments.push(
+                InputValueDefinition {
+                    description: None,
+                    name: name!("label"),
+                    ty: ty!(String).into(),
+                    default_value: None,
+                    directives: Default::default(),
+                }
+                .into(),
+            );
+        }
         DirectiveDefinition {
             description: None,
             name: alias.clone().unwrap_or(OVERRIDE_DIRECTIVE_NAME),
-            arguments: vec![InputValueDefinition {
-                description: None,
-                name: name!("from"),
-                ty: ty!(String!).into(),
-                default_value: None,
-                directives: Default::default(),
-            }
-            .into()],
+            arguments,
             repeatable: false,
             locations: vec![DirectiveLocation::FieldDefinition],
         }
@@ -510,6 +639,208 @@ impl FederationSpecDefinitions {
         }
     }
 
+    /// directive @authenticated on FIELD_DEFINITION | OBJECT | INTERFACE | SCALAR | ENUM
+    fn authenticated_directive_definition(&self, alias: &Option<Name>) -> DirectiveDefinition {
+        DirectiveDefinition {
+            description: None,
+            name: alias.clone().unwrap_or(AUTHENTICATED_DIRECTIVE_NAME),
+            arguments: Vec::new(),
+            repeatable: false,
+            locations: Self::access_control_locations(),
+        }
+    }
+
+    /// directive @requiresScopes(scopes: [[Scope!]!]!) on
+    ///   | FIELD_DEFINITION
+    ///   | OBJECT
+    ///   | INTERFACE
+    ///   | SCALAR
+    ///   | ENUM
+    fn requires_scopes_directive_definition(&self, alias: &Option<Name>) -> DirectiveDefinition {
+        DirectiveDefinition {
+            description: None,
+            name: alias.clone().unwrap_or(REQUIRES_SCOPES_DIRECTIVE_NAME),
+            arguments: vec![InputValueDefinition {
+                description: None,
+                name: name!("scopes"),
+                ty: Self::nested_list_type(self.namespaced_type_name(&SCOPE_SCALAR_NAME, false))
+                    .into(),
+                default_value: None,
+                directives: Default::default(),
+            }
+            .into()],
+            repeatable: false,
+            locations: Self::access_control_locations(),
+        }
+    }
+
+    /// directive @policy(policies: [[Policy!]!]!) on
+    ///   | FIELD_DEFINITION
+    ///   | OBJECT
+    ///   | INTERFACE
+    ///   | SCALAR
+    ///   | ENUM
+    fn policy_directive_definition(&self, alias: &Option<Name>) -> DirectiveDefinition {
+        DirectiveDefinition {
+            description: None,
+            name: alias.clone().unwrap_or(POLICY_DIRECTIVE_NAME),
+            arguments: vec![InputValueDefinition {
+                description: None,
+                name: name!("policies"),
+                ty: Self::nested_list_type(self.namespaced_type_name(&POLICY_SCALAR_NAME, false))
+                    .into(),
+                default_value: None,
+                directives: Default::default(),
+            }
+            .into()],
+            repeatable: false,
+            locations: Self::access_control_locations(),
+        }
+    }
+
+    fn access_control_locations() -> Vec<DirectiveLocation> {
+        vec![
+            DirectiveLocation::FieldDefinition,
+            DirectiveLocation::Object,
+            DirectiveLocation::Interface,
+            DirectiveLocation::Scalar,
+            DirectiveLocation::Enum,
+        ]
+    }
+
+    /// [[name!]!]!
+    fn nested_list_type(name: Name) -> Type {
+        Type::Named(name)
+            .non_null()
+            .list()
+            .non_null()
+            .list()
+            .non_null()
+    }
+
+    /// directive @context(name: String!) repeatable on INTERFACE | OBJECT | UNION
+    fn context_directive_definition(&self, alias: &Option<Name>) -> DirectiveDefinition {
+        DirectiveDefinition {
+            description: None,
+            name: alias.clone().unwrap_or(CONTEXT_DIRECTIVE_NAME),
+            arguments: vec![InputValueDefinition {
+                description: None,
+                name: name!("name"),
+                ty: ty!(String!).into(),
+                default_value: None,
+                directives: Default::default(),
+            }
+            .into()],
+            repeatable: true,
+            locations: vec![
+                DirectiveLocation::Interface,
+                DirectiveLocation::Object,
+                DirectiveLocation::Union,
+            ],
+        }
+    }
+
+    /// directive @fromContext(field: ContextFieldValue) on ARGUMENT_DEFINITION
+    // The name mirrors the directive, it is not a conversion.
+    #[allow(clippy::wrong_self_convention)]
+    fn from_context_directive_definition(&self, alias: &Option<Name>) -> DirectiveDefinition {
+        DirectiveDefinition {
+            description: None,
+            name: alias.clone().unwrap_or(FROM_CONTEXT_DIRECTIVE_NAME),
+            arguments: vec![InputValueDefinition {
+                description: None,
+                name: name!("field"),
+                ty: Type::Named(self.namespaced_type_name(&CONTEXT_FIELD_VALUE_SCALAR_NAME, false))
+                    .into(),
+                default_value: None,
+                directives: Default::default(),
+            }
+            .into()],
+            repeatable: false,
+            locations: vec![DirectiveLocation::ArgumentDefinition],
+        }
+    }
+
+    /// directive @cost(weight: Int!) on
+    ///   | ARGUMENT_DEFINITION
+    ///   | ENUM
+    ///   | FIELD_DEFINITION
+    ///   | INPUT_FIELD_DEFINITION
+    ///   | OBJECT
+    ///   | SCALAR
+    fn cost_directive_definition(&self, alias: &Option<Name>) -> DirectiveDefinition {
+        DirectiveDefinition {
+            description: None,
+            name: alias.clone().unwrap_or(COST_DIRECTIVE_NAME),
+            arguments: vec![InputValueDefinition {
+                description: None,
+                name: name!("weight"),
+                ty: ty!(Int!).into(),
+                default_value: None,
+                directives: Default::default(),
+            }
+            .into()],
+            repeatable: false,
+            locations: vec![
+                DirectiveLocation::ArgumentDefinition,
+                DirectiveLocation::Enum,
+                DirectiveLocation::FieldDefinition,
+                DirectiveLocation::InputFieldDefinition,
+                DirectiveLocation::Object,
+                DirectiveLocation::Scalar,
+            ],
+        }
+    }
+
+    /// directive @listSize(
+    ///   assumedSize: Int,
+    ///   slicingArguments: [String!],
+    ///   sizedFields: [String!],
+    ///   requireOneSlicingArgument: Boolean = true
+    /// ) on FIELD_DEFINITION
+    fn list_size_directive_definition(&self, alias: &Option<Name>) -> DirectiveDefinition {
+        DirectiveDefinition {
+            description: None,
+            name: alias.clone().unwrap_or(LIST_SIZE_DIRECTIVE_NAME),
+            arguments: vec![
+                InputValueDefinition {
+                    description: None,
+                    name: name!("assumedSize"),
+                    ty: ty!(Int).into(),
+                    default_value: None,
+                    directives: Default::default(),
+                }
+                .into(),
+                InputValueDefinition {
+                    description: None,
+                    name: name!("slicingArguments"),
+                    ty: ty!([String!]).into(),
+                    default_value: None,
+                    directives: Default::default(),
+                }
+                .into(),
+                InputValueDefinition {
+                    description: None,
+                    name: name!("sizedFields"),
+                    ty: ty!([String!]).into(),
+                    default_value: None,
+                    directives: Default::default(),
+                }
+                .into(),
+                InputValueDefinition {
+                    description: None,
+                    name: name!("requireOneSlicingArgument"),
+                    ty: ty!(Boolean).into(),
+                    default_value: Some(true.into()),
+                    directives: Default::default(),
+                }
+                .into(),
+            ],
+            repeatable: false,
+            locations: vec![DirectiveLocation::FieldDefinition],
+        }
+    }
+
     pub(crate) fn any_scalar_definition(&self) -> ExtendedType {
         let any_scalar = ScalarType {
             description: None,
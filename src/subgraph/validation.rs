@@ -0,0 +1,667 @@
+//! Federation-specific validation of subgraph schemas.
+//!
+//! This checks the rules that a subgraph must follow on top of being valid GraphQL, mostly around
+//! the usage of the federation directives (`@key`, `@provides`, `@requires` and `@external`), so
+//! that an invalid subgraph is rejected with a precise message before being composed.
+
+use apollo_compiler::ast::{Directive, Value};
+use apollo_compiler::executable::{FieldSet, Selection, SelectionSet};
+use apollo_compiler::execution::GraphQLLocation;
+use apollo_compiler::schema::{ExtendedType, FieldDefinition, Name};
+use apollo_compiler::validation::Valid;
+use apollo_compiler::{Node, NodeLocation, Schema};
+use indexmap::IndexSet;
+
+use crate::error::{FederationError, MultipleFederationErrors, SingleFederationError};
+use crate::link::spec::Version;
+use crate::subgraph::spec::{
+    FederationSpecDefinitions, EXTERNAL_DIRECTIVE_NAME, INTF_OBJECT_DIRECTIVE_NAME,
+    KEY_DIRECTIVE_NAME, PROVIDES_DIRECTIVE_NAME, REQUIRES_DIRECTIVE_NAME, SHAREABLE_DIRECTIVE_NAME,
+};
+
+/// The federation directives that take a field set, which are validated the same way, save for
+/// the error codes and a few directive-specific rules.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldSetDirective {
+    Key,
+    Provides,
+    Requires,
+}
+
+impl FieldSetDirective {
+    fn name(self) -> &'static str {
+        match self {
+            FieldSetDirective::Key => "key",
+            FieldSetDirective::Provides => "provides",
+            FieldSetDirective::Requires => "requires",
+        }
+    }
+
+    fn invalid_fields_type(self, message: String) -> SingleFederationError {
+        match self {
+            FieldSetDirective::Key => SingleFederationError::KeyInvalidFieldsType { message },
+            FieldSetDirective::Provides => {
+                SingleFederationError::ProvidesInvalidFieldsType { message }
+            }
+            FieldSetDirective::Requires => {
+                SingleFederationError::RequiresInvalidFieldsType { message }
+            }
+        }
+    }
+
+    fn invalid_fields(self, message: String) -> SingleFederationError {
+        match self {
+            FieldSetDirective::Key => SingleFederationError::KeyInvalidFields { message },
+            FieldSetDirective::Provides => SingleFederationError::ProvidesInvalidFields { message },
+            FieldSetDirective::Requires => SingleFederationError::RequiresInvalidFields { message },
+        }
+    }
+
+    fn directive_in_fields_args(self, message: String) -> SingleFederationError {
+        match self {
+            FieldSetDirective::Key => SingleFederationError::KeyDirectiveInFieldsArgs { message },
+            FieldSetDirective::Provides => {
+                SingleFederationError::ProvidesDirectiveInFieldsArgs { message }
+            }
+            FieldSetDirective::Requires => {
+                SingleFederationError::RequiresDirectiveInFieldsArgs { message }
+            }
+        }
+    }
+}
+
+/// Validates the federation-specific rules of the given subgraph schema, returning all the
+/// violations found (rather than stopping at the first one).
+///
+/// `is_fed_2` should be false for subgraphs that don't `@link` to the federation spec, for which
+/// the rules that are new in federation 2 are not enforced.
+pub(crate) fn validate_federation_subgraph(
+    subgraph_name: &str,
+    schema: &Valid<Schema>,
+    fed_definitions: &FederationSpecDefinitions,
+    is_fed_2: bool,
+) -> Result<(), FederationError> {
+    let mut validator = SubgraphValidator {
+        subgraph_name,
+        schema,
+        key_directive_name: fed_definitions.namespaced_type_name(&KEY_DIRECTIVE_NAME, true),
+        provides_directive_name: fed_definitions
+            .namespaced_type_name(&PROVIDES_DIRECTIVE_NAME, true),
+        requires_directive_name: fed_definitions
+            .namespaced_type_name(&REQUIRES_DIRECTIVE_NAME, true),
+        external_directive_name: fed_definitions
+            .namespaced_type_name(&EXTERNAL_DIRECTIVE_NAME, true),
+        shareable_directive_name: fed_definitions
+            .namespaced_type_name(&SHAREABLE_DIRECTIVE_NAME, true),
+        interface_object_directive_name: fed_definitions
+            .namespaced_type_name(&INTF_OBJECT_DIRECTIVE_NAME, true),
+        federation_version: fed_definitions.version().clone(),
+        used_fields: IndexSet::new(),
+        errors: Vec::new(),
+    };
+    validator.validate_root_type_names();
+    validator.validate_field_set_directives();
+    if is_fed_2 {
+        validator.validate_external_fields();
+        validator.validate_shareable_usages();
+        validator.validate_interface_keys();
+        validator.validate_interface_objects();
+    }
+    MultipleFederationErrors {
+        errors: validator.errors,
+    }
+    .into_result()
+}
+
+struct SubgraphValidator<'a> {
+    subgraph_name: &'a str,
+    schema: &'a Valid<Schema>,
+    key_directive_name: Name,
+    provides_directive_name: Name,
+    requires_directive_name: Name,
+    external_directive_name: Name,
+    shareable_directive_name: Name,
+    interface_object_directive_name: Name,
+    federation_version: Version,
+    /// The fields (as type and field names) selected by a `@key`, `@provides` or `@requires`.
+    used_fields: IndexSet<(Name, Name)>,
+    errors: Vec<SingleFederationError>,
+}
+
+impl<'a> SubgraphValidator<'a> {
+    /// Records an error, along with the subgraph name and the location of the offending element
+    /// when known.
+    fn report(
+        &mut self,
+        error: impl FnOnce(String) -> SingleFederationError,
+        message: String,
+        location: Option<NodeLocation>,
+    ) {
+        self.errors.push(SingleFederationError::InvalidSubgraph {
+            subgraph: self.subgraph_name.to_owned(),
+            error: Box::new(error(message)),
+            locations: GraphQLLocation::from_node(&self.schema.sources, location)
+                .into_iter()
+                .collect(),
+        });
+    }
+
+    // PORT_NOTE: In the JS codebase, this was part of `validateSchema()` of the federation
+    // blueprint.
+    fn validate_root_type_names(&mut self) {
+        let schema = self.schema;
+        let roots = [
+            (
+                "query",
+                "Query",
+                schema.schema_definition.query.as_ref(),
+                (|message| SingleFederationError::RootQueryUsed { message })
+                    as fn(String) -> SingleFederationError,
+            ),
+            (
+                "mutation",
+                "Mutation",
+                schema.schema_definition.mutation.as_ref(),
+                |message| SingleFederationError::RootMutationUsed { message },
+            ),
+            (
+                "subscription",
+                "Subscription",
+                schema.schema_definition.subscription.as_ref(),
+                |message| SingleFederationError::RootSubscriptionUsed { message },
+            ),
+        ];
+        for (kind, default_name, root, error) in roots {
+            let Some(root) = root else {
+                continue;
+            };
+            if root.name == default_name {
+                continue;
+            }
+            if let Some(default_type) = schema.types.get(default_name) {
+                self.report(
+                    error,
+                    format!(
+                        "The schema has a type named \"{default_name}\" but it is not set as the {kind} root type (\"{}\" is instead): this is not supported by federation. If a root type does not use its default name, there should be no other type with that default name.",
+                        root.name,
+                    ),
+                    default_type.location(),
+                );
+            }
+        }
+    }
+
+    fn validate_field_set_directives(&mut self) {
+        let schema = self.schema;
+        let key_directive_name = self.key_directive_name.clone();
+        let provides_directive_name = self.provides_directive_name.clone();
+        let requires_directive_name = self.requires_directive_name.clone();
+        for (type_name, ty) in &schema.types {
+            let (is_interface, directives, fields) = match ty {
+                ExtendedType::Object(object) => (false, &object.directives, &object.fields),
+                ExtendedType::Interface(interface) => {
+                    (true, &interface.directives, &interface.fields)
+                }
+                _ => continue,
+            };
+            for key in directives.get_all(&key_directive_name) {
+                if is_interface && self.federation_version < (Version { major: 2, minor: 3 }) {
+                    self.report(
+                        |message| SingleFederationError::KeyUnsupportedOnInterface { message },
+                        format!(
+                            "Cannot use @key on interface \"{type_name}\": @key is not yet supported on interfaces"
+                        ),
+                        key.location(),
+                    );
+                    continue;
+                }
+                let context = format!("On type \"{type_name}\"");
+                self.validate_field_set(FieldSetDirective::Key, key, type_name, &context);
+            }
+            for (field_name, field) in fields {
+                let coordinate = format!("{type_name}.{field_name}");
+                for provides in field.directives.get_all(&provides_directive_name) {
+                    self.validate_provides(provides, &coordinate, field, is_interface);
+                }
+                for requires in field.directives.get_all(&requires_directive_name) {
+                    if is_interface {
+                        self.report(
+                            |message| SingleFederationError::RequiresUnsupportedOnInterface {
+                                message,
+                            },
+                            format!(
+                                "Cannot use @requires on field \"{coordinate}\" of parent type \"{type_name}\": @requires is not yet supported within interfaces"
+                            ),
+                            requires.location(),
+                        );
+                        continue;
+                    }
+                    let context = format!("On field \"{coordinate}\"");
+                    self.validate_field_set(
+                        FieldSetDirective::Requires,
+                        requires,
+                        type_name,
+                        &context,
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_provides(
+        &mut self,
+        provides: &Node<Directive>,
+        coordinate: &str,
+        field: &FieldDefinition,
+        is_interface: bool,
+    ) {
+        if is_interface {
+            let (type_name, _) = coordinate.split_once('.').unwrap_or_default();
+            self.report(
+                |message| SingleFederationError::ProvidesUnsupportedOnInterface { message },
+                format!(
+                    "Cannot use @provides on field \"{coordinate}\" of parent type \"{type_name}\": @provides is not yet supported within interfaces"
+                ),
+                provides.location(),
+            );
+            return;
+        }
+        let base_type = field.ty.inner_named_type();
+        let is_composite = matches!(
+            self.schema.types.get(base_type),
+            Some(ExtendedType::Object(_) | ExtendedType::Interface(_) | ExtendedType::Union(_))
+        );
+        if !is_composite {
+            self.report(
+                |message| SingleFederationError::ProvidesOnNonObjectField { message },
+                format!(
+                    "Invalid @provides directive on field \"{coordinate}\": field has type \"{}\" which is not a Composite Type",
+                    field.ty,
+                ),
+                provides.location(),
+            );
+            return;
+        }
+        let context = format!("On field \"{coordinate}\"");
+        self.validate_field_set(FieldSetDirective::Provides, provides, base_type, &context);
+    }
+
+    /// Validates the `fields` argument of the given directive application, which selects fields
+    /// of `parent_type_name`.
+    fn validate_field_set(
+        &mut self,
+        kind: FieldSetDirective,
+        directive: &Node<Directive>,
+        parent_type_name: &Name,
+        context: &str,
+    ) {
+        let Some(fields) = directive.argument_by_name("fields") else {
+            // Missing required arguments are reported by GraphQL validation.
+            return;
+        };
+        let Value::String(fields) = fields.as_ref() else {
+            self.report(
+                |message| kind.invalid_fields_type(message),
+                format!(
+                    "{context}, for @{}(fields: {fields}): Invalid value for argument \"fields\": must be a string.",
+                    kind.name(),
+                ),
+                directive.location(),
+            );
+            return;
+        };
+        let context = format!("{context}, for @{}(fields: \"{fields}\")", kind.name());
+
+        let field_set = match FieldSet::parse(
+            self.schema,
+            parent_type_name.clone(),
+            fields.as_str(),
+            "field_set.graphql",
+        ) {
+            Ok(field_set) => match field_set.validate(self.schema) {
+                Ok(()) => field_set,
+                Err(diagnostics) => {
+                    self.report_invalid_fields(kind, &context, &diagnostics, directive);
+                    return;
+                }
+            },
+            Err(with_errors) => {
+                self.report_invalid_fields(kind, &context, &with_errors.errors, directive);
+                return;
+            }
+        };
+        self.validate_selection_set(kind, &field_set.selection_set, &context, directive, false);
+    }
+
+    fn report_invalid_fields(
+        &mut self,
+        kind: FieldSetDirective,
+        context: &str,
+        diagnostics: &apollo_compiler::validation::DiagnosticList,
+        directive: &Node<Directive>,
+    ) {
+        for diagnostic in diagnostics.iter() {
+            self.report(
+                |message| kind.invalid_fields(message),
+                format!("{context}: {}", diagnostic.error),
+                directive.location(),
+            );
+        }
+    }
+
+    fn validate_selection_set(
+        &mut self,
+        kind: FieldSetDirective,
+        selection_set: &SelectionSet,
+        context: &str,
+        directive: &Node<Directive>,
+        is_under_external: bool,
+    ) {
+        for selection in &selection_set.selections {
+            let (directives, nested) = match selection {
+                Selection::Field(field) => (&field.directives, Some(&field.selection_set)),
+                Selection::InlineFragment(fragment) => (&fragment.directives, None),
+                Selection::FragmentSpread(spread) => (&spread.directives, None),
+            };
+            if !directives.is_empty() {
+                self.report(
+                    |message| kind.directive_in_fields_args(message),
+                    format!(
+                        "{context}: cannot have directive applications in the @{}(fields:) argument but found {}.",
+                        kind.name(),
+                        directives
+                            .iter()
+                            .map(|directive| directive.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                    directive.location(),
+                );
+            }
+            match selection {
+                Selection::Field(field) => {
+                    let parent_type_name = &selection_set.ty;
+                    let coordinate = format!("{parent_type_name}.{}", field.name);
+                    if field.name.starts_with("__") {
+                        continue;
+                    }
+                    self.used_fields
+                        .insert((parent_type_name.clone(), field.name.clone()));
+                    let is_external = field
+                        .definition
+                        .directives
+                        .has(&self.external_directive_name);
+                    self.validate_selected_field(
+                        kind,
+                        field.definition.as_ref(),
+                        &coordinate,
+                        context,
+                        directive,
+                        is_external || is_under_external,
+                    );
+                    if let Some(nested) = nested {
+                        self.validate_selection_set(
+                            kind,
+                            nested,
+                            context,
+                            directive,
+                            is_external || is_under_external,
+                        );
+                    }
+                }
+                Selection::InlineFragment(fragment) => self.validate_selection_set(
+                    kind,
+                    &fragment.selection_set,
+                    context,
+                    directive,
+                    is_under_external,
+                ),
+                Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+
+    fn validate_selected_field(
+        &mut self,
+        kind: FieldSetDirective,
+        definition: &FieldDefinition,
+        coordinate: &str,
+        context: &str,
+        directive: &Node<Directive>,
+        is_external: bool,
+    ) {
+        if kind != FieldSetDirective::Requires && !definition.arguments.is_empty() {
+            self.report(
+                |message| match kind {
+                    FieldSetDirective::Key => SingleFederationError::KeyFieldsHasArgs { message },
+                    _ => SingleFederationError::ProvidesFieldsHasArgs { message },
+                },
+                format!(
+                    "{context}: field {coordinate} cannot be included because it has arguments (fields: {})",
+                    definition
+                        .arguments
+                        .iter()
+                        .map(|argument| argument.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                directive.location(),
+            );
+        }
+        match kind {
+            FieldSetDirective::Key => {
+                let type_kind = match self.schema.types.get(definition.ty.inner_named_type()) {
+                    Some(ExtendedType::Interface(_)) => Some("InterfaceType"),
+                    Some(ExtendedType::Union(_)) => Some("UnionType"),
+                    _ => None,
+                };
+                if let Some(type_kind) = type_kind {
+                    self.report(
+                        |message| SingleFederationError::KeyFieldsSelectInvalidType { message },
+                        format!(
+                            "{context}: field \"{coordinate}\" is a {type_kind} type which is not allowed in @key"
+                        ),
+                        directive.location(),
+                    );
+                }
+            }
+            FieldSetDirective::Provides if !is_external => self.report(
+                |message| SingleFederationError::ProvidesFieldsMissingExternal { message },
+                format!(
+                    "{context}: field \"{coordinate}\" should not be part of a @provides since it is already provided by this subgraph (it is not marked @external)"
+                ),
+                directive.location(),
+            ),
+            FieldSetDirective::Requires if !is_external => self.report(
+                |message| SingleFederationError::RequiresFieldsMissingExternal { message },
+                format!(
+                    "{context}: field \"{coordinate}\" should not be part of a @requires since it is already provided by this subgraph (it is not marked @external)"
+                ),
+                directive.location(),
+            ),
+            _ => {}
+        }
+    }
+
+    // PORT_NOTE: In the JS codebase, this was named `validateAllExternalFieldsUsed()` (the check
+    // for interfaces was part of `validateInterfaceRuntimeImplementationFieldsTypes()`).
+    fn validate_external_fields(&mut self) {
+        let schema = self.schema;
+        for (type_name, ty) in &schema.types {
+            match ty {
+                ExtendedType::Object(object) => {
+                    for (field_name, field) in &object.fields {
+                        if !field.directives.has(&self.external_directive_name)
+                            || self
+                                .used_fields
+                                .contains(&(type_name.clone(), field_name.clone()))
+                        {
+                            continue;
+                        }
+                        let satisfies_interface =
+                            object.implements_interfaces.iter().any(|interface| {
+                                matches!(
+                                    schema.types.get(&interface.name),
+                                    Some(ExtendedType::Interface(interface))
+                                        if interface.fields.contains_key(field_name)
+                                )
+                            });
+                        if satisfies_interface {
+                            continue;
+                        }
+                        self.report(
+                            |message| SingleFederationError::ExternalUnused { message },
+                            format!(
+                                "Field \"{type_name}.{field_name}\" is marked @external but is not used in any federation directive (@key, @provides, @requires) or to satisfy an interface; the field declaration has no use and should be removed (or the field should not be @external)."
+                            ),
+                            field.location(),
+                        );
+                    }
+                }
+                ExtendedType::Interface(interface) => {
+                    for (field_name, field) in &interface.fields {
+                        if field.directives.has(&self.external_directive_name) {
+                            self.report(
+                                |message| SingleFederationError::ExternalOnInterface { message },
+                                format!(
+                                    "Interface type field \"{type_name}.{field_name}\" is marked @external but @external is not allowed on interface fields (it is nonsensical)."
+                                ),
+                                field.location(),
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    /// Validates that `@shareable` is only applied to object types and their fields, and at most
+    /// once per type definition or extension and per field.
+    // PORT_NOTE: In the JS codebase, this was named `validateShareableNotRepeatedOnSameDeclaration()`
+    // (the check for interfaces was part of `onValidation()` of the federation blueprint).
+    fn validate_shareable_usages(&mut self) {
+        let schema = self.schema;
+        let shareable_directive_name = self.shareable_directive_name.clone();
+        const NOT_REPEATABLE: &str = "@shareable is only repeatable on types so it can be used simultaneously on a type definition and its extensions, but it should not be duplicated on the same definition/extension declaration";
+        for (type_name, ty) in &schema.types {
+            match ty {
+                ExtendedType::Object(object) => {
+                    let mut origins = Vec::new();
+                    for shareable in object.directives.get_all(&shareable_directive_name) {
+                        if origins.contains(&&shareable.origin) {
+                            self.report(
+                                |message| SingleFederationError::InvalidShareableUsage { message },
+                                format!(
+                                    "Invalid duplicate application of @shareable on the same type declaration of \"{type_name}\": {NOT_REPEATABLE}"
+                                ),
+                                shareable.location(),
+                            );
+                        }
+                        origins.push(&shareable.origin);
+                    }
+                    for (field_name, field) in &object.fields {
+                        if let Some(shareable) =
+                            field.directives.get_all(&shareable_directive_name).nth(1)
+                        {
+                            self.report(
+                                |message| SingleFederationError::InvalidShareableUsage { message },
+                                format!(
+                                    "Invalid duplicate application of @shareable on field \"{type_name}.{field_name}\": {NOT_REPEATABLE}"
+                                ),
+                                shareable.location(),
+                            );
+                        }
+                    }
+                }
+                ExtendedType::Interface(interface) => {
+                    for (field_name, field) in &interface.fields {
+                        if let Some(shareable) = field.directives.get(&shareable_directive_name) {
+                            self.report(
+                                |message| SingleFederationError::InvalidShareableUsage { message },
+                                format!(
+                                    "Invalid use of @shareable on field \"{type_name}.{field_name}\": only object type fields can be marked with @shareable"
+                                ),
+                                shareable.location(),
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    /// Validates that each implementation of an interface with a `@key` has the same key, as the
+    /// interface can then be resolved from any of its implementations.
+    // PORT_NOTE: In the JS codebase, this was named
+    // `validateKeyOnInterfacesAreAlsoOnAllImplementations()`.
+    fn validate_interface_keys(&mut self) {
+        let schema = self.schema;
+        let key_directive_name = self.key_directive_name.clone();
+        for (interface_name, ty) in &schema.types {
+            let ExtendedType::Interface(interface) = ty else {
+                continue;
+            };
+            for key in interface.directives.get_all(&key_directive_name) {
+                let Some(Value::String(fields)) =
+                    key.argument_by_name("fields").map(|v| v.as_ref())
+                else {
+                    continue;
+                };
+                let fields = normalize_field_set(fields.as_str());
+                let types_without_key = schema
+                    .types
+                    .iter()
+                    .filter(|(_, ty)| {
+                        matches!(ty, ExtendedType::Object(object)
+                            if object.implements_interfaces.contains(interface_name)
+                                && !object.directives.get_all(&key_directive_name).any(|key| {
+                                    matches!(key.argument_by_name("fields").map(|v| v.as_ref()),
+                                        Some(Value::String(other)) if normalize_field_set(other.as_str()) == fields)
+                                }))
+                    })
+                    .map(|(name, _)| format!("\"{name}\""))
+                    .collect::<Vec<_>>();
+                if types_without_key.is_empty() {
+                    continue;
+                }
+                self.report(
+                    |message| SingleFederationError::InterfaceKeyNotOnImplementation { message },
+                    format!(
+                        "Key @key(fields: \"{fields}\") on interface type \"{interface_name}\" is missing on implementation type{} {}.",
+                        if types_without_key.len() == 1 { "" } else { "s" },
+                        types_without_key.join(", "),
+                    ),
+                    key.location(),
+                );
+            }
+        }
+    }
+
+    fn validate_interface_objects(&mut self) {
+        let schema = self.schema;
+        for (type_name, ty) in &schema.types {
+            let ExtendedType::Object(object) = ty else {
+                continue;
+            };
+            let Some(interface_object) =
+                object.directives.get(&self.interface_object_directive_name)
+            else {
+                continue;
+            };
+            if !object.directives.has(&self.key_directive_name) {
+                self.report(
+                    |message| SingleFederationError::InterfaceObjectUsageError { message },
+                    format!(
+                        "The @interfaceObject directive can only be applied to entity types but type \"{type_name}\" has no @key in this subgraph."
+                    ),
+                    interface_object.location(),
+                );
+            }
+        }
+    }
+}
+
+/// Collapses the whitespace of a field set, so that field sets can be compared.
+fn normalize_field_set(fields: &str) -> String {
+    fields.split_whitespace().collect::<Vec<_>>().join(" ")
+}
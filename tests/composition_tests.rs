@@ -1,5 +1,8 @@
-use apollo_compiler::Schema;
-use apollo_federation::subgraph::Subgraph;
+use apollo_compiler::execution::GraphQLLocation;
+use apollo_compiler::{ExecutableDocument, Schema};
+use apollo_federation::error::{ErrorCode, FederationError, SingleFederationError};
+use apollo_federation::merge::{HintCode, SubgraphLocation};
+use apollo_federation::subgraph::{Subgraph, ValidSubgraph};
 use apollo_federation::Supergraph;
 
 fn print_sdl(schema: &Schema) -> String {
@@ -198,3 +201,1795 @@ fn compose_removes_federation_directives() {
             .schema()
     ));
 }
+
+#[test]
+fn can_compose_fed_v2_8_subgraphs() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.8", import: [ "@key" ])
+
+            type Query {
+              products: [Product!]
+            }
+
+            type Product @key(fields: "sku") {
+              sku: String!
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.8", import: [ "@key" ])
+
+            type Product @key(fields: "sku") {
+              sku: String!
+              name: String!
+            }
+        "#,
+    )
+    .unwrap();
+
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    insta::assert_snapshot!(print_sdl(
+        supergraph
+            .to_api_schema(Default::default())
+            .unwrap()
+            .schema()
+    ), @r###"
+    type Product {
+      sku: String!
+      name: String!
+    }
+
+    type Query {
+      products: [Product!]
+    }
+    "###);
+}
+
+#[test]
+fn compose_rejects_context_and_cost_directives() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema
+              @link(url: "https://specs.apollo.dev/federation/v2.9", import: ["@key", "@context", "@fromContext", "@cost", "@listSize"])
+
+            type Query {
+              products(first: Int): [Product!] @listSize(slicingArguments: ["first"])
+            }
+
+            type Product @key(fields: "sku") @context(name: "product") {
+              sku: String!
+              currency: String! @cost(weight: 2)
+              price(currency: String @fromContext(field: "$product { currency }")): Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.9", import: ["@key"])
+
+            type Product @key(fields: "sku") {
+              sku: String!
+              name: String!
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    UNSUPPORTED_FEATURE: Directive "@listSize" is applied to "Query.products", but composing it into the supergraph is not supported yet
+    UNSUPPORTED_FEATURE: Directive "@context" is applied to "Product", but composing it into the supergraph is not supported yet
+    UNSUPPORTED_FEATURE: Directive "@cost" is applied to "Product.currency", but composing it into the supergraph is not supported yet
+    UNSUPPORTED_FEATURE: Directive "@fromContext" is applied to "Product.price(currency:)", but composing it into the supergraph is not supported yet
+    "###);
+    assert!(failure
+        .errors
+        .iter()
+        .all(|error| error.subgraphs().eq(["SubgraphA"])));
+}
+
+#[test]
+fn compose_reports_inconsistent_descriptions_as_hints() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            type Query {
+              "The products"
+              products: [Product!]
+            }
+
+            type Product {
+              sku: String!
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            type Query {
+              "All the products"
+              products: [Product!]
+            }
+
+            type Product {
+              sku: String!
+            }
+        "#,
+    )
+    .unwrap();
+
+    let hints = apollo_federation::merge::merge_subgraphs(vec![&s1, &s2])
+        .unwrap()
+        .composition_hints;
+    assert_eq!(hints.len(), 1);
+    let hint = &hints[0];
+    assert_eq!(hint.code, HintCode::InconsistentDescription);
+    assert_eq!(hint.code.to_string(), "INCONSISTENT_DESCRIPTION");
+    assert_eq!(
+        hint.locations,
+        vec![SubgraphLocation {
+            subgraph: "SubgraphB".to_owned(),
+            location: Some(GraphQLLocation {
+                line: 3,
+                column: 15
+            }),
+        }]
+    );
+    insta::assert_snapshot!(hint, @r###"[INCONSISTENT_DESCRIPTION]: Element "Query.products" has inconsistent descriptions across subgraphs. The supergraph will use description "The products", but subgraph "SubgraphB" has description "All the products"."###);
+}
+
+#[test]
+fn compose_reports_errors_with_codes_and_subgraphs() {
+    let s1 = Subgraph::parse_and_expand(
+        "Subgraph-1",
+        "https://subgraph1",
+        r#"
+            type Query {
+              t: String
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1]) else {
+        panic!("composition should fail");
+    };
+    assert_eq!(failure.errors.len(), 1);
+    let error = &failure.errors[0];
+    assert!(matches!(error.code(), ErrorCode::InvalidSubgraphName));
+    assert_eq!(error.subgraphs().collect::<Vec<_>>(), vec!["Subgraph-1"]);
+}
+
+#[test]
+fn compose_merges_compatible_field_types() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            type Query {
+              t: T
+            }
+
+            type T @key(fields: "k") {
+              k: ID!
+              f: Int!
+            }
+
+            input I {
+              x: Int
+              y: String = "y"
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            type Query {
+              ts(i: I): [T]
+            }
+
+            type T @key(fields: "k") {
+              k: ID!
+              f: Int
+            }
+
+            input I {
+              x: Int!
+              y: String = "y"
+            }
+        "#,
+    )
+    .unwrap();
+
+    let success = apollo_federation::merge::merge_subgraphs(vec![&s1, &s2]).unwrap();
+    let schema = success.schema;
+    insta::assert_snapshot!(schema.types["T"], @r###"
+    type T @join__type(graph: SUBGRAPHA, key: "k") @join__type(graph: SUBGRAPHB, key: "k") {
+      k: ID!
+      f: Int @join__field(graph: SUBGRAPHA, type: "Int!") @join__field(graph: SUBGRAPHB, type: "Int")
+    }
+    "###);
+    insta::assert_snapshot!(schema.types["I"], @r###"
+    input I @join__type(graph: SUBGRAPHA) @join__type(graph: SUBGRAPHB) {
+      x: Int! @join__field(graph: SUBGRAPHA, type: "Int") @join__field(graph: SUBGRAPHB, type: "Int!")
+      y: String = "y"
+    }
+    "###);
+    let hints = success
+        .composition_hints
+        .iter()
+        .map(|hint| hint.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(hints, @r###"
+    [INCONSISTENT_BUT_COMPATIBLE_FIELD_TYPE]: Type of field "T.f" is inconsistent but compatible across subgraphs: will use type "Int" (from subgraph "SubgraphB") in supergraph but it has subtype "Int!" in subgraph "SubgraphA"
+    [INCONSISTENT_BUT_COMPATIBLE_FIELD_TYPE]: Type of input field "I.x" is inconsistent but compatible across subgraphs: will use type "Int!" (from subgraph "SubgraphB") in supergraph but it has supertype "Int" in subgraph "SubgraphA"
+    "###);
+
+    // Each subgraph keeps its own type when extracted back from the supergraph.
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    let subgraphs = supergraph.extract_subgraphs().unwrap();
+    let subgraph_a = subgraphs.get("SubgraphA").unwrap().schema.schema();
+    assert_eq!(
+        subgraph_a.type_field("T", "f").unwrap().ty.to_string(),
+        "Int!"
+    );
+}
+
+#[test]
+fn compose_reports_incompatible_field_types() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            type Query {
+              t: T
+            }
+
+            type T @key(fields: "k") {
+              k: ID!
+              f: Int
+            }
+
+            input I {
+              x: Int = 1
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            type Query {
+              ts(i: I): [T]
+            }
+
+            type T @key(fields: "k") {
+              k: ID!
+              f: String
+            }
+
+            input I {
+              x: Int = 2
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    FIELD_TYPE_MISMATCH: Type of field "T.f" is incompatible across subgraphs: it has type "Int" in subgraph "SubgraphA" but type "String" in subgraph "SubgraphB"
+    INPUT_FIELD_DEFAULT_MISMATCH: Input field "I.x" has incompatible default values across subgraphs: it has default value 1 in subgraph "SubgraphA" but default value 2 in subgraph "SubgraphB"
+    "###);
+    assert_eq!(
+        failure.errors[0].subgraphs().collect::<Vec<_>>(),
+        vec!["SubgraphA", "SubgraphB"]
+    );
+}
+
+#[test]
+fn compose_merges_enum_values_depending_on_usage() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            type Query {
+              output: Output
+              input(i: Input): Int
+            }
+
+            enum Output {
+              A
+              B
+            }
+
+            enum Input {
+              A
+              B
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            type Query {
+              other: Int
+            }
+
+            enum Output {
+              B
+              C
+            }
+
+            enum Input {
+              B
+              C
+            }
+        "#,
+    )
+    .unwrap();
+
+    let success = apollo_federation::merge::merge_subgraphs(vec![&s1, &s2]).unwrap();
+    let schema = success.schema;
+    insta::assert_snapshot!(schema.types["Output"], @r###"
+    enum Output @join__type(graph: SUBGRAPHA) @join__type(graph: SUBGRAPHB) {
+      A @join__enumValue(graph: SUBGRAPHA)
+      B @join__enumValue(graph: SUBGRAPHA) @join__enumValue(graph: SUBGRAPHB)
+      C @join__enumValue(graph: SUBGRAPHB)
+    }
+    "###);
+    insta::assert_snapshot!(schema.types["Input"], @r###"
+    enum Input @join__type(graph: SUBGRAPHA) @join__type(graph: SUBGRAPHB) {
+      B @join__enumValue(graph: SUBGRAPHA) @join__enumValue(graph: SUBGRAPHB)
+    }
+    "###);
+    let hints = success
+        .composition_hints
+        .iter()
+        .map(|hint| hint.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(hints, @r###"
+    [INCONSISTENT_ENUM_VALUE_FOR_OUTPUT_ENUM]: Value "A" of enum type "Output" has been added to the supergraph but is only defined in a subset of the subgraphs defining "Output": it is defined in subgraph "SubgraphA" but not in subgraph "SubgraphB"
+    [INCONSISTENT_ENUM_VALUE_FOR_OUTPUT_ENUM]: Value "C" of enum type "Output" has been added to the supergraph but is only defined in a subset of the subgraphs defining "Output": it is defined in subgraph "SubgraphB" but not in subgraph "SubgraphA"
+    [INCONSISTENT_ENUM_VALUE_FOR_INPUT_ENUM]: Value "A" of enum type "Input" will not be part of the supergraph as it is not defined in all the subgraphs defining "Input": it is defined in subgraph "SubgraphA" but not in subgraph "SubgraphB"
+    [INCONSISTENT_ENUM_VALUE_FOR_INPUT_ENUM]: Value "C" of enum type "Input" will not be part of the supergraph as it is not defined in all the subgraphs defining "Input": it is defined in subgraph "SubgraphB" but not in subgraph "SubgraphA"
+    "###);
+}
+
+#[test]
+fn compose_reports_inconsistent_enum_used_as_input_and_output() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            type Query {
+              e(e: E): E
+            }
+
+            enum E {
+              A
+              B
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            type Query {
+              other: Int
+            }
+
+            enum E {
+              A
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    ENUM_VALUE_MISMATCH: Enum type "E" is used as both input type (for example, as type of "Query.e(e:)") and output type (for example, as type of "Query.e"), but value "B" is not defined in all the subgraphs defining "E": it is defined in subgraph "SubgraphA" but not in subgraph "SubgraphB"
+    "###);
+}
+
+#[test]
+fn compose_reports_unsatisfiable_fields_of_value_types() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            type Query {
+              t: T
+            }
+
+            type T {
+              a: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            type T {
+              b: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    assert_eq!(failure.errors.len(), 1);
+    let error = &failure.errors[0];
+    assert!(matches!(error.code(), ErrorCode::SatisfiabilityError));
+    assert_eq!(error.subgraphs().collect::<Vec<_>>(), vec!["SubgraphA"]);
+    insta::assert_snapshot!(error, @r###"
+    The following supergraph API query:
+    {
+      t {
+        b
+      }
+    }
+    cannot be satisfied by the subgraphs because:
+    - from subgraph "SubgraphA": cannot find field "T.b".
+    - from subgraph "SubgraphA": cannot move to subgraph "SubgraphB", which has field "T.b", because type "T" has no @key defined in subgraph "SubgraphB".
+    "###);
+}
+
+#[test]
+fn compose_reports_unsatisfiable_keys() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            type Query {
+              t(id: ID!): T
+            }
+
+            type T @key(fields: "id") {
+              id: ID!
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            type Query {
+              other: Int
+            }
+
+            type T @key(fields: "k") {
+              k: ID!
+              b: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let messages = failure
+        .errors
+        .iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>();
+    insta::assert_snapshot!(messages.join("\n\n"), @r###"
+    The following supergraph API query:
+    {
+      t(id: "0") {
+        b
+      }
+    }
+    cannot be satisfied by the subgraphs because:
+    - from subgraph "SubgraphA": cannot find field "T.b".
+    - from subgraph "SubgraphA": cannot move to subgraph "SubgraphB" using @key(fields: "k") of "T", the key field(s) cannot be resolved from subgraph "SubgraphA".
+
+    The following supergraph API query:
+    {
+      t(id: "0") {
+        k
+      }
+    }
+    cannot be satisfied by the subgraphs because:
+    - from subgraph "SubgraphA": cannot find field "T.k".
+    - from subgraph "SubgraphA": cannot move to subgraph "SubgraphB" using @key(fields: "k") of "T", the key field(s) cannot be resolved from subgraph "SubgraphA".
+    "###);
+}
+
+fn access_control_subgraphs() -> (ValidSubgraph, ValidSubgraph) {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.6", import: [ "@key", "@shareable", "@authenticated", "@requiresScopes" ])
+
+            scalar Date @authenticated
+
+            type Query {
+              product: Product @requiresScopes(scopes: [["read:product"]])
+              today: Date
+            }
+
+            type Product @key(fields: "sku") {
+              sku: String!
+              name: String @shareable @requiresScopes(scopes: [["read:name"]])
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.6", import: [ "@key", "@shareable", "@policy" ])
+
+            type Query {
+              products: [Product!]
+            }
+
+            type Product @key(fields: "sku") @policy(policies: [["catalog"]]) {
+              sku: String!
+              name: String @shareable @federation__requiresScopes(scopes: [["read:all"], ["read:name"]])
+            }
+        "#,
+    )
+    .unwrap();
+    (s1, s2)
+}
+
+#[test]
+fn compose_merges_access_control_directives() {
+    let (s1, s2) = access_control_subgraphs();
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    insta::assert_snapshot!(print_sdl(supergraph.schema.schema()), @r###"
+    schema @link(url: "https://specs.apollo.dev/link/v1.0") @link(url: "https://specs.apollo.dev/join/v0.3", for: EXECUTION) @link(url: "https://specs.apollo.dev/authenticated/v0.1", for: SECURITY) @link(url: "https://specs.apollo.dev/requiresScopes/v0.1", for: SECURITY) @link(url: "https://specs.apollo.dev/policy/v0.1", for: SECURITY) {
+      query: Query
+    }
+
+    directive @authenticated on FIELD_DEFINITION | OBJECT | INTERFACE | SCALAR | ENUM
+
+    directive @join__enumValue(graph: join__Graph!) repeatable on ENUM_VALUE
+
+    directive @join__field(graph: join__Graph, requires: join__FieldSet, provides: join__FieldSet, type: String, external: Boolean, override: String, usedOverridden: Boolean) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+
+    directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+    directive @join__implements(graph: join__Graph!, interface: String!) repeatable on INTERFACE | OBJECT
+
+    directive @join__type(graph: join__Graph!, key: join__FieldSet, extension: Boolean! = false, resolvable: Boolean! = true, isInterfaceObject: Boolean! = false) repeatable on ENUM | INPUT_OBJECT | INTERFACE | OBJECT | SCALAR | UNION
+
+    directive @join__unionMember(graph: join__Graph!, member: String!) repeatable on UNION
+
+    directive @link(url: String, as: String, for: link__Purpose, import: [link__Import]) repeatable on SCHEMA
+
+    directive @policy(policies: [[policy__Policy!]!]!) on FIELD_DEFINITION | OBJECT | INTERFACE | SCALAR | ENUM
+
+    directive @requiresScopes(scopes: [[requiresScopes__Scope!]!]!) on FIELD_DEFINITION | OBJECT | INTERFACE | SCALAR | ENUM
+
+    scalar Date @join__type(graph: SUBGRAPHA) @authenticated
+
+    type Product @join__type(graph: SUBGRAPHA, key: "sku") @join__type(graph: SUBGRAPHB, key: "sku") @policy(policies: [["catalog"]]) {
+      sku: String!
+      name: String @join__field(graph: SUBGRAPHA) @join__field(graph: SUBGRAPHB) @requiresScopes(scopes: [["read:name"], ["read:all"]])
+    }
+
+    type Query @join__type(graph: SUBGRAPHA) @join__type(graph: SUBGRAPHB) {
+      product: Product @join__field(graph: SUBGRAPHA) @requiresScopes(scopes: [["read:product"]])
+      today: Date @join__field(graph: SUBGRAPHA)
+      products: [Product!] @join__field(graph: SUBGRAPHB)
+    }
+
+    scalar join__FieldSet
+
+    enum join__Graph {
+      SUBGRAPHA @join__graph(name: "SubgraphA", url: "https://subgraphA")
+      SUBGRAPHB @join__graph(name: "SubgraphB", url: "https://subgraphB")
+    }
+
+    scalar link__Import
+
+    enum link__Purpose {
+      """
+      SECURITY features provide metadata necessary to securely resolve fields.
+      """
+      SECURITY
+      """EXECUTION features provide metadata necessary for operation execution."""
+      EXECUTION
+    }
+
+    scalar policy__Policy
+
+    scalar requiresScopes__Scope
+    "###);
+    // access control directives are not part of the API schema, nor of the extracted subgraphs
+    supergraph.to_api_schema(Default::default()).unwrap();
+    supergraph.extract_subgraphs().unwrap();
+}
+
+#[test]
+fn collects_access_control_requirements_of_operations() {
+    let (s1, s2) = access_control_subgraphs();
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    let document = ExecutableDocument::parse_and_validate(
+        supergraph.schema.schema(),
+        r#"
+            query Today { today }
+            query Names { products { ...Name } }
+            fragment Name on Product { name }
+        "#,
+        "operation.graphql",
+    )
+    .unwrap();
+
+    let today = supergraph
+        .access_control_requirements(&document, Some("Today"))
+        .unwrap();
+    assert!(today.requires_authentication);
+    assert!(today.scopes.is_empty());
+    assert!(today.policies.is_empty());
+
+    let names = supergraph
+        .access_control_requirements(&document, Some("Names"))
+        .unwrap();
+    assert!(!names.requires_authentication);
+    assert_eq!(
+        names.scopes.into_iter().collect::<Vec<_>>(),
+        ["read:name", "read:all"]
+    );
+    assert_eq!(names.policies.into_iter().collect::<Vec<_>>(), ["catalog"]);
+}
+
+#[test]
+fn collects_access_control_requirements_of_abstract_type_implementations() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.6", import: [ "@key", "@authenticated", "@requiresScopes", "@policy" ])
+
+            type Query {
+              media: [Media]
+            }
+
+            interface Media {
+              title: String
+            }
+
+            type Book implements Media @requiresScopes(scopes: [["read:book"]]) {
+              title: String @authenticated
+            }
+
+            type Movie implements Media {
+              title: String @policy(policies: [["movies"]])
+            }
+        "#,
+    )
+    .unwrap();
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.6", import: [ "@key" ])
+
+            type Query {
+              other: Int
+            }
+        "#,
+    )
+    .unwrap();
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    let document = ExecutableDocument::parse_and_validate(
+        supergraph.schema.schema(),
+        "{ media { title } }",
+        "operation.graphql",
+    )
+    .unwrap();
+
+    let requirements = supergraph
+        .access_control_requirements(&document, None)
+        .unwrap();
+    assert!(requirements.requires_authentication);
+    assert_eq!(
+        requirements.scopes.into_iter().collect::<Vec<_>>(),
+        ["read:book"]
+    );
+    assert_eq!(
+        requirements.policies.into_iter().collect::<Vec<_>>(),
+        ["movies"]
+    );
+}
+
+#[test]
+fn access_control_requirements_reject_unknown_operations() {
+    let (s1, s2) = access_control_subgraphs();
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    let document = ExecutableDocument::parse_and_validate(
+        supergraph.schema.schema(),
+        "query Today { today }",
+        "operation.graphql",
+    )
+    .unwrap();
+
+    let error = supergraph
+        .access_control_requirements(&document, Some("Tomorrow"))
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        FederationError::SingleFederationError(SingleFederationError::InvalidGraphQL { .. })
+    ));
+    insta::assert_snapshot!(error, @r###"
+    Unknown operation named "Tomorrow".
+    "###);
+}
+
+#[test]
+fn compose_custom_directives_with_compose_directive() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema
+              @link(url: "https://specs.apollo.dev/federation/v2.1", import: ["@key", "@composeDirective"])
+              @link(url: "https://custom.dev/hello/v1.0", import: ["@hello"])
+              @composeDirective(name: "@hello")
+
+            directive @hello(name: String!) on FIELD_DEFINITION | OBJECT
+
+            type Query {
+              product: Product @hello(name: "query")
+            }
+
+            type Product @key(fields: "sku") @hello(name: "product") {
+              sku: String!
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema
+              @link(url: "https://specs.apollo.dev/federation/v2.1", import: ["@key", "@composeDirective"])
+              @link(url: "https://custom.dev/hello/v1.1", import: ["@hello"])
+              @composeDirective(name: "@hello")
+
+            directive @hello(name: String!, loud: Boolean) on FIELD_DEFINITION | OBJECT | ENUM_VALUE
+
+            type Product @key(fields: "sku") @hello(name: "other product") {
+              sku: String!
+              status: Status
+            }
+
+            enum Status {
+              AVAILABLE @hello(name: "available", loud: true)
+            }
+        "#,
+    )
+    .unwrap();
+
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    insta::assert_snapshot!(print_sdl(supergraph.schema.schema()), @r###"
+    schema @link(url: "https://specs.apollo.dev/link/v1.0") @link(url: "https://specs.apollo.dev/join/v0.3", for: EXECUTION) @link(url: "https://custom.dev/hello/v1.1") {
+      query: Query
+    }
+
+    directive @hello(name: String!, loud: Boolean) on FIELD_DEFINITION | OBJECT | ENUM_VALUE
+
+    directive @join__enumValue(graph: join__Graph!) repeatable on ENUM_VALUE
+
+    directive @join__field(graph: join__Graph, requires: join__FieldSet, provides: join__FieldSet, type: String, external: Boolean, override: String, usedOverridden: Boolean) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+
+    directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+    directive @join__implements(graph: join__Graph!, interface: String!) repeatable on INTERFACE | OBJECT
+
+    directive @join__type(graph: join__Graph!, key: join__FieldSet, extension: Boolean! = false, resolvable: Boolean! = true, isInterfaceObject: Boolean! = false) repeatable on ENUM | INPUT_OBJECT | INTERFACE | OBJECT | SCALAR | UNION
+
+    directive @join__unionMember(graph: join__Graph!, member: String!) repeatable on UNION
+
+    directive @link(url: String, as: String, for: link__Purpose, import: [link__Import]) repeatable on SCHEMA
+
+    type Product @join__type(graph: SUBGRAPHA, key: "sku") @join__type(graph: SUBGRAPHB, key: "sku") @hello(name: "product") {
+      sku: String!
+      status: Status @join__field(graph: SUBGRAPHB)
+    }
+
+    type Query @join__type(graph: SUBGRAPHA) @join__type(graph: SUBGRAPHB) {
+      product: Product @join__field(graph: SUBGRAPHA) @hello(name: "query")
+    }
+
+    enum Status @join__type(graph: SUBGRAPHB) {
+      AVAILABLE @join__enumValue(graph: SUBGRAPHB) @hello(name: "available", loud: true)
+    }
+
+    scalar join__FieldSet
+
+    enum join__Graph {
+      SUBGRAPHA @join__graph(name: "SubgraphA", url: "https://subgraphA")
+      SUBGRAPHB @join__graph(name: "SubgraphB", url: "https://subgraphB")
+    }
+
+    scalar link__Import
+
+    enum link__Purpose {
+      """
+      SECURITY features provide metadata necessary to securely resolve fields.
+      """
+      SECURITY
+      """EXECUTION features provide metadata necessary for operation execution."""
+      EXECUTION
+    }
+    "###);
+    let hints = apollo_federation::merge::merge_subgraphs(vec![&s1, &s2])
+        .unwrap()
+        .composition_hints
+        .iter()
+        .map(|hint| hint.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(hints, @r###"
+    [INCONSISTENT_NON_REPEATABLE_DIRECTIVE_ARGUMENTS]: Non-repeatable directive "@hello" is applied to "Product" with inconsistent arguments across subgraphs. The supergraph will use "@hello(name: "product")", but subgraph "SubgraphB" applies "@hello(name: "other product")".
+    "###);
+}
+
+#[test]
+fn compose_custom_directives_with_enum_and_input_object_arguments() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema
+              @link(url: "https://specs.apollo.dev/federation/v2.1", import: ["@key", "@composeDirective"])
+              @link(url: "https://custom.dev/audit/v1.0", import: ["@audit", "Level", "Options", "Duration"])
+              @composeDirective(name: "@audit")
+
+            directive @audit(level: Level!, ttl: Duration, options: Options) on FIELD_DEFINITION
+
+            enum Level {
+              LOW
+              HIGH
+            }
+
+            input Options {
+              reason: String
+              retention: Retention
+            }
+
+            input Retention {
+              days: Int!
+              level: Level
+            }
+
+            scalar Duration
+
+            type Query {
+              product: String @audit(level: HIGH, options: { reason: "pii" })
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.1", import: ["@key"])
+
+            type Query {
+              other: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    insta::assert_snapshot!(print_sdl(supergraph.schema.schema()), @r###"
+    schema @link(url: "https://specs.apollo.dev/link/v1.0") @link(url: "https://specs.apollo.dev/join/v0.3", for: EXECUTION) @link(url: "https://custom.dev/audit/v1.0") {
+      query: Query
+    }
+
+    directive @audit(level: Level!, ttl: Duration, options: Options) on FIELD_DEFINITION
+
+    directive @join__enumValue(graph: join__Graph!) repeatable on ENUM_VALUE
+
+    directive @join__field(graph: join__Graph, requires: join__FieldSet, provides: join__FieldSet, type: String, external: Boolean, override: String, usedOverridden: Boolean) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+
+    directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+    directive @join__implements(graph: join__Graph!, interface: String!) repeatable on INTERFACE | OBJECT
+
+    directive @join__type(graph: join__Graph!, key: join__FieldSet, extension: Boolean! = false, resolvable: Boolean! = true, isInterfaceObject: Boolean! = false) repeatable on ENUM | INPUT_OBJECT | INTERFACE | OBJECT | SCALAR | UNION
+
+    directive @join__unionMember(graph: join__Graph!, member: String!) repeatable on UNION
+
+    directive @link(url: String, as: String, for: link__Purpose, import: [link__Import]) repeatable on SCHEMA
+
+    scalar Duration
+
+    enum Level @join__type(graph: SUBGRAPHA) {
+      LOW @join__enumValue(graph: SUBGRAPHA)
+      HIGH @join__enumValue(graph: SUBGRAPHA)
+    }
+
+    input Options @join__type(graph: SUBGRAPHA) {
+      reason: String
+      retention: Retention
+    }
+
+    type Query @join__type(graph: SUBGRAPHA) @join__type(graph: SUBGRAPHB) {
+      product: String @join__field(graph: SUBGRAPHA) @audit(level: HIGH, options: {reason: "pii"})
+      other: Int @join__field(graph: SUBGRAPHB)
+    }
+
+    input Retention @join__type(graph: SUBGRAPHA) {
+      days: Int!
+      level: Level
+    }
+
+    scalar join__FieldSet
+
+    enum join__Graph {
+      SUBGRAPHA @join__graph(name: "SubgraphA", url: "https://subgraphA")
+      SUBGRAPHB @join__graph(name: "SubgraphB", url: "https://subgraphB")
+    }
+
+    scalar link__Import
+
+    enum link__Purpose {
+      """
+      SECURITY features provide metadata necessary to securely resolve fields.
+      """
+      SECURITY
+      """EXECUTION features provide metadata necessary for operation execution."""
+      EXECUTION
+    }
+    "###);
+}
+
+#[test]
+fn compose_reports_invalid_compose_directive_usages() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema
+              @link(url: "https://specs.apollo.dev/federation/v2.1", import: ["@key", "@composeDirective"])
+              @link(url: "https://custom.dev/hello/v1.0", import: ["@hello"])
+              @composeDirective(name: "hello")
+              @composeDirective(name: "@unknown")
+              @composeDirective(name: "@notLinked")
+              @composeDirective(name: "@key")
+              @composeDirective(name: "@hello")
+
+            directive @hello on OBJECT
+            directive @notLinked on OBJECT
+
+            type Query {
+              t: String
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema
+              @link(url: "https://specs.apollo.dev/federation/v2.1", import: ["@key", "@composeDirective"])
+              @link(url: "https://custom.dev/hello/v2.0", import: ["@hello"])
+              @composeDirective(name: "@hello")
+
+            directive @hello on OBJECT
+
+            type Query {
+              u: String
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    DIRECTIVE_COMPOSITION_ERROR: Argument to @composeDirective "hello" in subgraph "SubgraphA" must have a leading "@"
+    DIRECTIVE_COMPOSITION_ERROR: Could not find matching directive definition for argument to @composeDirective "@unknown" in subgraph "SubgraphA".
+    DIRECTIVE_COMPOSITION_ERROR: Directive "@notLinked" in subgraph "SubgraphA" cannot be composed because it is not a member of a core feature
+    DIRECTIVE_COMPOSITION_ERROR: Composing federation directive "@key" in subgraph "SubgraphA" is not supported
+    DIRECTIVE_COMPOSITION_ERROR: Core feature "https://custom.dev/hello" requested to be merged has major version mismatch across subgraphs
+    "###);
+}
+
+#[test]
+fn compose_merges_executable_directives() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            "Lowercases the field value"
+            directive @lowercase on FIELD
+            directive @trim(all: Boolean = false, chars: String) repeatable on FIELD | FRAGMENT_SPREAD
+            directive @onlyInA on FIELD
+            directive @noCommonLocation on QUERY
+
+            type Query {
+              a: String
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            directive @lowercase on FIELD
+            directive @trim(all: Boolean = false) on FIELD | INLINE_FRAGMENT
+            directive @noCommonLocation on MUTATION
+
+            type Query {
+              b: String
+            }
+        "#,
+    )
+    .unwrap();
+
+    let hints = apollo_federation::merge::merge_subgraphs(vec![&s1, &s2])
+        .unwrap()
+        .composition_hints
+        .iter()
+        .map(|hint| hint.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(hints, @r###"
+    [INCONSISTENT_EXECUTABLE_DIRECTIVE_LOCATIONS]: Executable directive "@trim" has inconsistent locations across subgraphs and will use location "FIELD" (intersection of all subgraphs) in the supergraph, but has: locations "FIELD, FRAGMENT_SPREAD" in subgraph "SubgraphA" but locations "FIELD, INLINE_FRAGMENT" in subgraph "SubgraphB"
+    [INCONSISTENT_EXECUTABLE_DIRECTIVE_REPEATABLE]: Executable directive "@trim" will not be marked repeatable in the supergraph as it is inconsistently marked repeatable in subgraphs: it is repeatable in subgraph "SubgraphA" but not repeatable in subgraph "SubgraphB"
+    [INCONSISTENT_ARGUMENT_PRESENCE]: Optional argument "@trim(chars:)" will not be included in the supergraph as it does not appear in all subgraphs: it is defined in subgraph "SubgraphA" but not in subgraph "SubgraphB"
+    [INCONSISTENT_EXECUTABLE_DIRECTIVE_PRESENCE]: Executable directive "@onlyInA" will not be part of the supergraph as it does not appear in all subgraphs: it is defined in subgraph "SubgraphA" but not in subgraph "SubgraphB"
+    [NO_EXECUTABLE_DIRECTIVE_LOCATIONS_INTERSECTION]: Executable directive "@noCommonLocation" has no location that is common to all subgraphs: it will not be part of the supergraph
+    "###);
+
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+    insta::assert_snapshot!(api_schema.schema(), @r###"
+    """Lowercases the field value"""
+    directive @lowercase on FIELD
+
+    directive @trim(all: Boolean = false) on FIELD
+
+    type Query {
+      a: String
+      b: String
+    }
+    "###);
+}
+
+#[test]
+fn compose_reports_required_executable_directive_arguments_missing_in_some_subgraphs() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            directive @format(pattern: String!) on FIELD
+
+            type Query {
+              a: String
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            directive @format on FIELD
+
+            type Query {
+              b: String
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    REQUIRED_ARGUMENT_MISSING_IN_SOME_SUBGRAPH: Argument "@format(pattern:)" is required in some subgraphs but does not appear in all subgraphs: it is required in subgraph "SubgraphA" but does not appear in subgraph "SubgraphB"
+    "###);
+}
+
+fn print_type(schema: &Schema, type_name: &str) -> String {
+    schema.types[type_name].to_string()
+}
+
+#[test]
+fn compose_applies_overrides() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@override", "@external", "@requires"])
+
+            type Query {
+              t: T
+            }
+
+            type T @key(fields: "k") {
+              k: ID
+              a: Int @override(from: "SubgraphB")
+              b: Int @override(from: "SubgraphB")
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@override", "@external", "@requires"])
+
+            type T @key(fields: "k") @key(fields: "b") {
+              k: ID
+              a: Int
+              b: Int
+              c: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    insta::assert_snapshot!(print_type(supergraph.schema.schema(), "T"), @r###"
+    type T @join__type(graph: SUBGRAPHA, key: "k") @join__type(graph: SUBGRAPHB, key: "k") @join__type(graph: SUBGRAPHB, key: "b") {
+      k: ID
+      a: Int @join__field(graph: SUBGRAPHA, override: "SubgraphB")
+      b: Int @join__field(graph: SUBGRAPHA, override: "SubgraphB") @join__field(graph: SUBGRAPHB, usedOverridden: true)
+      c: Int @join__field(graph: SUBGRAPHB)
+    }
+    "###);
+
+    let hints = apollo_federation::merge::merge_subgraphs(vec![&s1, &s2])
+        .unwrap()
+        .composition_hints
+        .iter()
+        .map(|hint| hint.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(hints, @r###"
+    [OVERRIDDEN_FIELD_CAN_BE_REMOVED]: Field "T.a" on subgraph "SubgraphB" is overridden. Consider removing it.
+    [OVERRIDDEN_FIELD_CAN_BE_REMOVED]: Field "T.b" on subgraph "SubgraphB" is overridden. It is still used in some federation directive(s) (@key, @requires, and/or @provides) and/or to satisfy interface constraint(s), but consider marking it @external explicitly or removing it along with its references.
+    "###);
+}
+
+#[test]
+fn compose_applies_progressive_overrides() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.7", import: ["@key", "@override"])
+
+            type Query {
+              t: T
+            }
+
+            type T @key(fields: "k") {
+              k: ID
+              a: Int @override(from: "SubgraphB", label: "percent(25)")
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.7", import: ["@key"])
+
+            type T @key(fields: "k") {
+              k: ID
+              a: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    let schema = supergraph.schema.schema();
+    insta::assert_snapshot!(schema.directive_definitions["join__field"].to_string(), @r###"
+    directive @join__field(graph: join__Graph, requires: join__FieldSet, provides: join__FieldSet, type: String, external: Boolean, override: String, usedOverridden: Boolean, overrideLabel: String) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+    "###);
+    insta::assert_snapshot!(print_type(schema, "T"), @r###"
+    type T @join__type(graph: SUBGRAPHA, key: "k") @join__type(graph: SUBGRAPHB, key: "k") {
+      k: ID
+      a: Int @join__field(graph: SUBGRAPHA, override: "SubgraphB", overrideLabel: "percent(25)") @join__field(graph: SUBGRAPHB, overrideLabel: "percent(25)")
+    }
+    "###);
+}
+
+#[test]
+fn compose_reports_invalid_overrides() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@override", "@external", "@requires"])
+
+            type Query {
+              t: T
+            }
+
+            interface I {
+              x: Int @override(from: "SubgraphB")
+            }
+
+            type T @key(fields: "k") {
+              k: ID
+              a: Int @override(from: "SubgraphA")
+              b: Int @override(from: "SubgraphB")
+              c: Int @override(from: "SubgraphB")
+              d: Int @external @override(from: "SubgraphB")
+              f: Int @requires(fields: "d")
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@override", "@external", "@requires"])
+
+            type T @key(fields: "k") {
+              k: ID
+              b: Int @override(from: "SubgraphA")
+              c: Int @requires(fields: "e")
+              d: Int
+              e: Int @external
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    OVERRIDE_ON_INTERFACE: @override cannot be used on field "I.x" on subgraph "SubgraphA": @override is not supported on interface type fields.
+    OVERRIDE_FROM_SELF_ERROR: Source and destination subgraphs "SubgraphA" are the same for overridden field "T.a"
+    OVERRIDE_SOURCE_HAS_OVERRIDE: Field "T.b" on subgraph "SubgraphA" is also marked with directive @override in subgraph "SubgraphB". Only one @override directive is allowed per field.
+    OVERRIDE_SOURCE_HAS_OVERRIDE: Field "T.b" on subgraph "SubgraphB" is also marked with directive @override in subgraph "SubgraphA". Only one @override directive is allowed per field.
+    INVALID_FIELD_SHARING: Non-shareable field "T.b" is resolved from multiple subgraphs: it is resolved from subgraphs "SubgraphA" and "SubgraphB" and defined as non-shareable in all of them
+    OVERRIDE_COLLISION_WITH_ANOTHER_DIRECTIVE: @override cannot be used on field "T.c" on subgraph "SubgraphA" since "T.c" on "SubgraphB" is marked with directive "@requires"
+    INVALID_FIELD_SHARING: Non-shareable field "T.c" is resolved from multiple subgraphs: it is resolved from subgraphs "SubgraphA" and "SubgraphB" and defined as non-shareable in all of them
+    OVERRIDE_COLLISION_WITH_ANOTHER_DIRECTIVE: @override cannot be used on field "T.d" on subgraph "SubgraphA" since "T.d" on "SubgraphA" is marked with directive "@external"
+    "###);
+}
+
+#[test]
+fn compose_reports_invalid_field_sharing() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable", "@external", "@provides"])
+
+            type Query {
+              a: Int
+              t: T @provides(fields: "c")
+            }
+
+            type T @key(fields: "k") {
+              k: ID
+              b: Int @shareable
+              c: Int @external
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable"])
+
+            type Query {
+              a: Int
+            }
+
+            type T @key(fields: "k") {
+              k: ID
+              b: Int
+              c: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    INVALID_FIELD_SHARING: Non-shareable field "Query.a" is resolved from multiple subgraphs: it is resolved from subgraphs "SubgraphA" and "SubgraphB" and defined as non-shareable in all of them
+    INVALID_FIELD_SHARING: Non-shareable field "T.b" is resolved from multiple subgraphs: it is resolved from subgraphs "SubgraphA" and "SubgraphB" and defined as non-shareable in subgraph "SubgraphB"
+    INVALID_FIELD_SHARING: Non-shareable field "T.c" is resolved from multiple subgraphs: it is resolved from subgraphs "SubgraphA" and "SubgraphB" and defined as non-shareable in subgraph "SubgraphB"
+    "###);
+}
+
+#[test]
+fn compose_treats_fed1_fields_as_shareable() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            type Query {
+              a: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            type Query {
+              a: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    Supergraph::compose(vec![&s1, &s2]).unwrap();
+}
+
+#[test]
+fn compose_validates_runtime_types_of_shareable_fields() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@shareable"])
+
+            type Query {
+              u: U @shareable
+              v: V @shareable
+            }
+
+            union U = X | Y
+            union V = X
+
+            type X @shareable {
+              x: Int
+            }
+
+            type Y @shareable {
+              y: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@shareable"])
+
+            type Query {
+              u: U @shareable
+              v: V @shareable
+            }
+
+            union U = Y | Z
+            union V = Z
+
+            type Y @shareable {
+              y: Int
+            }
+
+            type Z {
+              z: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = apollo_federation::merge::merge_subgraphs(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    SHAREABLE_HAS_MISMATCHED_RUNTIME_TYPES: Shared field "Query.v" has a non-intersecting set of possible runtime types across subgraphs. Runtime types in subgraphs are:
+     - in subgraph "SubgraphA", type "X";
+     - in subgraph "SubgraphB", type "Z".
+    This is not allowed as shared fields must resolve the same way in all subgraphs, and that imply at least some common runtime types between the subgraphs.
+    "###);
+    let hints = failure
+        .composition_hints
+        .iter()
+        .map(|hint| hint.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(hints, @r###"
+    [INCONSISTENT_RUNTIME_TYPES_FOR_SHAREABLE_RETURN]: Shared field "Query.u" has different sets of possible runtime types across subgraphs.
+    Since a shared field must be resolved the same way in all subgraphs, make sure that subgraphs "SubgraphA" and "SubgraphB" only resolve "Query.u" to objects of type "Y". In particular:
+     - in subgraph "SubgraphA", types "X", "Y";
+     - in subgraph "SubgraphB", types "Y", "Z".
+    Otherwise the @shareable contract will be broken.
+    "###);
+}
+
+fn entity_interface_subgraphs() -> (ValidSubgraph, ValidSubgraph) {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
+            type Query {
+              i: I
+            }
+
+            interface I @key(fields: "id") {
+              id: ID!
+              x: Int
+            }
+
+            type A implements I @key(fields: "id") {
+              id: ID!
+              x: Int
+              a: Int
+            }
+
+            type B implements I @key(fields: "id") {
+              id: ID!
+              x: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@interfaceObject"])
+
+            type Query {
+              is: [I!]!
+            }
+
+            type I @key(fields: "id") @interfaceObject {
+              id: ID!
+              y: Int
+            }
+        "#,
+    )
+    .unwrap();
+    (s1, s2)
+}
+
+#[test]
+fn compose_merges_interface_objects() {
+    let (s1, s2) = entity_interface_subgraphs();
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    let schema = supergraph.schema.schema();
+    insta::assert_snapshot!(print_type(schema, "I"), @r###"
+    interface I @join__type(graph: SUBGRAPHA, key: "id") @join__type(graph: SUBGRAPHB, isInterfaceObject: true, key: "id") {
+      id: ID!
+      x: Int @join__field(graph: SUBGRAPHA)
+      y: Int @join__field(graph: SUBGRAPHB)
+    }
+    "###);
+    insta::assert_snapshot!(print_type(schema, "A"), @r###"
+    type A implements I @join__type(graph: SUBGRAPHA, key: "id") @join__implements(graph: SUBGRAPHA, interface: "I") {
+      id: ID!
+      x: Int @join__field(graph: SUBGRAPHA)
+      a: Int @join__field(graph: SUBGRAPHA)
+      y: Int @join__field
+    }
+    "###);
+}
+
+#[test]
+fn compose_reports_invalid_interface_objects() {
+    let (s1, _) = entity_interface_subgraphs();
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@interfaceObject"])
+
+            type Query {
+              j: J
+            }
+
+            type J @key(fields: "id") @interfaceObject {
+              id: ID!
+            }
+
+            interface I @key(fields: "id") {
+              id: ID!
+            }
+
+            type C implements I @key(fields: "id") {
+              id: ID!
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    INTERFACE_OBJECT_USAGE_ERROR: Type "J" is declared with @interfaceObject in all the subgraphs in which it is defined (it is defined in subgraph "SubgraphB" but should be defined as an interface in at least one subgraph)
+    INTERFACE_KEY_MISSING_IMPLEMENTATION_TYPE: Interface type "I" has a resolvable key (@key(fields: "id")) in subgraph "SubgraphA" but that subgraph is missing some of the supergraph implementation types of "I". Subgraph "SubgraphA" should define type "C" (and have it implement "I").
+    INTERFACE_KEY_MISSING_IMPLEMENTATION_TYPE: Interface type "I" has a resolvable key (@key(fields: "id")) in subgraph "SubgraphB" but that subgraph is missing some of the supergraph implementation types of "I". Subgraph "SubgraphB" should define types "A" and "B" (and have them implement "I").
+    "###);
+}
+
+#[test]
+fn compose_merges_field_arguments() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@shareable"])
+
+            type Query @shareable {
+              products(
+                "The maximum number of products"
+                first: Int = 10
+                after: String
+                category: String
+              ): [String]
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@shareable"])
+
+            type Query @shareable {
+              products(first: Int! = 10, after: String = "", filter: String): [String]
+            }
+        "#,
+    )
+    .unwrap();
+
+    let success = apollo_federation::merge::merge_subgraphs(vec![&s1, &s2]).unwrap();
+    insta::assert_snapshot!(print_type(&success.schema, "Query"), @r###"
+    type Query @join__type(graph: SUBGRAPHA) @join__type(graph: SUBGRAPHB) {
+      products(
+        """The maximum number of products"""
+        first: Int! = 10,
+        after: String,
+      ): [String] @join__field(graph: SUBGRAPHA) @join__field(graph: SUBGRAPHB)
+    }
+    "###);
+    let hints = success
+        .composition_hints
+        .iter()
+        .map(|hint| hint.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(hints, @r###"
+    [INCONSISTENT_BUT_COMPATIBLE_ARGUMENT_TYPE]: Type of argument "Query.products(first:)" is inconsistent but compatible across subgraphs: will use type "Int!" (from subgraph "SubgraphB") in supergraph but it has supertype "Int" in subgraph "SubgraphA"
+    [INCONSISTENT_DEFAULT_VALUE_PRESENCE]: Argument "Query.products(after:)" has a default value in only some subgraphs: will not use a default in the supergraph (there is no default in subgraph "SubgraphA") but it has default value "" in subgraph "SubgraphB"
+    [INCONSISTENT_ARGUMENT_PRESENCE]: Optional argument "Query.products(category:)" will not be included in the supergraph as it does not appear in all subgraphs: it is defined in subgraph "SubgraphA" but not in subgraph "SubgraphB"
+    [INCONSISTENT_ARGUMENT_PRESENCE]: Optional argument "Query.products(filter:)" will not be included in the supergraph as it does not appear in all subgraphs: it is defined in subgraph "SubgraphB" but not in subgraph "SubgraphA"
+    "###);
+}
+
+#[test]
+fn compose_reports_invalid_field_arguments() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@shareable"])
+
+            type Query @shareable {
+              products(first: Int = 10, sort: String!, after: String): [String]
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@shareable"])
+
+            type Query @shareable {
+              products(first: Int = 20, after: Int): [String]
+            }
+        "#,
+    )
+    .unwrap();
+
+    let Err(failure) = Supergraph::compose(vec![&s1, &s2]) else {
+        panic!("composition should fail");
+    };
+    let errors = failure
+        .errors
+        .iter()
+        .map(|error| format!("{}: {error}", error.code().definition().code()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    insta::assert_snapshot!(errors, @r###"
+    FIELD_ARGUMENT_DEFAULT_MISMATCH: Argument "Query.products(first:)" has incompatible default values across subgraphs: it has default value 10 in subgraph "SubgraphA" but default value 20 in subgraph "SubgraphB"
+    REQUIRED_ARGUMENT_MISSING_IN_SOME_SUBGRAPH: Argument "Query.products(sort:)" is required in some subgraphs but does not appear in all subgraphs: it is required in subgraph "SubgraphA" but does not appear in subgraph "SubgraphB"
+    FIELD_ARGUMENT_TYPE_MISMATCH: Type of argument "Query.products(after:)" is incompatible across subgraphs: it has type "String" in subgraph "SubgraphA" but type "Int" in subgraph "SubgraphB"
+    "###);
+}
+
+#[test]
+fn compose_resolves_renamed_federation_directives() {
+    let s1 = Subgraph::parse_and_expand(
+        "SubgraphA",
+        "https://subgraphA",
+        r#"
+            extend schema
+              @link(
+                url: "https://specs.apollo.dev/federation/v2.3"
+                import: [{ name: "@key", as: "@primaryKey" }]
+              )
+
+            type Query {
+              t: T
+            }
+
+            type T @primaryKey(fields: "id") {
+              id: ID!
+              name: String @federation__shareable
+              price: Int @federation__override(from: "SubgraphB")
+              weight: Int @federation__external
+              shipping: Int @federation__requires(fields: "weight")
+            }
+        "#,
+    )
+    .unwrap();
+
+    let s2 = Subgraph::parse_and_expand(
+        "SubgraphB",
+        "https://subgraphB",
+        r#"
+            extend schema
+              @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable"])
+
+            type T @key(fields: "id") {
+              id: ID!
+              name: String @shareable
+              price: Int
+              weight: Int
+            }
+        "#,
+    )
+    .unwrap();
+
+    let supergraph = Supergraph::compose(vec![&s1, &s2]).unwrap();
+    insta::assert_snapshot!(print_type(supergraph.schema.schema(), "T"), @r###"
+    type T @join__type(graph: SUBGRAPHA, key: "id") @join__type(graph: SUBGRAPHB, key: "id") {
+      id: ID!
+      name: String @join__field(graph: SUBGRAPHA) @join__field(graph: SUBGRAPHB)
+      price: Int @join__field(graph: SUBGRAPHA, override: "SubgraphB")
+      weight: Int @join__field(graph: SUBGRAPHA, external: true) @join__field(graph: SUBGRAPHB)
+      shipping: Int @join__field(graph: SUBGRAPHA, requires: "weight")
+    }
+    "###);
+}
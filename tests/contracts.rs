@@ -0,0 +1,184 @@
+use apollo_federation::ContractOptions;
+use apollo_federation::Supergraph;
+
+const TAG_V03_HEADER: &str = r#"
+    directive @link(url: String, as: String, for: link__Purpose, import: [link__Import]) repeatable on SCHEMA
+
+    scalar link__Import
+
+    enum link__Purpose {
+      SECURITY
+      EXECUTION
+    }
+
+    directive @join__field(graph: join__Graph, requires: join__FieldSet, provides: join__FieldSet, type: String, external: Boolean, override: String, usedOverridden: Boolean) repeatable on FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+
+    directive @join__graph(name: String!, url: String!) on ENUM_VALUE
+
+    directive @join__type(graph: join__Graph!, key: join__FieldSet, extension: Boolean! = false, resolvable: Boolean! = true, isInterfaceObject: Boolean! = false) repeatable on OBJECT | INTERFACE | UNION | ENUM | INPUT_OBJECT | SCALAR
+
+    scalar join__FieldSet
+
+    enum join__Graph {
+      PRODUCTS @join__graph(name: "products", url: "http://products")
+    }
+
+    directive @tag(name: String!) repeatable on FIELD_DEFINITION | OBJECT | INTERFACE | UNION | ARGUMENT_DEFINITION | SCALAR | ENUM | ENUM_VALUE | INPUT_OBJECT | INPUT_FIELD_DEFINITION | SCHEMA
+
+    schema
+      @link(url: "https://specs.apollo.dev/link/v1.0")
+      @link(url: "https://specs.apollo.dev/join/v0.3", for: EXECUTION)
+      @link(url: "https://specs.apollo.dev/tag/v0.3")
+    {
+      query: Query
+    }
+"#;
+
+fn contract_api_schema(input: &str, include: &[&str], exclude: &[&str]) -> String {
+    let supergraph = Supergraph::new(&format!("{TAG_V03_HEADER}{input}")).unwrap();
+    let options = ContractOptions {
+        include_tags: include.iter().map(|tag| tag.to_string()).collect(),
+        exclude_tags: exclude.iter().map(|tag| tag.to_string()).collect(),
+    };
+    let contract = supergraph
+        .to_contract(&options, Default::default())
+        .unwrap();
+    contract.api_schema.schema().to_string()
+}
+
+#[test]
+fn excludes_tagged_elements() {
+    let api_schema = contract_api_schema(
+        r#"
+        type Query @join__type(graph: PRODUCTS) {
+          products(filter: Filter): [Product!]
+          internal: Internal @tag(name: "internal")
+        }
+
+        type Product @join__type(graph: PRODUCTS) {
+          id: ID!
+          cost: Int @tag(name: "internal")
+          status: Status
+        }
+
+        enum Status @join__type(graph: PRODUCTS) {
+          AVAILABLE
+          DISCONTINUED @tag(name: "internal")
+        }
+
+        input Filter @join__type(graph: PRODUCTS) {
+          name: String
+          secret: String @tag(name: "internal")
+        }
+
+        # only reachable through an excluded field
+        type Internal @join__type(graph: PRODUCTS) {
+          notes: String
+        }
+        "#,
+        &[],
+        &["internal"],
+    );
+    insta::assert_snapshot!(api_schema, @r###"
+    type Query {
+      products(filter: Filter): [Product!]
+    }
+
+    type Product {
+      id: ID!
+      status: Status
+    }
+
+    enum Status {
+      AVAILABLE
+    }
+
+    input Filter {
+      name: String
+    }
+    "###);
+}
+
+#[test]
+fn includes_tagged_elements() {
+    let api_schema = contract_api_schema(
+        r#"
+        type Query @join__type(graph: PRODUCTS) {
+          products: [Product!] @tag(name: "public")
+          reviews: [Review!]
+          me: User @tag(name: "public")
+        }
+
+        # the fields of an included type are included
+        type Product @join__type(graph: PRODUCTS) @tag(name: "public") {
+          id: ID!
+          name: String
+          cost: Int @tag(name: "internal")
+        }
+
+        # a type with an included field is included
+        type User @join__type(graph: PRODUCTS) {
+          id: ID! @tag(name: "public")
+          email: String
+        }
+
+        type Review @join__type(graph: PRODUCTS) {
+          body: String
+        }
+        "#,
+        &["public"],
+        &["internal"],
+    );
+    insta::assert_snapshot!(api_schema, @r###"
+    type Query {
+      products: [Product!]
+      me: User
+    }
+
+    type Product {
+      id: ID!
+      name: String
+    }
+
+    type User {
+      id: ID!
+    }
+    "###);
+}
+
+#[test]
+fn excludes_fields_depending_on_excluded_elements() {
+    let api_schema = contract_api_schema(
+        r#"
+        type Query @join__type(graph: PRODUCTS) {
+          product(id: ID!, filter: Filter): Product
+          search(filter: Filter!): [Product!]
+          secret: Secret
+        }
+
+        type Product @join__type(graph: PRODUCTS) {
+          id: ID!
+        }
+
+        # excluding the only field of a type excludes the type
+        type Secret @join__type(graph: PRODUCTS) {
+          value: String @tag(name: "internal")
+        }
+
+        input Filter @join__type(graph: PRODUCTS) @tag(name: "internal") {
+          name: String
+        }
+        "#,
+        &[],
+        &["internal"],
+    );
+    insta::assert_snapshot!(api_schema, @r###"
+    type Query {
+      product(id: ID!): Product
+    }
+
+    type Product {
+      id: ID!
+    }
+    "###);
+}
@@ -1,2 +1,4 @@
 mod operation_optimization_tests;
 mod operation_validations_tests;
+mod parity_tests;
+mod statistics_tests;
@@ -0,0 +1,172 @@
+use apollo_federation::query_plan::parity::diff_query_plans;
+use apollo_federation::query_plan::parity::PlanFixture;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn plans_match_js_query_planner_fixtures() {
+    let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/query_plan/fixtures");
+    let mut paths: Vec<_> = fs::read_dir(fixtures_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "plan")
+        })
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    let mut failures = Vec::new();
+    for path in paths {
+        let fixture = PlanFixture::read(&path).unwrap();
+        let differences = fixture.run(Default::default()).unwrap();
+        if !differences.is_empty() {
+            let differences: Vec<_> = differences.iter().map(ToString::to_string).collect();
+            failures.push(format!("{}:\n{}", fixture.name, differences.join("\n")));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn diff_ignores_insignificant_ordering() {
+    let differences = diff_query_plans(
+        r#"
+        QueryPlan {
+          Parallel {
+            Fetch(service: "a") {
+              {
+                t {
+                  __typename
+                  id
+                }
+              }
+            },
+            Fetch(service: "b") {
+              {
+                u
+              }
+            },
+          },
+        }
+        "#,
+        r#"
+        QueryPlan {
+          Parallel {
+            Fetch(service: "b") {
+              {
+                        u
+              }
+            }
+            Fetch(service: "a") {
+              {
+                        t {
+                  id
+                  __typename
+                }
+              }
+            }
+          }
+        }
+        "#,
+    );
+    assert_eq!(differences, vec![]);
+}
+
+#[test]
+fn diff_reports_structural_differences() {
+    let differences = diff_query_plans(
+        r#"
+        QueryPlan {
+          Sequence {
+            Fetch(service: "a") {
+              {
+                t {
+                  __typename
+                  id
+                }
+              }
+            },
+            Flatten(path: "t") {
+              Fetch(service: "b") {
+                {
+                  ... on T {
+                    __typename
+                    id
+                  }
+                } =>
+                {
+                  ... on T {
+                    x
+                  }
+                }
+              },
+            },
+          },
+        }
+        "#,
+        r#"
+        QueryPlan {
+          Sequence {
+            Fetch(service: "a") {
+              {
+                t {
+                  __typename
+                  id
+                  x
+                }
+              }
+            }
+            Flatten(path: "t") {
+              Fetch(service: "c") {
+                {
+                  ... on T {
+                    __typename
+                    id
+                  }
+                } => {
+                  ... on T {
+                    x
+                  }
+                }
+              }
+            }
+          }
+        }
+        "#,
+    );
+    let differences: Vec<_> = differences.iter().map(ToString::to_string).collect();
+    insta::assert_snapshot!(differences.join("\n"), @r###"
+    In QueryPlan > Sequence > Fetch(service: "a") > { > t:
+    + x
+
+    In QueryPlan > Sequence > Flatten(path: "t"):
+    - Fetch(service: "b") {
+    -   {
+    -     ... on T {
+    -       __typename
+    -       id
+    -     }
+    -   } =>
+    -   {
+    -     ... on T {
+    -       x
+    -     }
+    -   }
+    - }
+    + Fetch(service: "c") {
+    +   {
+    +     ... on T {
+    +       __typename
+    +       id
+    +     }
+    +   } =>
+    +   {
+    +     ... on T {
+    +       x
+    +     }
+    +   }
+    + }
+    "###);
+}
@@ -0,0 +1,112 @@
+use apollo_compiler::validation::Valid;
+use apollo_compiler::ExecutableDocument;
+use apollo_federation::error::{ErrorCode, FederationError, SingleFederationError};
+use apollo_federation::query_plan::query_planner::{QueryPlanner, QueryPlannerConfig};
+use apollo_federation::subgraph::Subgraph;
+use apollo_federation::Supergraph;
+use std::time::Instant;
+
+fn planner_and_operation(config: QueryPlannerConfig) -> (QueryPlanner, Valid<ExecutableDocument>) {
+    let users = Subgraph::parse_and_expand(
+        "users",
+        "https://users",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable"])
+
+            type Query {
+              me: User @shareable
+            }
+
+            type User @key(fields: "id") {
+              id: ID!
+              name: String
+            }
+        "#,
+    )
+    .unwrap();
+    let reviews = Subgraph::parse_and_expand(
+        "reviews",
+        "https://reviews",
+        r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable"])
+
+            type Query {
+              me: User @shareable
+            }
+
+            type User @key(fields: "id") {
+              id: ID!
+              reviews: [Review]
+            }
+
+            type Review {
+              body: String
+            }
+        "#,
+    )
+    .unwrap();
+    let supergraph = Supergraph::compose(vec![&users, &reviews]).unwrap();
+    let api_schema = supergraph.to_api_schema(Default::default()).unwrap();
+    let document = ExecutableDocument::parse_and_validate(
+        api_schema.schema(),
+        "{ me { name reviews { body } } }",
+        "operation.graphql",
+    )
+    .unwrap();
+    let planner = QueryPlanner::new(&supergraph, config).unwrap();
+    (planner, document)
+}
+
+#[test]
+fn query_plan_reports_planning_statistics() {
+    let (planner, document) = planner_and_operation(Default::default());
+    let start = Instant::now();
+    let plan = planner
+        .build_query_plan(&document, None, Default::default())
+        .unwrap();
+    let elapsed = start.elapsed();
+
+    let statistics = &plan.statistics;
+    // `me.name` and `me.reviews.body`, plus the `@key` conditions to jump from either subgraph to
+    // the other.
+    assert_eq!(statistics.closed_branch_count, 4);
+    // `me` and `me.name` can each be fetched from either subgraph, which makes 4 complete plans.
+    // The costs of the partial plans computed on the way to them aren't counted.
+    assert_eq!(statistics.evaluated_plan_count, 4);
+    assert!(!statistics.paths_limit_reached);
+    let (Some(normalization), Some(traversal), Some(processing)) = (
+        statistics.normalization_duration,
+        statistics.traversal_duration,
+        statistics.processing_duration,
+    ) else {
+        panic!("expected all the durations to be recorded, got: {statistics:?}");
+    };
+    assert!(normalization + traversal + processing <= elapsed);
+}
+
+#[test]
+fn paths_limit_error_reports_planning_statistics() {
+    let mut config = QueryPlannerConfig::default();
+    config.debug.paths_limit = Some(1);
+    let (planner, document) = planner_and_operation(config);
+    let failure = planner
+        .build_query_plan_with_statistics(&document, None, Default::default())
+        .unwrap_err();
+
+    let FederationError::SingleFederationError(
+        error @ SingleFederationError::PathsLimitExceeded { limit: 1, .. },
+    ) = &failure.error
+    else {
+        panic!("expected a paths limit error, got: {}", failure.error);
+    };
+    assert!(matches!(
+        error.code(),
+        ErrorCode::QueryPlanComplexityExceeded
+    ));
+    let statistics = &failure.statistics;
+    assert!(statistics.paths_limit_reached);
+    assert!(statistics.normalization_duration.is_some());
+    assert!(statistics.traversal_duration.is_some());
+    assert!(statistics.processing_duration.is_none());
+    assert_eq!(statistics.evaluated_plan_count, 0);
+}
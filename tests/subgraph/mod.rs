@@ -1 +1,2 @@
 mod parse_expand_tests;
+mod validation_tests;
@@ -41,7 +41,7 @@ fn can_parse_and_expand_with_renames() -> Result<(), String> {
 
         type T @myKey(fields: "id") {
             id: ID!
-            x: Int
+            x: Int @federation__external
         }
         "#;
 
@@ -141,6 +141,86 @@ fn can_parse_and_expand_works_with_fed_v1() -> Result<(), String> {
     Ok(())
 }
 
+#[test]
+fn can_parse_and_expand_with_fed_v2_9_directives() -> Result<(), String> {
+    let schema = r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.9", import: [ "@key", "@context", "@fromContext", "@authenticated", "@requiresScopes", "@policy", "@cost", "@listSize" ])
+
+        type Query {
+            t: T @authenticated
+            ts(first: Int): [T] @listSize(slicingArguments: ["first"]) @cost(weight: 5)
+        }
+
+        type T @key(fields: "id") @context(name: "tContext") @policy(policies: [["read"]]) {
+            id: ID!
+            x: Int @requiresScopes(scopes: [["read:x"]])
+            u: U
+        }
+
+        type U @key(fields: "id") {
+            id: ID!
+            field(a: String @fromContext(field: "$tContext { id }")): Int
+        }
+        "#;
+
+    let subgraph = Subgraph::parse_and_expand("S1", "http://s1", schema).map_err(|e| {
+        println!("{}", e);
+        String::from("failed to parse and expand the subgraph, see errors above for details")
+    })?;
+    for directive in [
+        "context",
+        "fromContext",
+        "authenticated",
+        "requiresScopes",
+        "policy",
+        "cost",
+        "listSize",
+    ] {
+        assert!(subgraph
+            .schema
+            .directive_definitions
+            .contains_key(directive));
+    }
+    for scalar in [
+        "federation__Scope",
+        "federation__Policy",
+        "federation__ContextFieldValue",
+    ] {
+        assert!(subgraph.schema.types.contains_key(scalar));
+    }
+    Ok(())
+}
+
+#[test]
+fn can_parse_and_expand_only_adds_directives_of_linked_version() -> Result<(), String> {
+    let schema = r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.5", import: [ "@key" ])
+
+        type Query {
+            t: T
+        }
+
+        type T @key(fields: "id") {
+            id: ID!
+        }
+        "#;
+
+    let subgraph = Subgraph::parse_and_expand("S1", "http://s1", schema).map_err(|e| {
+        println!("{}", e);
+        String::from("failed to parse and expand the subgraph, see errors above for details")
+    })?;
+    let directives = &subgraph.schema.directive_definitions;
+    assert!(directives.contains_key("federation__requiresScopes"));
+    assert!(!directives.contains_key("federation__policy"));
+    assert!(!directives.contains_key("federation__context"));
+    assert!(!directives.contains_key("federation__cost"));
+    assert!(subgraph.schema.types.contains_key("federation__Scope"));
+    assert!(!subgraph.schema.types.contains_key("federation__Policy"));
+    Ok(())
+}
+
 #[test]
 fn can_parse_and_expand_will_fail_when_importing_same_spec_twice() {
     let schema = r#"
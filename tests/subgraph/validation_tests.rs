@@ -0,0 +1,379 @@
+use apollo_federation::error::{FederationError, SingleFederationError};
+use apollo_federation::subgraph::Subgraph;
+
+/// Expands the given subgraph schema, expecting it to fail federation validation, and returns the
+/// code, message and locations (as `line:column`) of each reported error.
+fn validation_errors(schema: &str) -> Vec<(String, String, Vec<String>)> {
+    let Err(err) = Subgraph::parse_and_expand("S", "http://s", schema) else {
+        panic!("subgraph validation should fail");
+    };
+    let errors = match err {
+        FederationError::SingleFederationError(error) => vec![error],
+        FederationError::MultipleFederationErrors(errors) => errors.errors,
+        FederationError::AggregateFederationError(error) => error.causes,
+    };
+    errors
+        .into_iter()
+        .map(|error| {
+            let locations = match &error {
+                SingleFederationError::InvalidSubgraph { locations, .. } => locations
+                    .iter()
+                    .map(|location| format!("{}:{}", location.line, location.column))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            (
+                error.code().definition().code().to_owned(),
+                error.to_string(),
+                locations,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn rejects_key_fields_with_arguments_or_abstract_types() {
+    let errors = validation_errors(
+        r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
+        type Query {
+          t: T
+        }
+
+        type T @key(fields: "id(x: 1)") @key(fields: "i { id }") {
+          id(x: Int): ID!
+          i: I
+        }
+
+        interface I {
+          id: ID!
+        }
+        "#,
+    );
+    insta::assert_debug_snapshot!(errors, @r###"
+    [
+        (
+            "KEY_FIELDS_HAS_ARGS",
+            "[S] On type \"T\", for @key(fields: \"id(x: 1)\"): field T.id cannot be included because it has arguments (fields: x)",
+            [
+                "9:16",
+            ],
+        ),
+        (
+            "KEY_FIELDS_SELECT_INVALID_TYPE",
+            "[S] On type \"T\", for @key(fields: \"i { id }\"): field \"T.i\" is a InterfaceType type which is not allowed in @key",
+            [
+                "9:41",
+            ],
+        ),
+    ]
+    "###);
+}
+
+#[test]
+fn rejects_invalid_field_sets() {
+    let errors = validation_errors(
+        r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@requires", "@external"])
+
+        type Query {
+          t: T
+        }
+
+        type T @key(fields: "unknown") @key(fields: "id @include(if: true)") {
+          id: ID!
+          x: Int @external
+          y: Int @requires(fields: 3)
+        }
+        "#,
+    );
+    insta::assert_debug_snapshot!(errors, @r###"
+    [
+        (
+            "KEY_INVALID_FIELDS",
+            "[S] On type \"T\", for @key(fields: \"unknown\"): type `T` does not have a field `unknown`",
+            [
+                "9:16",
+            ],
+        ),
+        (
+            "KEY_DIRECTIVE_IN_FIELDS_ARG",
+            "[S] On type \"T\", for @key(fields: \"id @include(if: true)\"): cannot have directive applications in the @key(fields:) argument but found @include(if: true).",
+            [
+                "9:40",
+            ],
+        ),
+        (
+            "REQUIRES_INVALID_FIELDS_TYPE",
+            "[S] On field \"T.y\", for @requires(fields: 3): Invalid value for argument \"fields\": must be a string.",
+            [
+                "12:18",
+            ],
+        ),
+        (
+            "EXTERNAL_UNUSED",
+            "[S] Field \"T.x\" is marked @external but is not used in any federation directive (@key, @provides, @requires) or to satisfy an interface; the field declaration has no use and should be removed (or the field should not be @external).",
+            [
+                "11:11",
+            ],
+        ),
+    ]
+    "###);
+}
+
+#[test]
+fn rejects_provides_and_requires_of_non_external_fields() {
+    let errors = validation_errors(
+        r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@provides", "@requires"])
+
+        type Query {
+          t: T @provides(fields: "x")
+          n: Int @provides(fields: "x")
+        }
+
+        type T @key(fields: "id") {
+          id: ID!
+          x: Int
+          y: Int @requires(fields: "x")
+        }
+        "#,
+    );
+    insta::assert_debug_snapshot!(errors, @r###"
+    [
+        (
+            "PROVIDES_FIELDS_MISSING_EXTERNAL",
+            "[S] On field \"Query.t\", for @provides(fields: \"x\"): field \"T.x\" should not be part of a @provides since it is already provided by this subgraph (it is not marked @external)",
+            [
+                "6:16",
+            ],
+        ),
+        (
+            "PROVIDES_ON_NON_OBJECT_FIELD",
+            "[S] Invalid @provides directive on field \"Query.n\": field has type \"Int\" which is not a Composite Type",
+            [
+                "7:18",
+            ],
+        ),
+        (
+            "REQUIRES_FIELDS_MISSING_EXTERNAL",
+            "[S] On field \"T.y\", for @requires(fields: \"x\"): field \"T.x\" should not be part of a @requires since it is already provided by this subgraph (it is not marked @external)",
+            [
+                "13:18",
+            ],
+        ),
+    ]
+    "###);
+}
+
+#[test]
+fn rejects_unused_external_fields() {
+    let errors = validation_errors(
+        r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@external"])
+
+        type Query {
+          t: T
+        }
+
+        interface I {
+          x: Int @external
+        }
+
+        type T implements I @key(fields: "id") {
+          id: ID!
+          x: Int @external
+          y: Int @external
+        }
+        "#,
+    );
+    insta::assert_debug_snapshot!(errors, @r###"
+    [
+        (
+            "EXTERNAL_ON_INTERFACE",
+            "[S] Interface type field \"I.x\" is marked @external but @external is not allowed on interface fields (it is nonsensical).",
+            [
+                "10:11",
+            ],
+        ),
+        (
+            "EXTERNAL_UNUSED",
+            "[S] Field \"T.y\" is marked @external but is not used in any federation directive (@key, @provides, @requires) or to satisfy an interface; the field declaration has no use and should be removed (or the field should not be @external).",
+            [
+                "16:11",
+            ],
+        ),
+    ]
+    "###);
+}
+
+#[test]
+fn rejects_invalid_shareable_usages() {
+    let errors = validation_errors(
+        r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@shareable"])
+
+        type Query {
+          t: T
+        }
+
+        interface I {
+          x: Int @shareable
+        }
+
+        type T implements I @key(fields: "id") @shareable @shareable {
+          id: ID!
+          x: Int @shareable @shareable
+        }
+
+        extend type T @shareable {
+          y: Int
+        }
+        "#,
+    );
+    insta::assert_debug_snapshot!(errors, @r###"
+    [
+        (
+            "INVALID_SHAREABLE_USAGE",
+            "[S] Invalid use of @shareable on field \"I.x\": only object type fields can be marked with @shareable",
+            [
+                "10:18",
+            ],
+        ),
+        (
+            "INVALID_SHAREABLE_USAGE",
+            "[S] Invalid duplicate application of @shareable on the same type declaration of \"T\": @shareable is only repeatable on types so it can be used simultaneously on a type definition and its extensions, but it should not be duplicated on the same definition/extension declaration",
+            [
+                "13:59",
+            ],
+        ),
+        (
+            "INVALID_SHAREABLE_USAGE",
+            "[S] Invalid duplicate application of @shareable on field \"T.x\": @shareable is only repeatable on types so it can be used simultaneously on a type definition and its extensions, but it should not be duplicated on the same definition/extension declaration",
+            [
+                "15:29",
+            ],
+        ),
+    ]
+    "###);
+}
+
+#[test]
+fn rejects_invalid_entity_interfaces() {
+    let errors = validation_errors(
+        r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@interfaceObject"])
+
+        type Query {
+          i: I
+          j: J
+        }
+
+        interface I @key(fields: "id") {
+          id: ID!
+        }
+
+        type A implements I @key(fields: "id") {
+          id: ID!
+        }
+
+        type B implements I {
+          id: ID!
+        }
+
+        type J @interfaceObject {
+          id: ID!
+        }
+        "#,
+    );
+    insta::assert_debug_snapshot!(errors, @r###"
+    [
+        (
+            "INTERFACE_KEY_NOT_ON_IMPLEMENTATION",
+            "[S] Key @key(fields: \"id\") on interface type \"I\" is missing on implementation type \"B\".",
+            [
+                "10:21",
+            ],
+        ),
+        (
+            "INTERFACE_OBJECT_USAGE_ERROR",
+            "[S] The @interfaceObject directive can only be applied to entity types but type \"J\" has no @key in this subgraph.",
+            [
+                "22:16",
+            ],
+        ),
+    ]
+    "###);
+}
+
+#[test]
+fn rejects_root_types_with_default_names_used_elsewhere() {
+    let errors = validation_errors(
+        r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
+        schema {
+          query: MyQuery
+        }
+
+        type MyQuery {
+          t: T
+        }
+
+        type Query {
+          other: Int
+        }
+
+        type T {
+          id: ID!
+        }
+        "#,
+    );
+    insta::assert_debug_snapshot!(errors, @r###"
+    [
+        (
+            "ROOT_QUERY_USED",
+            "[S] The schema has a type named \"Query\" but it is not set as the query root type (\"MyQuery\" is instead): this is not supported by federation. If a root type does not use its default name, there should be no other type with that default name.",
+            [
+                "13:9",
+            ],
+        ),
+    ]
+    "###);
+}
+
+#[test]
+fn accepts_valid_federation_directive_usages() {
+    Subgraph::parse_and_expand(
+        "S",
+        "http://s",
+        r#"
+        extend schema
+          @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@external", "@provides", "@requires"])
+
+        type Query {
+          t: T @provides(fields: "x { a }")
+        }
+
+        type T @key(fields: "id") @key(fields: "id x { a }") {
+          id: ID!
+          x: X @external
+          y: Int @requires(fields: "x { a b }")
+        }
+
+        type X {
+          a: Int
+          b: Int
+        }
+        "#,
+    )
+    .unwrap();
+}